@@ -86,4 +86,8 @@ pub enum SystemProgramError {
     CpiContextAlreadySet,
     InvalidTreeHeight,
     TooManyOutputAccounts,
+    DecompressionRecipientIndexOutOfBounds,
+    DecompressionRecipientsWithCompressOrDecompressLamports,
+    DecompressionRecipientsInCpiContext,
+    CpiContextAccountTooSmall,
 }
@@ -216,6 +216,7 @@ pub fn cpi_compressed_token_transfer_pda<'info>(
         cpi_context: Some(cpi_context),
         lamports_change_account_merkle_tree_index: None,
         with_transaction_hash: false,
+        input_tlvs: Vec::new(),
     };
 
     let mut inputs = Vec::new();
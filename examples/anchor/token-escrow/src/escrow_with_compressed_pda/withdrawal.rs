@@ -207,6 +207,7 @@ pub fn cpi_compressed_token_withdrawal<'info>(
         cpi_context: Some(cpi_context),
         lamports_change_account_merkle_tree_index: None,
         with_transaction_hash: false,
+        input_tlvs: Vec::new(),
     };
 
     let mut inputs = Vec::new();
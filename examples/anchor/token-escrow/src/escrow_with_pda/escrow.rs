@@ -97,6 +97,7 @@ pub fn cpi_compressed_token_transfer<'info>(
         cpi_context: None,
         lamports_change_account_merkle_tree_index: None,
         with_transaction_hash: false,
+        input_tlvs: Vec::new(),
     };
 
     let mut inputs = Vec::new();
@@ -19,8 +19,14 @@ use light_client::{
 use light_compressed_account::TreeType;
 use light_registry::{
     protocol_config::state::ProtocolConfig,
-    sdk::{create_finalize_registration_instruction, create_report_work_instruction},
-    utils::{get_epoch_pda_address, get_forester_epoch_pda_from_authority},
+    sdk::{
+        create_claim_forester_rewards_instruction, create_finalize_registration_instruction,
+        create_report_work_instruction,
+    },
+    utils::{
+        get_epoch_pda_address, get_epoch_reward_vault_address,
+        get_forester_epoch_pda_from_authority,
+    },
     EpochPda, ForesterEpochPda,
 };
 use solana_program::{instruction::InstructionError, pubkey::Pubkey};
@@ -35,8 +41,8 @@ use tracing::{debug, error, info, info_span, instrument, trace, warn};
 use crate::{
     batch_processor::{process_batched_operations, BatchContext},
     errors::{
-        ChannelError, ConfigurationError, ForesterError, InitializationError, RegistrationError,
-        WorkReportError,
+        ChannelError, ClaimError, ConfigurationError, ForesterError, InitializationError,
+        RegistrationError, WorkReportError,
     },
     indexer_type::{rollover_address_merkle_tree, rollover_state_merkle_tree, IndexerType},
     metrics::{push_metrics, queue_metric_update, update_forester_sol_balance},
@@ -433,8 +439,13 @@ impl<R: RpcConnection, I: Indexer<R> + IndexerType<R>> EpochManager<R, I> {
             self.report_work(&registration_info).await?;
         }
 
-        // TODO: implement
-        // self.claim(&registration_info).await?;
+        // Wait for post phase
+        if self.sync_slot().await? < phases.post.start {
+            self.wait_for_post_phase(&registration_info).await?;
+        }
+
+        // Claim rewards
+        self.claim(&registration_info).await?;
 
         // Ensure we reset the processing flag when we're done
         let _reset_guard = scopeguard::guard((), |_| {
@@ -1170,9 +1181,88 @@ impl<R: RpcConnection, I: Indexer<R> + IndexerType<R>> EpochManager<R, I> {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    async fn claim(&self, _forester_epoch_info: ForesterEpochInfo) {
-        todo!()
+    #[instrument(level = "debug", skip(self, epoch_info), fields(forester = %self.config.payer_keypair.pubkey(), epoch = epoch_info.epoch.epoch
+    ))]
+    async fn wait_for_post_phase(&self, epoch_info: &ForesterEpochInfo) -> Result<()> {
+        info!("Waiting for post phase");
+        let mut rpc = self.rpc_pool.get_connection().await?;
+        let post_start_slot = epoch_info.epoch.phases.post.start;
+        wait_until_slot_reached(&mut *rpc, &self.slot_tracker, post_start_slot).await?;
+
+        info!("Finished waiting for post phase");
+        Ok(())
+    }
+
+    /// Claims the rewards of the epoch.
+    /// The forester epoch account is closed by the claim,
+    /// if it doesn't exist rewards have already been claimed.
+    #[instrument(level = "debug", skip(self, epoch_info), fields(forester = %self.config.payer_keypair.pubkey(), epoch = epoch_info.epoch.epoch
+    ))]
+    async fn claim(&self, epoch_info: &ForesterEpochInfo) -> Result<()> {
+        info!("Claiming rewards");
+        let mut rpc = self.rpc_pool.get_connection().await?;
+
+        let forester_epoch_pda_pubkey = get_forester_epoch_pda_from_authority(
+            &self.config.derivation_pubkey,
+            epoch_info.epoch.epoch,
+        )
+        .0;
+        match rpc
+            .get_anchor_account::<ForesterEpochPda>(&forester_epoch_pda_pubkey)
+            .await?
+        {
+            Some(forester_epoch_pda) => {
+                if !forester_epoch_pda.has_reported_work {
+                    info!("Work not reported, no rewards to claim");
+                    return Ok(());
+                }
+            }
+            None => {
+                info!("Rewards already claimed");
+                return Ok(());
+            }
+        }
+        if rpc
+            .get_account(get_epoch_reward_vault_address(epoch_info.epoch.epoch))
+            .await?
+            .is_none()
+        {
+            info!("Epoch reward vault is not funded, no rewards to claim");
+            return Ok(());
+        }
+
+        let ix = create_claim_forester_rewards_instruction(
+            &self.config.payer_keypair.pubkey(),
+            &self.config.derivation_pubkey,
+            epoch_info.epoch.epoch,
+        );
+
+        match rpc
+            .create_and_send_transaction(
+                &[ix],
+                &self.config.payer_keypair.pubkey(),
+                &[&self.config.payer_keypair],
+            )
+            .await
+        {
+            Ok(_) => {
+                info!("Rewards claimed successfully");
+                Ok(())
+            }
+            Err(e) => {
+                if let RpcError::ClientError(client_error) = &e {
+                    if let Some(TransactionError::InstructionError(
+                        _,
+                        InstructionError::Custom(error_code),
+                    )) = client_error.get_transaction_error()
+                    {
+                        return ClaimError::from_registry_error(error_code, epoch_info.epoch.epoch)
+                            .map_err(|e| anyhow::Error::from(ForesterError::from(e)));
+                    }
+                }
+                Err(anyhow::Error::from(ClaimError::Transaction(Box::new(e))))
+            }
+        }
     }
 }
 
@@ -23,6 +23,9 @@ pub enum ForesterError {
     #[error("Work report error: {0}")]
     WorkReport(#[from] WorkReportError),
 
+    #[error("Claim error: {0}")]
+    Claim(#[from] ClaimError),
+
     #[error("Epoch registration returned no result")]
     EmptyRegistration,
 
@@ -190,6 +193,34 @@ impl WorkReportError {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum ClaimError {
+    #[error("Registry instruction error: {error_code}")]
+    RegistryInstruction { error_code: u32 },
+
+    #[error("Transaction failed: {0}")]
+    Transaction(#[from] Box<RpcError>),
+}
+
+impl ClaimError {
+    pub(crate) fn from_registry_error(error_code: u32, epoch: u64) -> Result<(), Self> {
+        match error_code {
+            code if code == u32::from(RegistryError::ForesterWorkNotReported) => {
+                info!("Work not reported for epoch {}. Skipping claim.", epoch);
+                Ok(())
+            }
+            code if code == u32::from(RegistryError::EpochStillInProgress) => {
+                warn!(
+                    "Report work phase of epoch {} has not ended. Skipping claim.",
+                    epoch
+                );
+                Ok(())
+            }
+            code => Err(Self::RegistryInstruction { error_code: code }),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum PhotonApiErrorWrapper {
     #[error(transparent)]
@@ -19,7 +19,7 @@ use tokio::{
     signal::ctrl_c,
     sync::{mpsc, oneshot},
 };
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), ForesterError> {
@@ -82,6 +82,11 @@ async fn main() -> Result<(), ForesterError> {
             if let Some(limiter) = &photon_rate_limiter {
                 indexer.set_rate_limiter(limiter.clone());
             }
+            // Address trees are only required for batched address updates,
+            // the forester still serves the remaining trees without them.
+            if let Err(e) = indexer.load_address_merkle_trees().await {
+                warn!("Failed to load address Merkle trees: {:?}", e);
+            }
 
             let indexer = Arc::new(tokio::sync::Mutex::new(indexer));
 
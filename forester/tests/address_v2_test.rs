@@ -394,6 +394,7 @@ async fn create_v2_addresses<R: RpcConnection + MerkleTreeExt>(
             read_only_accounts: Vec::new(),
             read_only_addresses: Vec::new(),
             cpi_context: Default::default(),
+            decompression_recipients: Vec::new(),
         };
 
         let remaining_accounts = to_account_metas(remaining_accounts);
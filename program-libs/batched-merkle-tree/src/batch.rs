@@ -1,4 +1,6 @@
-use light_bloom_filter::BloomFilter;
+use light_bloom_filter::{
+    BloomFilter, BloomFilterRef, CountingBloomFilter, CountingBloomFilterRef,
+};
 use light_hasher::{Hasher, Poseidon};
 use light_zero_copy::vec::ZeroCopyVecU64;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
@@ -34,6 +36,38 @@ impl From<BatchState> for u64 {
     }
 }
 
+/// Bloom filter variant used by the batches of an input queue.
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Default, BorshSerialize, BorshDeserialize)]
+#[repr(u8)]
+pub enum BloomFilterType {
+    /// Bloom filter, values cannot be removed,
+    /// the bloom filter is zeroed before the batch is reused.
+    #[default]
+    Standard,
+    /// Counting bloom filter with 4 bit counters,
+    /// values can be removed individually.
+    /// Stores capacity / 4 counters in the same space.
+    Counting,
+}
+
+impl TryFrom<u8> for BloomFilterType {
+    type Error = BatchedMerkleTreeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(BloomFilterType::Standard),
+            1 => Ok(BloomFilterType::Counting),
+            _ => Err(BatchedMerkleTreeError::UnsupportedBloomFilterType),
+        }
+    }
+}
+
+impl From<BloomFilterType> for u8 {
+    fn from(val: BloomFilterType) -> Self {
+        val as u8
+    }
+}
+
 /// Batch structure that holds
 /// the metadata and state of a batch.
 ///
@@ -89,7 +123,10 @@ pub struct Batch {
     pub root_index: u32,
     start_slot_is_set: u8,
     bloom_filter_is_zeroed: u8,
-    _padding: [u8; 2],
+    bloom_filter_type: u8,
+    /// Number of inserted zkp batches whose values
+    /// have been removed from the counting bloom filter.
+    num_removed_zkp_batches: u8,
 }
 
 impl Batch {
@@ -115,10 +152,19 @@ impl Batch {
             start_slot: 0,
             start_slot_is_set: 0,
             bloom_filter_is_zeroed: 0,
-            _padding: [0u8; 2],
+            bloom_filter_type: BloomFilterType::Standard.into(),
+            num_removed_zkp_batches: 0,
         }
     }
 
+    pub fn get_bloom_filter_type(&self) -> Result<BloomFilterType, BatchedMerkleTreeError> {
+        BloomFilterType::try_from(self.bloom_filter_type)
+    }
+
+    pub fn set_bloom_filter_type(&mut self, bloom_filter_type: BloomFilterType) {
+        self.bloom_filter_type = bloom_filter_type.into();
+    }
+
     /// Returns the state of the batch.
     pub fn get_state(&self) -> BatchState {
         self.state.into()
@@ -163,6 +209,7 @@ impl Batch {
             self.sequence_number = 0;
             self.root_index = 0;
             self.num_inserted_zkp_batches = 0;
+            self.num_removed_zkp_batches = 0;
             self.start_slot_is_set = 0;
             self.start_slot = 0;
             if let Some(start_index) = start_index {
@@ -320,16 +367,22 @@ impl Batch {
             let other_bloom_filter_index = if bloom_filter_index == 0 { 1 } else { 0 };
 
             // 3. Insert value into the bloom filter at bloom_filter_index.
-            BloomFilter::new(
-                self.num_iters as usize,
-                self.bloom_filter_capacity,
-                bloom_filter_stores[bloom_filter_index],
-            )?
-            .insert(bloom_filter_value)?;
+            match self.get_bloom_filter_type()? {
+                BloomFilterType::Standard => BloomFilter::new(
+                    self.num_iters as usize,
+                    self.bloom_filter_capacity,
+                    bloom_filter_stores[bloom_filter_index],
+                )?
+                .insert(bloom_filter_value)?,
+                BloomFilterType::Counting => CountingBloomFilter::new(
+                    self.num_iters as usize,
+                    self.bloom_filter_capacity,
+                    bloom_filter_stores[bloom_filter_index],
+                )?
+                .insert(bloom_filter_value)?,
+            }
             // 4. Check that value is not in any other bloom filter.
-            Self::check_non_inclusion(
-                self.num_iters as usize,
-                self.bloom_filter_capacity,
+            self.check_value_non_inclusion(
                 bloom_filter_value,
                 bloom_filter_stores[other_bloom_filter_index],
             )?;
@@ -383,14 +436,124 @@ impl Batch {
         Ok(())
     }
 
-    /// Checks that value is not in the bloom filter.
+    /// Checks that value is not in the bloom filter of the batch,
+    /// uses the bloom filter type of the batch.
+    pub fn check_value_non_inclusion(
+        &self,
+        value: &[u8; 32],
+        store: &mut [u8],
+    ) -> Result<(), BatchedMerkleTreeError> {
+        match self.get_bloom_filter_type()? {
+            BloomFilterType::Standard => Self::check_non_inclusion(
+                self.num_iters as usize,
+                self.bloom_filter_capacity,
+                value,
+                store,
+            ),
+            BloomFilterType::Counting => {
+                let bloom_filter = CountingBloomFilter::new(
+                    self.num_iters as usize,
+                    self.bloom_filter_capacity,
+                    store,
+                )?;
+                if bloom_filter.contains(value) {
+                    return Err(BatchedMerkleTreeError::NonInclusionCheckFailed);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns for every value whether it is contained
+    /// in the bloom filter of the batch.
+    pub fn bloom_filter_contains_many(
+        &self,
+        values: &[[u8; 32]],
+        store: &[u8],
+    ) -> Result<Vec<bool>, BatchedMerkleTreeError> {
+        let num_iters = self.num_iters as usize;
+        let contained = match self.get_bloom_filter_type()? {
+            BloomFilterType::Standard => {
+                BloomFilterRef::new(num_iters, self.bloom_filter_capacity, store)?
+                    .contains_many(values)
+            }
+            BloomFilterType::Counting => {
+                CountingBloomFilterRef::new(num_iters, self.bloom_filter_capacity, store)?
+                    .contains_many(values)
+            }
+        };
+        Ok(contained)
+    }
+
+    /// Returns the number of inserted zkp batches whose values
+    /// have been removed from the counting bloom filter.
+    pub fn get_num_removed_zkp_batches(&self) -> u64 {
+        self.num_removed_zkp_batches as u64
+    }
+
+    /// Removes the values of the next inserted zkp batch
+    /// from the counting bloom filter of the batch.
+    /// Zkp batches are removed in insertion order.
+    /// The caller must ensure that values are the values of the zkp batch
+    /// and that no root which precedes their insertion
+    /// remains in the root history.
+    /// 1. Check that the bloom filter is a counting bloom filter.
+    /// 2. Check that the batch is inserted and the zkp batch is not removed yet.
+    /// 3. Remove values from the bloom filter.
+    /// 4. If all zkp batches are removed, mark the bloom filter as zeroed.
+    /// 5. Returns the index of the removed zkp batch.
+    pub fn remove_zkp_batch_from_bloom_filter(
+        &mut self,
+        values: &[[u8; 32]],
+        store: &mut [u8],
+    ) -> Result<u64, BatchedMerkleTreeError> {
+        // 1. Check that the bloom filter is a counting bloom filter.
+        if self.get_bloom_filter_type()? != BloomFilterType::Counting {
+            return Err(BatchedMerkleTreeError::UnsupportedBloomFilterType);
+        }
+        // 2. Check that the batch is inserted and the zkp batch is not removed yet.
+        let zkp_batch_index = self.get_num_removed_zkp_batches();
+        if self.get_state() != BatchState::Inserted
+            || self.bloom_filter_is_zeroed()
+            || zkp_batch_index >= self.get_num_zkp_batches()
+        {
+            return Err(BatchedMerkleTreeError::BatchNotReady);
+        }
+        if values.len() as u64 != self.zkp_batch_size {
+            return Err(BatchedMerkleTreeError::InvalidHashChain);
+        }
+        // 3. Remove values from the bloom filter.
+        {
+            let mut bloom_filter = CountingBloomFilter::new(
+                self.num_iters as usize,
+                self.bloom_filter_capacity,
+                store,
+            )?;
+            for value in values {
+                bloom_filter.remove(value)?;
+            }
+        }
+        self.num_removed_zkp_batches = self
+            .num_removed_zkp_batches
+            .checked_add(1)
+            .ok_or(BatchedMerkleTreeError::InvalidIndex)?;
+        // 4. If all zkp batches are removed, mark the bloom filter as zeroed.
+        //     Saturated counters are not decremented,
+        //     they can only cause false positives.
+        if self.get_num_removed_zkp_batches() == self.get_num_zkp_batches() {
+            self.set_bloom_filter_to_zeroed();
+        }
+        Ok(zkp_batch_index)
+    }
+
+    /// Checks that value is not in the (standard) bloom filter.
     pub fn check_non_inclusion(
         num_iters: usize,
         bloom_filter_capacity: u64,
         value: &[u8; 32],
         store: &mut [u8],
     ) -> Result<(), BatchedMerkleTreeError> {
-        let mut bloom_filter = BloomFilter::new(num_iters, bloom_filter_capacity, store)?;
+        let bloom_filter = BloomFilter::new(num_iters, bloom_filter_capacity, store)?;
         if bloom_filter.contains(value) {
             return Err(BatchedMerkleTreeError::NonInclusionCheckFailed);
         }
@@ -647,7 +810,7 @@ mod tests {
                         )
                         .is_err());
                 }
-                let mut bloom_filter = BloomFilter {
+                let bloom_filter = BloomFilter {
                     num_iters: batch.num_iters as usize,
                     capacity: batch.bloom_filter_capacity,
                     store: bloom_filter_stores[processing_index],
@@ -917,6 +1080,136 @@ mod tests {
         assert!(!batch.bloom_filter_is_zeroed());
     }
 
+    #[test]
+    fn test_bloom_filter_type() {
+        let mut batch = get_test_batch();
+        assert_eq!(batch.get_bloom_filter_type(), Ok(BloomFilterType::Standard));
+        batch.set_bloom_filter_type(BloomFilterType::Counting);
+        assert_eq!(batch.get_bloom_filter_type(), Ok(BloomFilterType::Counting));
+        batch.set_bloom_filter_type(BloomFilterType::Standard);
+        assert_eq!(batch.get_bloom_filter_type(), Ok(BloomFilterType::Standard));
+        batch.bloom_filter_type = 2;
+        assert_eq!(
+            batch.get_bloom_filter_type(),
+            Err(BatchedMerkleTreeError::UnsupportedBloomFilterType)
+        );
+    }
+
+    /// 1. Standard bloom filter batches cannot remove values.
+    /// 2. Insert value into counting bloom filter 0.
+    /// 3. Inserting the same value into batch 1 fails the non inclusion check.
+    /// 4. Removing values of a batch which is not inserted fails.
+    /// 5. Remove the inserted zkp batch from bloom filter 0,
+    ///     the bloom filter is zeroed.
+    /// 6. Removing the zkp batch again fails.
+    /// 7. Value can be inserted into batch 1.
+    #[test]
+    fn test_counting_bloom_filter_insert_and_remove() {
+        let mut batch = Batch::new(3, 160_000, 1, 1, 0);
+        let value = [1u8; 32];
+        let mut store_0 = vec![0u8; batch.bloom_filter_capacity as usize / 8];
+        let mut store_1 = vec![0u8; batch.bloom_filter_capacity as usize / 8];
+        // Capacity for the hash chains of batch and other batch.
+        let mut hash_chain_store =
+            vec![0u8; ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(2)];
+        let mut hash_chain_store =
+            ZeroCopyVecU64::<[u8; 32]>::new(2, &mut hash_chain_store).unwrap();
+        // 1. Standard bloom filter batches cannot remove values.
+        assert_eq!(
+            batch.remove_zkp_batch_from_bloom_filter(&[value], &mut store_0),
+            Err(BatchedMerkleTreeError::UnsupportedBloomFilterType)
+        );
+        batch.set_bloom_filter_type(BloomFilterType::Counting);
+        // 2. Insert value into counting bloom filter 0.
+        {
+            let mut stores = [store_0.as_mut_slice(), store_1.as_mut_slice()];
+            batch
+                .insert(&value, &value, &mut stores, &mut hash_chain_store, 0, &1)
+                .unwrap();
+            assert_eq!(
+                batch.check_value_non_inclusion(&value, stores[0]),
+                Err(BatchedMerkleTreeError::NonInclusionCheckFailed)
+            );
+            // 3. Inserting the same value into batch 1 fails the non inclusion check.
+            let mut other_batch = Batch::new(3, 160_000, 1, 1, 0);
+            other_batch.set_bloom_filter_type(BloomFilterType::Counting);
+            let mut stores = [store_1.as_mut_slice(), store_0.as_mut_slice()];
+            assert_eq!(
+                other_batch.insert(&value, &value, &mut stores, &mut hash_chain_store, 0, &1),
+                Err(BatchedMerkleTreeError::NonInclusionCheckFailed)
+            );
+        }
+        // 4. Removing values of a batch which is not inserted fails.
+        assert_eq!(
+            batch.remove_zkp_batch_from_bloom_filter(&[value], &mut store_0),
+            Err(BatchedMerkleTreeError::BatchNotReady)
+        );
+        batch.mark_as_inserted_in_merkle_tree(0, 0, 10).unwrap();
+        // 5. Remove the inserted zkp batch from bloom filter 0.
+        assert_eq!(
+            batch.remove_zkp_batch_from_bloom_filter(&[value], &mut store_0),
+            Ok(0)
+        );
+        batch
+            .check_value_non_inclusion(&value, &mut store_0)
+            .unwrap();
+        assert!(store_0.iter().all(|x| *x == 0));
+        assert!(batch.bloom_filter_is_zeroed());
+        assert_eq!(batch.get_num_removed_zkp_batches(), 1);
+        // 6. Removing the zkp batch again fails.
+        assert_eq!(
+            batch.remove_zkp_batch_from_bloom_filter(&[value], &mut store_0),
+            Err(BatchedMerkleTreeError::BatchNotReady)
+        );
+        // 7. Value can be inserted into batch 1.
+        store_1.iter_mut().for_each(|x| *x = 0);
+        batch.advance_state_to_fill(None).unwrap();
+        assert_eq!(batch.get_num_removed_zkp_batches(), 0);
+        let mut hash_chain_store =
+            vec![0u8; ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(1)];
+        let mut hash_chain_store =
+            ZeroCopyVecU64::<[u8; 32]>::new(1, &mut hash_chain_store).unwrap();
+        let mut stores = [store_0.as_mut_slice(), store_1.as_mut_slice()];
+        batch
+            .insert(&value, &value, &mut stores, &mut hash_chain_store, 1, &1)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_bloom_filter_contains_many() {
+        let values = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        for bloom_filter_type in [BloomFilterType::Standard, BloomFilterType::Counting] {
+            let mut batch = get_test_batch();
+            batch.set_bloom_filter_type(bloom_filter_type);
+            let mut store_0 = vec![0u8; batch.bloom_filter_capacity as usize / 8];
+            let mut store_1 = vec![0u8; batch.bloom_filter_capacity as usize / 8];
+            let mut hash_chain_store =
+                vec![0u8; ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(10)];
+            let mut hash_chain_store =
+                ZeroCopyVecU64::<[u8; 32]>::new(10, &mut hash_chain_store).unwrap();
+            let mut stores = [store_0.as_mut_slice(), store_1.as_mut_slice()];
+            for value in [values[0], values[2]] {
+                batch
+                    .insert(&value, &value, &mut stores, &mut hash_chain_store, 0, &1)
+                    .unwrap();
+            }
+            assert_eq!(
+                batch.bloom_filter_contains_many(&values, &store_0).unwrap(),
+                vec![true, false, true]
+            );
+            assert_eq!(
+                batch.bloom_filter_contains_many(&values, &store_1).unwrap(),
+                vec![false, false, false]
+            );
+            assert_eq!(
+                batch.bloom_filter_contains_many(&values, &store_0[1..]),
+                Err(BatchedMerkleTreeError::BloomFilter(
+                    light_bloom_filter::BloomFilterError::InvalidStoreCapacity
+                ))
+            );
+        }
+    }
+
     #[test]
     fn test_num_ready_zkp_updates() {
         let mut batch = get_test_batch();
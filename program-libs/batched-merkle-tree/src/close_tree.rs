@@ -0,0 +1,98 @@
+use light_compressed_account::pubkey::Pubkey;
+use light_merkle_tree_metadata::errors::MerkleTreeMetadataError;
+
+use crate::{
+    errors::BatchedMerkleTreeError, merkle_tree::BatchedMerkleTreeAccount, msg,
+    queue::BatchedQueueAccount,
+};
+
+/// Check that a rolled over batched state tree
+/// and its output queue can be closed.
+///
+/// 1. Check that Merkle tree and queue are associated.
+/// 2. Check that Merkle tree and queue are rolled over,
+///     and close threshold slots have passed since the rollover.
+/// 3. Check that output queue is empty,
+///     all leaves have been appended to the tree.
+/// 4. Check that input queue is empty,
+///     all nullifiers have been inserted into the tree.
+/// 5. Check that the tree is drained,
+///     every leaf in the tree has been nullified.
+///
+/// Proofs against the closed tree fail since its roots don't exist anymore.
+/// Close threshold needs to be large enough so that clients stopped using
+/// the old tree after the rollover.
+pub fn check_batched_state_tree_is_closable(
+    merkle_tree: &BatchedMerkleTreeAccount<'_>,
+    mt_pubkey: &Pubkey,
+    output_queue: &BatchedQueueAccount<'_>,
+    queue_pubkey: &Pubkey,
+    current_slot: u64,
+) -> Result<(), BatchedMerkleTreeError> {
+    // 1. Check that Merkle tree and queue are associated.
+    if merkle_tree.metadata.associated_queue != *queue_pubkey
+        || output_queue.metadata.associated_merkle_tree != *mt_pubkey
+    {
+        return Err(MerkleTreeMetadataError::MerkleTreeAndQueueNotAssociated.into());
+    }
+    // 2. Check that Merkle tree and queue are rolled over.
+    merkle_tree
+        .metadata
+        .rollover_metadata
+        .check_closable(current_slot)?;
+    output_queue
+        .metadata
+        .rollover_metadata
+        .check_closable(current_slot)?;
+    // 3. Check that output queue is empty.
+    if !output_queue
+        .batch_metadata
+        .all_elements_inserted_into_tree()
+        || output_queue.batch_metadata.next_index != merkle_tree.next_index
+    {
+        msg!("Output queue is not empty.");
+        return Err(BatchedMerkleTreeError::TreeNotEmpty);
+    }
+    // 4. Check that input queue is empty.
+    if !merkle_tree.queue_batches.all_elements_inserted_into_tree() {
+        msg!("Input queue is not empty.");
+        return Err(BatchedMerkleTreeError::TreeNotEmpty);
+    }
+    // 5. Check that every leaf has been nullified.
+    if merkle_tree.queue_batches.next_index != merkle_tree.next_index {
+        #[cfg(not(feature = "pinocchio"))]
+        msg!(
+            "{} of {} leaves are nullified.",
+            merkle_tree.queue_batches.next_index,
+            merkle_tree.next_index
+        );
+        return Err(BatchedMerkleTreeError::TreeNotEmpty);
+    }
+    Ok(())
+}
+
+/// Check that a rolled over batched address tree can be closed.
+///
+/// 1. Check that Merkle tree is rolled over,
+///     and close threshold slots have passed since the rollover.
+/// 2. Check that address queue is empty,
+///     all addresses have been inserted into the tree.
+///
+/// Addresses are derived with the address tree pubkey,
+/// hence no address of the closed tree can be created in another tree.
+pub fn check_batched_address_tree_is_closable(
+    merkle_tree: &BatchedMerkleTreeAccount<'_>,
+    current_slot: u64,
+) -> Result<(), BatchedMerkleTreeError> {
+    // 1. Check that Merkle tree is rolled over.
+    merkle_tree
+        .metadata
+        .rollover_metadata
+        .check_closable(current_slot)?;
+    // 2. Check that address queue is empty.
+    if !merkle_tree.queue_batches.all_elements_inserted_into_tree() {
+        msg!("Address queue is not empty.");
+        return Err(BatchedMerkleTreeError::TreeNotEmpty);
+    }
+    Ok(())
+}
@@ -51,6 +51,12 @@ pub enum BatchedMerkleTreeError {
     NonInclusionCheckFailed,
     #[error("Bloom filter must be zeroed prior to reusing a batch.")]
     BloomFilterNotZeroed,
+    #[error("Batched Merkle tree or queue is not empty.")]
+    TreeNotEmpty,
+    #[error("Operation is not supported by the bloom filter type.")]
+    UnsupportedBloomFilterType,
+    #[error("Values do not match the hash chain of the zkp batch.")]
+    InvalidHashChain,
     #[error("Account error {0}")]
     AccountError(#[from] AccountError),
 }
@@ -70,6 +76,9 @@ impl From<BatchedMerkleTreeError> for u32 {
             BatchedMerkleTreeError::TreeIsFull => 14310,
             BatchedMerkleTreeError::NonInclusionCheckFailed => 14311,
             BatchedMerkleTreeError::BloomFilterNotZeroed => 14312,
+            BatchedMerkleTreeError::TreeNotEmpty => 14313,
+            BatchedMerkleTreeError::UnsupportedBloomFilterType => 14314,
+            BatchedMerkleTreeError::InvalidHashChain => 14315,
             BatchedMerkleTreeError::Hasher(e) => e.into(),
             BatchedMerkleTreeError::ZeroCopy(e) => e.into(),
             BatchedMerkleTreeError::MerkleTreeMetadata(e) => e.into(),
@@ -1,7 +1,9 @@
 use light_account_checks::{checks::check_account_balance_is_rent_exempt, error::AccountError};
 use light_compressed_account::{pubkey::Pubkey, TreeType};
 use light_merkle_tree_metadata::{
-    access::AccessMetadata, fee::compute_rollover_fee, merkle_tree::MerkleTreeMetadata,
+    access::AccessMetadata,
+    fee::{compute_rollover_fee, FeeModel},
+    merkle_tree::MerkleTreeMetadata,
     rollover::RolloverMetadata,
 };
 
@@ -154,7 +156,10 @@ pub fn validate_batched_address_tree_params(params: InitAddressTreeAccountsInstr
     assert!(params.bloom_filter_capacity > 0);
     assert!(params.root_history_capacity > 0);
     assert!(params.input_queue_batch_size > 0);
-    assert_eq!(params.close_threshold, None);
+    // Only rolled over trees can be closed.
+    if params.close_threshold.is_some() {
+        assert!(params.rollover_threshold.is_some());
+    }
     assert_eq!(params.height, DEFAULT_BATCH_ADDRESS_TREE_HEIGHT);
 }
 
@@ -170,6 +175,22 @@ pub fn get_address_merkle_tree_account_size_from_params(
     )
 }
 
+/// Returns the fee model of an address tree.
+/// The rollover fee of the model equals the rollover fee
+/// charged per created address.
+pub fn get_address_tree_fee_model(
+    params: InitAddressTreeAccountsInstructionData,
+    minimum_balance: impl Fn(usize) -> u64,
+) -> FeeModel {
+    FeeModel {
+        rent: minimum_balance(get_address_merkle_tree_account_size_from_params(params)),
+        height: params.height,
+        rollover_threshold: params.rollover_threshold,
+        network_fee: params.network_fee.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
 #[cfg(feature = "test-only")]
 pub mod test_utils {
     pub use super::InitAddressTreeAccountsInstructionData;
@@ -314,9 +335,10 @@ fn test_root_history_capacity_zero() {
 
 #[test]
 #[should_panic]
-fn test_close_threshold_not_none() {
+fn test_close_threshold_without_rollover_threshold() {
     let params = InitAddressTreeAccountsInstructionData {
         close_threshold: Some(10),
+        rollover_threshold: None,
         ..InitAddressTreeAccountsInstructionData::default()
     };
     validate_batched_address_tree_params(params);
@@ -2,13 +2,17 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use light_account_checks::checks::check_account_balance_is_rent_exempt;
 use light_compressed_account::{pubkey::Pubkey, QueueType, TreeType};
 use light_merkle_tree_metadata::{
-    access::AccessMetadata, fee::compute_rollover_fee, merkle_tree::MerkleTreeMetadata,
-    queue::QueueMetadata, rollover::RolloverMetadata,
+    access::AccessMetadata,
+    fee::{compute_rollover_fee, FeeModel, NetworkFeeCurve},
+    merkle_tree::MerkleTreeMetadata,
+    queue::QueueMetadata,
+    rollover::RolloverMetadata,
 };
 
 #[cfg(not(feature = "pinocchio"))]
 use crate::AccountInfoTrait;
 use crate::{
+    batch::BloomFilterType,
     constants::{
         DEFAULT_BATCH_SIZE, DEFAULT_BATCH_STATE_TREE_HEIGHT, DEFAULT_CPI_CONTEXT_ACCOUNT_SIZE,
         DEFAULT_ZKP_BATCH_SIZE,
@@ -20,7 +24,7 @@ use crate::{
 };
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy, BorshDeserialize, BorshSerialize, PartialEq)]
+#[derive(Debug, Clone, Copy, BorshSerialize, PartialEq)]
 pub struct InitStateTreeAccountsInstructionData {
     /// Unchecked identifier of the state tree.
     pub index: u64,
@@ -39,9 +43,79 @@ pub struct InitStateTreeAccountsInstructionData {
     pub root_history_capacity: u32,
     pub network_fee: Option<u64>,
     pub rollover_threshold: Option<u64>,
-    /// Placeholder unimplemented.
+    /// Number of slots after rollover after which the drained tree and queue
+    /// can be closed. None disables closing.
     pub close_threshold: Option<u64>,
     pub height: u32,
+    /// Bloom filter variant of the input queue batches.
+    /// Optional trailing field, end of data means Standard.
+    pub bloom_filter_type: BloomFilterType,
+    /// Network fee curve of the tree and output queue.
+    /// Optional trailing field, end of data means disabled.
+    pub network_fee_curve: NetworkFeeCurve,
+}
+
+impl BorshDeserialize for InitStateTreeAccountsInstructionData {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let index = u64::deserialize_reader(reader)?;
+        let program_owner = Option::<Pubkey>::deserialize_reader(reader)?;
+        let forester = Option::<Pubkey>::deserialize_reader(reader)?;
+        let additional_bytes = u64::deserialize_reader(reader)?;
+        let input_queue_batch_size = u64::deserialize_reader(reader)?;
+        let output_queue_batch_size = u64::deserialize_reader(reader)?;
+        let input_queue_zkp_batch_size = u64::deserialize_reader(reader)?;
+        let output_queue_zkp_batch_size = u64::deserialize_reader(reader)?;
+        let bloom_filter_num_iters = u64::deserialize_reader(reader)?;
+        let bloom_filter_capacity = u64::deserialize_reader(reader)?;
+        let root_history_capacity = u32::deserialize_reader(reader)?;
+        let network_fee = Option::<u64>::deserialize_reader(reader)?;
+        let rollover_threshold = Option::<u64>::deserialize_reader(reader)?;
+        let close_threshold = Option::<u64>::deserialize_reader(reader)?;
+        let height = u32::deserialize_reader(reader)?;
+        // End of data means a standard bloom filter.
+        let bloom_filter_type = {
+            let mut bloom_filter_type = [0u8; 1];
+            if reader.read(&mut bloom_filter_type)? == 0 {
+                BloomFilterType::Standard
+            } else {
+                BloomFilterType::deserialize(&mut bloom_filter_type.as_slice())?
+            }
+        };
+        // End of data means a disabled network fee curve.
+        let network_fee_curve = {
+            let mut min_fee = [0u8; 8];
+            if reader.read(&mut min_fee[..1])? == 0 {
+                NetworkFeeCurve::default()
+            } else {
+                reader.read_exact(&mut min_fee[1..])?;
+                NetworkFeeCurve {
+                    min_fee: u64::from_le_bytes(min_fee),
+                    max_fee: u64::deserialize_reader(reader)?,
+                    low_fill_threshold: u64::deserialize_reader(reader)?,
+                    high_fill_threshold: u64::deserialize_reader(reader)?,
+                }
+            }
+        };
+        Ok(Self {
+            index,
+            program_owner,
+            forester,
+            additional_bytes,
+            input_queue_batch_size,
+            output_queue_batch_size,
+            input_queue_zkp_batch_size,
+            output_queue_zkp_batch_size,
+            bloom_filter_num_iters,
+            bloom_filter_capacity,
+            root_history_capacity,
+            network_fee,
+            rollover_threshold,
+            close_threshold,
+            height,
+            bloom_filter_type,
+            network_fee_curve,
+        })
+    }
 }
 
 impl Default for InitStateTreeAccountsInstructionData {
@@ -59,6 +133,8 @@ impl Default for InitStateTreeAccountsInstructionData {
             height: DEFAULT_BATCH_STATE_TREE_HEIGHT,
             root_history_capacity: (DEFAULT_BATCH_SIZE / DEFAULT_ZKP_BATCH_SIZE * 2) as u32,
             bloom_filter_capacity: DEFAULT_BATCH_SIZE * 8,
+            bloom_filter_type: BloomFilterType::Standard,
+            network_fee_curve: NetworkFeeCurve::default(),
             network_fee: Some(5000),
             rollover_threshold: Some(95),
             close_threshold: None,
@@ -129,6 +205,7 @@ pub fn init_batched_state_merkle_tree_accounts<'a>(
     additional_bytes_rent: u64,
 ) -> Result<BatchedMerkleTreeAccount<'a>, BatchedMerkleTreeError> {
     let height = params.height;
+    params.network_fee_curve.validate()?;
     // Output queue
     {
         let rollover_fee = match params.rollover_threshold {
@@ -156,7 +233,7 @@ pub fn init_batched_state_merkle_tree_accounts<'a>(
             associated_merkle_tree: mt_pubkey,
         };
 
-        BatchedQueueAccount::init(
+        let mut output_queue = BatchedQueueAccount::init(
             output_queue_account_data,
             metadata,
             params.output_queue_batch_size,
@@ -166,6 +243,7 @@ pub fn init_batched_state_merkle_tree_accounts<'a>(
             0,
             output_queue_pubkey,
         )?;
+        output_queue.network_fee_curve = params.network_fee_curve;
     }
     let metadata = MerkleTreeMetadata {
         next_merkle_tree: Pubkey::default(),
@@ -187,7 +265,7 @@ pub fn init_batched_state_merkle_tree_accounts<'a>(
     // A nullifier is inserted when compressed state is spent.
     // Spending compressed state requires proving its inclusion,
     // which needs a root from the tree account.
-    BatchedMerkleTreeAccount::init(
+    let mut merkle_tree = BatchedMerkleTreeAccount::init(
         mt_account_data,
         &mt_pubkey,
         metadata,
@@ -198,7 +276,12 @@ pub fn init_batched_state_merkle_tree_accounts<'a>(
         params.bloom_filter_num_iters,
         params.bloom_filter_capacity,
         TreeType::StateV2,
-    )
+    )?;
+    merkle_tree
+        .queue_batches
+        .set_bloom_filter_type(params.bloom_filter_type);
+    merkle_tree.network_fee_curve = params.network_fee_curve;
+    Ok(merkle_tree)
 }
 
 pub fn validate_batched_tree_params(params: InitStateTreeAccountsInstructionData) {
@@ -231,12 +314,54 @@ pub fn validate_batched_tree_params(params: InitStateTreeAccountsInstructionData
         "Bloom filter capacity must be divisible by 8."
     );
     assert!(params.bloom_filter_capacity > 0);
+    assert!(params.network_fee_curve.validate().is_ok());
+    // Counting bloom filters store 4 bit counters
+    // and need 4x the capacity for the same false positive rate.
+    if params.bloom_filter_type == BloomFilterType::Counting {
+        assert!(
+            params.bloom_filter_capacity
+                >= params.input_queue_batch_size * 8 * light_bloom_filter::COUNTER_BITS
+        );
+    }
     assert!(params.root_history_capacity > 0);
     assert!(params.input_queue_batch_size > 0);
-    assert_eq!(params.close_threshold, None);
+    // Only rolled over trees can be closed.
+    if params.close_threshold.is_some() {
+        assert!(params.rollover_threshold.is_some());
+    }
     assert_eq!(params.height, DEFAULT_BATCH_STATE_TREE_HEIGHT);
 }
 
+/// Returns the fee model of a state tree, its output queue
+/// and the additional bytes account (cpi context).
+/// The rollover fee of the model equals the rollover fee
+/// charged per output compressed account.
+pub fn get_state_tree_fee_model(
+    params: InitStateTreeAccountsInstructionData,
+    minimum_balance: impl Fn(usize) -> u64,
+) -> FeeModel {
+    let queue_account_size = get_output_queue_account_size(
+        params.output_queue_batch_size,
+        params.output_queue_zkp_batch_size,
+    );
+    let mt_account_size = get_merkle_tree_account_size(
+        params.input_queue_batch_size,
+        params.bloom_filter_capacity,
+        params.input_queue_zkp_batch_size,
+        params.root_history_capacity,
+        params.height,
+    );
+    FeeModel {
+        rent: minimum_balance(mt_account_size)
+            + minimum_balance(queue_account_size)
+            + minimum_balance(params.additional_bytes as usize),
+        height: params.height,
+        rollover_threshold: params.rollover_threshold,
+        network_fee: params.network_fee.unwrap_or_default(),
+        network_fee_curve: params.network_fee_curve,
+    }
+}
+
 pub fn match_circuit_size(size: u64) -> bool {
     matches!(size, 10 | 100 | 500 | 1000)
 }
@@ -267,6 +392,8 @@ pub mod test_utils {
                 height: DEFAULT_BATCH_STATE_TREE_HEIGHT,
                 root_history_capacity: 20,
                 bloom_filter_capacity: 20_000 * 8,
+                bloom_filter_type: BloomFilterType::Standard,
+                network_fee_curve: NetworkFeeCurve::default(),
                 network_fee: Some(5000),
                 rollover_threshold: Some(95),
                 close_threshold: None,
@@ -287,6 +414,8 @@ pub mod test_utils {
                 height: DEFAULT_BATCH_STATE_TREE_HEIGHT,
                 root_history_capacity: 20,
                 bloom_filter_capacity: 20_000 * 8,
+                bloom_filter_type: BloomFilterType::Standard,
+                network_fee_curve: NetworkFeeCurve::default(),
                 network_fee: Some(5000),
                 rollover_threshold: Some(95),
                 close_threshold: None,
@@ -307,6 +436,8 @@ pub mod test_utils {
                 height: DEFAULT_BATCH_STATE_TREE_HEIGHT,
                 root_history_capacity: 20,
                 bloom_filter_capacity: 20_000 * 8,
+                bloom_filter_type: BloomFilterType::Standard,
+                network_fee_curve: NetworkFeeCurve::default(),
                 network_fee: Some(5000),
                 rollover_threshold: Some(95),
                 close_threshold: None,
@@ -417,6 +548,7 @@ pub mod test_utils {
         pub queue_pubkey: Pubkey,
         pub height: u32,
         pub network_fee: u64,
+        pub network_fee_curve: NetworkFeeCurve,
     }
 
     impl CreateOutputQueueParams {
@@ -440,6 +572,7 @@ pub mod test_utils {
                 associated_merkle_tree,
                 height: params.height,
                 network_fee: params.network_fee.unwrap_or_default(),
+                network_fee_curve: params.network_fee_curve,
                 queue_pubkey,
             }
         }
@@ -481,6 +614,7 @@ pub mod test_utils {
                 &params.associated_merkle_tree.to_bytes(),
             ),
             hashed_queue_pubkey: hash_to_bn254_field_size_be(&params.queue_pubkey.to_bytes()),
+            network_fee_curve: params.network_fee_curve,
         }
     }
 }
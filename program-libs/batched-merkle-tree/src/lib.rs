@@ -1,5 +1,6 @@
 #![allow(unexpected_cfgs)]
 pub mod batch;
+pub mod close_tree;
 pub mod constants;
 pub mod errors;
 pub mod initialize_address_tree;
@@ -5,9 +5,12 @@ use light_account_checks::{
     discriminator::{Discriminator, DISCRIMINATOR_LEN},
 };
 use light_compressed_account::{
-    hash_chain::create_hash_chain_from_array, hash_to_bn254_field_size_be,
-    instruction_data::compressed_proof::CompressedProof, nullifier::create_nullifier,
-    pubkey::Pubkey, QueueType, TreeType, ADDRESS_MERKLE_TREE_TYPE_V2, ADDRESS_QUEUE_TYPE_V2,
+    hash_chain::{create_hash_chain_from_array, create_hash_chain_from_slice},
+    hash_to_bn254_field_size_be,
+    instruction_data::compressed_proof::CompressedProof,
+    nullifier::create_nullifier,
+    pubkey::Pubkey,
+    QueueType, TreeType, ADDRESS_MERKLE_TREE_TYPE_V2, ADDRESS_QUEUE_TYPE_V2,
     INPUT_STATE_QUEUE_TYPE_V2, OUTPUT_STATE_QUEUE_TYPE_V2, STATE_MERKLE_TREE_TYPE_V2,
 };
 use light_hasher::Hasher;
@@ -20,7 +23,8 @@ use light_verifier::{
     verify_batch_address_update, verify_batch_append_with_proofs, verify_batch_update,
 };
 use light_zero_copy::{
-    cyclic_vec::ZeroCopyCyclicVecU64, errors::ZeroCopyError, vec::ZeroCopyVecU64,
+    cyclic_vec::ZeroCopyCyclicVecU64, errors::ZeroCopyError, layout::ZeroCopySection,
+    vec::ZeroCopyVecU64,
 };
 use zerocopy::Ref;
 
@@ -32,9 +36,7 @@ use crate::{
     constants::{ACCOUNT_COMPRESSION_PROGRAM_ID, ADDRESS_TREE_INIT_ROOT_40, NUM_BATCHES},
     errors::BatchedMerkleTreeError,
     merkle_tree_metadata::BatchedMerkleTreeMetadata,
-    queue::{
-        deserialize_bloom_filter_stores, insert_into_current_queue_batch, BatchedQueueAccount,
-    },
+    queue::{insert_into_current_queue_batch, BatchedInputQueueSections, BatchedQueueAccount},
     queue_batch_metadata::QueueBatches,
     AccountInfo, BorshDeserialize, BorshSerialize,
 };
@@ -50,6 +52,17 @@ pub struct InstructionDataBatchNullifyInputs {
     pub compressed_proof: CompressedProof,
 }
 
+/// Nullified compressed account of an input queue zkp batch.
+/// The nullifier Hash(compressed_account_hash, leaf_index, tx_hash)
+/// is part of the hash chain of the zkp batch,
+/// the compressed account hash is inserted into the bloom filter.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, BorshDeserialize, BorshSerialize)]
+pub struct NullifiedValue {
+    pub compressed_account_hash: [u8; 32],
+    pub leaf_index: u64,
+    pub tx_hash: [u8; 32],
+}
+
 /// Public inputs:
 /// 1. old root (last root in root history)
 /// 2. new root (send to chain)
@@ -64,6 +77,17 @@ pub type InstructionDataAddressAppendInputs = InstructionDataBatchNullifyInputs;
 /// 4. start index (get from batch)
 pub type InstructionDataBatchAppendInputs = InstructionDataBatchNullifyInputs;
 
+/// Zero copy sections of a batched Merkle tree account,
+/// which follow the discriminator and metadata:
+/// 1. root history
+/// 2. input or address queue sections
+///
+/// Config: `BatchedMerkleTreeMetadata::sections_config`
+pub type BatchedMerkleTreeSections<'a> = (
+    ZeroCopyCyclicVecU64<'a, [u8; 32]>,
+    BatchedInputQueueSections<'a>,
+);
+
 /// Batched Merkle tree zero copy account.
 /// The account is used to batched state
 /// and address Merkle trees, plus the input and address queues,
@@ -191,25 +215,19 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
             return Err(MerkleTreeMetadataError::InvalidTreeType.into());
         }
 
-        // Merkle tree root history.
-        // Latest root is root_history.last().
-        let (root_history, account_data) = ZeroCopyCyclicVecU64::from_bytes_at(account_data)?;
-
-        // Bloom filter stores for input or address queue.
-        let (bloom_filter_stores, account_data) = deserialize_bloom_filter_stores(
-            metadata.queue_batches.get_bloomfilter_size_bytes(),
-            account_data,
-        );
-
-        // Hash chain stores for input or address queue.
-        let (hash_chain_store_0, account_data) = ZeroCopyVecU64::from_bytes_at(account_data)?;
-        let hash_chain_store_1 = ZeroCopyVecU64::from_bytes(account_data)?;
+        // Merkle tree root history, latest root is root_history.last().
+        // Bloom filter and hash chain stores for input or address queue.
+        let ((root_history, (bloom_filter_stores, hash_chain_stores)), _) =
+            BatchedMerkleTreeSections::section_from_bytes(
+                account_data,
+                &metadata.sections_config(),
+            )?;
         Ok(BatchedMerkleTreeAccount {
             pubkey: *pubkey,
             metadata,
             root_history,
             bloom_filter_stores,
-            hash_chain_stores: [hash_chain_store_0, hash_chain_store_1],
+            hash_chain_stores,
         })
     }
 
@@ -263,10 +281,11 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
             return Err(ZeroCopyError::Size.into());
         }
 
-        let (mut root_history, account_data) = ZeroCopyCyclicVecU64::new_at(
-            account_metadata.root_history_capacity as u64,
-            account_data,
-        )?;
+        let ((mut root_history, (bloom_filter_stores, hash_chain_stores)), _) =
+            BatchedMerkleTreeSections::init_section(
+                account_data,
+                &account_metadata.sections_config(),
+            )?;
 
         // Initialize root history with zero bytes to enable
         // unified logic to zero out roots.
@@ -315,24 +334,12 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
             );
         }
 
-        let (bloom_filter_stores, account_data) = crate::queue::deserialize_bloom_filter_stores(
-            account_metadata.queue_batches.get_bloomfilter_size_bytes(),
-            account_data,
-        );
-        let (hash_chain_store_0, account_data) = ZeroCopyVecU64::new_at(
-            account_metadata.queue_batches.get_num_zkp_batches(),
-            account_data,
-        )?;
-        let hash_chain_store_1 = ZeroCopyVecU64::new(
-            account_metadata.queue_batches.get_num_zkp_batches(),
-            account_data,
-        )?;
         Ok(BatchedMerkleTreeAccount {
             pubkey: *pubkey,
             metadata: account_metadata,
             root_history,
             bloom_filter_stores,
-            hash_chain_stores: [hash_chain_store_0, hash_chain_store_1],
+            hash_chain_stores,
         })
     }
 
@@ -698,6 +705,69 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
         Ok(())
     }
 
+    /// Remove the nullified values of the next inserted zkp batch
+    /// from the counting bloom filter of a batch.
+    /// Removing all zkp batches of a batch replaces
+    /// zeroing out its bloom filter in zero_out_previous_batch_bloom_filter.
+    /// Removing values is optional, light foresters don't send nullified
+    /// values and rely on zero_out_previous_batch_bloom_filter.
+    /// 1. Check that the tree is a state tree.
+    /// 2. Check that the values match the hash chain of the zkp batch.
+    /// 3. Remove compressed account hashes from the bloom filter.
+    /// 4. Zero out roots if a root exists in root history
+    ///     which allows to prove inclusion of a removed value.
+    /// 5. Returns the index of the removed zkp batch.
+    pub fn remove_nullified_values_from_bloom_filter(
+        &mut self,
+        batch_index: usize,
+        nullified_values: &[NullifiedValue],
+    ) -> Result<u64, BatchedMerkleTreeError> {
+        // 1. Check that the tree is a state tree.
+        if self.tree_type != TreeType::StateV2 as u64 {
+            return Err(MerkleTreeMetadataError::InvalidTreeType.into());
+        }
+        let batch = self
+            .queue_batches
+            .batches
+            .get(batch_index)
+            .ok_or(BatchedMerkleTreeError::InvalidBatchIndex)?;
+        // 2. Check that the values match the hash chain of the zkp batch.
+        {
+            let zkp_batch_index = batch.get_num_removed_zkp_batches() as usize;
+            let hash_chain = self.hash_chain_stores[batch_index]
+                .get(zkp_batch_index)
+                .ok_or(BatchedMerkleTreeError::BatchNotReady)?;
+            let nullifiers = nullified_values
+                .iter()
+                .map(|value| {
+                    create_nullifier(
+                        &value.compressed_account_hash,
+                        value.leaf_index,
+                        &value.tx_hash,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if create_hash_chain_from_slice(nullifiers.as_slice())? != *hash_chain {
+                return Err(BatchedMerkleTreeError::InvalidHashChain);
+            }
+        }
+        // 3. Remove compressed account hashes from the bloom filter.
+        let compressed_account_hashes = nullified_values
+            .iter()
+            .map(|value| value.compressed_account_hash)
+            .collect::<Vec<_>>();
+        let batch = &mut self.metadata.queue_batches.batches[batch_index];
+        let zkp_batch_index = batch.remove_zkp_batch_from_bloom_filter(
+            compressed_account_hashes.as_slice(),
+            self.bloom_filter_stores[batch_index],
+        )?;
+        // 4. Zero out roots which allow to prove inclusion of removed values.
+        let seq = batch.sequence_number;
+        let root_index = batch.root_index;
+        self.zero_out_roots(seq, root_index);
+        Ok(zkp_batch_index)
+    }
+
     /// Zero out roots corresponding to batch.sequence numbers > tree.sequence_number.
     /// The batch.sequence_number indicates when roots no longer contain values
     /// from the queue's previous batch, as they've been overwritten by newer updates.
@@ -785,6 +855,10 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
     /// 3. Wait until next batch is 50% full as grace period for clients
     ///     to switch from proof by index to proof by zkp
     ///     for values inserted in the previous batch.
+    /// 4. Counting bloom filters whose nullified values have all been removed
+    ///     with remove_nullified_values_from_bloom_filter are already marked
+    ///     as zeroed and skipped. Otherwise they are zeroed out here
+    ///     like standard bloom filters.
     ///
     /// Steps:
     /// 1. Previous batch must be inserted and bloom filter must not be zeroed out.
@@ -870,6 +944,48 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
         &mut self.metadata
     }
 
+    /// Returns for every value whether it is contained
+    /// in a bloom filter of the input or address queue.
+    /// Clients can use it to check non-inclusion of nullifiers
+    /// or addresses before building a transaction.
+    pub fn bloom_filters_contain_many(
+        &self,
+        values: &[[u8; 32]],
+    ) -> Result<Vec<bool>, BatchedMerkleTreeError> {
+        bloom_filters_contain_many(
+            &self.queue_batches.batches,
+            [&*self.bloom_filter_stores[0], &*self.bloom_filter_stores[1]],
+            values,
+        )
+    }
+
+    /// Returns for every value whether it is contained
+    /// in a bloom filter of the input or address queue
+    /// without deserializing the account mutably.
+    /// Should only be used in client,
+    /// e.g. with fetched account data.
+    /// Checks the discriminator.
+    #[cfg(not(target_os = "solana"))]
+    pub fn bloom_filters_contain_many_from_bytes(
+        account_data: &[u8],
+        values: &[[u8; 32]],
+    ) -> Result<Vec<bool>, BatchedMerkleTreeError> {
+        light_account_checks::checks::check_discriminator::<Self>(account_data)?;
+        let (_discriminator, account_data) = account_data.split_at(DISCRIMINATOR_LEN);
+        let (metadata, account_data) =
+            Ref::<&[u8], BatchedMerkleTreeMetadata>::from_prefix(account_data)
+                .map_err(ZeroCopyError::from)?;
+        // Bloom filter stores follow the root history.
+        let (root_history_capacity, (bloom_filter_sizes, _)) = metadata.sections_config();
+        let offset = ZeroCopyCyclicVecU64::<[u8; 32]>::section_size(&root_history_capacity);
+        let size = <[&mut [u8]; NUM_BATCHES]>::section_size(&bloom_filter_sizes);
+        let account_data = account_data
+            .get(offset..offset + size)
+            .ok_or(ZeroCopyError::Size)?;
+        let (store_0, store_1) = account_data.split_at(bloom_filter_sizes[0]);
+        bloom_filters_contain_many(&metadata.queue_batches.batches, [store_0, store_1], values)
+    }
+
     /// Check non-inclusion in all bloom filters
     /// which are not zeroed.
     pub fn check_input_queue_non_inclusion(
@@ -877,12 +993,8 @@ impl<'a> BatchedMerkleTreeAccount<'a> {
         value: &[u8; 32],
     ) -> Result<(), BatchedMerkleTreeError> {
         for i in 0..self.queue_batches.num_batches as usize {
-            Batch::check_non_inclusion(
-                self.queue_batches.batches[i].num_iters as usize,
-                self.queue_batches.batches[i].bloom_filter_capacity,
-                value,
-                self.bloom_filter_stores[i],
-            )?;
+            self.queue_batches.batches[i]
+                .check_value_non_inclusion(value, self.bloom_filter_stores[i])?;
         }
         Ok(())
     }
@@ -948,6 +1060,22 @@ impl DerefMut for BatchedMerkleTreeAccount<'_> {
     }
 }
 
+fn bloom_filters_contain_many(
+    batches: &[Batch; 2],
+    stores: [&[u8]; 2],
+    values: &[[u8; 32]],
+) -> Result<Vec<bool>, BatchedMerkleTreeError> {
+    let mut contained = vec![false; values.len()];
+    for (batch, store) in batches.iter().zip(stores) {
+        let batch_contained = batch.bloom_filter_contains_many(values, store)?;
+        contained
+            .iter_mut()
+            .zip(batch_contained)
+            .for_each(|(contained, batch_contained)| *contained |= batch_contained);
+    }
+    Ok(contained)
+}
+
 pub fn get_merkle_tree_account_size(
     batch_size: u64,
     bloom_filter_capacity: u64,
@@ -1065,7 +1193,9 @@ mod test {
     use rand::{Rng, SeedableRng};
 
     use super::*;
-    use crate::merkle_tree::test_utils::get_merkle_tree_account_size_default;
+    use crate::{
+        batch::BloomFilterType, merkle_tree::test_utils::get_merkle_tree_account_size_default,
+    };
 
     #[test]
     fn test_from_bytes_invalid_tree_type() {
@@ -1656,6 +1786,212 @@ mod test {
         }
     }
 
+    /// 1. Removing values of a batch which is not inserted fails.
+    /// 2. Values which do not match the hash chain fail.
+    /// 3. Remove zkp batches of the inserted batch 0 in order,
+    ///     removed values are not contained anymore.
+    /// 4. Once all zkp batches are removed the bloom filter
+    ///     is zeroed and marked as zeroed,
+    ///     roots preceding the batch insertion are zeroed.
+    /// 5. Removing values again fails.
+    /// 6. Batch 1 is half full -> the bloom filter of batch 0
+    ///     is not zeroed out again.
+    #[test]
+    fn test_remove_nullified_values_from_bloom_filter() {
+        let batch_size = 4;
+        let zkp_batch_size = 2;
+        let num_zkp_updates = batch_size / zkp_batch_size;
+        let root_history_len = 10;
+        let bloom_filter_capacity = 8000;
+        let height = 32;
+        let current_slot = 1;
+        let pubkey = Pubkey::new_unique();
+        let mut account_data = vec![
+            0u8;
+            get_merkle_tree_account_size(
+                batch_size,
+                bloom_filter_capacity,
+                zkp_batch_size,
+                root_history_len,
+                height,
+            )
+        ];
+        let mut account = BatchedMerkleTreeAccount::init(
+            &mut account_data,
+            &pubkey,
+            MerkleTreeMetadata::default(),
+            root_history_len,
+            batch_size,
+            zkp_batch_size,
+            height,
+            1,
+            bloom_filter_capacity,
+            TreeType::StateV2,
+        )
+        .unwrap();
+        account
+            .queue_batches
+            .set_bloom_filter_type(BloomFilterType::Counting);
+        let rng = &mut rand::rngs::StdRng::from_seed([0u8; 32]);
+        // Compressed account hashes are smaller than the field size.
+        let mut rnd_hash = || {
+            let mut hash = [0u8; 32];
+            hash[1..].copy_from_slice(&rng.gen::<[u8; 31]>());
+            hash
+        };
+        let mut nullified_values = vec![];
+        for i in 0..batch_size {
+            let value = NullifiedValue {
+                compressed_account_hash: rnd_hash(),
+                leaf_index: i,
+                tx_hash: [1u8; 32],
+            };
+            account
+                .insert_nullifier_into_queue(
+                    &value.compressed_account_hash,
+                    value.leaf_index,
+                    &value.tx_hash,
+                    &current_slot,
+                )
+                .unwrap();
+            nullified_values.push(value);
+        }
+        let zkp_batches = nullified_values
+            .chunks(zkp_batch_size as usize)
+            .collect::<Vec<_>>();
+        // 1. Removing values of a batch which is not inserted fails.
+        assert_eq!(
+            account.remove_nullified_values_from_bloom_filter(0, zkp_batches[0]),
+            Err(BatchedMerkleTreeError::BatchNotReady)
+        );
+        // Simulate batch insertion.
+        let mut latest_root = [0u8; 32];
+        for _ in 0..num_zkp_updates {
+            latest_root = rnd_hash();
+            account.root_history.push(latest_root);
+            account.metadata.sequence_number += 1;
+            let root_index = account.get_root_index();
+            let sequence_number = account.sequence_number;
+            let state = account.queue_batches.batches[0]
+                .mark_as_inserted_in_merkle_tree(sequence_number, root_index, root_history_len)
+                .unwrap();
+            account
+                .queue_batches
+                .increment_pending_batch_index_if_inserted(state);
+        }
+        assert_eq!(account.queue_batches.pending_batch_index, 1);
+        // 2. Values which do not match the hash chain fail.
+        {
+            let mut invalid_values = zkp_batches[0].to_vec();
+            invalid_values[0].tx_hash = [2u8; 32];
+            assert_eq!(
+                account.remove_nullified_values_from_bloom_filter(0, &invalid_values),
+                Err(BatchedMerkleTreeError::InvalidHashChain)
+            );
+            assert_eq!(
+                account.remove_nullified_values_from_bloom_filter(0, zkp_batches[1]),
+                Err(BatchedMerkleTreeError::InvalidHashChain)
+            );
+        }
+        // 3. Remove zkp batches in order.
+        for (zkp_batch_index, values) in zkp_batches.iter().enumerate() {
+            assert!(!account.queue_batches.batches[0].bloom_filter_is_zeroed());
+            let removed_zkp_batch_index = account
+                .remove_nullified_values_from_bloom_filter(0, values)
+                .unwrap();
+            assert_eq!(removed_zkp_batch_index, zkp_batch_index as u64);
+            let hashes = nullified_values
+                .iter()
+                .map(|value| value.compressed_account_hash)
+                .collect::<Vec<_>>();
+            let contained = account.bloom_filters_contain_many(&hashes).unwrap();
+            for (i, contained) in contained.iter().enumerate() {
+                let is_removed = i < (zkp_batch_index + 1) * zkp_batch_size as usize;
+                assert_eq!(*contained, !is_removed);
+            }
+        }
+        // 4. Bloom filter is zeroed, roots preceding the insertion are zeroed.
+        let batch = account.queue_batches.batches[0];
+        assert!(batch.bloom_filter_is_zeroed());
+        assert_eq!(batch.get_num_removed_zkp_batches(), num_zkp_updates);
+        assert!(account.bloom_filter_stores[0].iter().all(|x| *x == 0));
+        for i in 0..root_history_len as usize {
+            if i == batch.root_index as usize {
+                assert_eq!(account.root_history[i], latest_root);
+            } else {
+                assert_eq!(account.root_history[i], [0u8; 32]);
+            }
+        }
+        // 5. Removing values again fails.
+        assert_eq!(
+            account.remove_nullified_values_from_bloom_filter(0, zkp_batches[0]),
+            Err(BatchedMerkleTreeError::BatchNotReady)
+        );
+        // 6. Batch 1 is half full -> bloom filter 0 is not zeroed out again.
+        for i in 0..batch_size / 2 {
+            account
+                .insert_nullifier_into_queue(&rnd_hash(), batch_size + i, &[1u8; 32], &current_slot)
+                .unwrap();
+        }
+        let account_data_ref = account_data.clone();
+        let mut account =
+            BatchedMerkleTreeAccount::state_from_bytes(&mut account_data, &pubkey).unwrap();
+        account.zero_out_previous_batch_bloom_filter().unwrap();
+        assert_eq!(account_data, account_data_ref);
+    }
+
+    /// 1. Values are not contained in empty bloom filters.
+    /// 2. Values inserted into batch 0 and 1 are contained.
+    /// 3. Contained values are the same from immutable account bytes.
+    /// 4. Invalid discriminator fails.
+    #[test]
+    fn test_bloom_filters_contain_many() {
+        let mut account_data = vec![0u8; 3248];
+        let batch_size = 5;
+        let bloom_filter_capacity = 8000;
+        let rng = &mut rand::rngs::StdRng::from_seed([0u8; 32]);
+        let values: Vec<[u8; 32]> = (0..batch_size * 3).map(|_| rng.gen()).collect();
+        {
+            let mut account = BatchedMerkleTreeAccount::init(
+                &mut account_data,
+                &Pubkey::new_unique(),
+                MerkleTreeMetadata::default(),
+                10,
+                batch_size,
+                1,
+                40,
+                1,
+                bloom_filter_capacity,
+                TreeType::AddressV2,
+            )
+            .unwrap();
+            // 1. Values are not contained in empty bloom filters.
+            assert_eq!(
+                account.bloom_filters_contain_many(&values).unwrap(),
+                vec![false; values.len()]
+            );
+            // 2. Values inserted into batch 0 and 1 are contained.
+            for value in values.iter().take(batch_size as usize * 2) {
+                account.insert_address_into_queue(value, &1).unwrap();
+            }
+            let contained = account.bloom_filters_contain_many(&values).unwrap();
+            assert!(contained[..batch_size as usize * 2].iter().all(|x| *x));
+            assert!(contained[batch_size as usize * 2..].iter().all(|x| !*x));
+        }
+        // 3. Contained values are the same from immutable account bytes.
+        let contained =
+            BatchedMerkleTreeAccount::bloom_filters_contain_many_from_bytes(&account_data, &values)
+                .unwrap();
+        assert!(contained[..batch_size as usize * 2].iter().all(|x| *x));
+        assert!(contained[batch_size as usize * 2..].iter().all(|x| !*x));
+        // 4. Invalid discriminator fails.
+        account_data[0] = 0;
+        assert!(
+            BatchedMerkleTreeAccount::bloom_filters_contain_many_from_bytes(&account_data, &values)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_tree_is_full() {
         let mut account_data = vec![0u8; 3248];
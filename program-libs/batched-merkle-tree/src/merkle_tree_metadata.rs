@@ -1,13 +1,16 @@
 use aligned_sized::aligned_sized;
-use light_compressed_account::{hash_to_bn254_field_size_be, pubkey::Pubkey, QueueType, TreeType};
+use light_compressed_account::{hash_to_bn254_field_size_be, pubkey::Pubkey, TreeType};
 use light_merkle_tree_metadata::{
-    access::AccessMetadata, fee::compute_rollover_fee, merkle_tree::MerkleTreeMetadata,
+    access::AccessMetadata,
+    fee::{compute_rollover_fee, NetworkFeeCurve},
+    merkle_tree::MerkleTreeMetadata,
     rollover::RolloverMetadata,
 };
-use light_zero_copy::cyclic_vec::ZeroCopyCyclicVecU64;
+use light_zero_copy::layout::ZeroCopySection;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use crate::{
+    batch::BloomFilterType,
     constants::{
         DEFAULT_BATCH_STATE_TREE_HEIGHT, NUM_BATCHES, TEST_DEFAULT_BATCH_SIZE,
         TEST_DEFAULT_ZKP_BATCH_SIZE,
@@ -15,6 +18,7 @@ use crate::{
     errors::BatchedMerkleTreeError,
     initialize_address_tree::InitAddressTreeAccountsInstructionData,
     initialize_state_tree::InitStateTreeAccountsInstructionData,
+    merkle_tree::BatchedMerkleTreeSections,
     queue_batch_metadata::QueueBatches,
     BorshDeserialize, BorshSerialize,
 };
@@ -46,6 +50,9 @@ pub struct BatchedMerkleTreeMetadata {
     /// + 1 byte padding) Merkle tree pubkey.
     pub hashed_pubkey: [u8; 32],
     pub nullifier_next_index: u64,
+    /// Network fee curve keyed on the fill level of the input queue,
+    /// charged when inputs are nullified. Disabled for address trees.
+    pub network_fee_curve: NetworkFeeCurve,
 }
 
 impl Default for BatchedMerkleTreeMetadata {
@@ -68,21 +75,23 @@ impl Default for BatchedMerkleTreeMetadata {
             },
             hashed_pubkey: [0u8; 32],
             nullifier_next_index: 0,
+            network_fee_curve: NetworkFeeCurve::default(),
         }
     }
 }
 
 impl BatchedMerkleTreeMetadata {
-    pub fn get_account_size(&self) -> Result<usize, BatchedMerkleTreeError> {
-        let metadata_size = Self::LEN;
-        let root_history_size = ZeroCopyCyclicVecU64::<[u8; 32]>::required_size_for_capacity(
+    /// Returns the config of the zero copy sections
+    /// which follow the metadata, see `BatchedMerkleTreeSections`.
+    pub fn sections_config(&self) -> (u64, ([usize; NUM_BATCHES], [u64; NUM_BATCHES])) {
+        (
             self.root_history_capacity as u64,
-        );
-        let size = metadata_size
-            + root_history_size
-            + self
-                .queue_batches
-                .queue_account_size(QueueType::InputStateV2 as u64)?;
+            self.queue_batches.input_queue_sections_config(),
+        )
+    }
+
+    pub fn get_account_size(&self) -> Result<usize, BatchedMerkleTreeError> {
+        let size = Self::LEN + BatchedMerkleTreeSections::section_size(&self.sections_config());
         Ok(size)
     }
 
@@ -122,8 +131,23 @@ impl BatchedMerkleTreeMetadata {
             root_history_capacity,
             height,
             num_iters,
+            bloom_filter_type,
+            network_fee_curve,
             tree_pubkey,
         } = params;
+        let mut queue_batches = QueueBatches::new_input_queue(
+            batch_size,
+            bloom_filter_capacity,
+            zkp_batch_size,
+            num_iters,
+            if tree_type == TreeType::AddressV2 {
+                1
+            } else {
+                0
+            },
+        )
+        .unwrap();
+        queue_batches.set_bloom_filter_type(bloom_filter_type);
         Self {
             metadata: MerkleTreeMetadata {
                 next_merkle_tree: Pubkey::default(),
@@ -143,21 +167,11 @@ impl BatchedMerkleTreeMetadata {
             next_index: 0,
             height,
             root_history_capacity,
-            queue_batches: QueueBatches::new_input_queue(
-                batch_size,
-                bloom_filter_capacity,
-                zkp_batch_size,
-                num_iters,
-                if tree_type == TreeType::AddressV2 {
-                    1
-                } else {
-                    0
-                },
-            )
-            .unwrap(),
+            queue_batches,
             capacity: 2u64.pow(height),
             hashed_pubkey: hash_to_bn254_field_size_be(&tree_pubkey.to_bytes()),
             nullifier_next_index: 0,
+            network_fee_curve,
         }
     }
 }
@@ -177,6 +191,8 @@ pub struct CreateTreeParams {
     pub root_history_capacity: u32,
     pub height: u32,
     pub num_iters: u64,
+    pub bloom_filter_type: BloomFilterType,
+    pub network_fee_curve: NetworkFeeCurve,
     pub tree_pubkey: Pubkey,
 }
 impl CreateTreeParams {
@@ -198,6 +214,8 @@ impl CreateTreeParams {
             root_history_capacity: data.root_history_capacity,
             height: data.height,
             num_iters: data.bloom_filter_num_iters,
+            bloom_filter_type: data.bloom_filter_type,
+            network_fee_curve: data.network_fee_curve,
             tree_pubkey,
         }
     }
@@ -220,6 +238,8 @@ impl CreateTreeParams {
             root_history_capacity: data.root_history_capacity,
             height: data.height,
             num_iters: data.bloom_filter_num_iters,
+            bloom_filter_type: BloomFilterType::Standard,
+            network_fee_curve: NetworkFeeCurve::default(),
             tree_pubkey,
         }
     }
@@ -8,8 +8,10 @@ use light_account_checks::{
 use light_compressed_account::{
     hash_to_bn254_field_size_be, pubkey::Pubkey, QueueType, OUTPUT_STATE_QUEUE_TYPE_V2,
 };
-use light_merkle_tree_metadata::{errors::MerkleTreeMetadataError, queue::QueueMetadata};
-use light_zero_copy::{errors::ZeroCopyError, vec::ZeroCopyVecU64};
+use light_merkle_tree_metadata::{
+    errors::MerkleTreeMetadataError, fee::NetworkFeeCurve, queue::QueueMetadata,
+};
+use light_zero_copy::{errors::ZeroCopyError, layout::ZeroCopySection, vec::ZeroCopyVecU64};
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref};
 
 // Import the feature-gated types from lib.rs
@@ -47,6 +49,9 @@ pub struct BatchedQueueMetadata {
     pub tree_capacity: u64,
     pub hashed_merkle_tree_pubkey: [u8; 32],
     pub hashed_queue_pubkey: [u8; 32],
+    /// Network fee curve keyed on the fill level of the queue,
+    /// charged when outputs are inserted.
+    pub network_fee_curve: NetworkFeeCurve,
 }
 
 impl BatchedQueueMetadata {
@@ -84,6 +89,28 @@ impl BatchedQueueMetadata {
     }
 }
 
+/// Zero copy sections of an output queue account,
+/// which follow the discriminator and metadata:
+/// 1. value vecs
+/// 2. hash chain stores
+///
+/// Config: `QueueBatches::output_queue_sections_config`
+pub type BatchedQueueSections<'a> = (
+    [ZeroCopyVecU64<'a, [u8; 32]>; NUM_BATCHES],
+    [ZeroCopyVecU64<'a, [u8; 32]>; NUM_BATCHES],
+);
+
+/// Zero copy sections of an input or address queue,
+/// which are part of the Merkle tree account:
+/// 1. bloom filter stores
+/// 2. hash chain stores
+///
+/// Config: `QueueBatches::input_queue_sections_config`
+pub type BatchedInputQueueSections<'a> = (
+    [&'a mut [u8]; NUM_BATCHES],
+    [ZeroCopyVecU64<'a, [u8; 32]>; NUM_BATCHES],
+);
+
 /// Batched queue zero copy account.
 /// Used for output queues in light protocol.
 /// Output queues store compressed account hashes,
@@ -194,17 +221,16 @@ impl<'a> BatchedQueueAccount<'a> {
             return Err(MerkleTreeMetadataError::InvalidQueueType.into());
         }
 
-        let (value_vec0, account_data) = ZeroCopyVecU64::from_bytes_at(account_data)?;
-        let (value_vec1, account_data) = ZeroCopyVecU64::from_bytes_at(account_data)?;
-
-        let (hash_chain_store0, account_data) = ZeroCopyVecU64::from_bytes_at(account_data)?;
-        let hash_chain_store1 = ZeroCopyVecU64::from_bytes(account_data)?;
+        let ((value_vecs, hash_chain_stores), _) = BatchedQueueSections::section_from_bytes(
+            account_data,
+            &metadata.batch_metadata.output_queue_sections_config(),
+        )?;
 
         Ok(BatchedQueueAccount {
             pubkey,
             metadata,
-            value_vecs: [value_vec0, value_vec1],
-            hash_chain_stores: [hash_chain_store0, hash_chain_store1],
+            value_vecs,
+            hash_chain_stores,
         })
     }
 
@@ -251,20 +277,17 @@ impl<'a> BatchedQueueAccount<'a> {
             return Err(ZeroCopyError::Size.into());
         }
 
-        let value_vec_capacity = account_metadata.batch_metadata.batch_size;
-        let hash_chain_capacity = account_metadata.batch_metadata.get_num_zkp_batches();
-        let (value_vecs_0, account_data) =
-            ZeroCopyVecU64::new_at(value_vec_capacity, account_data)?;
-        let (value_vecs_1, account_data) =
-            ZeroCopyVecU64::new_at(value_vec_capacity, account_data)?;
-        let (hash_chain_0, account_data) =
-            ZeroCopyVecU64::new_at(hash_chain_capacity, account_data)?;
-        let hash_chain_1 = ZeroCopyVecU64::new(hash_chain_capacity, account_data)?;
+        let ((value_vecs, hash_chain_stores), _) = BatchedQueueSections::init_section(
+            account_data,
+            &account_metadata
+                .batch_metadata
+                .output_queue_sections_config(),
+        )?;
         Ok(BatchedQueueAccount {
             pubkey,
             metadata: account_metadata,
-            value_vecs: [value_vecs_0, value_vecs_1],
-            hash_chain_stores: [hash_chain_0, hash_chain_1],
+            value_vecs,
+            hash_chain_stores,
         })
     }
 
@@ -530,16 +553,6 @@ pub(crate) fn insert_into_current_queue_batch(
     Ok(())
 }
 
-#[inline(always)]
-pub(crate) fn deserialize_bloom_filter_stores(
-    bloom_filter_capacity: usize,
-    account_data: &mut [u8],
-) -> ([&mut [u8]; 2], &mut [u8]) {
-    let (slice_1, account_data) = account_data.split_at_mut(bloom_filter_capacity);
-    let (slice_2, account_data) = account_data.split_at_mut(bloom_filter_capacity);
-    ([slice_1, slice_2], account_data)
-}
-
 pub fn get_output_queue_account_size(batch_size: u64, zkp_batch_size: u64) -> usize {
     let metadata = BatchedQueueMetadata {
         metadata: QueueMetadata::default(),
@@ -767,3 +780,29 @@ fn test_pubkey() {
     .unwrap();
     assert_eq!(*account.pubkey(), pubkey);
 }
+
+#[test]
+fn test_from_bytes_section_capacity_mismatch() {
+    let mut account_data = vec![0u8; 1000];
+    let queue_metadata = QueueMetadata {
+        queue_type: QueueType::OutputStateV2 as u64,
+        ..Default::default()
+    };
+    let mut account = BatchedQueueAccount::init(
+        &mut account_data,
+        queue_metadata,
+        4,
+        2,
+        0,
+        0,
+        Pubkey::new_unique(),
+    )
+    .unwrap();
+    // Value vecs are initialized with capacity 4.
+    account.batch_metadata.batch_size = 2;
+    let account = BatchedQueueAccount::output_from_bytes(&mut account_data);
+    assert_eq!(
+        account.unwrap_err(),
+        ZeroCopyError::SectionCapacityMismatch(4, 2).into()
+    );
+}
@@ -1,13 +1,13 @@
 use light_compressed_account::QueueType;
 use light_merkle_tree_metadata::errors::MerkleTreeMetadataError;
-use light_zero_copy::vec::ZeroCopyVecU64;
+use light_zero_copy::layout::ZeroCopySection;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use crate::{
-    batch::{Batch, BatchState},
+    batch::{Batch, BatchState, BloomFilterType},
     constants::NUM_BATCHES,
     errors::BatchedMerkleTreeError,
-    queue::BatchedQueueMetadata,
+    queue::{BatchedInputQueueSections, BatchedQueueMetadata, BatchedQueueSections},
     BorshDeserialize, BorshSerialize,
 };
 
@@ -81,6 +81,64 @@ impl QueueBatches {
         &mut self.batches[self.currently_processing_batch_index as usize]
     }
 
+    /// Returns true if all elements inserted into the queue
+    /// have been inserted into the tree.
+    pub fn all_elements_inserted_into_tree(&self) -> bool {
+        self.batches.iter().all(|batch| {
+            batch.get_num_inserted_elements() == batch.get_num_elements_inserted_into_tree()
+        })
+    }
+
+    /// Returns the percentage of the queue capacity occupied by
+    /// elements which have not been inserted into the tree yet.
+    pub fn get_fill_level(&self) -> u64 {
+        let capacity = self.num_batches * self.batch_size;
+        if capacity == 0 {
+            return 0;
+        }
+        let num_pending_elements: u64 = self
+            .batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .get_num_inserted_elements()
+                    .saturating_sub(batch.get_num_elements_inserted_into_tree())
+            })
+            .sum();
+        num_pending_elements * 100 / capacity
+    }
+
+    /// Returns the slot since which the pending batch has been ready
+    /// to be inserted into the tree, None if there is no such batch.
+    ///
+    /// A full batch is pending until all of its zkp batches are inserted.
+    /// The next batch is only filled once the pending batch is full,
+    /// hence its start slot is a slot at which all zkp batches
+    /// of the pending batch were ready.
+    pub fn get_pending_batch_ready_slot(&self) -> Option<u64> {
+        let pending_batch_index = self.pending_batch_index as usize;
+        let pending_batch = &self.batches[pending_batch_index];
+        if pending_batch.get_state() != BatchState::Full
+            || pending_batch.get_num_ready_zkp_updates() == 0
+        {
+            return None;
+        }
+        let next_batch = &self.batches[(pending_batch_index + 1) % self.batches.len()];
+        // The start slot of an inserted batch is outdated
+        // until the batch is reset with the next insertion.
+        if next_batch.get_state() == BatchState::Inserted || !next_batch.start_slot_is_set() {
+            return None;
+        }
+        Some(next_batch.start_slot)
+    }
+
+    /// Sets the bloom filter type of all batches.
+    pub fn set_bloom_filter_type(&mut self, bloom_filter_type: BloomFilterType) {
+        self.batches
+            .iter_mut()
+            .for_each(|batch| batch.set_bloom_filter_type(bloom_filter_type));
+    }
+
     /// Returns the size of the bloom filter in bytes.
     pub fn get_bloomfilter_size_bytes(&self) -> usize {
         (self.bloom_filter_capacity / 8) as usize
@@ -205,25 +263,38 @@ impl QueueBatches {
         Ok((num_value_stores, num_stores, num_batches))
     }
 
+    /// Returns the config of the input or address queue sections
+    /// of a Merkle tree account, see `BatchedInputQueueSections`.
+    pub fn input_queue_sections_config(&self) -> ([usize; NUM_BATCHES], [u64; NUM_BATCHES]) {
+        let bloom_filter_size = self.get_bloomfilter_size_bytes();
+        let num_zkp_batches = self.get_num_zkp_batches();
+        (
+            [bloom_filter_size; NUM_BATCHES],
+            [num_zkp_batches; NUM_BATCHES],
+        )
+    }
+
+    /// Returns the config of the output queue sections
+    /// of a queue account, see `BatchedQueueSections`.
+    pub fn output_queue_sections_config(&self) -> ([u64; NUM_BATCHES], [u64; NUM_BATCHES]) {
+        let num_zkp_batches = self.get_num_zkp_batches();
+        (
+            [self.batch_size; NUM_BATCHES],
+            [num_zkp_batches; NUM_BATCHES],
+        )
+    }
+
     pub fn queue_account_size(&self, queue_type: u64) -> Result<usize, BatchedMerkleTreeError> {
-        let (num_value_vec, num_bloom_filter_stores, num_hash_chain_store) =
-            self.get_size_parameters(queue_type)?;
-        let account_size = if queue_type == QueueType::InputStateV2 as u64 {
-            // Input queue is part of the Merkle tree account.
-            0
-        } else {
+        let size = if queue_type == QueueType::OutputStateV2 as u64 {
             // Output queue is a separate account.
             BatchedQueueMetadata::LEN
+                + BatchedQueueSections::section_size(&self.output_queue_sections_config())
+        } else if queue_type == QueueType::InputStateV2 as u64 {
+            // Input queue is part of the Merkle tree account.
+            BatchedInputQueueSections::section_size(&self.input_queue_sections_config())
+        } else {
+            return Err(MerkleTreeMetadataError::InvalidQueueType.into());
         };
-        let value_vecs_size =
-            ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(self.batch_size) * num_value_vec;
-        // Bloomfilter capacity is in bits.
-        let bloom_filter_stores_size = self.get_bloomfilter_size_bytes() * num_bloom_filter_stores;
-        let hash_chain_store_size =
-            ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(self.get_num_zkp_batches())
-                * num_hash_chain_store;
-        let size =
-            account_size + value_vecs_size + bloom_filter_stores_size + hash_chain_store_size;
         Ok(size)
     }
 }
@@ -420,3 +491,56 @@ fn test_get_current_batch_index_and_batch() {
         assert_eq!(previous_batch.start_index, 0);
     }
 }
+
+#[test]
+fn test_get_fill_level() {
+    let mut metadata = QueueBatches::new_output_queue(10, 5).unwrap();
+    assert_eq!(metadata.get_fill_level(), 0);
+    // 10 of 20 elements are pending.
+    metadata.batches[0].num_full_zkp_batches = 2;
+    assert_eq!(metadata.get_fill_level(), 50);
+    // 15 of 20 elements are pending.
+    metadata.batches[1].num_full_zkp_batches = 1;
+    assert_eq!(metadata.get_fill_level(), 75);
+    // Elements inserted into the tree are not pending.
+    metadata.batches[0]
+        .mark_as_inserted_in_merkle_tree(0, 0, 0)
+        .unwrap();
+    assert_eq!(metadata.get_fill_level(), 50);
+    metadata.batches[0]
+        .mark_as_inserted_in_merkle_tree(0, 0, 0)
+        .unwrap();
+    assert_eq!(metadata.get_fill_level(), 25);
+}
+
+#[test]
+fn test_get_pending_batch_ready_slot() {
+    let mut metadata = QueueBatches::new_output_queue(10, 5).unwrap();
+    assert_eq!(metadata.get_pending_batch_ready_slot(), None);
+    metadata.batches[0].set_start_slot(&5);
+    metadata.batches[0].num_full_zkp_batches = 2;
+    metadata.batches[0].advance_state_to_full().unwrap();
+    // The next batch has not been started yet.
+    assert_eq!(metadata.get_pending_batch_ready_slot(), None);
+    metadata.batches[1].set_start_slot(&20);
+    assert_eq!(metadata.get_pending_batch_ready_slot(), Some(20));
+    // Partially inserted batches are still pending.
+    metadata.batches[0]
+        .mark_as_inserted_in_merkle_tree(0, 0, 0)
+        .unwrap();
+    assert_eq!(metadata.get_pending_batch_ready_slot(), Some(20));
+    // Inserted batches are not pending.
+    metadata.batches[0]
+        .mark_as_inserted_in_merkle_tree(0, 0, 0)
+        .unwrap();
+    assert_eq!(metadata.get_pending_batch_ready_slot(), None);
+
+    // The start slot of an inserted next batch is outdated.
+    let mut metadata = QueueBatches::new_output_queue(10, 5).unwrap();
+    metadata.batches[0].num_full_zkp_batches = 2;
+    metadata.batches[0].advance_state_to_full().unwrap();
+    metadata.batches[1].set_start_slot(&1);
+    metadata.batches[1].advance_state_to_full().unwrap();
+    metadata.batches[1].advance_state_to_inserted().unwrap();
+    assert_eq!(metadata.get_pending_batch_ready_slot(), None);
+}
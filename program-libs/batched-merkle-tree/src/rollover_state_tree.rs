@@ -153,7 +153,7 @@ pub fn rollover_batched_state_tree(
         .old_output_queue
         .metadata
         .rollover(params.old_mt_pubkey, params.new_output_queue_pubkey)?;
-    let init_params = InitStateTreeAccountsInstructionData::from(&params);
+    let init_params = InitStateTreeAccountsInstructionData::try_from(&params)?;
     let owner = params.old_merkle_tree.metadata.access_metadata.owner;
 
     // 4. Initialize the new merkle tree and output queue.
@@ -171,10 +171,12 @@ pub fn rollover_batched_state_tree(
     Ok(())
 }
 
-impl From<&RolloverBatchStateTreeParams<'_>> for InitStateTreeAccountsInstructionData {
+impl TryFrom<&RolloverBatchStateTreeParams<'_>> for InitStateTreeAccountsInstructionData {
+    type Error = BatchedMerkleTreeError;
+
     #[inline(always)]
-    fn from(params: &RolloverBatchStateTreeParams<'_>) -> Self {
-        InitStateTreeAccountsInstructionData {
+    fn try_from(params: &RolloverBatchStateTreeParams<'_>) -> Result<Self, Self::Error> {
+        Ok(InitStateTreeAccountsInstructionData {
             index: params.old_merkle_tree.metadata.rollover_metadata.index,
             program_owner: if_equals_none(
                 params
@@ -194,6 +196,9 @@ impl From<&RolloverBatchStateTreeParams<'_>> for InitStateTreeAccountsInstructio
             bloom_filter_capacity: params.old_merkle_tree.queue_batches.bloom_filter_capacity,
             // All num iters are the same.
             bloom_filter_num_iters: params.old_merkle_tree.queue_batches.batches[0].num_iters,
+            bloom_filter_type: params.old_merkle_tree.queue_batches.batches[0]
+                .get_bloom_filter_type()?,
+            network_fee_curve: params.old_merkle_tree.network_fee_curve,
             root_history_capacity: params.old_merkle_tree.root_history_capacity,
             network_fee: params.network_fee,
             rollover_threshold: if_equals_none(
@@ -215,7 +220,7 @@ impl From<&RolloverBatchStateTreeParams<'_>> for InitStateTreeAccountsInstructio
             additional_bytes: params.additional_bytes,
             output_queue_batch_size: params.old_output_queue.batch_metadata.batch_size,
             output_queue_zkp_batch_size: params.old_output_queue.batch_metadata.zkp_batch_size,
-        }
+        })
     }
 }
 
@@ -0,0 +1,279 @@
+#![cfg(feature = "test-only")]
+use light_batched_merkle_tree::{
+    close_tree::{check_batched_address_tree_is_closable, check_batched_state_tree_is_closable},
+    errors::BatchedMerkleTreeError,
+    initialize_address_tree::{
+        init_batched_address_merkle_tree_account, InitAddressTreeAccountsInstructionData,
+    },
+    initialize_state_tree::{
+        init_batched_state_merkle_tree_accounts, InitStateTreeAccountsInstructionData,
+    },
+    merkle_tree::{test_utils::get_merkle_tree_account_size_default, BatchedMerkleTreeAccount},
+    queue::{test_utils::get_output_queue_account_size_default, BatchedQueueAccount},
+};
+use light_compressed_account::pubkey::Pubkey;
+use light_merkle_tree_metadata::errors::MerkleTreeMetadataError;
+
+const CLOSE_THRESHOLD: u64 = 10;
+const ROLLEDOVER_SLOT: u64 = 1;
+
+fn mark_rolled_over(mt_account_data: &mut [u8], mt_pubkey: &Pubkey, queue_data: &mut [u8]) {
+    let mut merkle_tree =
+        BatchedMerkleTreeAccount::state_from_bytes(mt_account_data, mt_pubkey).unwrap();
+    merkle_tree
+        .get_metadata_mut()
+        .metadata
+        .rollover_metadata
+        .rolledover_slot = ROLLEDOVER_SLOT;
+    let mut output_queue = BatchedQueueAccount::output_from_bytes(queue_data).unwrap();
+    output_queue
+        .get_metadata_mut()
+        .metadata
+        .rollover_metadata
+        .rolledover_slot = ROLLEDOVER_SLOT;
+}
+
+fn check_closable(
+    mt_account_data: &mut [u8],
+    mt_pubkey: &Pubkey,
+    queue_data: &mut [u8],
+    queue_pubkey: &Pubkey,
+    current_slot: u64,
+) -> Result<(), BatchedMerkleTreeError> {
+    let merkle_tree =
+        BatchedMerkleTreeAccount::state_from_bytes(mt_account_data, mt_pubkey).unwrap();
+    let output_queue = BatchedQueueAccount::output_from_bytes(queue_data).unwrap();
+    check_batched_state_tree_is_closable(
+        &merkle_tree,
+        mt_pubkey,
+        &output_queue,
+        queue_pubkey,
+        current_slot,
+    )
+}
+
+/// Test close of state tree
+/// 1. failing: close threshold not set
+/// 2. failing: not rolled over
+/// 3. failing: close threshold slots have not passed
+/// 4. failing: tree and queue not associated
+/// 5. failing: output queue is not empty
+/// 6. failing: input queue is not empty
+/// 7. failing: tree is not drained
+/// 8. functional: close state tree
+#[test]
+fn test_close_state_tree() {
+    let owner = Pubkey::new_unique();
+    let mt_pubkey = Pubkey::new_unique();
+    let queue_pubkey = Pubkey::new_unique();
+    let current_slot = ROLLEDOVER_SLOT + CLOSE_THRESHOLD + 1;
+    let init = |params: InitStateTreeAccountsInstructionData| {
+        let mut mt_account_data = vec![0; get_merkle_tree_account_size_default()];
+        let mut queue_account_data = vec![0; get_output_queue_account_size_default()];
+        init_batched_state_merkle_tree_accounts(
+            owner,
+            params,
+            &mut queue_account_data,
+            queue_pubkey,
+            1_000_000_001,
+            &mut mt_account_data,
+            mt_pubkey,
+            1_000_000_000,
+            1_000_000_002,
+        )
+        .unwrap();
+        (mt_account_data, queue_account_data)
+    };
+    let params = InitStateTreeAccountsInstructionData {
+        close_threshold: Some(CLOSE_THRESHOLD),
+        ..InitStateTreeAccountsInstructionData::test_default()
+    };
+    // 1. failing: close threshold not set
+    {
+        let (mut mt_account_data, mut queue_account_data) =
+            init(InitStateTreeAccountsInstructionData::test_default());
+        mark_rolled_over(&mut mt_account_data, &mt_pubkey, &mut queue_account_data);
+        let result = check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot,
+        );
+        assert_eq!(
+            result,
+            Err(MerkleTreeMetadataError::CloseNotConfigured.into())
+        );
+    }
+    let (mut mt_account_data, mut queue_account_data) = init(params);
+    // 2. failing: not rolled over
+    {
+        let result = check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot,
+        );
+        assert_eq!(result, Err(MerkleTreeMetadataError::NotRolledOver.into()));
+    }
+    mark_rolled_over(&mut mt_account_data, &mt_pubkey, &mut queue_account_data);
+    // 3. failing: close threshold slots have not passed
+    {
+        let result = check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot - 1,
+        );
+        assert_eq!(
+            result,
+            Err(MerkleTreeMetadataError::NotReadyForClose.into())
+        );
+    }
+    // 4. failing: tree and queue not associated
+    {
+        let result = check_closable(
+            &mut mt_account_data.clone(),
+            &mt_pubkey,
+            &mut queue_account_data.clone(),
+            &Pubkey::new_unique(),
+            current_slot,
+        );
+        assert_eq!(
+            result,
+            Err(MerkleTreeMetadataError::MerkleTreeAndQueueNotAssociated.into())
+        );
+    }
+    // 5. failing: output queue is not empty
+    {
+        let mut queue_account_data = queue_account_data.clone();
+        BatchedQueueAccount::output_from_bytes(&mut queue_account_data)
+            .unwrap()
+            .insert_into_current_batch(&[1u8; 32], &current_slot)
+            .unwrap();
+        let result = check_closable(
+            &mut mt_account_data.clone(),
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot,
+        );
+        assert_eq!(result, Err(BatchedMerkleTreeError::TreeNotEmpty));
+    }
+    // 6. failing: input queue is not empty
+    {
+        let mut mt_account_data = mt_account_data.clone();
+        BatchedMerkleTreeAccount::state_from_bytes(&mut mt_account_data, &mt_pubkey)
+            .unwrap()
+            .insert_nullifier_into_queue(&[1u8; 32], 0, &[2u8; 32], &current_slot)
+            .unwrap();
+        let result = check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data.clone(),
+            &queue_pubkey,
+            current_slot,
+        );
+        assert_eq!(result, Err(BatchedMerkleTreeError::TreeNotEmpty));
+    }
+    // 7. failing: tree is not drained
+    {
+        let mut mt_account_data = mt_account_data.clone();
+        let mut queue_account_data = queue_account_data.clone();
+        BatchedMerkleTreeAccount::state_from_bytes(&mut mt_account_data, &mt_pubkey)
+            .unwrap()
+            .get_metadata_mut()
+            .next_index = 1;
+        BatchedQueueAccount::output_from_bytes(&mut queue_account_data)
+            .unwrap()
+            .get_metadata_mut()
+            .batch_metadata
+            .next_index = 1;
+        let result = check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot,
+        );
+        assert_eq!(result, Err(BatchedMerkleTreeError::TreeNotEmpty));
+    }
+    // 8. functional: close state tree
+    {
+        check_closable(
+            &mut mt_account_data,
+            &mt_pubkey,
+            &mut queue_account_data,
+            &queue_pubkey,
+            current_slot,
+        )
+        .unwrap();
+    }
+}
+
+/// Test close of address tree
+/// 1. failing: not rolled over
+/// 2. failing: close threshold slots have not passed
+/// 3. failing: address queue is not empty
+/// 4. functional: close address tree
+#[test]
+fn test_close_address_tree() {
+    let owner = Pubkey::new_unique();
+    let mt_pubkey = Pubkey::new_unique();
+    let current_slot = ROLLEDOVER_SLOT + CLOSE_THRESHOLD + 1;
+    let params = InitAddressTreeAccountsInstructionData {
+        close_threshold: Some(CLOSE_THRESHOLD),
+        ..InitAddressTreeAccountsInstructionData::test_default()
+    };
+    let mut mt_account_data = vec![0; get_merkle_tree_account_size_default()];
+    init_batched_address_merkle_tree_account(
+        owner,
+        params,
+        &mut mt_account_data,
+        1_000_000_000,
+        mt_pubkey,
+    )
+    .unwrap();
+    // 1. failing: not rolled over
+    {
+        let merkle_tree =
+            BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &mt_pubkey).unwrap();
+        let result = check_batched_address_tree_is_closable(&merkle_tree, current_slot);
+        assert_eq!(result, Err(MerkleTreeMetadataError::NotRolledOver.into()));
+    }
+    BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &mt_pubkey)
+        .unwrap()
+        .get_metadata_mut()
+        .metadata
+        .rollover_metadata
+        .rolledover_slot = ROLLEDOVER_SLOT;
+    // 2. failing: close threshold slots have not passed
+    {
+        let merkle_tree =
+            BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &mt_pubkey).unwrap();
+        let result = check_batched_address_tree_is_closable(&merkle_tree, current_slot - 1);
+        assert_eq!(
+            result,
+            Err(MerkleTreeMetadataError::NotReadyForClose.into())
+        );
+    }
+    // 3. failing: address queue is not empty
+    {
+        let mut mt_account_data = mt_account_data.clone();
+        let mut merkle_tree =
+            BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &mt_pubkey).unwrap();
+        merkle_tree
+            .insert_address_into_queue(&[1u8; 32], &current_slot)
+            .unwrap();
+        let result = check_batched_address_tree_is_closable(&merkle_tree, current_slot);
+        assert_eq!(result, Err(BatchedMerkleTreeError::TreeNotEmpty));
+    }
+    // 4. functional: close address tree
+    {
+        let merkle_tree =
+            BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &mt_pubkey).unwrap();
+        check_batched_address_tree_is_closable(&merkle_tree, current_slot).unwrap();
+    }
+}
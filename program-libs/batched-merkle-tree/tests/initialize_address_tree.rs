@@ -2,10 +2,14 @@
 use light_batched_merkle_tree::{
     constants::NUM_BATCHES,
     initialize_address_tree::{
-        init_batched_address_merkle_tree_account, InitAddressTreeAccountsInstructionData,
+        get_address_tree_fee_model, init_batched_address_merkle_tree_account,
+        InitAddressTreeAccountsInstructionData,
     },
     initialize_state_tree::test_utils::assert_address_mt_zero_copy_initialized,
-    merkle_tree::{get_merkle_tree_account_size, test_utils::get_merkle_tree_account_size_default},
+    merkle_tree::{
+        get_merkle_tree_account_size, test_utils::get_merkle_tree_account_size_default,
+        BatchedMerkleTreeAccount,
+    },
     merkle_tree_metadata::{BatchedMerkleTreeMetadata, CreateTreeParams},
 };
 use light_compressed_account::pubkey::Pubkey;
@@ -36,6 +40,47 @@ fn test_account_init() {
     assert_address_mt_zero_copy_initialized(&mut mt_account_data, ref_mt_account, &tree_pubkey);
 }
 
+#[test]
+fn test_fee_model() {
+    let minimum_balance = |size: usize| (size as u64 + 128) * 6960;
+    for params in [
+        InitAddressTreeAccountsInstructionData::test_default(),
+        InitAddressTreeAccountsInstructionData::default(),
+    ] {
+        let tree_pubkey = Pubkey::new_unique();
+        let mt_account_size = get_merkle_tree_account_size(
+            params.input_queue_batch_size,
+            params.bloom_filter_capacity,
+            params.input_queue_zkp_batch_size,
+            params.root_history_capacity,
+            params.height,
+        );
+        let mut mt_account_data = vec![0; mt_account_size];
+        let merkle_tree_rent = minimum_balance(mt_account_size);
+        init_batched_address_merkle_tree_account(
+            Pubkey::new_unique(),
+            params,
+            &mut mt_account_data,
+            merkle_tree_rent,
+            tree_pubkey,
+        )
+        .unwrap();
+
+        let fee_model = get_address_tree_fee_model(params, minimum_balance);
+        assert_eq!(fee_model.rent, merkle_tree_rent);
+        let merkle_tree =
+            BatchedMerkleTreeAccount::address_from_bytes(&mut mt_account_data, &tree_pubkey)
+                .unwrap();
+        let report = fee_model.report(0).unwrap();
+        assert_eq!(
+            report.rollover_fee,
+            merkle_tree.metadata.rollover_metadata.rollover_fee
+        );
+        assert!(report.lifetime_rollover_revenue >= fee_model.rent);
+        assert!(report.break_even_transactions.unwrap() <= report.lifetime_transactions);
+    }
+}
+
 #[test]
 fn test_rnd_account_init() {
     use rand::SeedableRng;
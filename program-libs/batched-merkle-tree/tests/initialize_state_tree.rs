@@ -1,8 +1,10 @@
 #![cfg(feature = "test-only")]
+use borsh::{BorshDeserialize, BorshSerialize};
 use light_batched_merkle_tree::{
+    batch::BloomFilterType,
     constants::NUM_BATCHES,
     initialize_state_tree::{
-        init_batched_state_merkle_tree_accounts,
+        get_state_tree_fee_model, init_batched_state_merkle_tree_accounts,
         test_utils::{
             assert_state_mt_zero_copy_initialized, create_output_queue_account,
             CreateOutputQueueParams, InitStateTreeAccountsInstructionData,
@@ -13,10 +15,11 @@ use light_batched_merkle_tree::{
     queue::{
         get_output_queue_account_size,
         test_utils::{assert_queue_zero_copy_inited, get_output_queue_account_size_default},
-        BatchedQueueMetadata,
+        BatchedQueueAccount, BatchedQueueMetadata,
     },
 };
 use light_compressed_account::pubkey::Pubkey;
+use light_merkle_tree_metadata::fee::NetworkFeeCurve;
 use light_zero_copy::{cyclic_vec::ZeroCopyCyclicVecU64, vec::ZeroCopyVecU64};
 use rand::{rngs::StdRng, Rng};
 #[test]
@@ -80,6 +83,50 @@ fn test_different_parameters() {
     }
 }
 
+#[test]
+fn test_trailing_fields_are_optional() {
+    let params = InitStateTreeAccountsInstructionData {
+        bloom_filter_type: BloomFilterType::Counting,
+        network_fee_curve: NetworkFeeCurve {
+            min_fee: 1,
+            max_fee: 10_000,
+            low_fill_threshold: 20,
+            high_fill_threshold: 80,
+        },
+        ..InitStateTreeAccountsInstructionData::test_default()
+    };
+    let mut bytes = params.try_to_vec().unwrap();
+    assert_eq!(
+        InitStateTreeAccountsInstructionData::try_from_slice(&bytes).unwrap(),
+        params
+    );
+    // Data without the trailing network fee curve.
+    bytes.truncate(bytes.len() - size_of::<NetworkFeeCurve>());
+    assert_eq!(
+        InitStateTreeAccountsInstructionData::try_from_slice(&bytes).unwrap(),
+        InitStateTreeAccountsInstructionData {
+            network_fee_curve: NetworkFeeCurve::default(),
+            ..params
+        }
+    );
+    // Truncated network fee curve.
+    bytes.push(1);
+    assert!(InitStateTreeAccountsInstructionData::try_from_slice(&bytes).is_err());
+    bytes.pop();
+    // Data without the trailing bloom filter type, e.g. from older clients.
+    bytes.pop();
+    assert_eq!(
+        InitStateTreeAccountsInstructionData::try_from_slice(&bytes).unwrap(),
+        InitStateTreeAccountsInstructionData {
+            bloom_filter_type: BloomFilterType::Standard,
+            network_fee_curve: NetworkFeeCurve::default(),
+            ..params
+        }
+    );
+    bytes.push(2);
+    assert!(InitStateTreeAccountsInstructionData::try_from_slice(&bytes).is_err());
+}
+
 #[test]
 fn test_account_init() {
     let owner = Pubkey::new_unique();
@@ -127,6 +174,66 @@ fn test_account_init() {
     assert_state_mt_zero_copy_initialized(&mut mt_account_data, ref_mt_account, &mt_pubkey);
 }
 
+#[test]
+fn test_fee_model() {
+    let minimum_balance = |size: usize| (size as u64 + 128) * 6960;
+    for params in [
+        InitStateTreeAccountsInstructionData::test_default(),
+        InitStateTreeAccountsInstructionData::default(),
+    ] {
+        let owner = Pubkey::new_unique();
+        let queue_account_size = get_output_queue_account_size(
+            params.output_queue_batch_size,
+            params.output_queue_zkp_batch_size,
+        );
+        let mut output_queue_account_data = vec![0; queue_account_size];
+        let output_queue_pubkey = Pubkey::new_unique();
+        let mt_account_size = get_merkle_tree_account_size(
+            params.input_queue_batch_size,
+            params.bloom_filter_capacity,
+            params.input_queue_zkp_batch_size,
+            params.root_history_capacity,
+            params.height,
+        );
+        let mut mt_account_data = vec![0; mt_account_size];
+        let mt_pubkey = Pubkey::new_unique();
+        let merkle_tree_rent = minimum_balance(mt_account_size);
+        let queue_rent = minimum_balance(queue_account_size);
+        let additional_bytes_rent = minimum_balance(params.additional_bytes as usize);
+        init_batched_state_merkle_tree_accounts(
+            owner,
+            params,
+            &mut output_queue_account_data,
+            output_queue_pubkey,
+            queue_rent,
+            &mut mt_account_data,
+            mt_pubkey,
+            merkle_tree_rent,
+            additional_bytes_rent,
+        )
+        .unwrap();
+
+        let fee_model = get_state_tree_fee_model(params, minimum_balance);
+        assert_eq!(
+            fee_model.rent,
+            merkle_tree_rent + queue_rent + additional_bytes_rent
+        );
+        let output_queue =
+            BatchedQueueAccount::output_from_bytes(&mut output_queue_account_data).unwrap();
+        let report = fee_model.report(0).unwrap();
+        assert_eq!(
+            report.rollover_fee,
+            output_queue.metadata.rollover_metadata.rollover_fee
+        );
+        assert_eq!(
+            report.network_fee,
+            output_queue.metadata.rollover_metadata.network_fee
+        );
+        assert!(report.lifetime_rollover_revenue >= fee_model.rent);
+        assert!(report.break_even_transactions.unwrap() <= report.lifetime_transactions);
+    }
+}
+
 #[test]
 fn test_rnd_account_init() {
     use rand::SeedableRng;
@@ -160,6 +267,17 @@ fn test_rnd_account_init() {
             output_queue_zkp_batch_size,
             // 8 bits per byte, divisible by 8 for aligned memory
             bloom_filter_capacity: rng.gen_range(0..100) * 8 * 8,
+            bloom_filter_type: if rng.gen_bool(0.5) {
+                BloomFilterType::Counting
+            } else {
+                BloomFilterType::Standard
+            },
+            network_fee_curve: NetworkFeeCurve {
+                min_fee: rng.gen_range(0..1000),
+                max_fee: rng.gen_range(1000..2000),
+                low_fill_threshold: rng.gen_range(0..50),
+                high_fill_threshold: rng.gen_range(50..=100),
+            },
             network_fee: Some(rng.gen_range(0..1000)),
             rollover_threshold: Some(rng.gen_range(0..100)),
             close_threshold: None,
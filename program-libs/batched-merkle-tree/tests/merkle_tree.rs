@@ -181,7 +181,7 @@ pub fn assert_input_queue_insert(
             inserted_batch_index
         );
         // New value exists in the current batch bloom filter
-        let mut bloom_filter = BloomFilter::new(
+        let bloom_filter = BloomFilter::new(
             merkle_tree_account.queue_batches.batches[inserted_batch_index].num_iters as usize,
             merkle_tree_account.queue_batches.batches[inserted_batch_index].bloom_filter_capacity,
             merkle_tree_account.bloom_filter_stores[inserted_batch_index],
@@ -207,7 +207,7 @@ pub fn assert_input_queue_insert(
         {
             // Skip current batch it is already checked above
             if i != inserted_batch_index {
-                let mut bloom_filter =
+                let bloom_filter =
                     BloomFilter::new(num_iters, bloom_filter_capacity, store).unwrap();
                 assert!(!bloom_filter.contains(insert_value));
             }
@@ -1968,7 +1968,7 @@ async fn test_fill_state_queues_completely() {
                 assert_eq!(post_batch.get_num_inserted_zkp_batch(), 1);
                 let bloom_filter_store =
                     merkle_tree_account.bloom_filter_stores.get_mut(0).unwrap();
-                let mut bloom_filter = BloomFilter::new(
+                let bloom_filter = BloomFilter::new(
                     params.bloom_filter_num_iters as usize,
                     params.bloom_filter_capacity,
                     bloom_filter_store,
@@ -1,5 +1,6 @@
 #![cfg(feature = "test-only")]
 use light_batched_merkle_tree::{
+    batch::BloomFilterType,
     errors::BatchedMerkleTreeError,
     initialize_state_tree::{
         init_batched_state_merkle_tree_accounts,
@@ -26,7 +27,7 @@ use light_batched_merkle_tree::{
     },
 };
 use light_compressed_account::pubkey::Pubkey;
-use light_merkle_tree_metadata::errors::MerkleTreeMetadataError;
+use light_merkle_tree_metadata::{errors::MerkleTreeMetadataError, fee::NetworkFeeCurve};
 use light_zero_copy::errors::ZeroCopyError;
 use rand::{
     rngs::{StdRng, ThreadRng},
@@ -537,6 +538,12 @@ fn test_rnd_rollover() {
             output_queue_zkp_batch_size,
             // 8 bits per byte, divisible by 8 for aligned memory
             bloom_filter_capacity: rng.gen_range(0..100) * 8 * 8,
+            bloom_filter_type: if rng.gen_bool(0.5) {
+                BloomFilterType::Counting
+            } else {
+                BloomFilterType::Standard
+            },
+            network_fee_curve: NetworkFeeCurve::default(),
             network_fee,
             rollover_threshold: Some(rng.gen_range(0..100)),
             close_threshold: None,
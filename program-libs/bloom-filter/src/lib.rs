@@ -8,6 +8,8 @@ pub enum BloomFilterError {
     Full,
     #[error("Invalid store capacity")]
     InvalidStoreCapacity,
+    #[error("Value not found in bloom filter")]
+    ValueNotFound,
 }
 
 impl From<BloomFilterError> for u32 {
@@ -15,6 +17,7 @@ impl From<BloomFilterError> for u32 {
         match e {
             BloomFilterError::Full => 14201,
             BloomFilterError::InvalidStoreCapacity => 14202,
+            BloomFilterError::ValueNotFound => 14203,
         }
     }
 }
@@ -52,6 +55,14 @@ impl<'a> BloomFilter<'a> {
         k.ceil() as usize
     }
 
+    /// Returns the expected false positive rate
+    /// of a bloom filter with m bits and k hash functions
+    /// after n insertions, (1 - e^(-kn/m))^k.
+    pub fn calculate_false_positive_rate(n: usize, m: usize, k: usize) -> f64 {
+        let exponent = -((k * n) as f64) / m as f64;
+        (1.0 - exponent.exp()).powi(k as i32)
+    }
+
     pub fn new(
         num_iters: usize,
         capacity: u64,
@@ -87,9 +98,13 @@ impl<'a> BloomFilter<'a> {
         }
     }
 
-    // TODO: reconsider &mut self
-    pub fn contains(&mut self, value: &[u8; 32]) -> bool {
-        !self._insert(value, false)
+    pub fn contains(&self, value: &[u8; 32]) -> bool {
+        BloomFilterRef {
+            num_iters: self.num_iters,
+            capacity: self.capacity,
+            store: self.store,
+        }
+        .contains(value)
     }
 
     fn _insert(&mut self, value: &[u8; 32], insert: bool) -> bool {
@@ -112,6 +127,204 @@ impl<'a> BloomFilter<'a> {
     }
 }
 
+/// Read only bloom filter, can be used to check
+/// non-inclusion against immutable account data.
+#[derive(Debug, Clone, Copy)]
+pub struct BloomFilterRef<'a> {
+    pub num_iters: usize,
+    pub capacity: u64,
+    pub store: &'a [u8],
+}
+
+impl<'a> BloomFilterRef<'a> {
+    pub fn new(num_iters: usize, capacity: u64, store: &'a [u8]) -> Result<Self, BloomFilterError> {
+        // Capacity is in bits while store is in bytes.
+        if store.len() * 8 != capacity as usize {
+            return Err(BloomFilterError::InvalidStoreCapacity);
+        }
+        Ok(Self {
+            num_iters,
+            capacity,
+            store,
+        })
+    }
+
+    pub fn contains(&self, value: &[u8; 32]) -> bool {
+        use bitvec::prelude::*;
+
+        let bits = BitSlice::<u8, Msb0>::from_slice(self.store);
+        (0..self.num_iters)
+            .all(|i| bits[BloomFilter::probe_index_fast_murmur(value, i, &self.capacity)])
+    }
+
+    /// Returns for every value whether it is contained.
+    pub fn contains_many(&self, values: &[[u8; 32]]) -> Vec<bool> {
+        values.iter().map(|value| self.contains(value)).collect()
+    }
+}
+
+/// Number of bits per counter of a counting bloom filter.
+pub const COUNTER_BITS: u64 = 4;
+/// Counters saturate at this value and are not decremented anymore.
+const COUNTER_MAX: u8 = (1 << COUNTER_BITS) - 1;
+
+/// Counting bloom filter with 4 bit counters.
+/// In contrast to the bloom filter values can be removed
+/// by decrementing the counters of a value.
+/// Uses the same store as the bloom filter, the capacity is in bits
+/// hence it has capacity / COUNTER_BITS counters.
+#[derive(Debug)]
+pub struct CountingBloomFilter<'a> {
+    pub num_iters: usize,
+    pub capacity: u64,
+    pub store: &'a mut [u8],
+}
+
+impl<'a> CountingBloomFilter<'a> {
+    /// Returns the capacity in bits of a counting bloom filter
+    /// with n values and false positive rate p.
+    pub fn calculate_counting_bloom_filter_size(n: usize, p: f64) -> usize {
+        BloomFilter::calculate_bloom_filter_size(n, p) * COUNTER_BITS as usize
+    }
+
+    /// Returns the expected false positive rate of a counting bloom filter
+    /// with capacity in bits and k hash functions after n insertions.
+    pub fn calculate_false_positive_rate(n: usize, capacity: usize, k: usize) -> f64 {
+        BloomFilter::calculate_false_positive_rate(n, capacity / COUNTER_BITS as usize, k)
+    }
+
+    pub fn new(
+        num_iters: usize,
+        capacity: u64,
+        store: &'a mut [u8],
+    ) -> Result<Self, BloomFilterError> {
+        // Capacity is in bits while store is in bytes.
+        if store.len() * 8 != capacity as usize || capacity < COUNTER_BITS {
+            return Err(BloomFilterError::InvalidStoreCapacity);
+        }
+        Ok(Self {
+            num_iters,
+            capacity,
+            store,
+        })
+    }
+
+    pub fn num_counters(&self) -> u64 {
+        self.capacity / COUNTER_BITS
+    }
+
+    /// Inserts a value by incrementing its counters.
+    /// Fails if the value is already contained.
+    pub fn insert(&mut self, value: &[u8; 32]) -> Result<(), BloomFilterError> {
+        if self.contains(value) {
+            return Err(BloomFilterError::Full);
+        }
+        for i in 0..self.num_iters {
+            let index = self.probe_index(value, i);
+            let counter = self.get_counter(index);
+            if counter < COUNTER_MAX {
+                self.set_counter(index, counter + 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes a value by decrementing its counters.
+    /// Saturated counters are not decremented
+    /// since the number of values they count is unknown.
+    /// Fails if the value is not contained.
+    pub fn remove(&mut self, value: &[u8; 32]) -> Result<(), BloomFilterError> {
+        if !self.contains(value) {
+            return Err(BloomFilterError::ValueNotFound);
+        }
+        for i in 0..self.num_iters {
+            let index = self.probe_index(value, i);
+            let counter = self.get_counter(index);
+            // Counters can reach zero before the last iteration
+            // if the same index is probed more than once.
+            if counter < COUNTER_MAX && counter > 0 {
+                self.set_counter(index, counter - 1);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, value: &[u8; 32]) -> bool {
+        self.as_ref().contains(value)
+    }
+
+    fn as_ref(&self) -> CountingBloomFilterRef<'_> {
+        CountingBloomFilterRef {
+            num_iters: self.num_iters,
+            capacity: self.capacity,
+            store: self.store,
+        }
+    }
+
+    fn probe_index(&self, value: &[u8; 32], iteration: usize) -> usize {
+        self.as_ref().probe_index(value, iteration)
+    }
+
+    fn get_counter(&self, index: usize) -> u8 {
+        get_counter(self.store, index)
+    }
+
+    fn set_counter(&mut self, index: usize, counter: u8) {
+        let byte = &mut self.store[index / 2];
+        if index % 2 == 1 {
+            *byte = (*byte & !COUNTER_MAX) | counter;
+        } else {
+            *byte = (*byte & COUNTER_MAX) | (counter << COUNTER_BITS);
+        }
+    }
+}
+
+/// Read only counting bloom filter.
+#[derive(Debug, Clone, Copy)]
+pub struct CountingBloomFilterRef<'a> {
+    pub num_iters: usize,
+    pub capacity: u64,
+    pub store: &'a [u8],
+}
+
+impl<'a> CountingBloomFilterRef<'a> {
+    pub fn new(num_iters: usize, capacity: u64, store: &'a [u8]) -> Result<Self, BloomFilterError> {
+        // Capacity is in bits while store is in bytes.
+        if store.len() * 8 != capacity as usize || capacity < COUNTER_BITS {
+            return Err(BloomFilterError::InvalidStoreCapacity);
+        }
+        Ok(Self {
+            num_iters,
+            capacity,
+            store,
+        })
+    }
+
+    pub fn contains(&self, value: &[u8; 32]) -> bool {
+        (0..self.num_iters).all(|i| get_counter(self.store, self.probe_index(value, i)) != 0)
+    }
+
+    /// Returns for every value whether it is contained.
+    pub fn contains_many(&self, values: &[[u8; 32]]) -> Vec<bool> {
+        values.iter().map(|value| self.contains(value)).collect()
+    }
+
+    fn probe_index(&self, value: &[u8; 32], iteration: usize) -> usize {
+        BloomFilter::probe_index_fast_murmur(value, iteration, &(self.capacity / COUNTER_BITS))
+    }
+}
+
+/// Even counters are stored in the high,
+/// odd counters in the low 4 bits of a byte.
+fn get_counter(store: &[u8], index: usize) -> u8 {
+    let byte = store[index / 2];
+    if index % 2 == 1 {
+        byte & COUNTER_MAX
+    } else {
+        byte >> COUNTER_BITS
+    }
+}
+
 #[cfg(test)]
 mod test {
     use light_hasher::bigint::bigint_to_be_bytes_array;
@@ -140,6 +353,126 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_bloom_filter_ref_contains_many() -> Result<(), BloomFilterError> {
+        let mut store = [0u8; 1_000];
+        let values = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        {
+            let mut bf = BloomFilter::new(3, 1_000 * 8, &mut store)?;
+            bf.insert(&values[0])?;
+            bf.insert(&values[2])?;
+        }
+        assert_eq!(
+            BloomFilterRef::new(3, 1_000 * 8 + 8, &store).unwrap_err(),
+            BloomFilterError::InvalidStoreCapacity
+        );
+        let bf = BloomFilterRef::new(3, 1_000 * 8, &store)?;
+        assert!(bf.contains(&values[0]));
+        assert!(!bf.contains(&values[1]));
+        assert_eq!(bf.contains_many(&values), vec![true, false, true]);
+        assert_eq!(bf.contains_many(&[]), Vec::<bool>::new());
+
+        let mut store = [0u8; 1_000];
+        {
+            let mut bf = CountingBloomFilter::new(3, 1_000 * 8, &mut store)?;
+            bf.insert(&values[1])?;
+        }
+        let bf = CountingBloomFilterRef::new(3, 1_000 * 8, &store)?;
+        assert_eq!(bf.contains_many(&values), vec![false, true, false]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_counting_insert_contains_and_remove() -> Result<(), BloomFilterError> {
+        let mut store = [0u8; 1_000];
+        let capacity = 1_000 * 8;
+        let mut bf = CountingBloomFilter::new(3, capacity, &mut store)?;
+        assert_eq!(bf.num_counters(), capacity / COUNTER_BITS);
+
+        let value1 = [1u8; 32];
+        let value2 = [2u8; 32];
+
+        bf.insert(&value1)?;
+        assert!(bf.contains(&value1));
+        assert!(!bf.contains(&value2));
+        assert_eq!(bf.insert(&value1), Err(BloomFilterError::Full));
+
+        bf.insert(&value2)?;
+        bf.remove(&value1)?;
+        assert!(!bf.contains(&value1));
+        assert!(bf.contains(&value2));
+        assert_eq!(bf.remove(&value1), Err(BloomFilterError::ValueNotFound));
+
+        bf.remove(&value2)?;
+        assert!(bf.store.iter().all(|x| *x == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_counting_invalid_store_capacity() {
+        let mut store = [0u8; 10];
+        assert_eq!(
+            CountingBloomFilter::new(3, 10 * 8 + 1, &mut store).unwrap_err(),
+            BloomFilterError::InvalidStoreCapacity
+        );
+    }
+
+    #[test]
+    fn test_counting_saturated_counters() -> Result<(), BloomFilterError> {
+        // A single counter, every value increments it.
+        let mut store = [0u8; 1];
+        let mut bf = CountingBloomFilter {
+            num_iters: 1,
+            capacity: COUNTER_BITS,
+            store: &mut store,
+        };
+        bf.set_counter(0, COUNTER_MAX);
+        let value = [1u8; 32];
+        assert!(bf.contains(&value));
+        // Saturated counters are not decremented.
+        bf.remove(&value)?;
+        assert!(bf.contains(&value));
+        assert_eq!(bf.get_counter(0), COUNTER_MAX);
+        Ok(())
+    }
+
+    #[test]
+    fn test_counting_rnd_insert_and_remove() {
+        let mut rng = thread_rng();
+        let num_values = 500;
+        let capacity = CountingBloomFilter::calculate_counting_bloom_filter_size(num_values, 1e-6);
+        let capacity = capacity.next_multiple_of(8);
+        let mut store = vec![0u8; capacity / 8];
+        let mut bf = CountingBloomFilter::new(3, capacity as u64, &mut store).unwrap();
+        let values = (0..num_values)
+            .map(|_| bigint_to_be_bytes_array::<32>(&rng.gen_biguint(254)).unwrap())
+            .collect::<Vec<_>>();
+        for value in values.iter() {
+            bf.insert(value).unwrap();
+        }
+        for value in values.iter() {
+            assert!(bf.contains(value));
+            bf.remove(value).unwrap();
+        }
+        assert!(bf.store.iter().all(|x| *x == 0));
+    }
+
+    #[test]
+    fn test_calculate_false_positive_rate() {
+        let n = 5000;
+        let p = 0.000_000_000_1;
+        let m = BloomFilter::calculate_bloom_filter_size(n, p);
+        let k = BloomFilter::calculate_optimal_hash_functions(n, m);
+        let rate = BloomFilter::calculate_false_positive_rate(n, m, k);
+        assert!(rate <= p * 1.1);
+        let capacity = CountingBloomFilter::calculate_counting_bloom_filter_size(n, p);
+        assert_eq!(capacity, m * COUNTER_BITS as usize);
+        assert_eq!(
+            CountingBloomFilter::calculate_false_positive_rate(n, capacity, k),
+            rate
+        );
+    }
+
     #[test]
     fn short_rnd_test() {
         let capacity = 500;
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use light_compressed_account::instruction_data::zero_copy::ZCompressedAccount;
+use light_zero_copy::borsh::Deserialize;
+
+fuzz_target!(|data: &[u8]| {
+    // Malformed bytes must return an error instead of panicking.
+    if let Ok((_, remaining_bytes)) = ZCompressedAccount::zero_copy_at(data) {
+        assert!(remaining_bytes.len() <= data.len());
+    }
+});
@@ -1,14 +1,12 @@
 use std::collections::HashMap;
 
 use light_hasher::{Hasher, Poseidon};
+use light_zero_copy::ZeroCopy;
 
 use crate::{
-    address::pack_account,
-    hash_to_bn254_field_size_be,
-    instruction_data::{
-        data::OutputCompressedAccountWithPackedContext, zero_copy::ZCompressedAccount,
-    },
-    AnchorDeserialize, AnchorSerialize, CompressedAccountError, Pubkey, TreeType,
+    address::pack_account, hash_to_bn254_field_size_be,
+    instruction_data::data::OutputCompressedAccountWithPackedContext, AnchorDeserialize,
+    AnchorSerialize, CompressedAccountError, Pubkey, TreeType,
 };
 
 #[derive(Debug, PartialEq, Default, Clone, AnchorSerialize, AnchorDeserialize)]
@@ -217,8 +215,9 @@ pub fn pack_merkle_context(
         .collect::<Vec<_>>()
 }
 
-#[derive(Debug, PartialEq, Default, Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, PartialEq, Default, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedAccount {
+    #[zero_copy(ty = crate::pubkey::Pubkey)]
     pub owner: Pubkey,
     pub lamports: u64,
     pub address: Option<[u8; 32]>,
@@ -349,7 +348,7 @@ impl ZCompressedAccount<'_> {
         is_batched: bool,
     ) -> Result<[u8; 32], CompressedAccountError> {
         hash_with_hashed_values(
-            &(self.lamports.into()),
+            &(*self.lamports).into(),
             self.address.as_ref().map(|x| x.as_slice()),
             self.data
                 .as_ref()
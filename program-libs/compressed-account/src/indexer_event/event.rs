@@ -16,6 +16,7 @@ pub struct BatchPublicTransactionEvent {
     pub address_sequence_numbers: Vec<MerkleTreeSequenceNumber>,
     pub tx_hash: [u8; 32],
     pub batch_input_accounts: Vec<BatchNullifyContext>,
+    pub decompression_recipients: Vec<DecompressionRecipient>,
 }
 
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Default, PartialEq)]
@@ -39,6 +40,13 @@ pub struct NewAddress {
     pub queue_index: u64,
 }
 
+/// Lamports decompressed from the sol pool pda to a recipient.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecompressionRecipient {
+    pub recipient: Pubkey,
+    pub lamports: u64,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct BatchNullifyContext {
     pub tx_hash: [u8; 32],
@@ -4,8 +4,8 @@ use light_zero_copy::borsh::Deserialize;
 use super::{
     error::ParseIndexerEventError,
     event::{
-        BatchNullifyContext, BatchPublicTransactionEvent, MerkleTreeSequenceNumber, NewAddress,
-        PublicTransactionEvent,
+        BatchNullifyContext, BatchPublicTransactionEvent, DecompressionRecipient,
+        MerkleTreeSequenceNumber, NewAddress, PublicTransactionEvent,
     },
 };
 use crate::{
@@ -34,6 +34,7 @@ struct ExecutingSystemInstruction<'a> {
     is_compress: bool,
     relay_fee: Option<u64>,
     compress_or_decompress_lamports: Option<u64>,
+    decompression_recipients: Vec<DecompressionRecipient>,
     execute_cpi_context: bool,
     accounts: &'a [Pubkey],
 }
@@ -301,6 +302,7 @@ fn deserialize_instruction<'a>(
                 is_compress: data.is_compress,
                 relay_fee: data.relay_fee,
                 compress_or_decompress_lamports: data.compress_or_decompress_lamports,
+                decompression_recipients: Vec::new(),
                 execute_cpi_context: false,
                 accounts,
             })
@@ -319,6 +321,7 @@ fn deserialize_instruction<'a>(
                 is_compress: data.is_compress,
                 relay_fee: data.relay_fee,
                 compress_or_decompress_lamports: data.compress_or_decompress_lamports,
+                decompression_recipients: Vec::new(),
                 execute_cpi_context: data.cpi_context.is_some(),
                 accounts,
             })
@@ -335,7 +338,9 @@ fn deserialize_instruction<'a>(
                 11
             } else {
                 let mut len = 4;
-                if data.compress_or_decompress_lamports > 0 {
+                if data.compress_or_decompress_lamports > 0
+                    || !data.decompression_recipients.is_empty()
+                {
                     len += 1;
                 }
                 if !data.is_compress && data.compress_or_decompress_lamports > 0 {
@@ -348,6 +353,19 @@ fn deserialize_instruction<'a>(
             };
 
             let accounts = accounts.split_at(system_accounts_len).1;
+            // Recipient indices point to remaining accounts.
+            let decompression_recipients = data
+                .decompression_recipients
+                .iter()
+                .map(|x| {
+                    Ok(DecompressionRecipient {
+                        recipient: *accounts
+                            .get(x.recipient_index as usize)
+                            .ok_or(ParseIndexerEventError::DeserializeSystemInstructionError)?,
+                        lamports: x.lamports,
+                    })
+                })
+                .collect::<Result<Vec<_>, ParseIndexerEventError>>()?;
             Ok(ExecutingSystemInstruction {
                 output_compressed_accounts: data.output_compressed_accounts,
                 input_compressed_accounts: data
@@ -366,6 +384,7 @@ fn deserialize_instruction<'a>(
                 } else {
                     Some(data.compress_or_decompress_lamports)
                 },
+                decompression_recipients,
                 execute_cpi_context: data.with_cpi_context,
                 accounts,
             })
@@ -447,6 +466,7 @@ fn deserialize_instruction<'a>(
                 } else {
                     Some(data.compress_or_decompress_lamports)
                 },
+                decompression_recipients: Vec::new(),
                 execute_cpi_context: data.with_cpi_context,
                 accounts,
             };
@@ -533,6 +553,10 @@ fn create_batched_transaction_event(
             .map(From::from)
             .filter(|x: &MerkleTreeSequenceNumber| !(*x).is_empty())
             .collect::<Vec<MerkleTreeSequenceNumber>>(),
+        decompression_recipients: associated_instructions
+            .executing_system_instruction
+            .decompression_recipients
+            .clone(),
         batch_input_accounts: associated_instructions
             .insert_into_queues_instruction
             .nullifiers
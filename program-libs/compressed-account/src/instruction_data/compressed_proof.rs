@@ -36,6 +36,7 @@ impl Default for CompressedProof {
 
 impl<'a> Deserialize<'a> for CompressedProof {
     type Output = Ref<&'a [u8], Self>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         Ok(Ref::<&[u8], CompressedProof>::from_prefix(bytes)?)
     }
@@ -1,18 +1,22 @@
 use std::collections::HashMap;
 
+use light_zero_copy::{slice::ZeroCopySliceBorsh, ZeroCopy};
+
 use crate::{
     compressed_account::{CompressedAccount, PackedCompressedAccountWithMerkleContext},
-    instruction_data::compressed_proof::CompressedProof,
+    instruction_data::{compressed_proof::CompressedProof, zero_copy::ZNewAddressParamsPacked},
     AnchorDeserialize, AnchorSerialize, Pubkey,
 };
 
-#[derive(Debug, PartialEq, Default, Clone, AnchorDeserialize, AnchorSerialize)]
+#[derive(Debug, PartialEq, Default, Clone, AnchorDeserialize, AnchorSerialize, ZeroCopy)]
 pub struct InstructionDataInvoke {
     pub proof: Option<CompressedProof>,
     pub input_compressed_accounts_with_merkle_context:
         Vec<PackedCompressedAccountWithMerkleContext>,
     pub output_compressed_accounts: Vec<OutputCompressedAccountWithPackedContext>,
+    /// Relay fees are not supported, the system program rejects Some.
     pub relay_fee: Option<u64>,
+    #[zero_copy(ty = ZeroCopySliceBorsh<'a, ZNewAddressParamsPacked>)]
     pub new_address_params: Vec<NewAddressParamsPacked>,
     pub compress_or_decompress_lamports: Option<u64>,
     pub is_compress: bool,
@@ -69,6 +73,22 @@ impl NewAddressParamsAssignedPacked {
     }
 }
 
+/// New address params staged in a cpi context account.
+/// The address is derived with the owner,
+/// the program that staged the address in the cpi context.
+/// The assigned account index is relative to the output
+/// compressed accounts of the cpi context.
+#[derive(Debug, PartialEq, Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct CpiContextNewAddressParamsAssignedPacked {
+    pub owner: [u8; 32],
+    pub seed: [u8; 32],
+    pub address_queue_account_index: u8,
+    pub address_merkle_tree_account_index: u8,
+    pub address_merkle_tree_root_index: u16,
+    pub assigned_to_account: bool,
+    pub assigned_account_index: u8,
+}
+
 #[derive(Debug, PartialEq, Default, Clone, AnchorDeserialize, AnchorSerialize)]
 pub struct NewAddressParams {
     pub seed: [u8; 32],
@@ -93,6 +113,14 @@ pub struct PackedReadOnlyAddress {
     pub address_merkle_tree_account_index: u8,
 }
 
+/// Lamports decompressed from the sol pool pda
+/// to the remaining account at `recipient_index`.
+#[derive(Debug, PartialEq, Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct PackedDecompressionRecipient {
+    pub recipient_index: u8,
+    pub lamports: u64,
+}
+
 #[derive(Debug, PartialEq, Default, Clone, AnchorDeserialize, AnchorSerialize)]
 pub struct ReadOnlyAddress {
     pub address: [u8; 32],
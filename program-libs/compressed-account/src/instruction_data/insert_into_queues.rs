@@ -4,7 +4,8 @@ use std::{
 };
 
 use light_zero_copy::{
-    borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySlice, slice_mut::ZeroCopySliceMut,
+    borsh::Deserialize, errors::ZeroCopyError, init_mut::ZeroCopyNew, slice::ZeroCopySlice,
+    slice_mut::ZeroCopySliceMut,
 };
 use zerocopy::{
     little_endian::{U32, U64},
@@ -296,6 +297,48 @@ impl<'a> InsertIntoQueuesInstructionDataMut<'a> {
     }
 }
 
+/// Capacities of InsertIntoQueuesInstructionData.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct InsertIntoQueuesInstructionDataConfig {
+    pub leaves_capacity: u8,
+    pub nullifiers_capacity: u8,
+    pub addresses_capacity: u8,
+    pub num_output_trees: u8,
+    pub num_input_trees: u8,
+    pub num_address_trees: u8,
+}
+
+impl<'a> ZeroCopyNew<'a> for InsertIntoQueuesInstructionData<'a> {
+    type ZeroCopyConfig = InsertIntoQueuesInstructionDataConfig;
+    type Output = InsertIntoQueuesInstructionDataMut<'a>;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        InsertIntoQueuesInstructionDataMut::required_size_for_capacity(
+            config.leaves_capacity,
+            config.nullifiers_capacity,
+            config.addresses_capacity,
+            config.num_output_trees,
+            config.num_input_trees,
+            config.num_address_trees,
+        )
+    }
+
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> std::result::Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        InsertIntoQueuesInstructionDataMut::new_at(
+            bytes,
+            config.leaves_capacity,
+            config.nullifiers_capacity,
+            config.addresses_capacity,
+            config.num_output_trees,
+            config.num_input_trees,
+            config.num_address_trees,
+        )
+    }
+}
+
 impl Deref for InsertIntoQueuesInstructionDataMut<'_> {
     type Target = InsertIntoQueuesInstructionDataMeta;
 
@@ -319,31 +362,36 @@ fn test_rnd_insert_into_queues_ix_data() {
     let num_iters = 1000;
 
     for _ in 0..num_iters {
-        let leaves_capacity: u8 = rng.gen();
-        let nullifiers_capacity: u8 = rng.gen();
-        let addresses_capacity: u8 = rng.gen();
-        let num_output_trees: u8 = rng.gen();
-        let num_input_trees: u8 = rng.gen();
-        let num_address_trees: u8 = rng.gen();
-        let size = InsertIntoQueuesInstructionDataMut::required_size_for_capacity(
+        let config = InsertIntoQueuesInstructionDataConfig {
+            leaves_capacity: rng.gen(),
+            nullifiers_capacity: rng.gen(),
+            addresses_capacity: rng.gen(),
+            num_output_trees: rng.gen(),
+            num_input_trees: rng.gen(),
+            num_address_trees: rng.gen(),
+        };
+        let InsertIntoQueuesInstructionDataConfig {
             leaves_capacity,
             nullifiers_capacity,
             addresses_capacity,
-            num_output_trees,
-            num_input_trees,
-            num_address_trees,
+            ..
+        } = config;
+        let size = InsertIntoQueuesInstructionData::byte_len(&config);
+        assert_eq!(
+            size,
+            InsertIntoQueuesInstructionDataMut::required_size_for_capacity(
+                config.leaves_capacity,
+                config.nullifiers_capacity,
+                config.addresses_capacity,
+                config.num_output_trees,
+                config.num_input_trees,
+                config.num_address_trees,
+            )
         );
         let mut bytes = vec![0u8; size];
-        let (mut new_data, _) = InsertIntoQueuesInstructionDataMut::new_at(
-            &mut bytes,
-            leaves_capacity,
-            nullifiers_capacity,
-            addresses_capacity,
-            num_output_trees,
-            num_input_trees,
-            num_address_trees,
-        )
-        .unwrap();
+        let (mut new_data, remaining_bytes) =
+            InsertIntoQueuesInstructionData::new_zero_copy(&mut bytes, config).unwrap();
+        assert!(remaining_bytes.is_empty());
         *new_data.meta = InsertIntoQueuesInstructionDataMeta {
             is_invoked_by_program: rng.gen(),
             bump: rng.gen(),
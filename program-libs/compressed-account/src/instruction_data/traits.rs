@@ -5,7 +5,10 @@ use zerocopy::Ref;
 use super::{
     compressed_proof::CompressedProof,
     cpi_context::CompressedCpiContext,
-    zero_copy::{ZPackedMerkleContext, ZPackedReadOnlyAddress, ZPackedReadOnlyCompressedAccount},
+    zero_copy::{
+        ZPackedDecompressionRecipient, ZPackedMerkleContext, ZPackedReadOnlyAddress,
+        ZPackedReadOnlyCompressedAccount,
+    },
 };
 use crate::{compressed_account::CompressedAccountData, pubkey::Pubkey, CompressedAccountError};
 
@@ -16,6 +19,7 @@ pub trait InstructionData<'a> {
     fn output_accounts(&self) -> &[impl OutputAccount<'a>];
     fn read_only_accounts(&self) -> Option<&[ZPackedReadOnlyCompressedAccount]>;
     fn read_only_addresses(&self) -> Option<&[ZPackedReadOnlyAddress]>;
+    fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]>;
     fn is_compress(&self) -> bool;
     fn compress_or_decompress_lamports(&self) -> Option<u64>;
     fn proof(&self) -> Option<Ref<&'a [u8], CompressedProof>>;
@@ -34,6 +38,9 @@ where
     fn address_merkle_tree_account_index(&self) -> u8;
     fn address_merkle_tree_root_index(&self) -> u16;
     fn assigned_compressed_account_index(&self) -> Option<usize>;
+    /// Program the address is derived with,
+    /// is only set for addresses staged in a cpi context account.
+    fn owner(&self) -> Option<[u8; 32]>;
 }
 
 pub trait InputAccount<'a>
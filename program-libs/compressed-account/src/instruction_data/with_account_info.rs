@@ -1,6 +1,10 @@
 use std::ops::{Deref, DerefMut};
 
-use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh};
+use light_zero_copy::{
+    borsh::{check_vec_len, Deserialize},
+    errors::ZeroCopyError,
+    slice::ZeroCopySliceBorsh,
+};
 use zerocopy::{
     little_endian::{U16, U32, U64},
     FromBytes, Immutable, IntoBytes, KnownLayout, Ref, Unaligned,
@@ -13,8 +17,8 @@ use super::{
     traits::{AccountOptions, InputAccount, InstructionData, NewAddress, OutputAccount},
     with_readonly::ZInstructionDataInvokeCpiWithReadOnlyMeta,
     zero_copy::{
-        ZNewAddressParamsAssignedPacked, ZPackedMerkleContext, ZPackedReadOnlyAddress,
-        ZPackedReadOnlyCompressedAccount,
+        ZNewAddressParamsAssignedPacked, ZPackedDecompressionRecipient, ZPackedMerkleContext,
+        ZPackedReadOnlyAddress, ZPackedReadOnlyCompressedAccount,
     },
 };
 use crate::{
@@ -204,7 +208,10 @@ impl<'a> Deserialize<'a> for ZOutAccountInfo<'a> {
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         let (meta, bytes) = Ref::<&[u8], ZOutAccountInfoMeta>::from_prefix(bytes)?;
         let (len, bytes) = Ref::<&'a [u8], U32>::from_prefix(bytes)?;
-        let (data, bytes) = bytes.split_at(u64::from(*len) as usize);
+        let data_len =
+            usize::try_from(u32::from(*len)).map_err(|_| ZeroCopyError::LengthOverflow)?;
+        check_vec_len(data_len, 1, bytes.len())?;
+        let (data, bytes) = bytes.split_at(data_len);
         Ok((Self { meta, data }, bytes))
     }
 }
@@ -326,6 +333,10 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpiWithAccountInfo<'a> {
         Some(self.read_only_addresses.as_slice())
     }
 
+    fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]> {
+        None
+    }
+
     fn owner(&self) -> Pubkey {
         self.meta.invoking_program_id
     }
@@ -399,8 +410,8 @@ impl<'a> Deserialize<'a> for InstructionDataInvokeCpiWithAccountInfo {
         let (account_infos, bytes) = {
             let (num_slices, mut bytes) = Ref::<&[u8], U32>::from_prefix(bytes)?;
             let num_slices = u32::from(*num_slices) as usize;
-            // TODO: add check that remaining data is enough to read num_slices
-            // This prevents agains invalid data allocating a lot of heap memory
+            // Account infos are at least 3 option bytes.
+            check_vec_len(num_slices, 3, bytes.len())?;
             let mut slices = Vec::with_capacity(num_slices);
             for _ in 0..num_slices {
                 let (slice, _bytes) = CompressedAccountInfo::zero_copy_at_with_owner(
@@ -1,6 +1,10 @@
-use std::ops::Deref;
+use std::{mem::size_of, ops::Deref};
 
-use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh};
+use light_zero_copy::{
+    borsh::{check_vec_len, Deserialize},
+    errors::ZeroCopyError,
+    slice::ZeroCopySliceBorsh,
+};
 use zerocopy::{
     little_endian::{U16, U32, U64},
     FromBytes, Immutable, IntoBytes, KnownLayout, Ref, Unaligned,
@@ -11,13 +15,13 @@ use super::{
     cpi_context::CompressedCpiContext,
     data::{
         NewAddressParamsAssignedPacked, OutputCompressedAccountWithPackedContext,
-        PackedReadOnlyAddress,
+        PackedDecompressionRecipient, PackedReadOnlyAddress,
     },
     traits::{AccountOptions, InputAccount, InstructionData, NewAddress},
     zero_copy::{
         ZCompressedCpiContext, ZNewAddressParamsAssignedPacked,
-        ZOutputCompressedAccountWithPackedContext, ZPackedMerkleContext, ZPackedReadOnlyAddress,
-        ZPackedReadOnlyCompressedAccount,
+        ZOutputCompressedAccountWithPackedContext, ZPackedDecompressionRecipient,
+        ZPackedMerkleContext, ZPackedReadOnlyAddress, ZPackedReadOnlyCompressedAccount,
     },
 };
 use crate::{
@@ -193,7 +197,9 @@ impl<'a> Deref for ZInAccount<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Default, Clone, AnchorSerialize, AnchorDeserialize)]
+/// Borsh (de)serialization is implemented manually
+/// since decompression_recipients is optional trailing data.
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct InstructionDataInvokeCpiWithReadOnly {
     /// 0 With program ids
     /// 1 without program ids
@@ -213,6 +219,84 @@ pub struct InstructionDataInvokeCpiWithReadOnly {
     pub output_compressed_accounts: Vec<OutputCompressedAccountWithPackedContext>,
     pub read_only_addresses: Vec<PackedReadOnlyAddress>,
     pub read_only_accounts: Vec<PackedReadOnlyCompressedAccount>,
+    /// Decompress lamports from the sol pool pda to multiple remaining accounts.
+    /// Mutually exclusive with compress_or_decompress_lamports.
+    /// Optional, is only serialized if not empty,
+    /// end of data deserializes as empty.
+    pub decompression_recipients: Vec<PackedDecompressionRecipient>,
+}
+
+impl AnchorSerialize for InstructionDataInvokeCpiWithReadOnly {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.mode.serialize(writer)?;
+        self.bump.serialize(writer)?;
+        self.invoking_program_id.serialize(writer)?;
+        self.compress_or_decompress_lamports.serialize(writer)?;
+        self.is_compress.serialize(writer)?;
+        self.with_cpi_context.serialize(writer)?;
+        self.with_transaction_hash.serialize(writer)?;
+        self.cpi_context.serialize(writer)?;
+        self.proof.serialize(writer)?;
+        self.new_address_params.serialize(writer)?;
+        self.input_compressed_accounts.serialize(writer)?;
+        self.output_compressed_accounts.serialize(writer)?;
+        self.read_only_addresses.serialize(writer)?;
+        self.read_only_accounts.serialize(writer)?;
+        if !self.decompression_recipients.is_empty() {
+            self.decompression_recipients.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl AnchorDeserialize for InstructionDataInvokeCpiWithReadOnly {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mode = u8::deserialize_reader(reader)?;
+        let bump = u8::deserialize_reader(reader)?;
+        let invoking_program_id = Pubkey::deserialize_reader(reader)?;
+        let compress_or_decompress_lamports = u64::deserialize_reader(reader)?;
+        let is_compress = bool::deserialize_reader(reader)?;
+        let with_cpi_context = bool::deserialize_reader(reader)?;
+        let with_transaction_hash = bool::deserialize_reader(reader)?;
+        let cpi_context = CompressedCpiContext::deserialize_reader(reader)?;
+        let proof = Option::<CompressedProof>::deserialize_reader(reader)?;
+        let new_address_params = Vec::<NewAddressParamsAssignedPacked>::deserialize_reader(reader)?;
+        let input_compressed_accounts = Vec::<InAccount>::deserialize_reader(reader)?;
+        let output_compressed_accounts =
+            Vec::<OutputCompressedAccountWithPackedContext>::deserialize_reader(reader)?;
+        let read_only_addresses = Vec::<PackedReadOnlyAddress>::deserialize_reader(reader)?;
+        let read_only_accounts =
+            Vec::<PackedReadOnlyCompressedAccount>::deserialize_reader(reader)?;
+        // End of data means no decompression recipients.
+        let decompression_recipients = {
+            let mut len_bytes = [0u8; 4];
+            if reader.read(&mut len_bytes[..1])? == 0 {
+                Vec::new()
+            } else {
+                reader.read_exact(&mut len_bytes[1..])?;
+                (0..u32::from_le_bytes(len_bytes))
+                    .map(|_| PackedDecompressionRecipient::deserialize_reader(reader))
+                    .collect::<std::io::Result<Vec<_>>>()?
+            }
+        };
+        Ok(Self {
+            mode,
+            bump,
+            invoking_program_id,
+            compress_or_decompress_lamports,
+            is_compress,
+            with_cpi_context,
+            with_transaction_hash,
+            cpi_context,
+            proof,
+            new_address_params,
+            input_compressed_accounts,
+            output_compressed_accounts,
+            read_only_addresses,
+            read_only_accounts,
+            decompression_recipients,
+        })
+    }
 }
 
 #[repr(C)]
@@ -255,12 +339,17 @@ pub struct ZInstructionDataInvokeCpiWithReadOnly<'a> {
     pub output_compressed_accounts: Vec<ZOutputCompressedAccountWithPackedContext<'a>>,
     pub read_only_addresses: ZeroCopySliceBorsh<'a, ZPackedReadOnlyAddress>,
     pub read_only_accounts: ZeroCopySliceBorsh<'a, ZPackedReadOnlyCompressedAccount>,
+    /// None if the instruction data ends before decompression recipients.
+    pub decompression_recipients: Option<ZeroCopySliceBorsh<'a, ZPackedDecompressionRecipient>>,
 }
 
 impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpiWithReadOnly<'a> {
     fn account_option_config(&self) -> AccountOptions {
         AccountOptions {
-            sol_pool_pda: self.compress_or_decompress_lamports().is_some(),
+            sol_pool_pda: self.compress_or_decompress_lamports().is_some()
+                || self
+                    .decompression_recipients()
+                    .is_some_and(|recipients| !recipients.is_empty()),
             decompression_recipient: self.compress_or_decompress_lamports().is_some()
                 && !self.is_compress(),
             cpi_context_account: self.cpi_context().is_some(),
@@ -282,6 +371,12 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpiWithReadOnly<'a> {
         Some(self.read_only_addresses.as_slice())
     }
 
+    fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]> {
+        self.decompression_recipients
+            .as_ref()
+            .map(|recipients| recipients.as_slice())
+    }
+
     fn owner(&self) -> Pubkey {
         self.meta.invoking_program_id
     }
@@ -347,8 +442,8 @@ impl<'a> Deserialize<'a> for InstructionDataInvokeCpiWithReadOnly {
         let (input_compressed_accounts, bytes) = {
             let (num_slices, mut bytes) = Ref::<&[u8], U32>::from_prefix(bytes)?;
             let num_slices = u32::from(*num_slices) as usize;
-            // TODO: add check that remaining data is enough to read num_slices
-            // This prevents agains invalid data allocating a lot of heap memory
+            // Input accounts are at least meta and address option byte.
+            check_vec_len(num_slices, size_of::<ZInAccountMeta>() + 1, bytes.len())?;
             let mut slices = Vec::with_capacity(num_slices);
             for _ in 0..num_slices {
                 let (slice, _bytes) =
@@ -370,6 +465,15 @@ impl<'a> Deserialize<'a> for InstructionDataInvokeCpiWithReadOnly {
         let (read_only_accounts, bytes) =
             ZeroCopySliceBorsh::<'a, ZPackedReadOnlyCompressedAccount>::from_bytes_at(bytes)?;
 
+        // End of data means no decompression recipients.
+        let (decompression_recipients, bytes) = if bytes.is_empty() {
+            (None, bytes)
+        } else {
+            let (decompression_recipients, bytes) =
+                ZeroCopySliceBorsh::<'a, ZPackedDecompressionRecipient>::from_bytes_at(bytes)?;
+            (Some(decompression_recipients), bytes)
+        };
+
         Ok((
             ZInstructionDataInvokeCpiWithReadOnly {
                 meta,
@@ -379,6 +483,7 @@ impl<'a> Deserialize<'a> for InstructionDataInvokeCpiWithReadOnly {
                 output_compressed_accounts,
                 read_only_addresses,
                 read_only_accounts,
+                decompression_recipients,
             },
             bytes,
         ))
@@ -423,6 +528,20 @@ impl PartialEq<InstructionDataInvokeCpiWithReadOnly> for ZInstructionDataInvokeC
         {
             return false;
         }
+        let decompression_recipients = self.decompression_recipients().unwrap_or_default();
+        if decompression_recipients.len() != other.decompression_recipients.len() {
+            return false;
+        }
+        for (z_recipient, recipient) in decompression_recipients
+            .iter()
+            .zip(other.decompression_recipients.iter())
+        {
+            if z_recipient.recipient_index != recipient.recipient_index
+                || u64::from(z_recipient.lamports) != recipient.lamports
+            {
+                return false;
+            }
+        }
 
         true
     }
@@ -494,6 +613,10 @@ fn test_read_only_zero_copy() {
             },
             root_index: 8,
         }],
+        decompression_recipients: vec![PackedDecompressionRecipient {
+            recipient_index: 9,
+            lamports: 500,
+        }],
     };
     let bytes = borsh_struct.try_to_vec().unwrap();
 
@@ -502,6 +625,69 @@ fn test_read_only_zero_copy() {
     assert_eq!(zero_copy, borsh_struct);
 }
 
+/// Decompression recipients are optional trailing data:
+/// 1. Empty recipients are not serialized.
+/// 2. Data without recipients deserializes with empty recipients.
+/// 3. Recipients round trip.
+#[test]
+fn test_read_only_optional_decompression_recipients() {
+    let mut borsh_struct = InstructionDataInvokeCpiWithReadOnly {
+        mode: 1,
+        bump: 255,
+        invoking_program_id: Pubkey::new_from_array([1; 32]),
+        ..Default::default()
+    };
+    let bytes = borsh_struct.try_to_vec().unwrap();
+    // 1. Empty recipients are not serialized,
+    //    data ends after read only accounts.
+    let mut bytes_with_empty_vec = bytes.clone();
+    bytes_with_empty_vec.extend_from_slice(&0u32.to_le_bytes());
+    assert_eq!(
+        bytes.len() + 4,
+        bytes_with_empty_vec.len(),
+        "Empty recipients must not be serialized."
+    );
+    // 2. Data without recipients deserializes with empty recipients.
+    for bytes in [&bytes, &bytes_with_empty_vec] {
+        let deserialized = InstructionDataInvokeCpiWithReadOnly::try_from_slice(bytes).unwrap();
+        assert_eq!(deserialized, borsh_struct);
+        let (zero_copy, remaining) =
+            InstructionDataInvokeCpiWithReadOnly::zero_copy_at(bytes).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(zero_copy, borsh_struct);
+        assert!(zero_copy
+            .decompression_recipients()
+            .unwrap_or_default()
+            .is_empty());
+        assert!(!zero_copy.account_option_config().sol_pool_pda);
+    }
+    assert!(InstructionDataInvokeCpiWithReadOnly::zero_copy_at(&bytes)
+        .unwrap()
+        .0
+        .decompression_recipients
+        .is_none());
+    // 3. Recipients round trip.
+    borsh_struct.decompression_recipients = vec![
+        PackedDecompressionRecipient {
+            recipient_index: 1,
+            lamports: 100,
+        },
+        PackedDecompressionRecipient {
+            recipient_index: 2,
+            lamports: 200,
+        },
+    ];
+    let bytes_with_recipients = borsh_struct.try_to_vec().unwrap();
+    assert_eq!(
+        InstructionDataInvokeCpiWithReadOnly::try_from_slice(&bytes_with_recipients).unwrap(),
+        borsh_struct
+    );
+    let (zero_copy, _) =
+        InstructionDataInvokeCpiWithReadOnly::zero_copy_at(&bytes_with_recipients).unwrap();
+    assert_eq!(zero_copy, borsh_struct);
+    assert!(zero_copy.account_option_config().sol_pool_pda);
+}
+
 #[cfg(not(feature = "pinocchio"))]
 #[cfg(test)]
 mod test {
@@ -588,6 +774,11 @@ mod test {
         if reference.read_only_accounts.len() != z_copy.read_only_accounts.len() {
             return Err(CompressedAccountError::InvalidArgument);
         }
+        if reference.decompression_recipients.len()
+            != z_copy.decompression_recipients().unwrap_or_default().len()
+        {
+            return Err(CompressedAccountError::InvalidArgument);
+        }
 
         // If we're testing the traits, let's also check that the relevant trait methods work
         assert_eq!(
@@ -718,6 +909,12 @@ mod test {
                     root_index: rng.gen(),
                 })
                 .collect::<Vec<_>>(),
+            decompression_recipients: (0..rng.gen_range(0..5))
+                .map(|_| PackedDecompressionRecipient {
+                    recipient_index: rng.gen(),
+                    lamports: rng.gen(),
+                })
+                .collect::<Vec<_>>(),
         }
     }
 
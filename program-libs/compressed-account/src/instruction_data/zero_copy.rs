@@ -1,15 +1,21 @@
-use std::{mem::size_of, ops::Deref};
+use std::ops::Deref;
 
-use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh};
+use light_zero_copy::{
+    borsh::{check_vec_len, Deserialize},
+    errors::ZeroCopyError,
+    slice::ZeroCopySliceBorsh,
+};
 use zerocopy::{
     little_endian::{U16, U32, U64},
     FromBytes, Immutable, IntoBytes, KnownLayout, Ref, Unaligned,
 };
 
+pub use super::data::ZInstructionDataInvoke;
 use super::{
     invoke_cpi::InstructionDataInvokeCpi,
     traits::{AccountOptions, InputAccount, InstructionData, NewAddress, OutputAccount},
 };
+pub use crate::compressed_account::ZCompressedAccount;
 use crate::{
     compressed_account::{
         CompressedAccount, CompressedAccountData, PackedCompressedAccountWithMerkleContext,
@@ -35,13 +41,13 @@ pub struct ZPackedReadOnlyAddress {
 impl<'a> Deserialize<'a> for ZPackedReadOnlyAddress {
     type Output = Self;
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), ZeroCopyError> {
-        let (address, bytes) = bytes.split_at(size_of::<[u8; 32]>());
+        let (address, bytes) = Ref::<&'a [u8], [u8; 32]>::from_prefix(bytes)?;
         let (address_merkle_tree_root_index, bytes) = U16::ref_from_prefix(bytes)?;
         let (address_merkle_tree_account_index, bytes) = u8::zero_copy_at(bytes)?;
 
         Ok((
             ZPackedReadOnlyAddress {
-                address: address.try_into().unwrap(),
+                address: *address,
                 address_merkle_tree_root_index: *address_merkle_tree_root_index,
                 address_merkle_tree_account_index,
             },
@@ -61,6 +67,14 @@ pub struct ZNewAddressParamsPacked {
     pub address_merkle_tree_root_index: U16,
 }
 
+impl<'a> Deserialize<'a> for ZNewAddressParamsPacked {
+    type Output = Ref<&'a [u8], Self>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
+    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        Ok(Ref::<&[u8], Self>::from_prefix(bytes)?)
+    }
+}
+
 impl NewAddress<'_> for ZNewAddressParamsPacked {
     fn seed(&self) -> [u8; 32] {
         self.seed
@@ -80,6 +94,10 @@ impl NewAddress<'_> for ZNewAddressParamsPacked {
     fn address_merkle_tree_root_index(&self) -> u16 {
         self.address_merkle_tree_root_index.into()
     }
+
+    fn owner(&self) -> Option<[u8; 32]> {
+        None
+    }
 }
 
 #[repr(C)]
@@ -101,6 +119,7 @@ impl ZPackedMerkleContext {
 
 impl<'a> Deserialize<'a> for ZPackedMerkleContext {
     type Output = Ref<&'a [u8], Self>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         let (ref_value, bytes) = Ref::<&[u8], Self>::from_prefix(bytes)?;
         Ok((ref_value, bytes))
@@ -119,10 +138,10 @@ impl<'a> OutputAccount<'a> for ZOutputCompressedAccountWithPackedContext<'a> {
         false
     }
     fn lamports(&self) -> u64 {
-        self.compressed_account.lamports.into()
+        (*self.compressed_account.lamports).into()
     }
     fn owner(&self) -> Pubkey {
-        self.compressed_account.owner
+        *self.compressed_account.owner
     }
 
     fn merkle_tree_index(&self) -> u8 {
@@ -171,6 +190,15 @@ impl<'a> From<&ZOutputCompressedAccountWithPackedContext<'a>>
     }
 }
 
+impl<'a> Deserialize<'a> for OutputCompressedAccountWithPackedContext {
+    type Output = ZOutputCompressedAccountWithPackedContext<'a>;
+
+    #[inline]
+    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        ZOutputCompressedAccountWithPackedContext::zero_copy_at(bytes)
+    }
+}
+
 impl<'a> Deserialize<'a> for ZOutputCompressedAccountWithPackedContext<'a> {
     type Output = Self;
 
@@ -215,6 +243,15 @@ impl From<&ZCompressedAccountData<'_>> for CompressedAccountData {
     }
 }
 
+impl<'a> Deserialize<'a> for CompressedAccountData {
+    type Output = ZCompressedAccountData<'a>;
+
+    #[inline]
+    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        ZCompressedAccountData::zero_copy_at(bytes)
+    }
+}
+
 impl<'a> Deserialize<'a> for ZCompressedAccountData<'a> {
     type Output = Self;
 
@@ -224,7 +261,10 @@ impl<'a> Deserialize<'a> for ZCompressedAccountData<'a> {
     ) -> Result<(ZCompressedAccountData<'a>, &'a [u8]), ZeroCopyError> {
         let (discriminator, bytes) = Ref::<&'a [u8], [u8; 8]>::from_prefix(bytes)?;
         let (len, bytes) = Ref::<&'a [u8], U32>::from_prefix(bytes)?;
-        let (data, bytes) = bytes.split_at(u64::from(*len) as usize);
+        let data_len =
+            usize::try_from(u32::from(*len)).map_err(|_| ZeroCopyError::LengthOverflow)?;
+        check_vec_len(data_len, 1, bytes.len())?;
+        let (data, bytes) = bytes.split_at(data_len);
         let (data_hash, bytes) = Ref::<&'a [u8], [u8; 32]>::from_prefix(bytes)?;
 
         Ok((
@@ -238,29 +278,6 @@ impl<'a> Deserialize<'a> for ZCompressedAccountData<'a> {
     }
 }
 
-#[repr(C)]
-#[derive(Debug, PartialEq, KnownLayout, FromBytes, IntoBytes, Immutable)]
-pub struct AccountDesMeta {
-    pub owner: Pubkey,
-    pub lamports: U64,
-    address_option: u8,
-}
-
-#[derive(Debug, PartialEq, Clone)]
-pub struct ZCompressedAccount<'a> {
-    meta: Ref<&'a [u8], AccountDesMeta>,
-    pub address: Option<Ref<&'a [u8], [u8; 32]>>,
-    pub data: Option<ZCompressedAccountData<'a>>,
-}
-
-impl Deref for ZCompressedAccount<'_> {
-    type Target = AccountDesMeta;
-
-    fn deref(&self) -> &Self::Target {
-        &self.meta
-    }
-}
-
 impl From<&ZCompressedAccount<'_>> for CompressedAccount {
     fn from(compressed_account: &ZCompressedAccount) -> Self {
         let data: Option<CompressedAccountData> =
@@ -273,38 +290,14 @@ impl From<&ZCompressedAccount<'_>> for CompressedAccount {
                     data_hash: *data.data_hash,
                 });
         CompressedAccount {
-            owner: crate::Pubkey::from(compressed_account.owner),
-            lamports: compressed_account.lamports.into(),
+            owner: crate::Pubkey::from(*compressed_account.owner),
+            lamports: (*compressed_account.lamports).into(),
             address: compressed_account.address.map(|x| *x),
             data,
         }
     }
 }
 
-impl<'a> Deserialize<'a> for ZCompressedAccount<'a> {
-    type Output = Self;
-
-    #[inline]
-    fn zero_copy_at(bytes: &'a [u8]) -> Result<(ZCompressedAccount<'a>, &'a [u8]), ZeroCopyError> {
-        let (meta, bytes) = Ref::<&[u8], AccountDesMeta>::from_prefix(bytes)?;
-        let (address, bytes) = if meta.address_option == 1 {
-            let (address, bytes) = Ref::<&[u8], [u8; 32]>::zero_copy_at(bytes)?;
-            (Some(address), bytes)
-        } else {
-            (None, bytes)
-        };
-        let (data, bytes) = Option::<ZCompressedAccountData>::zero_copy_at(bytes)?;
-        Ok((
-            ZCompressedAccount {
-                meta,
-                address,
-                data,
-            },
-            bytes,
-        ))
-    }
-}
-
 #[repr(C)]
 #[derive(Debug, PartialEq, Immutable, KnownLayout, IntoBytes, FromBytes)]
 pub struct ZPackedCompressedAccountWithMerkleContextMeta {
@@ -340,7 +333,7 @@ impl<'a> InputAccount<'a> for ZPackedCompressedAccountWithMerkleContext<'a> {
         &self.compressed_account.owner
     }
     fn lamports(&self) -> u64 {
-        self.compressed_account.lamports.into()
+        (*self.compressed_account.lamports).into()
     }
     fn address(&self) -> Option<[u8; 32]> {
         self.compressed_account.address.map(|x| *x)
@@ -399,6 +392,15 @@ impl Deref for ZPackedCompressedAccountWithMerkleContext<'_> {
     }
 }
 
+impl<'a> Deserialize<'a> for PackedCompressedAccountWithMerkleContext {
+    type Output = ZPackedCompressedAccountWithMerkleContext<'a>;
+
+    #[inline]
+    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        ZPackedCompressedAccountWithMerkleContext::zero_copy_at(bytes)
+    }
+}
+
 impl<'a> Deserialize<'a> for ZPackedCompressedAccountWithMerkleContext<'a> {
     type Output = Self;
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), ZeroCopyError> {
@@ -419,18 +421,6 @@ impl<'a> Deserialize<'a> for ZPackedCompressedAccountWithMerkleContext<'a> {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct ZInstructionDataInvoke<'a> {
-    pub proof: Option<Ref<&'a [u8], CompressedProof>>,
-    pub input_compressed_accounts_with_merkle_context:
-        Vec<ZPackedCompressedAccountWithMerkleContext<'a>>,
-    pub output_compressed_accounts: Vec<ZOutputCompressedAccountWithPackedContext<'a>>,
-    pub relay_fee: Option<Ref<&'a [u8], U64>>,
-    pub new_address_params: ZeroCopySliceBorsh<'a, ZNewAddressParamsPacked>,
-    pub compress_or_decompress_lamports: Option<Ref<&'a [u8], U64>>,
-    pub is_compress: bool,
-}
-
 impl<'a> InstructionData<'a> for ZInstructionDataInvoke<'a> {
     fn bump(&self) -> Option<u8> {
         None
@@ -447,6 +437,9 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvoke<'a> {
     fn read_only_addresses(&self) -> Option<&[ZPackedReadOnlyAddress]> {
         None
     }
+    fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]> {
+        None
+    }
     fn proof(&self) -> Option<Ref<&'a [u8], CompressedProof>> {
         self.proof
     }
@@ -464,7 +457,7 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvoke<'a> {
         {
             Pubkey::default()
         } else {
-            self.input_compressed_accounts_with_merkle_context[0]
+            *self.input_compressed_accounts_with_merkle_context[0]
                 .compressed_account
                 .owner
         }
@@ -487,38 +480,6 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvoke<'a> {
         unimplemented!()
     }
 }
-impl<'a> Deserialize<'a> for ZInstructionDataInvoke<'a> {
-    type Output = Self;
-    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), ZeroCopyError> {
-        let (proof, bytes) = Option::<CompressedProof>::zero_copy_at(bytes)?;
-        let (input_compressed_accounts_with_merkle_context, bytes) =
-            Vec::<ZPackedCompressedAccountWithMerkleContext>::zero_copy_at(bytes)?;
-        let (output_compressed_accounts, bytes) =
-            Vec::<ZOutputCompressedAccountWithPackedContext>::zero_copy_at(bytes)?;
-        let (relay_fee, bytes) = Option::<Ref<&'a [u8], U64>>::zero_copy_at(bytes)?;
-        if relay_fee.is_some() {
-            return Err(ZeroCopyError::InvalidConversion);
-        }
-        let (new_address_params, bytes) = ZeroCopySliceBorsh::from_bytes_at(bytes)?;
-        let (compress_or_decompress_lamports, bytes) =
-            Option::<Ref<&'a [u8], U64>>::zero_copy_at(bytes)?;
-        let (is_compress, bytes) = u8::zero_copy_at(bytes)?;
-
-        Ok((
-            ZInstructionDataInvoke {
-                proof,
-                input_compressed_accounts_with_merkle_context,
-                output_compressed_accounts,
-                relay_fee: None,
-                new_address_params,
-                compress_or_decompress_lamports,
-                is_compress: is_compress == 1,
-            },
-            bytes,
-        ))
-    }
-}
-
 #[derive(Debug, PartialEq)]
 pub struct ZInstructionDataInvokeCpi<'a> {
     pub proof: Option<Ref<&'a [u8], CompressedProof>>,
@@ -540,7 +501,7 @@ impl ZInstructionDataInvokeCpi<'_> {
         {
             Pubkey::default()
         } else {
-            self.input_compressed_accounts_with_merkle_context[0]
+            *self.input_compressed_accounts_with_merkle_context[0]
                 .compressed_account
                 .owner
         }
@@ -572,6 +533,9 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpi<'a> {
     fn read_only_addresses(&self) -> Option<&[ZPackedReadOnlyAddress]> {
         None
     }
+    fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]> {
+        None
+    }
 
     fn owner(&self) -> Pubkey {
         if self
@@ -580,7 +544,7 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpi<'a> {
         {
             Pubkey::default()
         } else {
-            self.input_compressed_accounts_with_merkle_context[0]
+            *self.input_compressed_accounts_with_merkle_context[0]
                 .compressed_account
                 .owner
         }
@@ -610,11 +574,7 @@ impl<'a> InstructionData<'a> for ZInstructionDataInvokeCpi<'a> {
     fn cpi_context(&self) -> Option<CompressedCpiContext> {
         self.cpi_context
             .as_ref()
-            .map(|cpi_context| CompressedCpiContext {
-                set_context: cpi_context.set_context(),
-                first_set_context: cpi_context.first_set_context(),
-                cpi_context_account_index: cpi_context.cpi_context_account_index,
-            })
+            .map(|cpi_context| (&**cpi_context).into())
     }
 
     fn compress_or_decompress_lamports(&self) -> Option<u64> {
@@ -657,6 +617,16 @@ impl ZCompressedCpiContext {
     }
 }
 
+impl From<&ZCompressedCpiContext> for CompressedCpiContext {
+    fn from(cpi_context: &ZCompressedCpiContext) -> Self {
+        CompressedCpiContext {
+            set_context: cpi_context.set_context(),
+            first_set_context: cpi_context.first_set_context(),
+            cpi_context_account_index: cpi_context.cpi_context_account_index,
+        }
+    }
+}
+
 impl<'a> From<ZInstructionDataInvokeCpi<'a>> for ZInstructionDataInvoke<'a> {
     fn from(instruction_data_invoke: ZInstructionDataInvokeCpi<'a>) -> Self {
         ZInstructionDataInvoke {
@@ -682,8 +652,8 @@ impl<'a> Deserialize<'a> for ZInstructionDataInvokeCpi<'a> {
             Vec::<ZPackedCompressedAccountWithMerkleContext>::zero_copy_at(bytes)?;
         let (output_compressed_accounts, bytes) =
             Vec::<ZOutputCompressedAccountWithPackedContext>::zero_copy_at(bytes)?;
-        let (option_relay_fee, bytes) = bytes.split_at(1);
-        if option_relay_fee[0] == 1 {
+        let (option_relay_fee, bytes) = u8::zero_copy_at(bytes)?;
+        if option_relay_fee == 1 {
             return Err(ZeroCopyError::InvalidConversion);
         }
         let (compress_or_decompress_lamports, bytes) =
@@ -738,6 +708,24 @@ pub struct ZPackedReadOnlyCompressedAccount {
 
 impl<'a> Deserialize<'a> for ZPackedReadOnlyCompressedAccount {
     type Output = Ref<&'a [u8], Self>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
+    fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        Ok(Ref::<&[u8], Self>::from_prefix(bytes)?)
+    }
+}
+
+#[repr(C)]
+#[derive(
+    Debug, PartialEq, Default, Clone, Copy, KnownLayout, Immutable, FromBytes, IntoBytes, Unaligned,
+)]
+pub struct ZPackedDecompressionRecipient {
+    pub recipient_index: u8,
+    pub lamports: U64,
+}
+
+impl<'a> Deserialize<'a> for ZPackedDecompressionRecipient {
+    type Output = Ref<&'a [u8], Self>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         Ok(Ref::<&[u8], Self>::from_prefix(bytes)?)
     }
@@ -802,6 +790,54 @@ impl NewAddress<'_> for ZNewAddressParamsAssignedPacked {
     fn address_merkle_tree_root_index(&self) -> u16 {
         self.address_merkle_tree_root_index.into()
     }
+
+    fn owner(&self) -> Option<[u8; 32]> {
+        None
+    }
+}
+
+#[repr(C)]
+#[derive(
+    Debug, PartialEq, Default, Clone, Copy, KnownLayout, Immutable, FromBytes, IntoBytes, Unaligned,
+)]
+pub struct ZCpiContextNewAddressParamsAssignedPacked {
+    pub owner: [u8; 32],
+    pub seed: [u8; 32],
+    pub address_queue_account_index: u8,
+    pub address_merkle_tree_account_index: u8,
+    pub address_merkle_tree_root_index: U16,
+    pub assigned_to_account: u8,
+    pub assigned_account_index: u8,
+}
+
+impl NewAddress<'_> for ZCpiContextNewAddressParamsAssignedPacked {
+    fn seed(&self) -> [u8; 32] {
+        self.seed
+    }
+    fn address_queue_index(&self) -> u8 {
+        self.address_queue_account_index
+    }
+
+    fn address_merkle_tree_account_index(&self) -> u8 {
+        self.address_merkle_tree_account_index
+    }
+
+    /// Index is relative to the output compressed accounts of the cpi context.
+    fn assigned_compressed_account_index(&self) -> Option<usize> {
+        if self.assigned_to_account > 0 {
+            Some(self.assigned_account_index as usize)
+        } else {
+            None
+        }
+    }
+
+    fn address_merkle_tree_root_index(&self) -> u16 {
+        self.address_merkle_tree_root_index.into()
+    }
+
+    fn owner(&self) -> Option<[u8; 32]> {
+        Some(self.owner)
+    }
 }
 
 #[cfg(not(feature = "pinocchio"))]
@@ -820,7 +856,10 @@ pub mod test {
             PackedMerkleContext,
         },
         instruction_data::{
-            data::{InstructionDataInvoke, NewAddressParamsPacked},
+            data::{
+                CpiContextNewAddressParamsAssignedPacked, InstructionDataInvoke,
+                NewAddressParamsPacked,
+            },
             invoke_cpi::InstructionDataInvokeCpi,
         },
         CompressedAccountError,
@@ -1024,6 +1063,30 @@ pub mod test {
         assert_eq!(z_copy, cpi_context);
     }
 
+    #[test]
+    fn test_cpi_context_new_address_params_deserialize() {
+        let new_address_params = CpiContextNewAddressParamsAssignedPacked {
+            owner: [1u8; 32],
+            seed: [2u8; 32],
+            address_queue_account_index: 3,
+            address_merkle_tree_account_index: 4,
+            address_merkle_tree_root_index: 5,
+            assigned_to_account: true,
+            assigned_account_index: 6,
+        };
+        let bytes = new_address_params.try_to_vec().unwrap();
+
+        let (z_copy, bytes) =
+            Ref::<&[u8], ZCpiContextNewAddressParamsAssignedPacked>::zero_copy_at(&bytes).unwrap();
+        assert!(bytes.is_empty());
+        assert_eq!(z_copy.owner(), Some(new_address_params.owner));
+        assert_eq!(z_copy.seed(), new_address_params.seed);
+        assert_eq!(z_copy.address_queue_index(), 3);
+        assert_eq!(z_copy.address_merkle_tree_account_index(), 4);
+        assert_eq!(z_copy.address_merkle_tree_root_index(), 5);
+        assert_eq!(z_copy.assigned_compressed_account_index(), Some(6));
+    }
+
     #[test]
     fn test_account_deserialize() {
         let test_account = get_test_account();
@@ -1401,10 +1464,10 @@ pub mod test {
         reference: &CompressedAccount,
         z_copy: &ZCompressedAccount,
     ) -> Result<(), CompressedAccountError> {
-        if reference.owner != z_copy.owner.into() {
+        if reference.owner != (*z_copy.owner).into() {
             return Err(CompressedAccountError::InvalidArgument);
         }
-        if reference.lamports != u64::from(z_copy.lamports) {
+        if reference.lamports != u64::from(*z_copy.lamports) {
             return Err(CompressedAccountError::InvalidArgument);
         }
         if reference.address != z_copy.address.map(|x| *x) {
@@ -1476,4 +1539,35 @@ pub mod test {
             compare_invoke_cpi_instruction_data(&value, &zero_copy).unwrap();
         }
     }
+
+    #[test]
+    fn test_instruction_data_invoke_cpi_truncated() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let value = get_rnd_instruction_data_invoke_cpi(&mut rng);
+            let bytes = value.try_to_vec().unwrap();
+            for i in 0..bytes.len() {
+                assert!(ZInstructionDataInvokeCpi::zero_copy_at(&bytes[..i]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_compressed_account_data_invalid_len() {
+        let data = CompressedAccountData {
+            discriminator: [1u8; 8],
+            data: vec![2u8; 10],
+            data_hash: [3u8; 32],
+        };
+        let mut bytes = data.try_to_vec().unwrap();
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            ZCompressedAccountData::zero_copy_at(&bytes),
+            Err(ZeroCopyError::InsufficientRemainingBytes(
+                u32::MAX as usize,
+                10 + 32
+            ))
+        );
+    }
 }
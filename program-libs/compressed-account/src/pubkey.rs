@@ -64,6 +64,7 @@ impl AsRef<Pubkey> for Pubkey {
 
 impl<'a> Deserialize<'a> for Pubkey {
     type Output = Ref<&'a [u8], Pubkey>;
+    const MIN_SIZE: usize = core::mem::size_of::<Self>();
 
     #[inline]
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Ref<&'a [u8], Pubkey>, &'a [u8]), ZeroCopyError> {
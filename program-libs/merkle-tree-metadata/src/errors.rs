@@ -20,6 +20,14 @@ pub enum MerkleTreeMetadataError {
     InvalidRolloverThreshold,
     #[error("Invalid Height.")]
     InvalidHeight,
+    #[error("Merkle tree is not rolled over.")]
+    NotRolledOver,
+    #[error("Close not configured.")]
+    CloseNotConfigured,
+    #[error("Merkle tree not ready for close.")]
+    NotReadyForClose,
+    #[error("Invalid network fee curve.")]
+    InvalidNetworkFeeCurve,
 }
 
 impl From<MerkleTreeMetadataError> for u32 {
@@ -34,6 +42,10 @@ impl From<MerkleTreeMetadataError> for u32 {
             MerkleTreeMetadataError::InvalidTreeType => 14007,
             MerkleTreeMetadataError::InvalidRolloverThreshold => 14008,
             MerkleTreeMetadataError::InvalidHeight => 14009,
+            MerkleTreeMetadataError::NotRolledOver => 14010,
+            MerkleTreeMetadataError::CloseNotConfigured => 14011,
+            MerkleTreeMetadataError::NotReadyForClose => 14012,
+            MerkleTreeMetadataError::InvalidNetworkFeeCurve => 14013,
         }
     }
 }
@@ -1,4 +1,7 @@
-use crate::errors::MerkleTreeMetadataError;
+use bytemuck::{Pod, Zeroable};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+use crate::{errors::MerkleTreeMetadataError, AnchorDeserialize, AnchorSerialize};
 
 pub fn compute_rollover_fee(
     rollover_threshold: u64,
@@ -17,6 +20,213 @@ pub fn compute_rollover_fee(
     Ok((rent * 100).div_ceil(number_of_transactions * rollover_threshold))
 }
 
+/// Network fee of batched trees which depends on the fill level of a queue.
+/// The fill level is the percentage of queue elements which are not
+/// yet inserted into the tree.
+/// - fill level <= low_fill_threshold -> min_fee
+/// - fill level >= high_fill_threshold -> max_fee
+/// - linear in between
+///
+/// A curve with max_fee == 0 is disabled and the static network fee applies.
+#[repr(C)]
+#[derive(
+    AnchorDeserialize,
+    AnchorSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    Pod,
+    Zeroable,
+    Clone,
+    Copy,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+)]
+pub struct NetworkFeeCurve {
+    pub min_fee: u64,
+    pub max_fee: u64,
+    /// Fill level in percent.
+    pub low_fill_threshold: u64,
+    /// Fill level in percent.
+    pub high_fill_threshold: u64,
+}
+
+impl NetworkFeeCurve {
+    pub fn is_enabled(&self) -> bool {
+        self.max_fee != 0
+    }
+
+    pub fn validate(&self) -> Result<(), MerkleTreeMetadataError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        if self.min_fee > self.max_fee
+            || self.low_fill_threshold > self.high_fill_threshold
+            || self.high_fill_threshold > 100
+        {
+            return Err(MerkleTreeMetadataError::InvalidNetworkFeeCurve);
+        }
+        Ok(())
+    }
+
+    /// Returns the network fee for a queue fill level in percent.
+    /// Trees without network fee are not forested, hence the curve only
+    /// applies to trees with a non zero static network fee.
+    pub fn get_network_fee(&self, network_fee: u64, fill_level: u64) -> u64 {
+        if !self.is_enabled() || network_fee == 0 {
+            network_fee
+        } else if fill_level <= self.low_fill_threshold {
+            self.min_fee
+        } else if fill_level >= self.high_fill_threshold {
+            self.max_fee
+        } else {
+            let fee_range = (self.max_fee - self.min_fee) as u128;
+            let fill_range = (self.high_fill_threshold - self.low_fill_threshold) as u128;
+            let fill = (fill_level - self.low_fill_threshold) as u128;
+            self.min_fee + (fee_range * fill / fill_range) as u64
+        }
+    }
+}
+
+/// Lifetime fee model of a Merkle tree and the accounts
+/// which are rolled over together with it.
+///
+/// Assumes that every transaction appends exactly one leaf.
+/// Rollover fees are charged per appended leaf,
+/// network fees per transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeModel {
+    /// Rent of all accounts which are rolled over with the tree.
+    pub rent: u64,
+    pub height: u32,
+    pub rollover_threshold: Option<u64>,
+    pub network_fee: u64,
+    pub network_fee_curve: NetworkFeeCurve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeReport {
+    pub rent: u64,
+    /// Rollover fee per transaction.
+    pub rollover_fee: u64,
+    /// Network fee per transaction.
+    pub network_fee: u64,
+    /// Number of transactions until the tree is ready for rollover.
+    pub lifetime_transactions: u64,
+    pub lifetime_rollover_revenue: u64,
+    pub lifetime_network_fee_revenue: u64,
+    /// Number of transactions after which the collected rollover fees
+    /// cover the rent of the next tree. None if no rollover fee is charged.
+    pub break_even_transactions: Option<u64>,
+}
+
+impl FeeModel {
+    /// Rollover fee charged per appended leaf.
+    pub fn rollover_fee(&self) -> Result<u64, MerkleTreeMetadataError> {
+        match self.rollover_threshold {
+            Some(rollover_threshold) => {
+                compute_rollover_fee(rollover_threshold, self.height, self.rent)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Network fee charged per transaction at a queue fill level in percent.
+    pub fn network_fee(&self, fill_level: u64) -> u64 {
+        self.network_fee_curve
+            .get_network_fee(self.network_fee, fill_level)
+    }
+
+    /// Number of leaves which can be appended
+    /// until the tree is ready for rollover.
+    pub fn lifetime_transactions(&self) -> u64 {
+        let capacity = 1u64 << self.height;
+        match self.rollover_threshold {
+            Some(rollover_threshold) => capacity * rollover_threshold / 100,
+            None => capacity,
+        }
+    }
+
+    pub fn break_even_transactions(&self) -> Result<Option<u64>, MerkleTreeMetadataError> {
+        let rollover_fee = self.rollover_fee()?;
+        if rollover_fee == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.rent.div_ceil(rollover_fee)))
+    }
+
+    pub fn report(&self, fill_level: u64) -> Result<FeeReport, MerkleTreeMetadataError> {
+        let rollover_fee = self.rollover_fee()?;
+        let network_fee = self.network_fee(fill_level);
+        let lifetime_transactions = self.lifetime_transactions();
+        Ok(FeeReport {
+            rent: self.rent,
+            rollover_fee,
+            network_fee,
+            lifetime_transactions,
+            lifetime_rollover_revenue: rollover_fee.saturating_mul(lifetime_transactions),
+            lifetime_network_fee_revenue: network_fee.saturating_mul(lifetime_transactions),
+            break_even_transactions: self.break_even_transactions()?,
+        })
+    }
+}
+
+#[test]
+fn test_network_fee_curve() {
+    let curve = NetworkFeeCurve {
+        min_fee: 1_000,
+        max_fee: 11_000,
+        low_fill_threshold: 20,
+        high_fill_threshold: 70,
+    };
+    curve.validate().unwrap();
+    assert_eq!(curve.get_network_fee(5_000, 0), 1_000);
+    assert_eq!(curve.get_network_fee(5_000, 20), 1_000);
+    assert_eq!(curve.get_network_fee(5_000, 45), 6_000);
+    assert_eq!(curve.get_network_fee(5_000, 69), 10_800);
+    assert_eq!(curve.get_network_fee(5_000, 70), 11_000);
+    assert_eq!(curve.get_network_fee(5_000, 100), 11_000);
+    // Trees without network fee are not charged.
+    assert_eq!(curve.get_network_fee(0, 100), 0);
+    // A disabled curve charges the static network fee.
+    let disabled_curve = NetworkFeeCurve::default();
+    disabled_curve.validate().unwrap();
+    assert_eq!(disabled_curve.get_network_fee(5_000, 100), 5_000);
+    // Step function.
+    let step_curve = NetworkFeeCurve {
+        low_fill_threshold: 50,
+        high_fill_threshold: 50,
+        ..curve
+    };
+    step_curve.validate().unwrap();
+    assert_eq!(step_curve.get_network_fee(5_000, 50), 1_000);
+    assert_eq!(step_curve.get_network_fee(5_000, 51), 11_000);
+
+    let invalid_curves = [
+        NetworkFeeCurve {
+            min_fee: 11_001,
+            ..curve
+        },
+        NetworkFeeCurve {
+            low_fill_threshold: 71,
+            ..curve
+        },
+        NetworkFeeCurve {
+            high_fill_threshold: 101,
+            ..curve
+        },
+    ];
+    for invalid_curve in invalid_curves {
+        assert_eq!(
+            invalid_curve.validate(),
+            Err(MerkleTreeMetadataError::InvalidNetworkFeeCurve)
+        );
+    }
+}
+
 #[test]
 fn test_compute_rollover_fee() {
     let rollover_threshold = 100;
@@ -87,3 +297,58 @@ fn test_address_tree_compute_rollover_fee() {
     );
     assert!(lifetime_lamports > (merkle_tree_lamports + queue_lamports));
 }
+
+#[test]
+fn test_fee_model() {
+    let merkle_tree_lamports = 9496836480;
+    let queue_lamports = 2293180800;
+    let cpi_context_lamports = 143487360;
+    let rent = merkle_tree_lamports + queue_lamports + cpi_context_lamports;
+    let fee_model = FeeModel {
+        rent,
+        height: 26,
+        rollover_threshold: Some(95),
+        network_fee: 5000,
+        network_fee_curve: NetworkFeeCurve::default(),
+    };
+    let report = fee_model.report(0).unwrap();
+    assert_eq!(
+        report.rollover_fee,
+        compute_rollover_fee(95, 26, rent).unwrap()
+    );
+    assert_eq!(report.network_fee, 5000);
+    assert_eq!(report.lifetime_transactions, (1 << 26) * 95 / 100);
+    assert_eq!(
+        report.lifetime_network_fee_revenue,
+        5000 * report.lifetime_transactions
+    );
+    assert!(report.lifetime_rollover_revenue >= rent);
+    let break_even_transactions = report.break_even_transactions.unwrap();
+    assert!(break_even_transactions <= report.lifetime_transactions);
+    assert!(break_even_transactions * report.rollover_fee >= rent);
+    assert!((break_even_transactions - 1) * report.rollover_fee < rent);
+
+    // The network fee curve applies per transaction.
+    let fee_model = FeeModel {
+        network_fee_curve: NetworkFeeCurve {
+            min_fee: 1000,
+            max_fee: 10_000,
+            low_fill_threshold: 10,
+            high_fill_threshold: 90,
+        },
+        ..fee_model
+    };
+    assert_eq!(fee_model.report(0).unwrap().network_fee, 1000);
+    assert_eq!(fee_model.report(100).unwrap().network_fee, 10_000);
+
+    // Trees without rollover threshold charge no rollover fee.
+    let fee_model = FeeModel {
+        rollover_threshold: None,
+        ..fee_model
+    };
+    let report = fee_model.report(0).unwrap();
+    assert_eq!(report.rollover_fee, 0);
+    assert_eq!(report.lifetime_transactions, 1 << 26);
+    assert_eq!(report.lifetime_rollover_revenue, 0);
+    assert_eq!(report.break_even_transactions, None);
+}
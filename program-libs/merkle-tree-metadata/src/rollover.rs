@@ -32,11 +32,15 @@ pub struct RolloverMetadata {
     /// The slot when the account was rolled over, a rolled over account should not be written to.
     pub rolledover_slot: u64,
     /// If current slot is greater than rolledover_slot + close_threshold and
-    /// the account is empty it can be closed. No 'close' functionality has been
-    /// implemented yet.
+    /// the account is empty it can be closed.
     pub close_threshold: u64,
-    /// Placeholder for bytes of additional accounts which are tied to the
-    /// Merkle trees operation and need to be rolled over as well.
+    /// Bytes of additional accounts which are tied to the Merkle trees
+    /// operation and need to be rolled over as well,
+    /// e.g. the cpi context account of a state Merkle tree.
+    /// Rent for these accounts is included in the rollover fee
+    /// and reimbursed with the rollover. Concurrent (v1) state Merkle trees
+    /// reimburse it only on rollovers by a registered program,
+    /// which creates the new cpi context account.
     pub additional_bytes: u64,
 }
 
@@ -80,21 +84,42 @@ impl RolloverMetadata {
         }
         Ok(())
     }
+
+    /// Checks that the account can be closed:
+    /// 1. close threshold is configured,
+    /// 2. the account is rolled over,
+    /// 3. current slot is greater than rolledover_slot + close_threshold.
+    ///
+    /// Emptiness checks are tree type specific.
+    pub fn check_closable(&self, current_slot: u64) -> Result<(), MerkleTreeMetadataError> {
+        if self.close_threshold == u64::MAX {
+            return Err(MerkleTreeMetadataError::CloseNotConfigured);
+        }
+        if self.rolledover_slot == u64::MAX {
+            return Err(MerkleTreeMetadataError::NotRolledOver);
+        }
+        if current_slot <= self.rolledover_slot.saturating_add(self.close_threshold) {
+            return Err(MerkleTreeMetadataError::NotReadyForClose);
+        }
+        Ok(())
+    }
 }
 
+/// Checks that the rollover fee collected until the rollover threshold
+/// covers rent of the queue, the Merkle tree and additional accounts.
 pub fn check_rollover_fee_sufficient(
     rollover_fee: u64,
     queue_rent: u64,
     merkle_tree_rent: u64,
+    additional_bytes_rent: u64,
     rollover_threshold: u64,
     height: u32,
 ) -> Result<(), MerkleTreeMetadataError> {
-    if rollover_threshold == 0 && rollover_fee >= queue_rent + merkle_tree_rent {
+    let rent = queue_rent + merkle_tree_rent + additional_bytes_rent;
+    if rollover_threshold == 0 && rollover_fee >= rent {
         return Ok(());
     }
-    if (rollover_fee * rollover_threshold * (2u64.pow(height))) / 100
-        < queue_rent + merkle_tree_rent
-    {
+    if (rollover_fee * rollover_threshold * (2u64.pow(height))) / 100 < rent {
         #[cfg(not(feature = "pinocchio"))]
         {
             use crate::msg;
@@ -103,10 +128,11 @@ pub fn check_rollover_fee_sufficient(
             msg!("height: {}", height);
             msg!("merkle_tree_rent: {}", merkle_tree_rent);
             msg!("queue_rent: {}", queue_rent);
+            msg!("additional_bytes_rent: {}", additional_bytes_rent);
             msg!(
                 "((rollover_fee * rollover_threshold * (2u64.pow(height))) / 100): {} < {} rent",
                 ((rollover_fee * rollover_threshold * (2u64.pow(height))) / 100),
-                queue_rent + merkle_tree_rent
+                rent
             );
         }
         return Err(MerkleTreeMetadataError::InsufficientRolloverFee);
@@ -152,6 +178,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_closable() {
+        let mut metadata = RolloverMetadata::new(0, 0, Some(95), 0, None, None);
+        assert_eq!(
+            metadata.check_closable(u64::MAX - 1),
+            Err(MerkleTreeMetadataError::CloseNotConfigured)
+        );
+        let mut metadata_with_close = RolloverMetadata::new(0, 0, Some(95), 0, Some(100), None);
+        assert_eq!(
+            metadata_with_close.check_closable(u64::MAX - 1),
+            Err(MerkleTreeMetadataError::NotRolledOver)
+        );
+        metadata.rollover().unwrap();
+        metadata_with_close.rollover().unwrap();
+        assert_eq!(
+            metadata.check_closable(u64::MAX - 1),
+            Err(MerkleTreeMetadataError::CloseNotConfigured)
+        );
+        // Mock rolledover_slot is 1.
+        assert_eq!(
+            metadata_with_close.check_closable(101),
+            Err(MerkleTreeMetadataError::NotReadyForClose)
+        );
+        assert_eq!(metadata_with_close.check_closable(102), Ok(()));
+    }
+
     #[test]
     fn test_check_rollover_fee_sufficient() {
         let queue_rent = 1_000_000_000;
@@ -166,6 +218,7 @@ mod tests {
             rollover_fee,
             queue_rent,
             merkle_tree_rent,
+            0,
             rollover_threshold,
             tree_height
         )
@@ -178,6 +231,7 @@ mod tests {
                     rollover_fee,
                     queue_rent,
                     merkle_tree_rent,
+                    0,
                     rollover_threshold,
                     invalid_height
                 ),
@@ -191,6 +245,7 @@ mod tests {
                     rollover_fee,
                     queue_rent,
                     merkle_tree_rent,
+                    0,
                     invalid_threshold,
                     tree_height
                 ),
@@ -204,6 +259,7 @@ mod tests {
                     rollover_fee,
                     invalid_queue_rent,
                     merkle_tree_rent,
+                    0,
                     rollover_threshold,
                     tree_height
                 ),
@@ -217,11 +273,38 @@ mod tests {
                     rollover_fee,
                     queue_rent,
                     merkle_tree_rent,
+                    0,
+                    rollover_threshold,
+                    tree_height
+                ),
+                Err(MerkleTreeMetadataError::InsufficientRolloverFee)
+            );
+        }
+        {
+            let additional_bytes_rent = 1_000_000_000;
+            assert_eq!(
+                check_rollover_fee_sufficient(
+                    rollover_fee,
+                    queue_rent,
+                    merkle_tree_rent,
+                    additional_bytes_rent,
                     rollover_threshold,
                     tree_height
                 ),
                 Err(MerkleTreeMetadataError::InsufficientRolloverFee)
             );
+            let rollover_fee = rollover_fee
+                + compute_rollover_fee(rollover_threshold, tree_height, additional_bytes_rent)
+                    .unwrap();
+            assert!(check_rollover_fee_sufficient(
+                rollover_fee,
+                queue_rent,
+                merkle_tree_rent,
+                additional_bytes_rent,
+                rollover_threshold,
+                tree_height
+            )
+            .is_ok());
         }
     }
 }
@@ -0,0 +1,53 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod zero_copy;
+
+/// Derives `light_zero_copy::borsh::Deserialize` for a struct with Borsh
+/// layout and generates its zero copy view `Z<StructName><'a>`.
+///
+/// Field types are mapped to zero copy types:
+/// - `u8` and `bool` are copied.
+/// - `u16`, `u32`, `u64`, `i16`, `i32`, `i64` are referenced as
+///   `Ref<&'a [u8], U16>` etc. little endian types, which have no alignment.
+/// - `[u8; N]` is referenced as `Ref<&'a [u8], [u8; N]>`.
+/// - `Vec<T>` of the primitives above is referenced as
+///   `ZeroCopySliceBorsh<'a, T>`, other `Vec<T>` are deserialized into a
+///   `Vec` of zero copy views.
+/// - `Option<T>` is deserialized into an `Option` of the zero copy view.
+/// - every other type must implement `Deserialize`, e.g. structs which
+///   derive `ZeroCopy`.
+///
+/// A field attribute `#[zero_copy(ty = Type)]` deserializes the field as
+/// `Type` instead. `Type` must implement `Deserialize` with the same layout
+/// as the Borsh serialization of the field and may use the lifetime `'a` of
+/// the zero copy view, e.g. to read a `Pubkey` of a foreign crate or a
+/// `Vec` of structs as a slice.
+///
+/// A field attribute `#[zero_copy(trailing)]` marks optional trailing data,
+/// e.g. fields which were appended to instruction data after clients were
+/// deployed. The field is deserialized as the default of its zero copy type
+/// if no bytes remain. Trailing fields must be the last fields of the struct
+/// and are not part of `MIN_SIZE`.
+///
+/// The generated code uses the `light_zero_copy` and `zerocopy` crates.
+///
+/// ```ignore
+/// #[derive(ZeroCopy, BorshSerialize)]
+/// pub struct InstructionData {
+///     pub amount: u64,
+///     pub recipients: Vec<[u8; 32]>,
+///     pub memo: Option<Vec<u8>>,
+/// }
+///
+/// let (data, remaining_bytes) = ZInstructionData::zero_copy_at(&bytes)?;
+/// ```
+#[proc_macro_derive(ZeroCopy, attributes(zero_copy))]
+pub fn derive_zero_copy(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    zero_copy::derive_zero_copy(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
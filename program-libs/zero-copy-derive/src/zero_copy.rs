@@ -0,0 +1,377 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Field, Fields, GenericArgument, PathArguments, Result,
+    Type,
+};
+
+pub(crate) fn derive_zero_copy(input: DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "ZeroCopy cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "ZeroCopy requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ZeroCopy can only be derived for structs",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let z_name = format_ident!("Z{}", name);
+    let vis = &input.vis;
+
+    let mut z_fields = Vec::with_capacity(fields.len());
+    let mut deserialize_fields = Vec::with_capacity(fields.len());
+    let mut field_names = Vec::with_capacity(fields.len());
+    let mut min_sizes = Vec::with_capacity(fields.len());
+    let mut is_trailing = false;
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let field_vis = &field.vis;
+        let attributes = field_attributes(field)?;
+        if is_trailing && !attributes.trailing {
+            return Err(Error::new_spanned(
+                field,
+                "ZeroCopy trailing fields must be the last fields of a struct",
+            ));
+        }
+        is_trailing = attributes.trailing;
+        let ty = match attributes.ty {
+            Some(ty) => ty,
+            None => deserialize_type(&field.ty)?,
+        };
+        z_fields.push(quote! {
+            #field_vis #ident: <#ty as ::light_zero_copy::borsh::Deserialize<'a>>::Output
+        });
+        if attributes.trailing {
+            deserialize_fields.push(quote! {
+                let (#ident, bytes) = if bytes.is_empty() {
+                    (::core::default::Default::default(), bytes)
+                } else {
+                    <#ty as ::light_zero_copy::borsh::Deserialize<'a>>::zero_copy_at(bytes)?
+                };
+            });
+        } else {
+            deserialize_fields.push(quote! {
+                let (#ident, bytes) =
+                    <#ty as ::light_zero_copy::borsh::Deserialize<'a>>::zero_copy_at(bytes)?;
+            });
+            min_sizes.push(quote! {
+                <#ty as ::light_zero_copy::borsh::Deserialize<'a>>::MIN_SIZE
+            });
+        }
+        field_names.push(ident);
+    }
+    let doc = format!(
+        " Zero copy view of [`{}`], same layout as its Borsh serialization.",
+        name
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #z_name<'a> {
+            #(#z_fields,)*
+        }
+
+        impl<'a> ::light_zero_copy::borsh::Deserialize<'a> for #name {
+            type Output = #z_name<'a>;
+            const MIN_SIZE: usize = 0 #(+ #min_sizes)*;
+
+            #[inline]
+            fn zero_copy_at(
+                bytes: &'a [u8],
+            ) -> ::core::result::Result<
+                (Self::Output, &'a [u8]),
+                ::light_zero_copy::errors::ZeroCopyError,
+            > {
+                #(#deserialize_fields)*
+                Ok((#z_name { #(#field_names,)* }, bytes))
+            }
+        }
+
+        impl<'a> ::light_zero_copy::borsh::Deserialize<'a> for #z_name<'a> {
+            type Output = Self;
+            const MIN_SIZE: usize = <#name as ::light_zero_copy::borsh::Deserialize<'a>>::MIN_SIZE;
+
+            #[inline]
+            fn zero_copy_at(
+                bytes: &'a [u8],
+            ) -> ::core::result::Result<
+                (Self::Output, &'a [u8]),
+                ::light_zero_copy::errors::ZeroCopyError,
+            > {
+                <#name as ::light_zero_copy::borsh::Deserialize<'a>>::zero_copy_at(bytes)
+            }
+        }
+    })
+}
+
+/// `#[zero_copy(..)]` field attributes.
+#[derive(Default)]
+struct FieldAttributes {
+    /// `ty = Type`, type which is deserialized instead of the field type.
+    ty: Option<Type>,
+    /// `trailing`, the field is optional trailing data and deserialized as
+    /// default if no bytes remain.
+    trailing: bool,
+}
+
+/// Parses the `#[zero_copy(..)]` attributes of a field.
+fn field_attributes(field: &Field) -> Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("zero_copy"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ty") {
+                attributes.ty = Some(meta.value()?.parse::<Type>()?);
+                Ok(())
+            } else if meta.path.is_ident("trailing") {
+                attributes.trailing = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported zero_copy attribute, expected `ty` or `trailing`"))
+            }
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// Returns the type which is deserialized for a field of type ty.
+fn deserialize_type(ty: &Type) -> Result<Type> {
+    if let Some(ty) = little_endian_type(ty) {
+        return Ok(ty);
+    }
+    match ty {
+        Type::Array(array) => {
+            if !is_ident(&array.elem, "u8") {
+                return Err(Error::new_spanned(
+                    ty,
+                    "ZeroCopy only supports arrays of u8, use a Vec or a struct instead",
+                ));
+            }
+            Ok(parse_quote!(::zerocopy::Ref<&'a [u8], #array>))
+        }
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let segment = type_path.path.segments.last().unwrap();
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+                    match arguments.args.first().unwrap() {
+                        GenericArgument::Type(inner) => Some(inner),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("Option", Some(inner)) => {
+                    let inner = deserialize_type(inner)?;
+                    Ok(parse_quote!(::core::option::Option<#inner>))
+                }
+                ("Vec", Some(inner)) => {
+                    if is_ident(inner, "u8") {
+                        Ok(parse_quote!(
+                            ::light_zero_copy::slice::ZeroCopySliceBorsh<'a, u8>
+                        ))
+                    } else if let Some(inner) = little_endian_type(inner) {
+                        Ok(parse_quote!(::light_zero_copy::slice::ZeroCopySliceBorsh<'a, #inner>))
+                    } else {
+                        let inner = deserialize_type(inner)?;
+                        Ok(parse_quote!(::std::vec::Vec<#inner>))
+                    }
+                }
+                _ => Ok(ty.clone()),
+            }
+        }
+        _ => Err(Error::new_spanned(
+            ty,
+            "ZeroCopy does not support this type",
+        )),
+    }
+}
+
+/// Integers wider than one byte are read as little endian types without
+/// alignment.
+fn little_endian_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let ident = type_path.path.get_ident()?;
+    match ident.to_string().as_str() {
+        "u16" => Some(parse_quote!(::zerocopy::little_endian::U16)),
+        "u32" => Some(parse_quote!(::zerocopy::little_endian::U32)),
+        "u64" => Some(parse_quote!(::zerocopy::little_endian::U64)),
+        "i16" => Some(parse_quote!(::zerocopy::little_endian::I16)),
+        "i32" => Some(parse_quote!(::zerocopy::little_endian::I32)),
+        "i64" => Some(parse_quote!(::zerocopy::little_endian::I64)),
+        _ => None,
+    }
+}
+
+fn is_ident(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.is_ident(name),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_type() {
+        let slice = quote!(::light_zero_copy::slice::ZeroCopySliceBorsh);
+        let cases: Vec<(Type, Type)> = vec![
+            (parse_quote!(u8), parse_quote!(u8)),
+            (parse_quote!(bool), parse_quote!(bool)),
+            (
+                parse_quote!(u64),
+                parse_quote!(::zerocopy::little_endian::U64),
+            ),
+            (
+                parse_quote!(i16),
+                parse_quote!(::zerocopy::little_endian::I16),
+            ),
+            (
+                parse_quote!([u8; 32]),
+                parse_quote!(::zerocopy::Ref<&'a [u8], [u8; 32]>),
+            ),
+            (parse_quote!(Vec<u8>), parse_quote!(#slice<'a, u8>)),
+            (
+                parse_quote!(Vec<u32>),
+                parse_quote!(#slice<'a, ::zerocopy::little_endian::U32>),
+            ),
+            (
+                parse_quote!(Option<Vec<u64>>),
+                parse_quote!(::core::option::Option<#slice<'a, ::zerocopy::little_endian::U64>>),
+            ),
+            (
+                parse_quote!(Vec<[u8; 32]>),
+                parse_quote!(::std::vec::Vec<::zerocopy::Ref<&'a [u8], [u8; 32]>>),
+            ),
+            (
+                parse_quote!(std::vec::Vec<Inner>),
+                parse_quote!(::std::vec::Vec<Inner>),
+            ),
+            (parse_quote!(Inner), parse_quote!(Inner)),
+        ];
+        for (ty, expected) in cases {
+            let ty = deserialize_type(&ty).unwrap();
+            assert_eq!(quote!(#ty).to_string(), quote!(#expected).to_string());
+        }
+    }
+
+    #[test]
+    fn test_field_attributes() {
+        let fields: Vec<Field> = match parse_quote!(
+            struct Fields {
+                #[zero_copy(ty = crate::pubkey::Pubkey)]
+                owner: Pubkey,
+                #[zero_copy(ty = ZeroCopySliceBorsh<'a, ZInner>)]
+                inner: Vec<Inner>,
+                #[doc = "no zero_copy attribute"]
+                amount: u64,
+                #[zero_copy(trailing)]
+                flag: bool,
+                #[zero_copy(ty = ZeroCopySliceBorsh<'a, u8>, trailing)]
+                data: Vec<u8>,
+            }
+        ) {
+            DeriveInput {
+                data: Data::Struct(data),
+                ..
+            } => data.fields.into_iter().collect(),
+            _ => unreachable!(),
+        };
+        let expected: Vec<(Option<Type>, bool)> = vec![
+            (Some(parse_quote!(crate::pubkey::Pubkey)), false),
+            (Some(parse_quote!(ZeroCopySliceBorsh<'a, ZInner>)), false),
+            (None, false),
+            (None, true),
+            (Some(parse_quote!(ZeroCopySliceBorsh<'a, u8>)), true),
+        ];
+        for (field, (expected_ty, expected_trailing)) in fields.iter().zip(expected) {
+            let attributes = field_attributes(field).unwrap();
+            let ty = attributes.ty;
+            assert_eq!(quote!(#ty).to_string(), quote!(#expected_ty).to_string());
+            assert_eq!(attributes.trailing, expected_trailing);
+        }
+    }
+
+    #[test]
+    fn failing_attribute_type() {
+        let input: DeriveInput = parse_quote!(
+            struct Fields {
+                #[zero_copy(skip)]
+                owner: Pubkey,
+            }
+        );
+        assert!(derive_zero_copy(input).is_err());
+    }
+
+    #[test]
+    fn failing_trailing_field_order() {
+        let input: DeriveInput = parse_quote!(
+            struct Fields {
+                #[zero_copy(trailing)]
+                flag: bool,
+                amount: u64,
+            }
+        );
+        assert!(derive_zero_copy(input).is_err());
+    }
+
+    #[test]
+    fn failing_deserialize_type() {
+        let cases: Vec<Type> = vec![
+            parse_quote!([u16; 2]),
+            parse_quote!((u8, u8)),
+            parse_quote!(&'static [u8]),
+        ];
+        for ty in cases {
+            assert!(deserialize_type(&ty).is_err());
+        }
+    }
+
+    #[test]
+    fn failing_derive_zero_copy() {
+        let cases: Vec<DeriveInput> = vec![
+            parse_quote!(
+                struct Generic<T> {
+                    a: T,
+                }
+            ),
+            parse_quote!(
+                struct Tuple(u8);
+            ),
+            parse_quote!(
+                struct Empty {}
+            ),
+            parse_quote!(
+                enum Enum {
+                    A,
+                }
+            ),
+        ];
+        for input in cases {
+            assert!(derive_zero_copy(input).is_err());
+        }
+    }
+}
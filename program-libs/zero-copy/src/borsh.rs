@@ -5,7 +5,7 @@ use core::{
 use std::vec::Vec;
 
 use zerocopy::{
-    little_endian::{U16, U32, U64},
+    little_endian::{I16, I32, I64, U16, U32, U64},
     FromBytes, Immutable, KnownLayout, Ref,
 };
 
@@ -16,12 +16,38 @@ where
     Self: Sized,
 {
     type Output;
+    /// Minimum number of bytes of a serialized value, used to check the
+    /// length prefix of a Vec before memory is allocated for its elements.
+    const MIN_SIZE: usize = 1;
 
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError>;
 }
 
+/// Checks that remaining_bytes can hold num_slices elements of at least
+/// min_element_size bytes each, before memory is allocated for them.
+/// Prevents that a length prefix of invalid data allocates a lot of heap
+/// memory.
+#[inline]
+pub fn check_vec_len(
+    num_slices: usize,
+    min_element_size: usize,
+    remaining_bytes: usize,
+) -> Result<(), ZeroCopyError> {
+    let required_bytes = num_slices
+        .checked_mul(min_element_size)
+        .ok_or(ZeroCopyError::LengthOverflow)?;
+    if required_bytes > remaining_bytes {
+        return Err(ZeroCopyError::InsufficientRemainingBytes(
+            required_bytes,
+            remaining_bytes,
+        ));
+    }
+    Ok(())
+}
+
 impl<'a, T: KnownLayout + Immutable + FromBytes> Deserialize<'a> for Ref<&'a [u8], T> {
     type Output = Ref<&'a [u8], T>;
+    const MIN_SIZE: usize = size_of::<T>();
 
     #[inline]
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), ZeroCopyError> {
@@ -64,11 +90,27 @@ impl Deserialize<'_> for u8 {
     }
 }
 
+impl Deserialize<'_> for bool {
+    type Output = Self;
+
+    /// Not a zero copy but cheaper, same as u8.
+    #[inline]
+    fn zero_copy_at(bytes: &[u8]) -> Result<(bool, &[u8]), ZeroCopyError> {
+        let (value, remaining_bytes) = u8::zero_copy_at(bytes)?;
+        match value {
+            0u8 => Ok((false, remaining_bytes)),
+            1u8 => Ok((true, remaining_bytes)),
+            _ => Err(ZeroCopyError::InvalidConversion),
+        }
+    }
+}
+
 macro_rules! impl_deserialize_for_primitive {
     ($($t:ty),*) => {
         $(
             impl<'a> Deserialize<'a> for $t {
                 type Output = Ref<&'a [u8], $t>;
+                const MIN_SIZE: usize = size_of::<$t>();
 
                 #[inline]
                 fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
@@ -80,16 +122,16 @@ macro_rules! impl_deserialize_for_primitive {
 }
 
 impl_deserialize_for_primitive!(u16, i16, u32, i32, u64, i64);
-impl_deserialize_for_primitive!(U16, U32, U64);
+impl_deserialize_for_primitive!(U16, U32, U64, I16, I32, I64);
 
 impl<'a, T: Deserialize<'a>> Deserialize<'a> for Vec<T> {
     type Output = Vec<T::Output>;
+    const MIN_SIZE: usize = size_of::<U32>();
     #[inline]
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         let (num_slices, mut bytes) = Ref::<&[u8], U32>::from_prefix(bytes)?;
         let num_slices = u32::from(*num_slices) as usize;
-        // TODO: add check that remaining data is enough to read num_slices
-        // This prevents agains invalid data allocating a lot of heap memory
+        check_vec_len(num_slices, T::MIN_SIZE, bytes.len())?;
         let mut slices = Vec::with_capacity(num_slices);
         for _ in 0..num_slices {
             let (slice, _bytes) = T::zero_copy_at(bytes)?;
@@ -100,6 +142,91 @@ impl<'a, T: Deserialize<'a>> Deserialize<'a> for Vec<T> {
     }
 }
 
+pub trait DeserializeMut<'a>
+where
+    Self: Sized,
+{
+    type Output;
+    /// Minimum number of bytes of a serialized value, see
+    /// Deserialize::MIN_SIZE.
+    const MIN_SIZE: usize = 1;
+
+    fn zero_copy_at_mut(bytes: &'a mut [u8])
+        -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError>;
+}
+
+impl<'a, T: KnownLayout + Immutable + FromBytes> DeserializeMut<'a> for Ref<&'a mut [u8], T> {
+    type Output = Ref<&'a mut [u8], T>;
+    const MIN_SIZE: usize = size_of::<T>();
+
+    #[inline]
+    fn zero_copy_at_mut(bytes: &'a mut [u8]) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        let (bytes, remaining_bytes) = Ref::<&mut [u8], T>::from_prefix(bytes)?;
+        Ok((bytes, remaining_bytes))
+    }
+}
+
+impl<'a, T: DeserializeMut<'a>> DeserializeMut<'a> for Option<T> {
+    type Output = Option<T::Output>;
+    #[inline]
+    fn zero_copy_at_mut(
+        bytes: &'a mut [u8],
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        if bytes.len() < size_of::<u8>() {
+            return Err(ZeroCopyError::ArraySize(1, bytes.len()));
+        }
+        let (option_byte, bytes) = bytes.split_at_mut(1);
+        Ok(match option_byte[0] {
+            0u8 => (None, bytes),
+            1u8 => {
+                let (value, bytes) = T::zero_copy_at_mut(bytes)?;
+                (Some(value), bytes)
+            }
+            _ => return Err(ZeroCopyError::InvalidOptionByte(option_byte[0])),
+        })
+    }
+}
+
+macro_rules! impl_deserialize_mut_for_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> DeserializeMut<'a> for $t {
+                type Output = Ref<&'a mut [u8], $t>;
+                const MIN_SIZE: usize = size_of::<$t>();
+
+                #[inline]
+                fn zero_copy_at_mut(bytes: &'a mut [u8]) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+                    Self::Output::zero_copy_at_mut(bytes)
+                }
+            }
+        )*
+    };
+}
+
+// u8 is referenced instead of copied so that it can be modified.
+impl_deserialize_mut_for_primitive!(u8, u16, i16, u32, i32, u64, i64);
+impl_deserialize_mut_for_primitive!(U16, U32, U64, I16, I32, I64);
+
+impl<'a, T: DeserializeMut<'a>> DeserializeMut<'a> for Vec<T> {
+    type Output = Vec<T::Output>;
+    const MIN_SIZE: usize = size_of::<U32>();
+    #[inline]
+    fn zero_copy_at_mut(
+        bytes: &'a mut [u8],
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        let (num_slices, mut bytes) = Ref::<&mut [u8], U32>::from_prefix(bytes)?;
+        let num_slices = u32::from(*num_slices) as usize;
+        check_vec_len(num_slices, T::MIN_SIZE, bytes.len())?;
+        let mut slices = Vec::with_capacity(num_slices);
+        for _ in 0..num_slices {
+            let (slice, _bytes) = T::zero_copy_at_mut(bytes)?;
+            bytes = _bytes;
+            slices.push(slice);
+        }
+        Ok((slices, bytes))
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct VecU8<T>(Vec<T>);
 impl<T> VecU8<T> {
@@ -128,6 +255,7 @@ impl<'a, T: Deserialize<'a>> Deserialize<'a> for VecU8<T> {
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self::Output, &'a [u8]), ZeroCopyError> {
         let (num_slices, mut bytes) = Ref::<&[u8], u8>::from_prefix(bytes)?;
         let num_slices = u32::from(*num_slices) as usize;
+        check_vec_len(num_slices, T::MIN_SIZE, bytes.len())?;
         let mut slices = Vec::with_capacity(num_slices);
         for _ in 0..num_slices {
             let (slice, _bytes) = T::zero_copy_at(bytes)?;
@@ -138,6 +266,26 @@ impl<'a, T: Deserialize<'a>> Deserialize<'a> for VecU8<T> {
     }
 }
 
+impl<'a, T: DeserializeMut<'a>> DeserializeMut<'a> for VecU8<T> {
+    type Output = Vec<T::Output>;
+
+    #[inline]
+    fn zero_copy_at_mut(
+        bytes: &'a mut [u8],
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        let (num_slices, mut bytes) = Ref::<&mut [u8], u8>::from_prefix(bytes)?;
+        let num_slices = u32::from(*num_slices) as usize;
+        check_vec_len(num_slices, T::MIN_SIZE, bytes.len())?;
+        let mut slices = Vec::with_capacity(num_slices);
+        for _ in 0..num_slices {
+            let (slice, _bytes) = T::zero_copy_at_mut(bytes)?;
+            bytes = _bytes;
+            slices.push(slice);
+        }
+        Ok((slices, bytes))
+    }
+}
+
 #[test]
 fn test_vecu8() {
     use std::vec;
@@ -188,6 +336,30 @@ fn test_deserialize_u8() {
     assert_eq!(res, Err(ZeroCopyError::ArraySize(1, 0)));
 }
 
+#[test]
+fn test_deserialize_bool() {
+    let (value, remaining) = bool::zero_copy_at(&[1, 2]).unwrap();
+    assert!(value);
+    assert_eq!(remaining, &[2]);
+    let (value, remaining) = bool::zero_copy_at(&[0]).unwrap();
+    assert!(!value);
+    assert_eq!(remaining, &[]);
+    let res = bool::zero_copy_at(&[2]);
+    assert_eq!(res, Err(ZeroCopyError::InvalidConversion));
+    let res = bool::zero_copy_at(&[]);
+    assert_eq!(res, Err(ZeroCopyError::ArraySize(1, 0)));
+}
+
+#[test]
+fn test_deserialize_i64() {
+    let bytes = (-2323i64).to_le_bytes();
+    let (value, remaining) = I64::zero_copy_at(bytes.as_slice()).unwrap();
+    assert_eq!(i64::from(*value), -2323i64);
+    assert_eq!(remaining, &[]);
+    let res = I64::zero_copy_at(&[0u8; 7]);
+    assert_eq!(res, Err(ZeroCopyError::Size));
+}
+
 #[test]
 fn test_deserialize_u16() {
     let bytes = 2323u16.to_le_bytes();
@@ -224,3 +396,153 @@ fn test_deserialize_vecu8() {
     assert_eq!(vec, std::vec![4, 5, 6]);
     assert_eq!(remaining, &[]);
 }
+
+#[test]
+fn test_deserialize_mut_ref() {
+    let mut bytes = [1, 0, 0, 0, 2];
+    {
+        let (mut ref_data, remaining) =
+            Ref::<&mut [u8], U32>::zero_copy_at_mut(&mut bytes).unwrap();
+        assert_eq!(u32::from(*ref_data), 1);
+        assert_eq!(remaining, &[2]);
+        *ref_data = U32::new(3);
+    }
+    assert_eq!(bytes, [3, 0, 0, 0, 2]);
+    let res = Ref::<&mut [u8], U32>::zero_copy_at_mut(&mut []);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::Size));
+}
+
+#[test]
+fn test_deserialize_mut_option() {
+    let mut bytes = [1, 2];
+    {
+        let (option_value, remaining) = Option::<u8>::zero_copy_at_mut(&mut bytes).unwrap();
+        assert_eq!(remaining, &[]);
+        let mut value = option_value.unwrap();
+        assert_eq!(*value, 2);
+        *value = 3;
+    }
+    assert_eq!(bytes, [1, 3]);
+    let mut bytes = [0];
+    let (option_value, remaining) = Option::<u8>::zero_copy_at_mut(&mut bytes).unwrap();
+    assert!(option_value.is_none());
+    assert_eq!(remaining, &[]);
+    let res = Option::<u8>::zero_copy_at_mut(&mut []);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::ArraySize(1, 0)));
+    let res = Option::<u8>::zero_copy_at_mut(&mut [2, 0]);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::InvalidOptionByte(2)));
+}
+
+#[test]
+fn test_deserialize_mut_u64() {
+    let mut bytes = 2323u64.to_le_bytes();
+    {
+        let (mut value, remaining) = U64::zero_copy_at_mut(bytes.as_mut_slice()).unwrap();
+        assert_eq!(u64::from(*value), 2323u64);
+        assert_eq!(remaining, &[]);
+        *value = U64::new(1);
+    }
+    assert_eq!(bytes, 1u64.to_le_bytes());
+    let res = u64::zero_copy_at_mut(&mut [0u8; 7]);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::Size));
+}
+
+#[test]
+fn test_deserialize_mut_vec() {
+    let mut bytes = [2, 0, 0, 0, 1, 2, 3];
+    {
+        let (mut vec, remaining) = Vec::<u8>::zero_copy_at_mut(&mut bytes).unwrap();
+        assert_eq!(vec.iter().map(|x| **x).collect::<Vec<_>>(), std::vec![1, 2]);
+        assert_eq!(remaining, &[3]);
+        *vec[1] = 4;
+    }
+    assert_eq!(bytes, [2, 0, 0, 0, 1, 4, 3]);
+    let res = Vec::<u8>::zero_copy_at_mut(&mut [2, 0, 0, 0, 1]);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::Size));
+}
+
+#[test]
+fn test_deserialize_mut_vecu8() {
+    let mut bytes = [3, 4, 5, 6];
+    {
+        let (mut vec, remaining) = VecU8::<u8>::zero_copy_at_mut(&mut bytes).unwrap();
+        assert_eq!(vec.len(), 3);
+        assert_eq!(remaining, &[]);
+        *vec[0] = 7;
+    }
+    assert_eq!(bytes, [3, 7, 5, 6]);
+}
+
+#[test]
+fn test_check_vec_len() {
+    assert_eq!(check_vec_len(0, 1, 0), Ok(()));
+    assert_eq!(check_vec_len(4, 8, 32), Ok(()));
+    assert_eq!(
+        check_vec_len(5, 8, 32),
+        Err(ZeroCopyError::InsufficientRemainingBytes(40, 32))
+    );
+    assert_eq!(
+        check_vec_len(usize::MAX, 2, 32),
+        Err(ZeroCopyError::LengthOverflow)
+    );
+}
+
+#[test]
+fn test_deserialize_vec_invalid_length() {
+    // Length u32::MAX must not allocate u32::MAX elements.
+    let bytes = [255, 255, 255, 255, 1, 2];
+    let res = Vec::<u8>::zero_copy_at(&bytes);
+    assert_eq!(
+        res,
+        Err(ZeroCopyError::InsufficientRemainingBytes(
+            u32::MAX as usize,
+            2
+        ))
+    );
+    let bytes = [3, 0, 0, 0, 1, 2];
+    let res = Vec::<u8>::zero_copy_at(&bytes);
+    assert_eq!(res, Err(ZeroCopyError::InsufficientRemainingBytes(3, 2)));
+    let mut bytes = [255, 255, 255, 255, 1, 2];
+    let res = Vec::<u8>::zero_copy_at_mut(&mut bytes);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(
+            u32::MAX as usize,
+            2
+        ))
+    );
+    let bytes = [3, 1, 2];
+    let res = VecU8::<u8>::zero_copy_at(&bytes);
+    assert_eq!(res, Err(ZeroCopyError::InsufficientRemainingBytes(3, 2)));
+}
+
+#[test]
+fn test_deserialize_vec_invalid_length_multi_byte_elements() {
+    // 2^24 u64 elements require 2^27 bytes, only 8 bytes remain.
+    let bytes = [0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+    let res = Vec::<u64>::zero_copy_at(&bytes);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(1 << 27, 8))
+    );
+    // 2 u64 elements don't fit into 12 bytes even though 2 < 12.
+    let bytes = [2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0];
+    let res = Vec::<u64>::zero_copy_at(&bytes);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(16, 12))
+    );
+    // Every nested vec has a 4 byte length prefix.
+    let mut bytes = [0, 0, 0, 1, 0, 0, 0, 0];
+    let res = Vec::<Vec<u8>>::zero_copy_at_mut(&mut bytes);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(1 << 26, 4))
+    );
+    let bytes = [3, 1, 0, 0, 0, 2, 0, 0, 0];
+    let res = VecU8::<u32>::zero_copy_at(&bytes);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(12, 8))
+    );
+}
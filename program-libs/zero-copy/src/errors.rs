@@ -30,6 +30,12 @@ pub enum ZeroCopyError {
     LengthGreaterThanCapacity,
     #[error("Current index is greater than length.")]
     CurrentIndexGreaterThanLength,
+    #[error("Length overflows the addressable memory.")]
+    LengthOverflow,
+    #[error("Length requires {0} bytes, but only {1} bytes remain.")]
+    InsufficientRemainingBytes(usize, usize),
+    #[error("Section capacity {0} does not match the layout capacity {1}.")]
+    SectionCapacityMismatch(u64, u64),
 }
 
 impl From<ZeroCopyError> for u32 {
@@ -48,6 +54,9 @@ impl From<ZeroCopyError> for u32 {
             ZeroCopyError::InvalidCapacity => 15012,
             ZeroCopyError::LengthGreaterThanCapacity => 15013,
             ZeroCopyError::CurrentIndexGreaterThanLength => 15014,
+            ZeroCopyError::LengthOverflow => 15015,
+            ZeroCopyError::InsufficientRemainingBytes(_, _) => 15016,
+            ZeroCopyError::SectionCapacityMismatch(_, _) => 15017,
         }
     }
 }
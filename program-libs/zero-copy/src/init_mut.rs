@@ -0,0 +1,216 @@
+use core::mem::size_of;
+use std::vec::Vec;
+
+use zerocopy::{
+    little_endian::{I16, I32, I64, U16, U32, U64},
+    FromBytes, Immutable, KnownLayout, Ref,
+};
+
+use crate::errors::ZeroCopyError;
+
+/// Initializes the Borsh layout of Self in a caller provided buffer and
+/// returns a mutable zero copy view to fill it.
+/// Counterpart of `Deserialize`, bytes written through the view can be
+/// deserialized with Borsh or `Deserialize`.
+pub trait ZeroCopyNew<'a>
+where
+    Self: Sized,
+{
+    /// Sizes of the variable length fields, e.g. vector lengths and option presence.
+    type ZeroCopyConfig;
+    type Output;
+
+    /// Returns the number of bytes required to store Self with config.
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize;
+
+    /// Writes vector lengths and option bytes of config into bytes.
+    /// Bytes are expected to be zeroed.
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError>;
+}
+
+impl<'a, T: KnownLayout + Immutable + FromBytes> ZeroCopyNew<'a> for Ref<&'a mut [u8], T> {
+    type ZeroCopyConfig = ();
+    type Output = Ref<&'a mut [u8], T>;
+
+    fn byte_len(_config: &Self::ZeroCopyConfig) -> usize {
+        size_of::<T>()
+    }
+
+    #[inline]
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        _config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        let (bytes, remaining_bytes) = Ref::<&mut [u8], T>::from_prefix(bytes)?;
+        Ok((bytes, remaining_bytes))
+    }
+}
+
+impl<'a, T: ZeroCopyNew<'a>> ZeroCopyNew<'a> for Option<T> {
+    /// None -> writes option byte 0, Some -> writes option byte 1 and
+    /// initializes the value.
+    type ZeroCopyConfig = Option<T::ZeroCopyConfig>;
+    type Output = Option<T::Output>;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        size_of::<u8>() + config.as_ref().map(T::byte_len).unwrap_or_default()
+    }
+
+    #[inline]
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        if bytes.len() < size_of::<u8>() {
+            return Err(ZeroCopyError::InsufficientMemoryAllocated(bytes.len(), 1));
+        }
+        let (option_byte, bytes) = bytes.split_at_mut(1);
+        match config {
+            Some(config) => {
+                option_byte[0] = 1;
+                let (value, bytes) = T::new_zero_copy(bytes, config)?;
+                Ok((Some(value), bytes))
+            }
+            None => {
+                option_byte[0] = 0;
+                Ok((None, bytes))
+            }
+        }
+    }
+}
+
+macro_rules! impl_zero_copy_new_for_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> ZeroCopyNew<'a> for $t {
+                type ZeroCopyConfig = ();
+                type Output = Ref<&'a mut [u8], $t>;
+
+                fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+                    Self::Output::byte_len(config)
+                }
+
+                #[inline]
+                fn new_zero_copy(
+                    bytes: &'a mut [u8],
+                    config: Self::ZeroCopyConfig,
+                ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+                    Self::Output::new_zero_copy(bytes, config)
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_copy_new_for_primitive!(u8, u16, i16, u32, i32, u64, i64);
+impl_zero_copy_new_for_primitive!(U16, U32, U64, I16, I32, I64);
+
+impl<'a> ZeroCopyNew<'a> for bool {
+    type ZeroCopyConfig = ();
+    /// Borsh bools are one byte 0 (false) or 1 (true).
+    type Output = Ref<&'a mut [u8], u8>;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        Self::Output::byte_len(config)
+    }
+
+    #[inline]
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        Self::Output::new_zero_copy(bytes, config)
+    }
+}
+
+/// Use ZeroCopySliceMutBorsh for vectors of primitives,
+/// it doesn't allocate a vector of views.
+impl<'a, T: ZeroCopyNew<'a>> ZeroCopyNew<'a> for Vec<T> {
+    /// One config per element.
+    type ZeroCopyConfig = Vec<T::ZeroCopyConfig>;
+    type Output = Vec<T::Output>;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        size_of::<U32>() + config.iter().map(T::byte_len).sum::<usize>()
+    }
+
+    #[inline]
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        let (mut num_slices, mut bytes) = Ref::<&mut [u8], U32>::from_prefix(bytes)?;
+        let len: u32 = config
+            .len()
+            .try_into()
+            .map_err(|_| ZeroCopyError::InvalidConversion)?;
+        *num_slices = U32::new(len);
+        let mut slices = Vec::with_capacity(config.len());
+        for config in config {
+            let (slice, _bytes) = T::new_zero_copy(bytes, config)?;
+            bytes = _bytes;
+            slices.push(slice);
+        }
+        Ok((slices, bytes))
+    }
+}
+
+#[test]
+fn test_new_zero_copy_primitive() {
+    let mut bytes = [0u8; 9];
+    assert_eq!(u64::byte_len(&()), 8);
+    assert_eq!(U64::byte_len(&()), 8);
+    {
+        let (mut value, remaining) = U64::new_zero_copy(&mut bytes, ()).unwrap();
+        *value = U64::new(2323);
+        assert_eq!(remaining, &[0]);
+    }
+    assert_eq!(bytes[..8], 2323u64.to_le_bytes());
+    let res = U64::new_zero_copy(&mut [0u8; 7], ());
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::Size));
+}
+
+#[test]
+fn test_new_zero_copy_option() {
+    let mut bytes = [0u8; 3];
+    assert_eq!(Option::<U16>::byte_len(&Some(())), 3);
+    assert_eq!(Option::<U16>::byte_len(&None), 1);
+    {
+        let (value, remaining) = Option::<U16>::new_zero_copy(&mut bytes, Some(())).unwrap();
+        let mut value = value.unwrap();
+        *value = U16::new(2);
+        assert!(remaining.is_empty());
+    }
+    assert_eq!(bytes, [1, 2, 0]);
+    {
+        let (value, remaining) = Option::<U16>::new_zero_copy(&mut bytes, None).unwrap();
+        assert!(value.is_none());
+        assert_eq!(remaining.len(), 2);
+    }
+    assert_eq!(bytes[0], 0);
+    let res = Option::<U16>::new_zero_copy(&mut [], None);
+    assert_eq!(
+        res.map(|_| ()),
+        Err(ZeroCopyError::InsufficientMemoryAllocated(0, 1))
+    );
+}
+
+#[test]
+fn test_new_zero_copy_vec() {
+    let config = std::vec![Some(()), None, Some(())];
+    let byte_len = Vec::<Option<u8>>::byte_len(&config);
+    assert_eq!(byte_len, 4 + 2 + 1 + 2);
+    let mut bytes = std::vec![0u8; byte_len];
+    {
+        let (mut vec, remaining) = Vec::<Option<u8>>::new_zero_copy(&mut bytes, config).unwrap();
+        assert!(remaining.is_empty());
+        **vec[0].as_mut().unwrap() = 1;
+        **vec[2].as_mut().unwrap() = 2;
+    }
+    assert_eq!(bytes, [3, 0, 0, 0, 1, 1, 0, 1, 2]);
+    let res = Vec::<u8>::new_zero_copy(&mut [0u8; 5], std::vec![(); 2]);
+    assert_eq!(res.map(|_| ()), Err(ZeroCopyError::Size));
+}
@@ -0,0 +1,381 @@
+use core::mem::size_of;
+
+use zerocopy::{FromBytes, Immutable, KnownLayout, Ref};
+
+use crate::{
+    cyclic_vec::ZeroCopyCyclicVec, errors::ZeroCopyError, slice_mut::ZeroCopySliceMut,
+    vec::ZeroCopyVec, ZeroCopyTraits,
+};
+
+/// Section of a zero copy account, e.g. a metadata struct, a vector or
+/// raw bytes. Tuples and arrays of sections are sections as well,
+/// the layout of an account is declared as one tuple of its sections.
+/// Account size, init and from bytes paths are derived from the same
+/// layout and config and cannot drift apart.
+///
+/// ```ignore
+/// type Sections<'a> = (
+///     ZeroCopyCyclicVecU64<'a, [u8; 32]>,
+///     [&'a mut [u8]; 2],
+///     [ZeroCopyVecU64<'a, [u8; 32]>; 2],
+/// );
+///
+/// let config = (root_history_capacity, [bloom_filter_size; 2], [num_zkp_batches; 2]);
+/// let account_size = Sections::section_size(&config);
+/// let ((root_history, bloom_filter_stores, hash_chain_stores), _) =
+///     Sections::section_from_bytes(account_data, &config)?;
+/// ```
+pub trait ZeroCopySection<'a>
+where
+    Self: Sized,
+{
+    /// Capacities of the section, e.g. the vector capacity.
+    type Config;
+
+    /// Returns the number of bytes of the section with config.
+    fn section_size(config: &Self::Config) -> usize;
+
+    /// Initializes the section with config in zeroed bytes.
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError>;
+
+    /// Deserializes the section and checks that it matches config.
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError>;
+}
+
+/// Metadata struct.
+impl<'a, T: KnownLayout + Immutable + FromBytes> ZeroCopySection<'a> for Ref<&'a mut [u8], T> {
+    type Config = ();
+
+    fn section_size(_config: &Self::Config) -> usize {
+        size_of::<T>()
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        _config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Ok(Ref::<&'a mut [u8], T>::from_prefix(bytes)?)
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::init_section(bytes, config)
+    }
+}
+
+/// Raw bytes, e.g. a bloom filter store.
+/// Config is the number of bytes.
+impl<'a> ZeroCopySection<'a> for &'a mut [u8] {
+    type Config = usize;
+
+    fn section_size(config: &Self::Config) -> usize {
+        *config
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        if bytes.len() < *config {
+            return Err(ZeroCopyError::InsufficientMemoryAllocated(
+                bytes.len(),
+                *config,
+            ));
+        }
+        Ok(bytes.split_at_mut(*config))
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::init_section(bytes, config)
+    }
+}
+
+/// Config is the vector capacity.
+impl<'a, L, T, const PAD: bool> ZeroCopySection<'a> for ZeroCopyVec<'a, L, T, PAD>
+where
+    L: ZeroCopyTraits,
+    T: ZeroCopyTraits,
+    u64: From<L> + TryInto<L>,
+{
+    type Config = L;
+
+    fn section_size(config: &Self::Config) -> usize {
+        Self::required_size_for_capacity(*config)
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::new_at(*config, bytes)
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        let (vec, bytes) = Self::from_bytes_at(bytes)?;
+        check_section_capacity(vec.capacity(), *config)?;
+        Ok((vec, bytes))
+    }
+}
+
+/// Config is the vector capacity.
+impl<'a, L, T, const PAD: bool> ZeroCopySection<'a> for ZeroCopyCyclicVec<'a, L, T, PAD>
+where
+    L: ZeroCopyTraits,
+    T: ZeroCopyTraits,
+    u64: From<L> + TryInto<L>,
+{
+    type Config = L;
+
+    fn section_size(config: &Self::Config) -> usize {
+        Self::required_size_for_capacity(*config)
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::new_at(*config, bytes)
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        let (vec, bytes) = Self::from_bytes_at(bytes)?;
+        check_section_capacity(vec.capacity(), *config)?;
+        Ok((vec, bytes))
+    }
+}
+
+/// Config is the slice length.
+impl<'a, L, T, const PAD: bool> ZeroCopySection<'a> for ZeroCopySliceMut<'a, L, T, PAD>
+where
+    L: ZeroCopyTraits,
+    T: ZeroCopyTraits,
+    u64: From<L>,
+{
+    type Config = L;
+
+    fn section_size(config: &Self::Config) -> usize {
+        Self::required_size_for_capacity(*config)
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::new_at(*config, bytes)
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        let (slice, bytes) = Self::from_bytes_at(bytes)?;
+        check_section_capacity(slice.len(), *config)?;
+        Ok((slice, bytes))
+    }
+}
+
+/// Sections are stored one after the other.
+impl<'a, S: ZeroCopySection<'a>, const N: usize> ZeroCopySection<'a> for [S; N] {
+    /// One config per section.
+    type Config = [S::Config; N];
+
+    fn section_size(config: &Self::Config) -> usize {
+        config.iter().map(S::section_size).sum()
+    }
+
+    #[inline]
+    fn init_section(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        sections_at(bytes, config, S::init_section)
+    }
+
+    #[inline]
+    fn section_from_bytes(
+        bytes: &'a mut [u8],
+        config: &Self::Config,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        sections_at(bytes, config, S::section_from_bytes)
+    }
+}
+
+type SectionAt<'a, S> = fn(
+    &'a mut [u8],
+    &<S as ZeroCopySection<'a>>::Config,
+) -> Result<(S, &'a mut [u8]), ZeroCopyError>;
+
+#[inline]
+fn sections_at<'a, S: ZeroCopySection<'a>, const N: usize>(
+    mut bytes: &'a mut [u8],
+    config: &[S::Config; N],
+    section_at: SectionAt<'a, S>,
+) -> Result<([S; N], &'a mut [u8]), ZeroCopyError> {
+    // Sections are built on the stack, every option is set
+    // once the loop completes without error.
+    let mut sections: [Option<S>; N] = core::array::from_fn(|_| None);
+    for (section, config) in sections.iter_mut().zip(config.iter()) {
+        let (value, remaining_bytes) = section_at(bytes, config)?;
+        bytes = remaining_bytes;
+        *section = Some(value);
+    }
+    Ok((sections.map(|section| section.unwrap()), bytes))
+}
+
+fn check_section_capacity<L>(capacity: usize, config: L) -> Result<(), ZeroCopyError>
+where
+    u64: From<L>,
+{
+    let expected_capacity = u64::from(config);
+    if capacity as u64 != expected_capacity {
+        return Err(ZeroCopyError::SectionCapacityMismatch(
+            capacity as u64,
+            expected_capacity,
+        ));
+    }
+    Ok(())
+}
+
+macro_rules! impl_zero_copy_section_for_tuple {
+    ($($section:ident $value:ident $index:tt),+) => {
+        /// Sections are stored in tuple order.
+        impl<'a, $($section: ZeroCopySection<'a>),+> ZeroCopySection<'a> for ($($section,)+) {
+            type Config = ($($section::Config,)+);
+
+            fn section_size(config: &Self::Config) -> usize {
+                0 $(+ $section::section_size(&config.$index))+
+            }
+
+            #[inline]
+            fn init_section(
+                bytes: &'a mut [u8],
+                config: &Self::Config,
+            ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+                $(let ($value, bytes) = $section::init_section(bytes, &config.$index)?;)+
+                Ok((($($value,)+), bytes))
+            }
+
+            #[inline]
+            fn section_from_bytes(
+                bytes: &'a mut [u8],
+                config: &Self::Config,
+            ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+                $(let ($value, bytes) = $section::section_from_bytes(bytes, &config.$index)?;)+
+                Ok((($($value,)+), bytes))
+            }
+        }
+    };
+}
+
+impl_zero_copy_section_for_tuple!(A a 0);
+impl_zero_copy_section_for_tuple!(A a 0, B b 1);
+impl_zero_copy_section_for_tuple!(A a 0, B b 1, C c 2);
+impl_zero_copy_section_for_tuple!(A a 0, B b 1, C c 2, D d 3);
+impl_zero_copy_section_for_tuple!(A a 0, B b 1, C c 2, D d 3, E e 4);
+impl_zero_copy_section_for_tuple!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5);
+
+#[cfg(test)]
+mod test {
+    use zerocopy::little_endian::U64;
+
+    use super::*;
+    use crate::{cyclic_vec::ZeroCopyCyclicVecU64, vec::ZeroCopyVecU64};
+
+    type TestSections<'a> = (
+        Ref<&'a mut [u8], U64>,
+        ZeroCopyCyclicVecU64<'a, [u8; 32]>,
+        [&'a mut [u8]; 2],
+        [ZeroCopyVecU64<'a, [u8; 32]>; 2],
+    );
+
+    #[test]
+    fn test_section_size() {
+        let config = ((), 3, [8, 16], [2, 1]);
+        let expected_size = 8
+            + ZeroCopyCyclicVecU64::<[u8; 32]>::required_size_for_capacity(3)
+            + 8
+            + 16
+            + ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(2)
+            + ZeroCopyVecU64::<[u8; 32]>::required_size_for_capacity(1);
+        assert_eq!(TestSections::section_size(&config), expected_size);
+    }
+
+    #[test]
+    fn test_init_and_from_bytes() {
+        let config = ((), 3, [8, 16], [2, 1]);
+        let mut bytes = std::vec![0u8; TestSections::section_size(&config) + 1];
+        {
+            let (
+                (mut value, mut root_history, bloom_filter_stores, mut hash_chain_stores),
+                remaining,
+            ) = TestSections::init_section(&mut bytes, &config).unwrap();
+            assert_eq!(remaining.len(), 1);
+            *value = U64::new(1);
+            root_history.push([2u8; 32]);
+            assert_eq!(bloom_filter_stores[0].len(), 8);
+            assert_eq!(bloom_filter_stores[1].len(), 16);
+            bloom_filter_stores[1].copy_from_slice(&[3u8; 16]);
+            hash_chain_stores[1].push([4u8; 32]).unwrap();
+        }
+        let ((value, root_history, bloom_filter_stores, hash_chain_stores), remaining) =
+            TestSections::section_from_bytes(&mut bytes, &config).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(*value, U64::new(1));
+        assert_eq!(root_history.as_slice(), &[[2u8; 32]]);
+        assert_eq!(bloom_filter_stores[0], &[0u8; 8]);
+        assert_eq!(bloom_filter_stores[1], &[3u8; 16]);
+        assert!(hash_chain_stores[0].is_empty());
+        assert_eq!(hash_chain_stores[1].as_slice(), &[[4u8; 32]]);
+    }
+
+    #[test]
+    fn test_from_bytes_capacity_mismatch() {
+        let config = ((), 3, [8, 16], [2, 1]);
+        let mut bytes = std::vec![0u8; TestSections::section_size(&config)];
+        TestSections::init_section(&mut bytes, &config).unwrap();
+        let invalid_config = ((), 3, [8, 16], [1, 2]);
+        let res = TestSections::section_from_bytes(&mut bytes, &invalid_config);
+        assert_eq!(
+            res.map(|_| ()),
+            Err(ZeroCopyError::SectionCapacityMismatch(2, 1))
+        );
+    }
+
+    #[test]
+    fn test_insufficient_bytes() {
+        let config = [8usize, 16];
+        let mut bytes = [0u8; 20];
+        let res = <[&mut [u8]; 2]>::section_from_bytes(&mut bytes, &config);
+        assert_eq!(
+            res.map(|_| ()),
+            Err(ZeroCopyError::InsufficientMemoryAllocated(12, 16))
+        );
+    }
+}
@@ -9,12 +9,34 @@ pub mod vec;
 use core::mem::{align_of, size_of};
 #[cfg(feature = "std")]
 pub mod borsh;
+#[cfg(feature = "std")]
+pub mod init_mut;
+#[cfg(feature = "std")]
+pub mod layout;
+#[cfg(feature = "derive")]
+pub use light_zero_copy_derive::ZeroCopy;
 
+use errors::ZeroCopyError;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 #[cfg(feature = "std")]
 extern crate std;
 
+/// Returns the number of elements and their size in bytes for a length read
+/// from untrusted bytes, errors instead of overflowing.
+#[inline]
+pub(crate) fn checked_data_size<L, T>(length: L) -> Result<(usize, usize), ZeroCopyError>
+where
+    u64: From<L>,
+{
+    let usize_len =
+        usize::try_from(u64::from(length)).map_err(|_| ZeroCopyError::LengthOverflow)?;
+    let data_size = usize_len
+        .checked_mul(size_of::<T>())
+        .ok_or(ZeroCopyError::LengthOverflow)?;
+    Ok((usize_len, data_size))
+}
+
 pub fn add_padding<LEN, T>(offset: &mut usize) {
     let padding = align_of::<T>().saturating_sub(size_of::<LEN>());
     *offset += padding;
@@ -4,7 +4,7 @@ use std::vec::Vec;
 
 use zerocopy::{little_endian::U32, Ref};
 
-use crate::{add_padding, errors::ZeroCopyError, ZeroCopyTraits};
+use crate::{add_padding, checked_data_size, errors::ZeroCopyError, ZeroCopyTraits};
 
 pub type ZeroCopySliceU64<'a, T> = ZeroCopySlice<'a, u64, T>;
 pub type ZeroCopySliceU32<'a, T> = ZeroCopySlice<'a, u32, T>;
@@ -47,12 +47,11 @@ where
 
         let (meta_data, bytes) = bytes.split_at(metadata_size);
         let (length, _padding) = Ref::<&[u8], L>::from_prefix(meta_data)?;
-        let usize_len: usize = u64::from(*length) as usize;
-        let full_vector_size = Self::data_size(*length);
+        let (usize_len, full_vector_size) = checked_data_size::<L, T>(*length)?;
         if bytes.len() < full_vector_size {
             return Err(ZeroCopyError::InsufficientMemoryAllocated(
                 bytes.len() + metadata_size,
-                full_vector_size + metadata_size,
+                full_vector_size.saturating_add(metadata_size),
             ));
         }
         let (bytes, remaining_bytes) = Ref::<&[u8], [T]>::from_prefix_with_elems(bytes, usize_len)?;
@@ -200,6 +199,7 @@ impl<'a, T: ZeroCopyTraits + crate::borsh::Deserialize<'a>> crate::borsh::Deseri
     for ZeroCopySliceBorsh<'a, T>
 {
     type Output = Self;
+    const MIN_SIZE: usize = size_of::<U32>();
 
     fn zero_copy_at(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), ZeroCopyError> {
         ZeroCopySliceBorsh::from_bytes_at(bytes)
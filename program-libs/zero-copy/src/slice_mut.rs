@@ -9,7 +9,7 @@ use std::vec::Vec;
 
 use zerocopy::{little_endian::U32, Ref};
 
-use crate::{add_padding, errors::ZeroCopyError, ZeroCopyTraits};
+use crate::{add_padding, checked_data_size, errors::ZeroCopyError, ZeroCopyTraits};
 
 pub type ZeroCopySliceMutU64<'a, T> = ZeroCopySliceMut<'a, u64, T>;
 pub type ZeroCopySliceMutU32<'a, T> = ZeroCopySliceMut<'a, u32, T>;
@@ -71,12 +71,11 @@ where
 
         let (meta_data, bytes) = bytes.split_at_mut(metadata_size);
         let (length, _padding) = Ref::<&[u8], L>::from_prefix(meta_data)?;
-        let usize_len: usize = u64::from(*length) as usize;
-        let full_vector_size = Self::data_size(*length);
+        let (usize_len, full_vector_size) = checked_data_size::<L, T>(*length)?;
         if bytes.len() < full_vector_size {
             return Err(ZeroCopyError::InsufficientMemoryAllocated(
                 bytes.len() + metadata_size,
-                full_vector_size + metadata_size,
+                full_vector_size.saturating_add(metadata_size),
             ));
         }
         let (bytes, remaining_bytes) =
@@ -276,3 +275,33 @@ where
         write!(f, "{:?}", self.as_slice())
     }
 }
+
+#[cfg(feature = "std")]
+impl<'a, T: ZeroCopyTraits + crate::borsh::DeserializeMut<'a>> crate::borsh::DeserializeMut<'a>
+    for ZeroCopySliceMutBorsh<'a, T>
+{
+    type Output = Self;
+    const MIN_SIZE: usize = size_of::<U32>();
+
+    fn zero_copy_at_mut(bytes: &'a mut [u8]) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        ZeroCopySliceMutBorsh::from_bytes_at(bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: ZeroCopyTraits> crate::init_mut::ZeroCopyNew<'a> for ZeroCopySliceMutBorsh<'a, T> {
+    /// Number of elements.
+    type ZeroCopyConfig = u32;
+    type Output = Self;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        Self::required_size_for_capacity(U32::new(*config))
+    }
+
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self, &'a mut [u8]), ZeroCopyError> {
+        Self::new_at(U32::new(config), bytes)
+    }
+}
@@ -9,7 +9,7 @@ use std::vec::Vec;
 
 use zerocopy::{little_endian::U32, Ref};
 
-use crate::{add_padding, errors::ZeroCopyError, ZeroCopyTraits};
+use crate::{add_padding, checked_data_size, errors::ZeroCopyError, ZeroCopyTraits};
 
 pub type ZeroCopyVecU64<'a, T> = ZeroCopyVec<'a, u64, T>;
 pub type ZeroCopyVecU32<'a, T> = ZeroCopyVec<'a, u32, T>;
@@ -83,18 +83,19 @@ where
 
         let (meta_data, bytes) = bytes.split_at_mut(metadata_size);
         let (metadata, _padding) = Ref::<&mut [u8], [L; 2]>::from_prefix(meta_data)?;
-        let usize_capacity: usize = u64::from(metadata[CAPACITY_INDEX]) as usize;
-        let usize_len: usize = u64::from(metadata[LENGTH_INDEX]) as usize;
+        let (usize_capacity, full_vector_size) =
+            checked_data_size::<L, T>(metadata[CAPACITY_INDEX])?;
+        let usize_len = usize::try_from(u64::from(metadata[LENGTH_INDEX]))
+            .map_err(|_| ZeroCopyError::LengthOverflow)?;
 
         if usize_len > usize_capacity {
             return Err(ZeroCopyError::LengthGreaterThanCapacity);
         }
 
-        let full_vector_size = Self::data_size(metadata[CAPACITY_INDEX]);
         if bytes.len() < full_vector_size {
             return Err(ZeroCopyError::InsufficientMemoryAllocated(
                 bytes.len() + metadata_size,
-                full_vector_size + metadata_size,
+                full_vector_size.saturating_add(metadata_size),
             ));
         }
         let (slice, remaining_bytes) =
@@ -0,0 +1,289 @@
+#![cfg(feature = "derive")]
+use borsh::BorshSerialize;
+use light_zero_copy::{
+    borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh, ZeroCopy,
+};
+use rand::{
+    distributions::{Distribution, Standard},
+    rngs::ThreadRng,
+    thread_rng, Rng,
+};
+use zerocopy::little_endian::{U32, U64};
+
+#[derive(ZeroCopy, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct Inner {
+    pub a: u8,
+    pub b: u64,
+    pub c: [u8; 32],
+}
+
+#[derive(ZeroCopy, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct Outer {
+    pub a: u8,
+    pub b: bool,
+    pub c: u16,
+    pub d: u32,
+    pub e: u64,
+    pub f: i16,
+    pub g: i32,
+    pub h: i64,
+    pub i: [u8; 32],
+    pub j: Vec<u8>,
+    pub k: Vec<u64>,
+    pub l: Option<u64>,
+    pub m: Option<Vec<u8>>,
+    pub n: Inner,
+    pub o: Vec<Inner>,
+    pub p: Option<Inner>,
+}
+
+/// Borsh types which do not implement Deserialize.
+#[derive(BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct Id(u32);
+
+#[derive(BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct Amount(u64);
+
+#[derive(ZeroCopy, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct WithAttributes {
+    #[zero_copy(ty = U32)]
+    pub id: Id,
+    #[zero_copy(ty = ZeroCopySliceBorsh<'a, U64>)]
+    pub amounts: Vec<Amount>,
+    pub owner: [u8; 32],
+}
+
+#[derive(ZeroCopy, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct WithTrailing {
+    pub amount: u64,
+    #[zero_copy(trailing)]
+    pub flag: bool,
+    #[zero_copy(trailing)]
+    pub data: Vec<Option<Vec<u8>>>,
+}
+
+fn rand_vec<T>(rng: &mut ThreadRng) -> Vec<T>
+where
+    Standard: Distribution<T>,
+{
+    let len = rng.gen_range(0..10);
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+fn rand_inner(rng: &mut ThreadRng) -> Inner {
+    Inner {
+        a: rng.gen(),
+        b: rng.gen(),
+        c: rng.gen(),
+    }
+}
+
+fn rand_outer(rng: &mut ThreadRng) -> Outer {
+    Outer {
+        a: rng.gen(),
+        b: rng.gen(),
+        c: rng.gen(),
+        d: rng.gen(),
+        e: rng.gen(),
+        f: rng.gen(),
+        g: rng.gen(),
+        h: rng.gen(),
+        i: rng.gen(),
+        j: rand_vec(rng),
+        k: rand_vec(rng),
+        l: rng.gen::<bool>().then(|| rng.gen()),
+        m: rng.gen::<bool>().then(|| rand_vec(rng)),
+        n: rand_inner(rng),
+        o: (0..rng.gen_range(0..5)).map(|_| rand_inner(rng)).collect(),
+        p: rng.gen::<bool>().then(|| rand_inner(rng)),
+    }
+}
+
+fn assert_inner_eq(inner: &Inner, z_inner: &ZInner<'_>) {
+    assert_eq!(inner.a, z_inner.a);
+    assert_eq!(inner.b, u64::from(*z_inner.b));
+    assert_eq!(inner.c, *z_inner.c);
+}
+
+fn assert_outer_eq(outer: &Outer, z_outer: &ZOuter<'_>) {
+    assert_eq!(outer.a, z_outer.a);
+    assert_eq!(outer.b, z_outer.b);
+    assert_eq!(outer.c, u16::from(*z_outer.c));
+    assert_eq!(outer.d, u32::from(*z_outer.d));
+    assert_eq!(outer.e, u64::from(*z_outer.e));
+    assert_eq!(outer.f, i16::from(*z_outer.f));
+    assert_eq!(outer.g, i32::from(*z_outer.g));
+    assert_eq!(outer.h, i64::from(*z_outer.h));
+    assert_eq!(outer.i, *z_outer.i);
+    assert_eq!(outer.j.as_slice(), z_outer.j.as_slice());
+    assert_eq!(
+        outer.k,
+        z_outer.k.iter().map(|k| u64::from(*k)).collect::<Vec<_>>()
+    );
+    assert_eq!(outer.l, z_outer.l.as_ref().map(|l| u64::from(**l)));
+    assert_eq!(outer.m, z_outer.m.as_ref().map(|m| m.to_vec()));
+    assert_inner_eq(&outer.n, &z_outer.n);
+    assert_eq!(outer.o.len(), z_outer.o.len());
+    for (inner, z_inner) in outer.o.iter().zip(z_outer.o.iter()) {
+        assert_inner_eq(inner, z_inner);
+    }
+    match (&outer.p, &z_outer.p) {
+        (Some(inner), Some(z_inner)) => assert_inner_eq(inner, z_inner),
+        (None, None) => {}
+        _ => panic!("option mismatch {:?} {:?}", outer.p, z_outer.p),
+    }
+}
+
+#[test]
+fn test_derive_zero_copy_borsh_compat() {
+    let mut rng = thread_rng();
+    for _ in 0..1000 {
+        let outer = rand_outer(&mut rng);
+        let bytes = outer.try_to_vec().unwrap();
+
+        let (z_outer, remaining_bytes) = Outer::zero_copy_at(&bytes).unwrap();
+        assert_outer_eq(&outer, &z_outer);
+        assert!(remaining_bytes.is_empty());
+
+        // The view type deserializes the same.
+        let (z_outer_2, remaining_bytes) = ZOuter::zero_copy_at(&bytes).unwrap();
+        assert_eq!(z_outer, z_outer_2);
+        assert!(remaining_bytes.is_empty());
+
+        // Trailing bytes are returned.
+        let mut extended_bytes = bytes.clone();
+        extended_bytes.extend_from_slice(&[1, 2, 3]);
+        let (z_outer, remaining_bytes) = Outer::zero_copy_at(&extended_bytes).unwrap();
+        assert_outer_eq(&outer, &z_outer);
+        assert_eq!(remaining_bytes, &[1, 2, 3]);
+
+        // Every truncated input fails.
+        for i in 0..bytes.len() {
+            assert!(Outer::zero_copy_at(&bytes[..i]).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_derive_zero_copy_invalid_bytes() {
+    let mut rng = thread_rng();
+    let mut outer = rand_outer(&mut rng);
+    outer.l = None;
+    let mut bytes = outer.try_to_vec().unwrap();
+
+    // offset of b: bool
+    bytes[1] = 2;
+    assert_eq!(
+        Outer::zero_copy_at(&bytes),
+        Err(ZeroCopyError::InvalidConversion)
+    );
+    bytes[1] = 1;
+
+    // offset of l: Option<u64>
+    let offset = 1 + 1 + 2 + 4 + 8 + 2 + 4 + 8 + 32 + 4 + outer.j.len() + 4 + outer.k.len() * 8;
+    assert_eq!(bytes[offset], 0);
+    bytes[offset] = 2;
+    assert_eq!(
+        Outer::zero_copy_at(&bytes),
+        Err(ZeroCopyError::InvalidOptionByte(2))
+    );
+}
+
+#[test]
+fn test_derive_zero_copy_attribute_type() {
+    let mut rng = thread_rng();
+    for _ in 0..1000 {
+        let with_attributes = WithAttributes {
+            id: Id(rng.gen()),
+            amounts: rand_vec::<u64>(&mut rng).into_iter().map(Amount).collect(),
+            owner: rng.gen(),
+        };
+        let bytes = with_attributes.try_to_vec().unwrap();
+
+        let (z_with_attributes, remaining_bytes) = WithAttributes::zero_copy_at(&bytes).unwrap();
+        assert!(remaining_bytes.is_empty());
+        assert_eq!(with_attributes.id.0, u32::from(*z_with_attributes.id));
+        assert_eq!(
+            with_attributes
+                .amounts
+                .iter()
+                .map(|amount| amount.0)
+                .collect::<Vec<_>>(),
+            z_with_attributes
+                .amounts
+                .iter()
+                .map(|amount| u64::from(*amount))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(with_attributes.owner, *z_with_attributes.owner);
+
+        for i in 0..bytes.len() {
+            assert!(WithAttributes::zero_copy_at(&bytes[..i]).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_derive_zero_copy_min_size() {
+    assert_eq!(Inner::MIN_SIZE, 1 + 8 + 32);
+    assert_eq!(ZInner::MIN_SIZE, Inner::MIN_SIZE);
+    assert_eq!(WithAttributes::MIN_SIZE, 4 + 4 + 32);
+
+    // The minimum size is the size of a value with empty vecs and None options.
+    let mut rng = thread_rng();
+    let mut outer = rand_outer(&mut rng);
+    outer.j = Vec::new();
+    outer.k = Vec::new();
+    outer.l = None;
+    outer.m = None;
+    outer.o = Vec::new();
+    outer.p = None;
+    assert_eq!(Outer::MIN_SIZE, outer.try_to_vec().unwrap().len());
+
+    // A length prefix of 1000 elements requires 1000 * Inner::MIN_SIZE bytes.
+    let mut bytes = 1000u32.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&[0u8; 1000]);
+    assert_eq!(
+        Vec::<Inner>::zero_copy_at(&bytes).map(|_| ()),
+        Err(ZeroCopyError::InsufficientRemainingBytes(
+            1000 * Inner::MIN_SIZE,
+            1000
+        ))
+    );
+}
+
+#[test]
+fn test_derive_zero_copy_trailing() {
+    let with_trailing = WithTrailing {
+        amount: 1,
+        flag: true,
+        data: vec![Some(vec![1, 2]), None],
+    };
+    let bytes = with_trailing.try_to_vec().unwrap();
+    assert_eq!(WithTrailing::MIN_SIZE, 8);
+
+    let (z_with_trailing, remaining_bytes) = WithTrailing::zero_copy_at(&bytes).unwrap();
+    assert!(remaining_bytes.is_empty());
+    assert_eq!(u64::from(*z_with_trailing.amount), 1);
+    assert!(z_with_trailing.flag);
+    assert_eq!(z_with_trailing.data.len(), 2);
+    assert_eq!(
+        z_with_trailing.data[0].as_ref().unwrap().as_slice(),
+        &[1, 2]
+    );
+    assert!(z_with_trailing.data[1].is_none());
+
+    // Omitted trailing fields are deserialized as default.
+    let (z_with_trailing, remaining_bytes) = WithTrailing::zero_copy_at(&bytes[..9]).unwrap();
+    assert!(remaining_bytes.is_empty());
+    assert!(z_with_trailing.flag);
+    assert!(z_with_trailing.data.is_empty());
+    let (z_with_trailing, remaining_bytes) = WithTrailing::zero_copy_at(&bytes[..8]).unwrap();
+    assert!(remaining_bytes.is_empty());
+    assert!(!z_with_trailing.flag);
+    assert!(z_with_trailing.data.is_empty());
+
+    // Partially serialized trailing fields fail.
+    assert!(WithTrailing::zero_copy_at(&bytes[..7]).is_err());
+    assert!(WithTrailing::zero_copy_at(&bytes[..10]).is_err());
+}
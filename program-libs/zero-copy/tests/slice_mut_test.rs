@@ -533,3 +533,24 @@ fn test_debug_fmt() {
 
     assert_eq!(format!("{:?}", slice), "[1, 2, 3, 4]");
 }
+
+#[test]
+fn test_zero_copy_at_mut() {
+    use light_zero_copy::{borsh::DeserializeMut, slice_mut::ZeroCopySliceMutBorsh};
+    let mut buffer = vec![0u8; 20];
+    let values = [1u32, 2, 3, 4];
+    {
+        let (mut slice, _) = ZeroCopySliceMutBorsh::<u32>::new_at(U32::new(4), &mut buffer)
+            .expect("Failed to create ZeroCopySliceMut");
+        slice.as_mut_slice().copy_from_slice(&values);
+    }
+    {
+        let (mut zero_slice, remaining) =
+            ZeroCopySliceMutBorsh::<u32>::zero_copy_at_mut(&mut buffer).unwrap();
+        assert_eq!(zero_slice.as_slice(), &values);
+        assert!(remaining.is_empty());
+        zero_slice[3] = 5;
+    }
+    let (slice, _) = ZeroCopySliceMutBorsh::<u32>::from_bytes_at(&mut buffer).unwrap();
+    assert_eq!(slice.as_slice(), &[1u32, 2, 3, 5]);
+}
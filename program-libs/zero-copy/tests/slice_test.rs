@@ -254,6 +254,23 @@ fn test_zero_copy_u8_struct_vec() {
     );
 }
 
+#[test]
+fn test_from_bytes_length_overflow() {
+    let mut data = vec![0u8; 16];
+    // length * size_of::<u64>() overflows usize
+    data[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    let slice = ZeroCopySliceU64::<u64>::from_bytes(&data);
+    assert!(matches!(slice, Err(ZeroCopyError::LengthOverflow)));
+
+    // length exceeds the remaining bytes
+    data[..8].copy_from_slice(&2u64.to_le_bytes());
+    let slice = ZeroCopySliceU64::<u64>::from_bytes(&data);
+    assert!(matches!(
+        slice,
+        Err(ZeroCopyError::InsufficientMemoryAllocated(16, 24))
+    ));
+}
+
 #[test]
 fn test_empty() {
     let length = 0;
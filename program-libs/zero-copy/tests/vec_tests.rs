@@ -506,6 +506,23 @@ fn test_new_memory_not_zeroed() {
     assert!(matches!(vec, Err(ZeroCopyError::MemoryNotZeroed)));
 }
 
+#[test]
+fn test_from_bytes_capacity_overflow() {
+    let mut data = vec![0; ZeroCopyVecU64::<u64>::required_size_for_capacity(1)];
+    // capacity * size_of::<u64>() overflows usize
+    data[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+    let vec = ZeroCopyVecU64::<u64>::from_bytes(&mut data);
+    assert!(matches!(vec, Err(ZeroCopyError::LengthOverflow)));
+
+    // capacity exceeds the remaining bytes
+    data[8..16].copy_from_slice(&2u64.to_le_bytes());
+    let vec = ZeroCopyVecU64::<u64>::from_bytes(&mut data);
+    assert!(matches!(
+        vec,
+        Err(ZeroCopyError::InsufficientMemoryAllocated(24, 32))
+    ));
+}
+
 #[should_panic = "Capacity overflow. Cannot copy slice into ZeroCopyVec."]
 #[test]
 fn test_extend_from_slice_over_capacity() {
@@ -5,10 +5,12 @@ use std::mem;
 use account_compression::{
     errors::AccountCompressionErrorCode,
     state::QueueAccount,
-    utils::constants::{ADDRESS_MERKLE_TREE_CANOPY_DEPTH, ADDRESS_MERKLE_TREE_HEIGHT},
+    utils::constants::{
+        ADDRESS_MERKLE_TREE_CANOPY_DEPTH, ADDRESS_MERKLE_TREE_HEIGHT, CLOSED_ACCOUNT_DISCRIMINATOR,
+    },
     AddressMerkleTreeAccount, AddressMerkleTreeConfig, AddressQueueConfig, ID, SAFETY_MARGIN,
 };
-use anchor_lang::error::ErrorCode;
+use anchor_lang::{error::ErrorCode, InstructionData, ToAccountMetas};
 use ark_bn254::Fr;
 use ark_ff::{BigInteger, PrimeField, UniformRand};
 use light_account_checks::error::AccountError;
@@ -30,12 +32,13 @@ use light_test_utils::{
     create_address_merkle_tree_and_queue_account_with_assert, get_hash_set,
     get_indexed_merkle_tree,
     test_forester::{empty_address_queue_test, update_merkle_tree},
-    RpcConnection, RpcError,
+    AccountZeroCopy, RpcConnection, RpcError,
 };
 use num_bigint::ToBigUint;
 use rand::thread_rng;
 use solana_program_test::ProgramTest;
 use solana_sdk::{
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
@@ -454,6 +457,7 @@ async fn test_address_queue_and_tree_invalid_config() {
     }
     for invalid_close_threshold in (0..100).step_by(20) {
         let mut merkle_tree_config = merkle_tree_config.clone();
+        merkle_tree_config.rollover_threshold = None;
         merkle_tree_config.close_threshold = Some(invalid_close_threshold);
         let result = initialize_address_merkle_tree_and_queue(
             &mut context,
@@ -1404,6 +1408,255 @@ async fn test_address_merkle_tree_and_queue_rollover_custom() {
     }
 }
 
+async fn close_address_merkle_tree_and_queue(
+    context: &mut ProgramTestRpcConnection,
+    authority: &Keypair,
+    merkle_tree: Pubkey,
+    queue: Pubkey,
+    rent_recipient: Pubkey,
+) -> Result<Signature, RpcError> {
+    let instruction_data = account_compression::instruction::CloseAddressMerkleTreeAndQueue {};
+    let accounts = account_compression::accounts::CloseAddressMerkleTreeAndQueue {
+        authority: authority.pubkey(),
+        registered_program_pda: None,
+        merkle_tree,
+        queue,
+        rent_recipient,
+    };
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    };
+    context
+        .create_and_send_transaction(&[instruction], &authority.pubkey(), &[authority])
+        .await
+}
+
+/// Tests closing a rolled over address Merkle tree and queue:
+/// 1. Failing: Merkle tree is not rolled over.
+/// 2. Failing: close threshold slots have not passed since the rollover.
+/// 3. Failing: queue contains an address which is not inserted into the tree.
+/// 4. Failing: rent recipient is not the tree owner.
+/// 5. Functional: accounts are closed into tombstones,
+///     the rent is reimbursed to the tree owner.
+/// 6. Failing: Merkle tree and queue cannot be initialized again.
+#[tokio::test]
+async fn test_close_address_merkle_tree_and_queue() {
+    let close_threshold = 100;
+    let merkle_tree_config = AddressMerkleTreeConfig {
+        close_threshold: Some(close_threshold),
+        ..Default::default()
+    };
+    let queue_config = AddressQueueConfig::default();
+    let (mut context, payer, mut address_merkle_tree_bundle) =
+        test_setup_with_address_merkle_tree(&merkle_tree_config, &queue_config).await;
+    let address_merkle_tree_pubkey = address_merkle_tree_bundle.accounts.merkle_tree;
+    let address_queue_pubkey = address_merkle_tree_bundle.accounts.queue;
+
+    // CHECK 1: Merkle tree is not rolled over.
+    let result = close_address_merkle_tree_and_queue(
+        &mut context,
+        &payer,
+        address_merkle_tree_pubkey,
+        address_queue_pubkey,
+        payer.pubkey(),
+    )
+    .await;
+    assert_rpc_error(result, 0, MerkleTreeMetadataError::NotRolledOver.into()).unwrap();
+
+    let address = bigint_to_be_bytes_array(&30_u32.to_biguint().unwrap()).unwrap();
+    insert_addresses(
+        &mut context,
+        address_queue_pubkey,
+        address_merkle_tree_pubkey,
+        vec![address],
+    )
+    .await
+    .unwrap();
+    let next_index = get_indexed_merkle_tree::<
+        AddressMerkleTreeAccount,
+        ProgramTestRpcConnection,
+        Poseidon,
+        usize,
+        26,
+        16,
+    >(&mut context, address_merkle_tree_pubkey)
+    .await
+    .next_index();
+
+    // Rollover with the next index required for rollover,
+    // and reset the next index to insert the queued address afterwards.
+    let address_merkle_tree_lamports = context
+        .get_account(address_merkle_tree_pubkey)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let rollover_costs = context
+        .get_account(address_queue_pubkey)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports
+        + address_merkle_tree_lamports;
+    airdrop_lamports(&mut context, &address_queue_pubkey, rollover_costs)
+        .await
+        .unwrap();
+    let required_next_index = 2u64.pow(26) * merkle_tree_config.rollover_threshold.unwrap() / 100;
+    set_address_merkle_tree_next_index(
+        &mut context,
+        &address_merkle_tree_pubkey,
+        required_next_index,
+        address_merkle_tree_lamports,
+    )
+    .await;
+    perform_address_merkle_tree_roll_over(
+        &mut context,
+        &Keypair::new(),
+        &Keypair::new(),
+        &address_merkle_tree_pubkey,
+        &address_queue_pubkey,
+        &merkle_tree_config,
+        &queue_config,
+    )
+    .await
+    .unwrap();
+    set_address_merkle_tree_next_index(
+        &mut context,
+        &address_merkle_tree_pubkey,
+        next_index as u64,
+        address_merkle_tree_lamports,
+    )
+    .await;
+
+    // CHECK 2: close threshold slots have not passed since the rollover.
+    let result = close_address_merkle_tree_and_queue(
+        &mut context,
+        &payer,
+        address_merkle_tree_pubkey,
+        address_queue_pubkey,
+        payer.pubkey(),
+    )
+    .await;
+    assert_rpc_error(result, 0, MerkleTreeMetadataError::NotReadyForClose.into()).unwrap();
+
+    let rolledover_slot =
+        AccountZeroCopy::<AddressMerkleTreeAccount>::new(&mut context, address_merkle_tree_pubkey)
+            .await
+            .deserialized()
+            .metadata
+            .rollover_metadata
+            .rolledover_slot;
+    context
+        .warp_to_slot(rolledover_slot + close_threshold + 1)
+        .await
+        .unwrap();
+
+    // CHECK 3: queue contains an address which is not inserted into the tree.
+    let result = close_address_merkle_tree_and_queue(
+        &mut context,
+        &payer,
+        address_merkle_tree_pubkey,
+        address_queue_pubkey,
+        payer.pubkey(),
+    )
+    .await;
+    assert_rpc_error(
+        result,
+        0,
+        AccountCompressionErrorCode::AddressQueueNotEmpty.into(),
+    )
+    .unwrap();
+
+    empty_address_queue_test(
+        &payer,
+        &mut context,
+        &mut address_merkle_tree_bundle,
+        true,
+        0,
+        false,
+    )
+    .await
+    .unwrap();
+
+    // CHECK 4: rent recipient is not the tree owner.
+    let result = close_address_merkle_tree_and_queue(
+        &mut context,
+        &payer,
+        address_merkle_tree_pubkey,
+        address_queue_pubkey,
+        Pubkey::new_unique(),
+    )
+    .await;
+    assert_rpc_error(
+        result,
+        0,
+        AccountCompressionErrorCode::InvalidRentRecipient.into(),
+    )
+    .unwrap();
+
+    // CHECK 5: close Merkle tree and queue.
+    let mut closed_lamports = 0;
+    for pubkey in [address_merkle_tree_pubkey, address_queue_pubkey] {
+        closed_lamports += context.get_account(pubkey).await.unwrap().unwrap().lamports;
+    }
+    let payer_prior_balance = context
+        .get_account(payer.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    close_address_merkle_tree_and_queue(
+        &mut context,
+        &payer,
+        address_merkle_tree_pubkey,
+        address_queue_pubkey,
+        payer.pubkey(),
+    )
+    .await
+    .unwrap();
+    let tombstone_rent = context
+        .get_minimum_balance_for_rent_exemption(CLOSED_ACCOUNT_DISCRIMINATOR.len())
+        .await
+        .unwrap();
+    for pubkey in [address_merkle_tree_pubkey, address_queue_pubkey] {
+        let account = context.get_account(pubkey).await.unwrap().unwrap();
+        assert_eq!(account.owner, ID);
+        assert_eq!(account.data, CLOSED_ACCOUNT_DISCRIMINATOR.to_vec());
+        assert_eq!(account.lamports, tombstone_rent);
+    }
+    let payer_balance = context
+        .get_account(payer.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    // 1 signature costs 5000 lamports.
+    assert_eq!(
+        payer_balance + 5000,
+        payer_prior_balance + closed_lamports - 2 * tombstone_rent
+    );
+
+    // CHECK 6: closed Merkle tree and queue cannot be initialized again.
+    let instruction =
+        light_program_test::acp_sdk::create_initialize_address_merkle_tree_and_queue_instruction(
+            0,
+            payer.pubkey(),
+            None,
+            None,
+            Some(Pubkey::new_unique()),
+            address_merkle_tree_pubkey,
+            address_queue_pubkey,
+            merkle_tree_config.clone(),
+            queue_config.clone(),
+        );
+    let result = context
+        .create_and_send_transaction(&[instruction], &payer.pubkey(), &[&payer])
+        .await;
+    assert_rpc_error(result, 0, ErrorCode::AccountDiscriminatorAlreadySet.into()).unwrap();
+}
+
 pub async fn test_setup_with_address_merkle_tree(
     merkle_tree_config: &AddressMerkleTreeConfig,
     queue_config: &AddressQueueConfig,
@@ -19,7 +19,11 @@ use light_compressed_account::{
     TreeType,
 };
 use light_compressed_token::{
-    batch_compress::BatchCompressInstructionDataBorsh,
+    batch_compress::BatchCompressInstructionData,
+    compressed_mint::{
+        find_compressed_mint_pda, get_compressed_mint_address,
+        sdk::{create_create_compressed_mint_instruction, CreateCompressedMintInstructionInputs},
+    },
     constants::NUM_MAX_POOL_ACCOUNTS,
     delegation::sdk::{
         create_approve_instruction, create_revoke_instruction, CreateApproveInstructionInputs,
@@ -33,6 +37,7 @@ use light_compressed_token::{
         get_cpi_authority_pda, transfer_sdk::create_transfer_instruction, TokenTransferOutputData,
     },
     spl_compression::check_spl_token_pool_derivation_with_index,
+    swap::sdk::{create_swap_instruction, CreateSwapInstructionInputs},
     ErrorCode, TokenData,
 };
 use light_program_test::{
@@ -46,6 +51,7 @@ use light_program_test::{
 };
 use light_prover_client::gnark::helpers::{
     kill_prover, spawn_prover, spawn_validator, LightValidatorConfig, ProofType, ProverConfig,
+    ProverMode,
 };
 use light_registry::protocol_config::state::ProtocolConfig;
 use light_sdk::token::{AccountState, TokenDataWithMerkleContext};
@@ -957,9 +963,12 @@ async fn test_mint_to_failing() {
             .collect::<Vec<_>>();
 
         let instruction_data = light_compressed_token::instruction::MintTo {
-            amounts: amounts.clone(),
-            public_keys: recipients.clone(),
-            lamports: None,
+            inputs: (&light_compressed_token::MintToInstructionData {
+                public_keys: recipients.clone(),
+                amounts: amounts.clone(),
+                lamports: None,
+            })
+                .into(),
         };
         let token_program = if is_token_22 {
             anchor_spl::token_2022::ID
@@ -2292,7 +2301,8 @@ async fn test_approve_failing() {
             change_compressed_account_merkle_tree: delegated_compressed_account_merkle_tree,
             delegate: delegate.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
+            with_allowance: false,
         };
         let instruction = create_approve_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2338,7 +2348,8 @@ async fn test_approve_failing() {
             change_compressed_account_merkle_tree: invalid_change_merkle_tree.pubkey(),
             delegate: delegate.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
+            with_allowance: false,
         };
         let instruction = create_approve_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2388,7 +2399,8 @@ async fn test_approve_failing() {
             change_compressed_account_merkle_tree: delegated_compressed_account_merkle_tree,
             delegate: delegate.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: invalid_proof,
+            proof: Some(invalid_proof),
+            with_allowance: false,
         };
         let instruction = create_approve_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2434,7 +2446,8 @@ async fn test_approve_failing() {
             change_compressed_account_merkle_tree: delegated_compressed_account_merkle_tree,
             delegate: delegate.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
+            with_allowance: false,
         };
         let instruction = create_approve_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2483,7 +2496,8 @@ async fn test_approve_failing() {
             change_compressed_account_merkle_tree: delegated_compressed_account_merkle_tree,
             delegate: delegate.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
+            with_allowance: false,
         };
         let instruction = create_approve_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2737,7 +2751,7 @@ async fn test_revoke_failing() {
             mint,
             output_account_merkle_tree: merkle_tree_pubkey,
             root_indices: invalid_root_indices,
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
         };
         let instruction = create_revoke_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2779,7 +2793,7 @@ async fn test_revoke_failing() {
             mint,
             output_account_merkle_tree: invalid_merkle_tree.pubkey(),
             root_indices: proof_rpc_result.root_indices.clone(),
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
         };
         let instruction = create_revoke_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2822,7 +2836,7 @@ async fn test_revoke_failing() {
             mint: invalid_mint.pubkey(),
             output_account_merkle_tree: merkle_tree_pubkey,
             root_indices: proof_rpc_result.root_indices,
-            proof: proof_rpc_result.proof,
+            proof: Some(proof_rpc_result.proof),
         };
         let instruction = create_revoke_instruction(inputs).unwrap();
         let context_payer = rpc.get_payer().insecure_clone();
@@ -2846,6 +2860,184 @@ async fn test_revoke_failing() {
 /// 1. Burn tokens
 /// 1. Delegate tokens with approve
 /// 2. Burn delegated tokens
+fn create_test_approve_instruction(
+    fee_payer: &Pubkey,
+    authority: &Pubkey,
+    delegate: &Pubkey,
+    inputs: &[TokenDataWithMerkleContext],
+    root_indices: Vec<Option<u16>>,
+    delegated_amount: u64,
+    output_merkle_tree: &Pubkey,
+) -> Instruction {
+    create_approve_instruction(CreateApproveInstructionInputs {
+        fee_payer: *fee_payer,
+        authority: *authority,
+        root_indices,
+        proof: None,
+        input_token_data: inputs
+            .iter()
+            .map(|x| sdk_to_program_token_data(x.token_data.clone()))
+            .collect(),
+        input_compressed_accounts: inputs
+            .iter()
+            .map(|x| x.compressed_account.compressed_account.clone())
+            .collect(),
+        input_merkle_contexts: inputs
+            .iter()
+            .map(|x| x.compressed_account.merkle_context)
+            .collect(),
+        mint: inputs[0].token_data.mint,
+        delegated_amount,
+        delegate_lamports: None,
+        delegated_compressed_account_merkle_tree: *output_merkle_tree,
+        change_compressed_account_merkle_tree: *output_merkle_tree,
+        delegate: *delegate,
+        with_allowance: false,
+    })
+    .unwrap()
+}
+
+/// Approve and revoke with optional proof:
+/// 1. Functional: approve inputs proven by index without a proof
+/// 2. Functional: revoke inputs proven by index without a proof
+/// 3. Failing: approve without a proof of inputs which require a proof
+#[serial]
+#[tokio::test]
+async fn test_approve_and_revoke_with_optional_proof() {
+    let (mut rpc, env) = setup_test_programs_with_accounts(None).await;
+    let payer = rpc.get_payer().insecure_clone();
+    let queue_pubkey = env.batched_output_queue;
+    let mut test_indexer =
+        TestIndexer::<ProgramTestRpcConnection>::init_from_env(&payer, &env, None).await;
+    let sender = Keypair::new();
+    airdrop_lamports(&mut rpc, &sender.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let delegate = Pubkey::new_unique();
+    let mint = create_mint_helper(&mut rpc, &payer).await;
+    for merkle_tree_pubkey in [queue_pubkey, env.merkle_tree_pubkey] {
+        mint_tokens_helper(
+            &mut rpc,
+            &mut test_indexer,
+            &merkle_tree_pubkey,
+            &payer,
+            &mint,
+            vec![1_000],
+            vec![sender.pubkey()],
+        )
+        .await;
+    }
+    let get_sender_accounts = |test_indexer: &TestIndexer<ProgramTestRpcConnection>,
+                               queue: Pubkey| {
+        test_indexer
+            .token_compressed_accounts
+            .iter()
+            .filter(|x| {
+                x.token_data.owner == sender.pubkey()
+                    && x.compressed_account.merkle_context.queue_pubkey == queue
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    // 1. Functional: approve without a proof, the delegated and change
+    //    accounts are new outputs in the output queue.
+    {
+        let inputs = get_sender_accounts(&test_indexer, queue_pubkey);
+        assert_eq!(inputs.len(), 1);
+        let instruction = create_test_approve_instruction(
+            &payer.pubkey(),
+            &sender.pubkey(),
+            &delegate,
+            &inputs,
+            vec![None],
+            400,
+            &queue_pubkey,
+        );
+        let (event, _, slot) = rpc
+            .create_and_send_transaction_with_public_event(
+                &[instruction],
+                &payer.pubkey(),
+                &[&payer, &sender],
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        test_indexer.add_event_and_compressed_accounts(slot, &event);
+        let mut outputs = get_sender_accounts(&test_indexer, queue_pubkey)
+            .iter()
+            .map(|x| (x.token_data.amount, x.token_data.delegate))
+            .collect::<Vec<_>>();
+        outputs.sort();
+        assert_eq!(outputs, vec![(400, Some(delegate)), (600, None)]);
+    }
+    // 2. Functional: revoke the delegated account without a proof.
+    {
+        let inputs = get_sender_accounts(&test_indexer, queue_pubkey)
+            .into_iter()
+            .filter(|x| x.token_data.delegate.is_some())
+            .collect::<Vec<_>>();
+        let instruction = create_revoke_instruction(CreateRevokeInstructionInputs {
+            fee_payer: payer.pubkey(),
+            authority: sender.pubkey(),
+            root_indices: vec![None],
+            proof: None,
+            input_token_data: inputs
+                .iter()
+                .map(|x| sdk_to_program_token_data(x.token_data.clone()))
+                .collect(),
+            input_compressed_accounts: inputs
+                .iter()
+                .map(|x| x.compressed_account.compressed_account.clone())
+                .collect(),
+            input_merkle_contexts: inputs
+                .iter()
+                .map(|x| x.compressed_account.merkle_context)
+                .collect(),
+            mint,
+            output_account_merkle_tree: queue_pubkey,
+        })
+        .unwrap();
+        let (event, _, slot) = rpc
+            .create_and_send_transaction_with_public_event(
+                &[instruction],
+                &payer.pubkey(),
+                &[&payer, &sender],
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        test_indexer.add_event_and_compressed_accounts(slot, &event);
+        let mut outputs = get_sender_accounts(&test_indexer, queue_pubkey)
+            .iter()
+            .map(|x| (x.token_data.amount, x.token_data.delegate))
+            .collect::<Vec<_>>();
+        outputs.sort();
+        assert_eq!(outputs, vec![(400, None), (600, None)]);
+    }
+    // 3. Failing: inputs of a concurrent state tree are not proven by index,
+    //    approve without a proof fails.
+    {
+        let inputs = get_sender_accounts(&test_indexer, env.nullifier_queue_pubkey);
+        assert_eq!(inputs.len(), 1);
+        let instruction = create_test_approve_instruction(
+            &payer.pubkey(),
+            &sender.pubkey(),
+            &delegate,
+            &inputs,
+            vec![Some(0)],
+            400,
+            &queue_pubkey,
+        );
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &[&payer, &sender])
+            .await;
+        assert_rpc_error(result, 0, SystemProgramError::ProofIsNone.into()).unwrap();
+    }
+}
+
 #[serial]
 #[tokio::test]
 async fn test_burn() {
@@ -4307,6 +4499,27 @@ async fn test_failing_decompression() {
             .await
             .unwrap_err();
         }
+        // Test 1.1: decompress account is not owned by a token program
+        {
+            failing_compress_decompress(
+                &sender,
+                &mut context,
+                &mut test_indexer,
+                input_compressed_account.clone(),
+                decompress_amount, // needs to be consistent with compression amount
+                &merkle_tree_pubkey,
+                decompress_amount,
+                false,
+                &sender.pubkey(),
+                Some(get_token_pool_pda(&mint)),
+                &mint,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into(),
+                is_token_22,
+                None,
+            )
+            .await
+            .unwrap();
+        }
         // Test 2: invalid token pool pda (compress and decompress)
         {
             let invalid_token_account_keypair = Keypair::new();
@@ -4465,7 +4678,7 @@ async fn test_failing_decompression() {
                 &token_account_keypair.pubkey(),
                 Some(get_token_pool_pda_with_index(&mint, NUM_MAX_POOL_ACCOUNTS)),
                 &mint,
-                anchor_lang::error::ErrorCode::AccountNotInitialized.into(),
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into(),
                 is_token_22,
                 None,
             )
@@ -6010,7 +6223,7 @@ pub fn create_batch_compress_instruction(
         find_token_pool_pda_with_index(mint, token_pool_index)
     };
 
-    let instruction_input = BatchCompressInstructionDataBorsh {
+    let instruction_input = BatchCompressInstructionData {
         amounts,
         amount,
         pubkeys: public_keys,
@@ -6068,3 +6281,620 @@ pub fn create_batch_compress_instruction(
         data: instruction_data.data(),
     }
 }
+
+/// Creates a token 2022 mint with a TransferFeeConfig and its token pool.
+async fn create_transfer_fee_mint(
+    rpc: &mut ProgramTestRpcConnection,
+    payer: &Keypair,
+    withdraw_withheld_authority: &Pubkey,
+    fee_basis_points: u16,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ])
+    .unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rpc.get_minimum_balance_for_rent_exemption(space)
+                .await
+                .unwrap(),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            Some(&payer.pubkey()),
+            Some(withdraw_withheld_authority),
+            fee_basis_points,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            2,
+        )
+        .unwrap(),
+        create_create_token_pool_instruction(&payer.pubkey(), &mint.pubkey(), true),
+    ];
+    rpc.create_and_send_transaction(&instructions, &payer.pubkey(), &[payer, &mint])
+        .await
+        .unwrap();
+    mint.pubkey()
+}
+
+/// Creates a token 2022 account with space for the TransferFeeAmount
+/// extension.
+async fn create_transfer_fee_token_account(
+    rpc: &mut ProgramTestRpcConnection,
+    mint: &Pubkey,
+    owner: &Keypair,
+) -> Pubkey {
+    let account = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+        ExtensionType::TransferFeeAmount,
+    ])
+    .unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &owner.pubkey(),
+            &account.pubkey(),
+            rpc.get_minimum_balance_for_rent_exemption(space)
+                .await
+                .unwrap(),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_account(
+            &spl_token_2022::ID,
+            &account.pubkey(),
+            mint,
+            &owner.pubkey(),
+        )
+        .unwrap(),
+    ];
+    rpc.create_and_send_transaction(&instructions, &owner.pubkey(), &[owner, &account])
+        .await
+        .unwrap();
+    account.pubkey()
+}
+
+async fn get_token_22_balance(rpc: &mut ProgramTestRpcConnection, token_account: &Pubkey) -> u64 {
+    let account = rpc.get_account(*token_account).await.unwrap().unwrap();
+    spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+        &account.data,
+    )
+    .unwrap()
+    .base
+    .amount
+}
+
+/// Transfer instruction of a transfer fee mint, inputs are proven by index.
+#[allow(clippy::too_many_arguments)]
+fn create_transfer_fee_mint_transfer_instruction(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    inputs: &[TokenDataWithMerkleContext],
+    outputs: &[TokenTransferOutputData],
+    is_compress: bool,
+    compress_or_decompress_amount: Option<u64>,
+    token_account: Option<Pubkey>,
+    with_mint_account: bool,
+) -> Instruction {
+    let mint_account = if with_mint_account {
+        vec![*mint]
+    } else {
+        vec![]
+    };
+    create_transfer_instruction(
+        authority,
+        authority,
+        &inputs
+            .iter()
+            .map(|x| x.compressed_account.merkle_context)
+            .collect::<Vec<_>>(),
+        outputs,
+        &vec![None; inputs.len()],
+        &None,
+        inputs
+            .iter()
+            .map(|x| sdk_to_program_token_data(x.token_data.clone()))
+            .collect::<Vec<_>>()
+            .as_slice(),
+        &inputs
+            .iter()
+            .map(|x| x.compressed_account.compressed_account.clone())
+            .collect::<Vec<_>>(),
+        *mint,
+        None,
+        is_compress,
+        compress_or_decompress_amount,
+        token_account.map(|_| get_token_pool_pda(mint)),
+        token_account,
+        true,
+        None,
+        None,
+        true,
+        &mint_account,
+        false,
+    )
+    .unwrap()
+}
+
+async fn send_and_index_transaction(
+    rpc: &mut ProgramTestRpcConnection,
+    test_indexer: &mut TestIndexer<ProgramTestRpcConnection>,
+    instruction: Instruction,
+    signer: &Keypair,
+) {
+    let (event, _, slot) = rpc
+        .create_and_send_transaction_with_public_event(
+            &[instruction],
+            &signer.pubkey(),
+            &[signer],
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    test_indexer.add_event_and_compressed_accounts(slot, &event);
+}
+
+/// Compression, transfer and decompression of a token 2022 mint with a 1%
+/// transfer fee:
+/// 1. compression to another owner is charged once by the token pool
+/// 2. transfers to other owners create a fee output
+/// 3. decompression withholds the fee in the recipient token account
+/// Every step fails without the mint account.
+#[serial]
+#[tokio::test]
+async fn test_transfer_fee_mint() {
+    let (mut rpc, env) = setup_test_programs_with_accounts(None).await;
+    let payer = rpc.get_payer().insecure_clone();
+    let queue_pubkey = env.batched_output_queue;
+    let mut test_indexer =
+        TestIndexer::<ProgramTestRpcConnection>::init_from_env(&payer, &env, None).await;
+    let sender = Keypair::new();
+    airdrop_lamports(&mut rpc, &sender.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let recipient = Keypair::new();
+    airdrop_lamports(&mut rpc, &recipient.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let withdraw_withheld_authority = Pubkey::new_unique();
+    let mint = create_transfer_fee_mint(&mut rpc, &payer, &withdraw_withheld_authority, 100).await;
+    let token_account = create_transfer_fee_token_account(&mut rpc, &mint, &sender).await;
+    let mint_to_ix = spl_token_2022::instruction::mint_to(
+        &spl_token_2022::ID,
+        &mint,
+        &token_account,
+        &payer.pubkey(),
+        &[],
+        10_000,
+    )
+    .unwrap();
+    rpc.create_and_send_transaction(&[mint_to_ix], &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+
+    // 1. Compress 10_000 to the recipient. The token pool withholds 100,
+    //    the recipient receives the net amount without a second fee.
+    let compress_output = TokenTransferOutputData {
+        amount: 9_900,
+        owner: recipient.pubkey(),
+        lamports: None,
+        merkle_tree: queue_pubkey,
+    };
+    for with_mint_account in [false, true] {
+        let instruction = create_transfer_fee_mint_transfer_instruction(
+            &sender.pubkey(),
+            &mint,
+            &[],
+            &[compress_output],
+            true,
+            Some(10_000),
+            Some(token_account),
+            with_mint_account,
+        );
+        if with_mint_account {
+            send_and_index_transaction(&mut rpc, &mut test_indexer, instruction, &sender).await;
+        } else {
+            let result = rpc
+                .create_and_send_transaction(&[instruction], &sender.pubkey(), &[&sender])
+                .await;
+            assert_rpc_error(result, 0, ErrorCode::MintAccountUndefined.into()).unwrap();
+        }
+    }
+    assert_eq!(get_token_22_balance(&mut rpc, &token_account).await, 0);
+    let recipient_accounts = test_indexer
+        .get_compressed_token_accounts_by_owner(&recipient.pubkey(), None)
+        .await
+        .unwrap();
+    assert_eq!(recipient_accounts.len(), 1);
+    assert_eq!(recipient_accounts[0].token_data.amount, 9_900);
+    assert!(test_indexer
+        .get_compressed_token_accounts_by_owner(&withdraw_withheld_authority, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // 2. Transfer 5_000 to the sender, the fee output receives 50.
+    let transfer_outputs = [
+        TokenTransferOutputData {
+            amount: 5_000,
+            owner: sender.pubkey(),
+            lamports: None,
+            merkle_tree: queue_pubkey,
+        },
+        TokenTransferOutputData {
+            amount: 4_850,
+            owner: recipient.pubkey(),
+            lamports: None,
+            merkle_tree: queue_pubkey,
+        },
+    ];
+    for with_mint_account in [false, true] {
+        let instruction = create_transfer_fee_mint_transfer_instruction(
+            &recipient.pubkey(),
+            &mint,
+            &recipient_accounts,
+            &transfer_outputs,
+            false,
+            None,
+            None,
+            with_mint_account,
+        );
+        if with_mint_account {
+            send_and_index_transaction(&mut rpc, &mut test_indexer, instruction, &recipient).await;
+        } else {
+            let result = rpc
+                .create_and_send_transaction(&[instruction], &recipient.pubkey(), &[&recipient])
+                .await;
+            assert_rpc_error(result, 0, ErrorCode::MintAccountUndefined.into()).unwrap();
+        }
+    }
+    let fee_accounts = test_indexer
+        .get_compressed_token_accounts_by_owner(&withdraw_withheld_authority, None)
+        .await
+        .unwrap();
+    assert_eq!(fee_accounts.len(), 1);
+    assert_eq!(fee_accounts[0].token_data.amount, 50);
+
+    // 3. Decompress 5_000 without outputs, the recipient token account
+    //    withholds 50.
+    let sender_accounts = test_indexer
+        .get_compressed_token_accounts_by_owner(&sender.pubkey(), None)
+        .await
+        .unwrap();
+    assert_eq!(sender_accounts.len(), 1);
+    assert_eq!(sender_accounts[0].token_data.amount, 5_000);
+    for with_mint_account in [false, true] {
+        let instruction = create_transfer_fee_mint_transfer_instruction(
+            &sender.pubkey(),
+            &mint,
+            &sender_accounts,
+            &[],
+            false,
+            Some(5_000),
+            Some(token_account),
+            with_mint_account,
+        );
+        if with_mint_account {
+            send_and_index_transaction(&mut rpc, &mut test_indexer, instruction, &sender).await;
+        } else {
+            let result = rpc
+                .create_and_send_transaction(&[instruction], &sender.pubkey(), &[&sender])
+                .await;
+            assert_rpc_error(result, 0, ErrorCode::MintAccountUndefined.into()).unwrap();
+        }
+    }
+    assert_eq!(get_token_22_balance(&mut rpc, &token_account).await, 4_950);
+    assert!(test_indexer
+        .get_compressed_token_accounts_by_owner(&sender.pubkey(), None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Swap instruction of two parties, inputs are proven by index.
+#[allow(clippy::too_many_arguments)]
+fn create_test_swap_instruction(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    counterparty: &Pubkey,
+    authority_inputs: &[TokenDataWithMerkleContext],
+    counterparty_inputs: &[TokenDataWithMerkleContext],
+    authority_amount: u64,
+    counterparty_amount: u64,
+    output_merkle_tree: &Pubkey,
+) -> Instruction {
+    let inputs = [authority_inputs, counterparty_inputs].concat();
+    let token_data = |inputs: &[TokenDataWithMerkleContext]| {
+        inputs
+            .iter()
+            .map(|x| sdk_to_program_token_data(x.token_data.clone()))
+            .collect::<Vec<_>>()
+    };
+    create_swap_instruction(CreateSwapInstructionInputs {
+        fee_payer: *payer,
+        authority: *authority,
+        counterparty: *counterparty,
+        root_indices: vec![None; inputs.len()],
+        proof: None,
+        authority_input_token_data: token_data(authority_inputs),
+        counterparty_input_token_data: token_data(counterparty_inputs),
+        input_compressed_accounts: inputs
+            .iter()
+            .map(|x| x.compressed_account.compressed_account.clone())
+            .collect(),
+        input_merkle_contexts: inputs
+            .iter()
+            .map(|x| x.compressed_account.merkle_context)
+            .collect(),
+        authority_mint: authority_inputs[0].token_data.mint,
+        counterparty_mint: counterparty_inputs[0].token_data.mint,
+        authority_amount,
+        counterparty_amount,
+        output_merkle_tree: *output_merkle_tree,
+    })
+    .unwrap()
+}
+
+/// 1. Failing: swap without the mint account of the counterparty leg
+/// 2. Functional: swap of two spl token mints, the authority keeps change
+/// 3. Failing: swap of a transfer fee mint
+#[serial]
+#[tokio::test]
+async fn test_swap() {
+    let (mut rpc, env) = setup_test_programs_with_accounts(None).await;
+    let payer = rpc.get_payer().insecure_clone();
+    let queue_pubkey = env.batched_output_queue;
+    let mut test_indexer =
+        TestIndexer::<ProgramTestRpcConnection>::init_from_env(&payer, &env, None).await;
+    let authority = Keypair::new();
+    airdrop_lamports(&mut rpc, &authority.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let counterparty = Keypair::new();
+    airdrop_lamports(&mut rpc, &counterparty.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let mint_a = create_mint_helper(&mut rpc, &payer).await;
+    let mint_b = create_mint_helper(&mut rpc, &payer).await;
+    mint_tokens_helper(
+        &mut rpc,
+        &mut test_indexer,
+        &queue_pubkey,
+        &payer,
+        &mint_a,
+        vec![1_000],
+        vec![authority.pubkey()],
+    )
+    .await;
+    mint_tokens_helper(
+        &mut rpc,
+        &mut test_indexer,
+        &queue_pubkey,
+        &payer,
+        &mint_b,
+        vec![500],
+        vec![counterparty.pubkey()],
+    )
+    .await;
+    let authority_inputs = test_indexer
+        .get_compressed_token_accounts_by_owner(&authority.pubkey(), Some(mint_a))
+        .await
+        .unwrap();
+    let counterparty_inputs = test_indexer
+        .get_compressed_token_accounts_by_owner(&counterparty.pubkey(), Some(mint_b))
+        .await
+        .unwrap();
+    let instruction = create_test_swap_instruction(
+        &payer.pubkey(),
+        &authority.pubkey(),
+        &counterparty.pubkey(),
+        &authority_inputs,
+        &counterparty_inputs,
+        400,
+        500,
+        &queue_pubkey,
+    );
+    let signers = [&payer, &authority, &counterparty];
+
+    // 1. Failing: mint account of the counterparty leg is missing.
+    {
+        let mut instruction = instruction.clone();
+        instruction.accounts.pop();
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await;
+        assert_rpc_error(result, 0, ErrorCode::MintAccountUndefined.into()).unwrap();
+    }
+    // 2. Functional: authority sends 400 of mint a, counterparty sends its
+    //    whole balance of mint b.
+    {
+        let (event, _, slot) = rpc
+            .create_and_send_transaction_with_public_event(
+                &[instruction],
+                &payer.pubkey(),
+                &signers,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        test_indexer.add_event_and_compressed_accounts(slot, &event);
+        let balance = |accounts: Vec<TokenDataWithMerkleContext>| {
+            accounts
+                .iter()
+                .map(|x| x.token_data.amount)
+                .collect::<Vec<_>>()
+        };
+        for (owner, mint, expected_balance) in [
+            (authority.pubkey(), mint_a, vec![600]),
+            (authority.pubkey(), mint_b, vec![500]),
+            (counterparty.pubkey(), mint_a, vec![400]),
+            (counterparty.pubkey(), mint_b, vec![]),
+        ] {
+            let accounts = test_indexer
+                .get_compressed_token_accounts_by_owner(&owner, Some(mint))
+                .await
+                .unwrap();
+            assert_eq!(balance(accounts), expected_balance);
+        }
+    }
+    // 3. Failing: the counterparty leg is of a transfer fee mint.
+    {
+        let fee_mint = create_transfer_fee_mint(&mut rpc, &payer, &Pubkey::new_unique(), 100).await;
+        let token_account =
+            create_transfer_fee_token_account(&mut rpc, &fee_mint, &counterparty).await;
+        let mint_to_ix = spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            &fee_mint,
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            1_000,
+        )
+        .unwrap();
+        rpc.create_and_send_transaction(&[mint_to_ix], &payer.pubkey(), &[&payer])
+            .await
+            .unwrap();
+        let compress_output = TokenTransferOutputData {
+            amount: 990,
+            owner: counterparty.pubkey(),
+            lamports: None,
+            merkle_tree: queue_pubkey,
+        };
+        let instruction = create_transfer_fee_mint_transfer_instruction(
+            &counterparty.pubkey(),
+            &fee_mint,
+            &[],
+            &[compress_output],
+            true,
+            Some(1_000),
+            Some(token_account),
+            true,
+        );
+        send_and_index_transaction(&mut rpc, &mut test_indexer, instruction, &counterparty).await;
+
+        let authority_inputs = test_indexer
+            .get_compressed_token_accounts_by_owner(&authority.pubkey(), Some(mint_a))
+            .await
+            .unwrap();
+        let counterparty_inputs = test_indexer
+            .get_compressed_token_accounts_by_owner(&counterparty.pubkey(), Some(fee_mint))
+            .await
+            .unwrap();
+        let instruction = create_test_swap_instruction(
+            &payer.pubkey(),
+            &authority.pubkey(),
+            &counterparty.pubkey(),
+            &authority_inputs,
+            &counterparty_inputs,
+            100,
+            990,
+            &queue_pubkey,
+        );
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await;
+        assert_rpc_error(result, 0, ErrorCode::SwapMintExtensionNotSupported.into()).unwrap();
+    }
+}
+
+/// 1. Failing: freeze authority
+/// 2. Failing: address Merkle tree is not the compressed mint address tree
+/// 3. Functional: create compressed mint
+/// 4. Failing: second compressed mint of the same mint pda
+#[serial]
+#[tokio::test]
+async fn test_create_compressed_mint() {
+    let (mut rpc, env) = setup_test_programs_with_accounts(None).await;
+    spawn_prover(
+        true,
+        ProverConfig {
+            run_mode: Some(ProverMode::Rpc),
+            circuits: vec![],
+        },
+    )
+    .await;
+    let payer = rpc.get_payer().insecure_clone();
+    let mut test_indexer =
+        TestIndexer::<ProgramTestRpcConnection>::init_from_env(&payer, &env, None).await;
+    let mint_signer = Keypair::new();
+    let (spl_mint, _) = find_compressed_mint_pda(&mint_signer.pubkey());
+    let address = get_compressed_mint_address(&spl_mint);
+    let proof_rpc_result = test_indexer
+        .create_proof_for_compressed_accounts2(
+            None,
+            None,
+            Some(&[address]),
+            Some(vec![env.batch_address_merkle_tree]),
+            &mut rpc,
+        )
+        .await;
+    let create_instruction = |freeze_authority: Option<Pubkey>, address_merkle_tree: Pubkey| {
+        create_create_compressed_mint_instruction(CreateCompressedMintInstructionInputs {
+            fee_payer: payer.pubkey(),
+            authority: payer.pubkey(),
+            mint_signer: mint_signer.pubkey(),
+            decimals: 9,
+            freeze_authority,
+            proof: proof_rpc_result.proof.unwrap(),
+            address_merkle_tree_root_index: proof_rpc_result.address_root_indices[0],
+            address_merkle_tree,
+            output_merkle_tree: env.batched_output_queue,
+        })
+    };
+    let signers = [&payer, &mint_signer];
+
+    // 1. Failing: freeze authority
+    {
+        let instruction = create_instruction(Some(payer.pubkey()), env.batch_address_merkle_tree);
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await;
+        assert_rpc_error(
+            result,
+            0,
+            ErrorCode::CompressedMintFreezeAuthorityNotSupported.into(),
+        )
+        .unwrap();
+    }
+    // 2. Failing: v1 address Merkle tree
+    {
+        let instruction = create_instruction(None, env.address_merkle_tree_pubkey);
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await;
+        assert_rpc_error(
+            result,
+            0,
+            ErrorCode::InvalidCompressedMintAddressMerkleTree.into(),
+        )
+        .unwrap();
+    }
+    // 3. Functional: create compressed mint
+    {
+        let instruction = create_instruction(None, env.batch_address_merkle_tree);
+        rpc.create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await
+            .unwrap();
+    }
+    // 4. Failing: the compressed mint address of the mint pda already exists
+    {
+        let instruction = create_instruction(None, env.batch_address_merkle_tree);
+        let result = rpc
+            .create_and_send_transaction(&[instruction], &payer.pubkey(), &signers)
+            .await;
+        assert_rpc_error(result, 0, light_bloom_filter::BloomFilterError::Full.into()).unwrap();
+    }
+}
@@ -103,11 +103,11 @@ fn test_protocol_config_active_phase_continuity() {
         cpi_context_size: 20488,
         finalize_counter_limit: 100,
         place_holder: Pubkey::default(),
-        place_holder_a: 0,
-        place_holder_b: 0,
-        place_holder_c: 0,
-        place_holder_d: 0,
-        place_holder_e: 0,
+        network_fee_curve_min_fee: 0,
+        network_fee_curve_max_fee: 0,
+        network_fee_curve_low_fill_threshold: 0,
+        network_fee_curve_high_fill_threshold: 0,
+        forester_slashing_penalty: 10,
         place_holder_f: 0,
     };
 
@@ -122,11 +122,11 @@ fn test_protocol_config_active_phase_continuity() {
         cpi_context_size: 20488,
         finalize_counter_limit: 100,
         place_holder: Pubkey::default(),
-        place_holder_a: 0,
-        place_holder_b: 0,
-        place_holder_c: 0,
-        place_holder_d: 0,
-        place_holder_e: 0,
+        network_fee_curve_min_fee: 0,
+        network_fee_curve_max_fee: 0,
+        network_fee_curve_low_fill_threshold: 0,
+        network_fee_curve_high_fill_threshold: 0,
+        forester_slashing_penalty: 10,
         place_holder_f: 0,
     };
 
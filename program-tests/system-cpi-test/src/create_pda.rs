@@ -409,6 +409,7 @@ fn cpi_compressed_pda_transfer_as_program<'info>(
                 .collect::<Vec<_>>(),
             read_only_addresses: read_only_address.unwrap_or_default(),
             read_only_accounts: read_only_accounts.unwrap_or_default(),
+            decompression_recipients: Vec::new(),
         };
 
         let cpi_accounts = light_system_program::cpi::accounts::InvokeCpiInstruction {
@@ -336,6 +336,7 @@ pub fn cpi_compressed_token_transfer<'info>(
         cpi_context,
         lamports_change_account_merkle_tree_index: None,
         with_transaction_hash: false,
+        input_tlvs: Vec::new(),
     };
 
     let mut inputs = Vec::new();
@@ -405,6 +406,8 @@ pub fn cpi_compressed_token_approve_revoke<'info>(
                 change_account_merkle_tree_index: merkle_tree_index,
                 delegate_merkle_tree_index: merkle_tree_index,
                 cpi_context,
+                with_allowance: false,
+                input_tlvs: Vec::new(),
             };
             msg!("cpi test program calling approve");
 
@@ -417,6 +420,7 @@ pub fn cpi_compressed_token_approve_revoke<'info>(
                 input_token_data_with_context: vec![input_token_data_with_context],
                 output_account_merkle_tree_index: merkle_tree_index,
                 cpi_context,
+                input_tlvs: Vec::new(),
             };
             msg!("cpi test program calling revoke");
 
@@ -487,6 +491,7 @@ pub fn cpi_compressed_token_burn<'info>(
         burn_amount: input_token_data_with_context.amount - 1,
         change_account_merkle_tree_index: merkle_tree_index,
         cpi_context,
+        input_tlvs: Vec::new(),
     };
     CompressedTokenInstructionDataBurn::serialize(&inputs_struct, &mut inputs).unwrap();
     let cpi_accounts = light_compressed_token::cpi::accounts::BurnInstruction {
@@ -549,6 +554,7 @@ pub fn cpi_compressed_token_freeze_or_thaw<'info>(
                 owner: ctx.accounts.signer.key(),
                 cpi_context,
                 outputs_merkle_tree_index: merkle_tree_index,
+                input_tlvs: Vec::new(),
             };
             CompressedTokenInstructionDataFreeze::serialize(&inputs_struct, &mut inputs).unwrap();
         }
@@ -559,6 +565,7 @@ pub fn cpi_compressed_token_freeze_or_thaw<'info>(
                 outputs_merkle_tree_index: merkle_tree_index,
                 cpi_context,
                 owner: ctx.accounts.signer.key(),
+                input_tlvs: Vec::new(),
             };
             CompressedTokenInstructionDataThaw::serialize(&inputs_struct, &mut inputs).unwrap();
         }
@@ -106,6 +106,7 @@ async fn parse_batched_event_functional() {
             address_sequence_numbers: Vec::new(),
             input_sequence_numbers: Vec::new(),
             batch_input_accounts: Vec::new(),
+            decompression_recipients: Vec::new(),
             new_addresses: Vec::new(),
             tx_hash: [0u8; 32],
         };
@@ -252,6 +253,7 @@ async fn parse_batched_event_functional() {
                 seq: 0,
             }],
             batch_input_accounts,
+            decompression_recipients: Vec::new(),
             new_addresses: new_addresses
                 .iter()
                 .map(|x| NewAddress {
@@ -417,6 +419,7 @@ async fn parse_batched_event_functional() {
                 seq: 8,
             }],
             batch_input_accounts,
+            decompression_recipients: Vec::new(),
             new_addresses: new_addresses
                 .iter()
                 .enumerate()
@@ -487,6 +490,7 @@ async fn parse_multiple_batched_events_functional() {
             address_sequence_numbers: Vec::new(),
             input_sequence_numbers: Vec::new(),
             batch_input_accounts: Vec::new(),
+            decompression_recipients: Vec::new(),
             new_addresses: Vec::new(),
             tx_hash: [0u8; 32],
         };
@@ -576,6 +580,7 @@ async fn generate_photon_test_data_multiple_events() {
             address_sequence_numbers: Vec::new(),
             input_sequence_numbers: Vec::new(),
             batch_input_accounts: Vec::new(),
+            decompression_recipients: Vec::new(),
             new_addresses: Vec::new(),
             tx_hash: [0u8; 32],
         };
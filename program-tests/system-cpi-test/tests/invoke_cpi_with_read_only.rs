@@ -2754,6 +2754,155 @@ async fn compress_sol_with_read_only() {
     }
 }
 
+/// Test decompressing sol to multiple recipients with the read only instruction:
+/// 1. Compress sol.
+/// 2. Failing decompress, recipients exceed the input lamports.
+/// 3. Functional decompress to two recipients,
+///     the event contains every recipient.
+#[serial]
+#[tokio::test]
+async fn decompress_sol_to_recipients_with_read_only() {
+    let with_transaction_hash = false;
+    for is_small_ix in [true, false].into_iter() {
+        let (mut rpc, env) =
+            setup_test_programs_with_accounts_with_protocol_config_and_batched_tree_params(
+                Some(vec![(
+                    "create_address_test_program",
+                    create_address_test_program::ID,
+                )]),
+                ProtocolConfig {
+                    // Init with an active epoch which doesn't end
+                    active_phase_length: 1_000_000_000,
+                    slot_length: 1_000_000_000 - 1,
+                    genesis_slot: 0,
+                    registration_phase_length: 2,
+                    ..Default::default()
+                },
+                true,
+                InitStateTreeAccountsInstructionData::default(),
+                InitAddressTreeAccountsInstructionData::test_default(),
+            )
+            .await;
+        let queue = env.batched_output_queue;
+        let tree = env.batched_state_merkle_tree;
+
+        let payer = rpc.get_payer().insecure_clone();
+        let mut test_indexer =
+            TestIndexer::<ProgramTestRpcConnection>::init_from_env(&payer, &env, None).await;
+        let compression_lamports = 1_000_000;
+        // 1. Compress sol
+        {
+            let mut output_account = get_compressed_output_account(true, queue);
+            output_account.compressed_account.lamports = compression_lamports;
+            local_sdk::perform_test_transaction(
+                &mut rpc,
+                &mut test_indexer,
+                &payer,
+                vec![],
+                vec![output_account],
+                vec![],
+                None,
+                None,
+                None,
+                is_small_ix,
+                with_transaction_hash,
+                Vec::new(),
+                Vec::new(),
+                queue,
+                tree,
+                true,
+                Some(compression_lamports),
+                false,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        let mut input_account = get_compressed_input_account(MerkleContext {
+            merkle_tree_pubkey: tree,
+            queue_pubkey: queue,
+            leaf_index: 0,
+            prove_by_index: true,
+            tree_type: TreeType::StateV2,
+        });
+        input_account.compressed_account.lamports = compression_lamports;
+        let recipients = vec![
+            (Pubkey::new_unique(), 400_000),
+            (Pubkey::new_unique(), 600_000),
+        ];
+        // 2. Failing decompress, recipients exceed the input lamports.
+        {
+            let mut invalid_recipients = recipients.clone();
+            invalid_recipients[1].1 += 1;
+            let result = local_sdk::perform_test_transaction_with_decompression_recipients(
+                &mut rpc,
+                &mut test_indexer,
+                &payer,
+                vec![input_account.clone()],
+                vec![],
+                vec![],
+                None,
+                None,
+                None,
+                is_small_ix,
+                with_transaction_hash,
+                Vec::new(),
+                Vec::new(),
+                queue,
+                tree,
+                false,
+                None,
+                false,
+                None,
+                None,
+                invalid_recipients,
+            )
+            .await;
+            assert_rpc_error(result, 0, SystemProgramError::ComputeOutputSumFailed.into()).unwrap();
+        }
+        // 3. Functional decompress to two recipients.
+        {
+            let (events, _, _) = local_sdk::perform_test_transaction_with_decompression_recipients(
+                &mut rpc,
+                &mut test_indexer,
+                &payer,
+                vec![input_account],
+                vec![],
+                vec![],
+                None,
+                None,
+                None,
+                is_small_ix,
+                with_transaction_hash,
+                Vec::new(),
+                Vec::new(),
+                queue,
+                tree,
+                false,
+                None,
+                false,
+                None,
+                None,
+                recipients.clone(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            for (recipient, lamports) in recipients.iter() {
+                assert_eq!(rpc.get_balance(recipient).await.unwrap(), *lamports);
+            }
+            let event_recipients = events[0]
+                .decompression_recipients
+                .iter()
+                .map(|x| (Pubkey::from(x.recipient.to_bytes()), x.lamports))
+                .collect::<Vec<_>>();
+            assert_eq!(event_recipients, recipients);
+            assert_eq!(events[0].event.compress_or_decompress_lamports, None);
+        }
+    }
+}
+
 fn get_input_account_info(merkle_context: PackedMerkleContext) -> InAccountInfo {
     InAccountInfo {
         discriminator: u64::MAX.to_be_bytes(),
@@ -2793,6 +2942,7 @@ pub mod local_sdk {
         instruction_data::{
             compressed_proof::CompressedProof,
             cpi_context::CompressedCpiContext,
+            data::PackedDecompressionRecipient,
             with_account_info::{CompressedAccountInfo, InstructionDataInvokeCpiWithAccountInfo},
             with_readonly::{InAccount, InstructionDataInvokeCpiWithReadOnly},
         },
@@ -2856,6 +3006,68 @@ pub mod local_sdk {
             Vec<PackedCompressedAccountWithMerkleContext>,
         )>,
         RpcError,
+    > {
+        perform_test_transaction_with_decompression_recipients(
+            rpc,
+            test_indexer,
+            payer,
+            input_accounts,
+            output_accounts,
+            new_addresses,
+            proof,
+            sol_compression_recipient,
+            account_infos,
+            small_ix,
+            with_transaction_hash,
+            read_only_accounts,
+            read_only_addresses,
+            queue,
+            tree,
+            is_compress,
+            compress_or_decompress_lamports,
+            invalid_sol_pool,
+            cpi_context,
+            cpi_context_address,
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Decompression recipients are only supported
+    /// by the read only instruction (account_infos is None).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn perform_test_transaction_with_decompression_recipients<
+        R: RpcConnection,
+        I: Indexer<R> + TestIndexerExtensions<R>,
+    >(
+        rpc: &mut R,
+        test_indexer: &mut I,
+        payer: &Keypair,
+        input_accounts: Vec<CompressedAccountWithMerkleContext>,
+        output_accounts: Vec<OutputCompressedAccountWithContext>,
+        new_addresses: Vec<NewAddressParamsAssigned>,
+        proof: Option<CompressedProof>,
+        sol_compression_recipient: Option<Pubkey>,
+        account_infos: Option<Vec<CompressedAccountInfo>>,
+        small_ix: bool,
+        with_transaction_hash: bool,
+        read_only_accounts: Vec<ReadOnlyCompressedAccount>,
+        read_only_addresses: Vec<ReadOnlyAddress>,
+        queue: Pubkey,
+        tree: Pubkey,
+        is_compress: bool,
+        compress_or_decompress_lamports: Option<u64>,
+        invalid_sol_pool: bool,
+        cpi_context: Option<CompressedCpiContext>,
+        cpi_context_address: Option<Pubkey>,
+        decompression_recipients: Vec<(Pubkey, u64)>,
+    ) -> Result<
+        Option<(
+            Vec<BatchPublicTransactionEvent>,
+            Vec<OutputCompressedAccountWithPackedContext>,
+            Vec<PackedCompressedAccountWithMerkleContext>,
+        )>,
+        RpcError,
     > {
         let mut remaining_accounts = HashMap::<Pubkey, usize>::new();
 
@@ -2888,6 +3100,13 @@ pub mod local_sdk {
             pack_read_only_accounts(read_only_accounts.as_slice(), &mut remaining_accounts);
         let read_only_addresses =
             pack_read_only_address_params(read_only_addresses.as_slice(), &mut remaining_accounts);
+        let packed_decompression_recipients = decompression_recipients
+            .iter()
+            .map(|(recipient, lamports)| PackedDecompressionRecipient {
+                recipient_index: pack_pubkey_usize(recipient, &mut remaining_accounts),
+                lamports: *lamports,
+            })
+            .collect::<Vec<_>>();
 
         let ix_data = if account_infos.is_none() {
             InstructionDataInvokeCpiWithReadOnly {
@@ -2916,6 +3135,7 @@ pub mod local_sdk {
                 read_only_addresses,
                 with_cpi_context: cpi_context.is_some(),
                 cpi_context: cpi_context.unwrap_or_default(),
+                decompression_recipients: packed_decompression_recipients,
             }
             .try_to_vec()
             .unwrap()
@@ -2949,7 +3169,9 @@ pub mod local_sdk {
         let config = SystemAccountMetaConfig {
             self_program: create_address_test_program::ID,
             cpi_context: cpi_context_address,
-            sol_pool_pda: if compress_or_decompress_lamports.is_some() {
+            sol_pool_pda: if compress_or_decompress_lamports.is_some()
+                || !decompression_recipients.is_empty()
+            {
                 let pool_pubkey = if invalid_sol_pool {
                     Pubkey::find_program_address(&[&[1]], &light_system_program::ID).0
                 } else {
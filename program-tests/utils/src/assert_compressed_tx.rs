@@ -5,7 +5,7 @@ use forester_utils::account_zero_copy::{
 };
 use light_account_checks::discriminator::Discriminator as LightDiscriminator;
 use light_batched_merkle_tree::{
-    batch::Batch, merkle_tree::BatchedMerkleTreeAccount, queue::BatchedQueueMetadata,
+    merkle_tree::BatchedMerkleTreeAccount, queue::BatchedQueueMetadata,
 };
 use light_client::{
     indexer::{Indexer, StateMerkleTreeAccounts},
@@ -163,13 +163,9 @@ pub async fn assert_nullifiers_exist_in_hash_sets<R: RpcConnection>(
                 .unwrap();
                 let mut batches = merkle_tree.queue_batches.batches;
                 batches.iter_mut().enumerate().any(|(i, batch)| {
-                    Batch::check_non_inclusion(
-                        batch.num_iters as usize,
-                        batch.bloom_filter_capacity,
-                        hash,
-                        merkle_tree.bloom_filter_stores[i],
-                    )
-                    .is_err()
+                    batch
+                        .check_value_non_inclusion(hash, merkle_tree.bloom_filter_stores[i])
+                        .is_err()
                 });
             }
             _ => {
@@ -202,23 +198,15 @@ pub async fn assert_addresses_exist_in_hash_sets<R: RpcConnection>(
                 let mut batches = merkle_tree.queue_batches.batches;
                 // Must be included in one batch
                 batches.iter_mut().enumerate().any(|(i, batch)| {
-                    Batch::check_non_inclusion(
-                        batch.num_iters as usize,
-                        batch.bloom_filter_capacity,
-                        address,
-                        merkle_tree.bloom_filter_stores[i],
-                    )
-                    .is_err()
+                    batch
+                        .check_value_non_inclusion(address, merkle_tree.bloom_filter_stores[i])
+                        .is_err()
                 });
                 // must not be included in any other batch
                 batches.iter_mut().enumerate().any(|(i, batch)| {
-                    Batch::check_non_inclusion(
-                        batch.num_iters as usize,
-                        batch.bloom_filter_capacity,
-                        address,
-                        merkle_tree.bloom_filter_stores[i],
-                    )
-                    .is_ok()
+                    batch
+                        .check_value_non_inclusion(address, merkle_tree.bloom_filter_stores[i])
+                        .is_ok()
                 });
             }
             _ => {
@@ -135,6 +135,8 @@ pub async fn assert_registered_forester_pda<R: RpcConnection>(
         epoch_active_phase_start_slot,
         protocol_config: epoch_pda.protocol_config,
         finalize_counter: 0,
+        is_slashed: false,
+        work_light_slots: [0; 16],
     };
     let forester_epoch_pda = rpc
         .get_anchor_account::<ForesterEpochPda>(forester_epoch_pda_pubkey)
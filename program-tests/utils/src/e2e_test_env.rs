@@ -67,8 +67,8 @@ use std::collections::HashMap;
 
 use account_compression::{
     utils::constants::{STATE_MERKLE_TREE_CANOPY_DEPTH, STATE_MERKLE_TREE_HEIGHT},
-    AddressMerkleTreeConfig, AddressQueueConfig, NullifierQueueConfig, StateMerkleTreeConfig,
-    SAFETY_MARGIN,
+    AddressMerkleTreeConfig, AddressQueueConfig, NullifierQueueConfig, StateMerkleTreeAccount,
+    StateMerkleTreeConfig, SAFETY_MARGIN,
 };
 use anchor_lang::AnchorSerialize;
 use create_address_test_program::create_invoke_cpi_instruction;
@@ -2360,13 +2360,31 @@ where
         .await
         .unwrap();
         info!("Rollover signature: {:?}", rollover_signature_and_slot.0);
-        let additional_rent = self
+        let cpi_context_rent = self
             .rpc
             .get_minimum_balance_for_rent_exemption(
                 ProtocolConfig::default().cpi_context_size as usize,
             )
             .await
             .unwrap();
+        // Rent of additional bytes (cpi context account) is reimbursed
+        // by the rollover if the old tree was initialized with it.
+        let old_merkle_tree =
+            AccountZeroCopy::<StateMerkleTreeAccount>::new(&mut self.rpc, bundle.merkle_tree).await;
+        let additional_bytes = old_merkle_tree
+            .deserialized()
+            .metadata
+            .rollover_metadata
+            .additional_bytes;
+        let reimbursed_rent = if additional_bytes == 0 {
+            0
+        } else {
+            self.rpc
+                .get_minimum_balance_for_rent_exemption(additional_bytes as usize)
+                .await
+                .unwrap()
+        };
+        let additional_rent = cpi_context_rent - reimbursed_rent;
         info!("additional_rent: {:?}", additional_rent);
         assert_rolled_over_pair(
             &self.indexer.get_payer().pubkey(),
@@ -2781,6 +2799,7 @@ where
             output_compressed_accounts: invoke_cpi.output_compressed_accounts,
             read_only_accounts,
             read_only_addresses,
+            decompression_recipients: Vec::new(),
         };
         println!("ix_data: {:?}", ix_data);
         if ix_data.read_only_accounts.is_empty()
@@ -1195,7 +1195,8 @@ pub async fn approve_test<R: RpcConnection, I: Indexer<R> + TestIndexerExtension
         change_compressed_account_merkle_tree: *change_compressed_account_merkle_tree,
         delegate: *delegate,
         root_indices: proof_rpc_result.root_indices,
-        proof: proof_rpc_result.proof.unwrap_or_default(),
+        proof: proof_rpc_result.proof,
+        with_allowance: false,
     };
 
     let instruction = create_approve_instruction(inputs).unwrap();
@@ -1355,7 +1356,7 @@ pub async fn revoke_test<R: RpcConnection, I: Indexer<R> + TestIndexerExtensions
         mint,
         output_account_merkle_tree: *output_account_merkle_tree,
         root_indices: proof_rpc_result.root_indices,
-        proof: proof_rpc_result.proof.unwrap_or_default(),
+        proof: proof_rpc_result.proof,
     };
 
     let instruction = create_revoke_instruction(inputs).unwrap();
@@ -25,6 +25,8 @@ pub enum AccountCompressionErrorCode {
     InvalidNoopPubkey,
     #[msg("InvalidQueueType")]
     InvalidQueueType,
+    #[msg("Rent of closed accounts is reimbursed to the tree owner.")]
+    InvalidRentRecipient,
     #[msg("Invalid sequence threshold")]
     InvalidSequenceThreshold,
     #[msg("Could not find the leaf in the queue")]
@@ -66,4 +68,8 @@ pub enum AccountCompressionErrorCode {
     UnsupportedHeight,
     UnsupportedParameters,
     V1AccountMarkedAsProofByIndex,
+    #[msg("Concurrent and indexed (v1) Merkle trees cannot be closed as batched Merkle trees.")]
+    V1MerkleTreeNotClosable,
+    #[msg("Address queue contains addresses which are not inserted into the Merkle tree.")]
+    AddressQueueNotEmpty,
 }
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use light_batched_merkle_tree::merkle_tree::{
-    BatchedMerkleTreeAccount, InstructionDataBatchNullifyInputs,
+    BatchedMerkleTreeAccount, InstructionDataBatchNullifyInputs, NullifiedValue,
 };
 
 use crate::{
@@ -38,12 +38,15 @@ impl<'info> GroupAccounts<'info> for BatchNullify<'info> {
 /// The input queue is part of the state Merkle tree account.
 /// 1. Check Merkle tree account discriminator, tree type, and program ownership.
 /// 2. Check that signer is registered or authority.
-/// 3. Nullify leaves from the input queue to the state Merkle tree.
-///     3.1 Verifies batch zkp and updates root.
-/// 4. Emit indexer event.
+/// 3. Remove nullified values of the previous batch
+///     from its counting bloom filter (optional).
+/// 4. Nullify leaves from the input queue to the state Merkle tree.
+///     4.1 Verifies batch zkp and updates root.
+/// 5. Emit indexer event.
 pub fn process_batch_nullify<'a, 'b, 'c: 'info, 'info>(
     ctx: &'a Context<'a, 'b, 'c, 'info, BatchNullify<'info>>,
     instruction_data: InstructionDataBatchNullifyInputs,
+    nullified_values: Vec<NullifiedValue>,
 ) -> Result<()> {
     // 1. Check Merkle tree account discriminator, tree type, and program ownership.
     let merkle_tree =
@@ -54,10 +57,21 @@ pub fn process_batch_nullify<'a, 'b, 'c: 'info, 'info>(
         ctx,
         merkle_tree,
     )?;
-    // 3. Nullify leaves from the input queue to the state Merkle tree.
+    // 3. Remove nullified values of the previous batch from its counting bloom filter.
+    if !nullified_values.is_empty() {
+        let previous_batch_index = if merkle_tree.queue_batches.pending_batch_index == 0 {
+            1
+        } else {
+            0
+        };
+        merkle_tree
+            .remove_nullified_values_from_bloom_filter(previous_batch_index, &nullified_values)
+            .map_err(ProgramError::from)?;
+    }
+    // 4. Nullify leaves from the input queue to the state Merkle tree.
     let event = merkle_tree
         .update_tree_from_input_queue(instruction_data)
         .map_err(ProgramError::from)?;
-    // 4. Emit indexer event.
+    // 5. Emit indexer event.
     emit_indexer_event(event.try_to_vec()?, &ctx.accounts.log_wrapper)
 }
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use light_merkle_tree_metadata::{
+    errors::MerkleTreeMetadataError,
+    queue::{check_queue_type, QueueType},
+};
+
+use crate::{
+    errors::AccountCompressionErrorCode,
+    state::{queue_from_bytes_zero_copy_mut, QueueAccount},
+    utils::{
+        check_signer_is_registered_or_authority::{
+            check_signer_is_registered_or_authority, GroupAccounts,
+        },
+        transfer_lamports::close_account,
+    },
+    AddressMerkleTreeAccount, RegisteredProgram,
+};
+
+#[derive(Accounts)]
+pub struct CloseAddressMerkleTreeAndQueue<'info> {
+    pub authority: Signer<'info>,
+    pub registered_program_pda: Option<Account<'info, RegisteredProgram>>,
+    /// CHECK: loaded in the instruction, an AccountLoader would restore
+    /// the discriminator of the closed account on exit.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: loaded in the instruction.
+    #[account(mut)]
+    pub queue: AccountInfo<'info>,
+    /// CHECK: receives the rent of the closed accounts. Without a registered
+    /// program the signer is the tree owner and receives the rent.
+    /// A registered program chooses the rent beneficiary of its group.
+    #[account(mut, constraint = registered_program_pda.is_some() || rent_recipient.key() == authority.key() @ AccountCompressionErrorCode::InvalidRentRecipient)]
+    pub rent_recipient: AccountInfo<'info>,
+}
+
+impl<'info> GroupAccounts<'info> for CloseAddressMerkleTreeAndQueue<'info> {
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+    fn get_registered_program_pda(&self) -> &Option<Account<'info, RegisteredProgram>> {
+        &self.registered_program_pda
+    }
+}
+
+/// Close a rolled over indexed (v1) address Merkle tree and its queue.
+/// 1. Check that signer is registered or authority.
+/// 2. Check that Merkle tree and queue are associated.
+/// 3. Check that Merkle tree and queue are rolled over,
+///     and close threshold slots have passed since the rollover.
+/// 4. Check that every address of the queue has been inserted into the tree.
+/// 5. Close Merkle tree and queue accounts,
+///     transfer their rent to the rent recipient.
+///
+/// Addresses are derived with the address tree pubkey and closed accounts
+/// cannot be initialized again, hence no address of the closed tree
+/// can be created again.
+pub fn process_close_address_merkle_tree_and_queue<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseAddressMerkleTreeAndQueue<'info>>,
+) -> Result<()> {
+    {
+        let merkle_tree =
+            AccountLoader::<AddressMerkleTreeAccount>::try_from(&ctx.accounts.merkle_tree)?;
+        let merkle_tree = merkle_tree.load()?;
+        let queue = AccountLoader::<QueueAccount>::try_from(&ctx.accounts.queue)?;
+        let queue = queue.load()?;
+        // 1. Check that signer is registered or authority.
+        check_signer_is_registered_or_authority::<
+            CloseAddressMerkleTreeAndQueue,
+            AddressMerkleTreeAccount,
+        >(&ctx, &merkle_tree)?;
+        // 2. Check that Merkle tree and queue are associated.
+        check_queue_type(&queue.metadata.queue_type, &QueueType::AddressV1)
+            .map_err(ProgramError::from)?;
+        if merkle_tree.metadata.associated_queue != ctx.accounts.queue.key().into()
+            || queue.metadata.associated_merkle_tree != ctx.accounts.merkle_tree.key().into()
+        {
+            return Err(ProgramError::from(
+                MerkleTreeMetadataError::MerkleTreeAndQueueNotAssociated,
+            )
+            .into());
+        }
+        // 3. Check that Merkle tree and queue are rolled over.
+        let current_slot = Clock::get()?.slot;
+        merkle_tree
+            .metadata
+            .rollover_metadata
+            .check_closable(current_slot)
+            .map_err(ProgramError::from)?;
+        queue
+            .metadata
+            .rollover_metadata
+            .check_closable(current_slot)
+            .map_err(ProgramError::from)?;
+    }
+    {
+        // 4. Check that every address has been inserted into the tree.
+        //      Inserted addresses are marked with a sequence number.
+        let mut queue = ctx.accounts.queue.try_borrow_mut_data()?;
+        let queue = unsafe { queue_from_bytes_zero_copy_mut(&mut queue)? };
+        if queue.first_no_seq().map_err(ProgramError::from)?.is_some() {
+            return err!(AccountCompressionErrorCode::AddressQueueNotEmpty);
+        }
+    }
+    // 5. Close Merkle tree and queue accounts.
+    close_account(&ctx.accounts.merkle_tree, &ctx.accounts.rent_recipient)?;
+    close_account(&ctx.accounts.queue, &ctx.accounts.rent_recipient)?;
+    Ok(())
+}
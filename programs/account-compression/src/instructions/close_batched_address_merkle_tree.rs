@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use light_batched_merkle_tree::{
+    close_tree::check_batched_address_tree_is_closable, merkle_tree::BatchedMerkleTreeAccount,
+};
+
+use super::check_merkle_tree_is_not_v1;
+use crate::{
+    errors::AccountCompressionErrorCode,
+    utils::{
+        check_signer_is_registered_or_authority::{
+            check_signer_is_registered_or_authority, GroupAccounts,
+        },
+        transfer_lamports::close_account,
+    },
+    AddressMerkleTreeAccount, RegisteredProgram,
+};
+
+#[derive(Accounts)]
+pub struct CloseBatchedAddressMerkleTree<'info> {
+    pub authority: Signer<'info>,
+    pub registered_program_pda: Option<Account<'info, RegisteredProgram>>,
+    /// CHECK: in address_from_account_info.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: receives the rent of the closed account. Without a registered
+    /// program the signer is the tree owner and receives the rent.
+    /// A registered program chooses the rent beneficiary of its group.
+    #[account(mut, constraint = registered_program_pda.is_some() || rent_recipient.key() == authority.key() @ AccountCompressionErrorCode::InvalidRentRecipient)]
+    pub rent_recipient: AccountInfo<'info>,
+}
+
+impl<'info> GroupAccounts<'info> for CloseBatchedAddressMerkleTree<'info> {
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+    fn get_registered_program_pda(&self) -> &Option<Account<'info, RegisteredProgram>> {
+        &self.registered_program_pda
+    }
+}
+
+/// Close a rolled over address Merkle tree.
+/// 0. Reject indexed (v1) address Merkle trees.
+/// 1. Check Merkle tree account discriminator, tree type, and program ownership.
+/// 2. Check that signer is registered or authority.
+/// 3. Check that Merkle tree can be closed.
+/// 4. Close Merkle tree account,
+///     transfer its rent to the rent recipient.
+pub fn process_close_batched_address_merkle_tree<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseBatchedAddressMerkleTree<'info>>,
+) -> Result<()> {
+    check_merkle_tree_is_not_v1::<AddressMerkleTreeAccount>(&ctx.accounts.merkle_tree)?;
+    {
+        // 1. Check Merkle tree account discriminator, tree type, and program ownership.
+        let merkle_tree =
+            BatchedMerkleTreeAccount::address_from_account_info(&ctx.accounts.merkle_tree)
+                .map_err(ProgramError::from)?;
+        // 2. Check that signer is registered or authority.
+        check_signer_is_registered_or_authority::<
+            CloseBatchedAddressMerkleTree,
+            BatchedMerkleTreeAccount,
+        >(&ctx, &merkle_tree)?;
+        // 3. Check that Merkle tree can be closed.
+        check_batched_address_tree_is_closable(&merkle_tree, Clock::get()?.slot)
+            .map_err(ProgramError::from)?;
+    }
+    // 4. Close Merkle tree account.
+    close_account(&ctx.accounts.merkle_tree, &ctx.accounts.rent_recipient)?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use light_batched_merkle_tree::{
+    close_tree::check_batched_state_tree_is_closable, merkle_tree::BatchedMerkleTreeAccount,
+    queue::BatchedQueueAccount,
+};
+
+use crate::{
+    errors::AccountCompressionErrorCode,
+    utils::{
+        check_signer_is_registered_or_authority::{
+            check_signer_is_registered_or_authority, GroupAccounts,
+        },
+        transfer_lamports::close_account,
+    },
+    RegisteredProgram, StateMerkleTreeAccount,
+};
+
+#[derive(Accounts)]
+pub struct CloseBatchedStateMerkleTree<'info> {
+    pub authority: Signer<'info>,
+    pub registered_program_pda: Option<Account<'info, RegisteredProgram>>,
+    /// CHECK: in state_from_account_info.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: in output_from_account_info.
+    #[account(mut)]
+    pub output_queue: AccountInfo<'info>,
+    /// CHECK: receives the rent of the closed accounts. Without a registered
+    /// program the signer is the tree owner and receives the rent.
+    /// A registered program chooses the rent beneficiary of its group.
+    #[account(mut, constraint = registered_program_pda.is_some() || rent_recipient.key() == authority.key() @ AccountCompressionErrorCode::InvalidRentRecipient)]
+    pub rent_recipient: AccountInfo<'info>,
+}
+
+impl<'info> GroupAccounts<'info> for CloseBatchedStateMerkleTree<'info> {
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+    fn get_registered_program_pda(&self) -> &Option<Account<'info, RegisteredProgram>> {
+        &self.registered_program_pda
+    }
+}
+
+/// Close a rolled over state Merkle tree and its output queue.
+/// 0. Reject concurrent (v1) state Merkle trees.
+/// 1. Check Merkle tree and queue account discriminator, tree type, and program ownership.
+/// 2. Check that signer is registered or authority.
+/// 3. Check that Merkle tree and queue can be closed.
+/// 4. Close Merkle tree and queue accounts,
+///     transfer their rent to the rent recipient.
+pub fn process_close_batched_state_merkle_tree<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseBatchedStateMerkleTree<'info>>,
+) -> Result<()> {
+    check_merkle_tree_is_not_v1::<StateMerkleTreeAccount>(&ctx.accounts.merkle_tree)?;
+    {
+        // 1. Check Merkle tree and queue account discriminator, tree type, and program ownership.
+        let merkle_tree =
+            BatchedMerkleTreeAccount::state_from_account_info(&ctx.accounts.merkle_tree)
+                .map_err(ProgramError::from)?;
+        let output_queue =
+            BatchedQueueAccount::output_from_account_info(&ctx.accounts.output_queue)
+                .map_err(ProgramError::from)?;
+        // 2. Check that signer is registered or authority.
+        check_signer_is_registered_or_authority::<
+            CloseBatchedStateMerkleTree,
+            BatchedMerkleTreeAccount,
+        >(&ctx, &merkle_tree)?;
+        // 3. Check that Merkle tree and queue can be closed.
+        check_batched_state_tree_is_closable(
+            &merkle_tree,
+            &ctx.accounts.merkle_tree.key().into(),
+            &output_queue,
+            &ctx.accounts.output_queue.key().into(),
+            Clock::get()?.slot,
+        )
+        .map_err(ProgramError::from)?;
+    }
+    // 4. Close Merkle tree and queue accounts.
+    close_account(&ctx.accounts.merkle_tree, &ctx.accounts.rent_recipient)?;
+    close_account(&ctx.accounts.output_queue, &ctx.accounts.rent_recipient)?;
+    Ok(())
+}
+
+/// Concurrent (v1) state Merkle trees don't track whether every leaf
+/// has been nullified, closing one could destroy compressed accounts
+/// which are still spendable, hence they cannot be closed.
+/// Indexed (v1) address Merkle trees are closed with
+/// close_address_merkle_tree_and_queue.
+/// Fails with a dedicated error instead of a discriminator mismatch.
+pub fn check_merkle_tree_is_not_v1<T: Discriminator>(account: &AccountInfo) -> Result<()> {
+    let data = account.try_borrow_data()?;
+    if data.len() >= T::DISCRIMINATOR.len() && data[..T::DISCRIMINATOR.len()] == *T::DISCRIMINATOR {
+        msg!("Concurrent and indexed (v1) Merkle trees cannot be closed as batched Merkle trees.");
+        return err!(AccountCompressionErrorCode::V1MerkleTreeNotClosable);
+    }
+    Ok(())
+}
@@ -103,8 +103,11 @@ pub fn process_initialize_address_merkle_tree_and_queue<'info>(
         );
         return err!(AccountCompressionErrorCode::UnsupportedCanopyDepth);
     }
-    if merkle_tree_config.close_threshold.is_some() {
-        msg!("close_threshold is not supported yet");
+    // Only rolled over trees can be closed.
+    if merkle_tree_config.close_threshold.is_some()
+        && merkle_tree_config.rollover_threshold.is_none()
+    {
+        msg!("close_threshold requires a rollover_threshold");
         return err!(AccountCompressionErrorCode::UnsupportedCloseThreshold);
     }
     let minimum_sequence_threshold = merkle_tree_config.roots_size + SAFETY_MARGIN;
@@ -92,7 +92,7 @@ pub fn process_initialize_state_merkle_tree_and_nullifier_queue<'info>(
     forester: Option<Pubkey>,
     state_merkle_tree_config: StateMerkleTreeConfig,
     nullifier_queue_config: NullifierQueueConfig,
-    _additional_bytes: u64,
+    additional_bytes: u64,
 ) -> Result<()> {
     if state_merkle_tree_config.height as u64 != STATE_MERKLE_TREE_HEIGHT {
         msg!(
@@ -140,6 +140,11 @@ pub fn process_initialize_state_merkle_tree_and_nullifier_queue<'info>(
         queue_expected_size,
     )
     .map_err(ProgramError::from)?;
+    let additional_bytes_rent = if additional_bytes == 0 {
+        0
+    } else {
+        Rent::get()?.minimum_balance(additional_bytes as usize)
+    };
     let owner = match ctx.accounts.registered_program_pda.as_ref() {
         Some(registered_program_pda) => {
             check_signer_is_registered_or_authority::<
@@ -166,6 +171,8 @@ pub fn process_initialize_state_merkle_tree_and_nullifier_queue<'info>(
         state_merkle_tree_config.close_threshold,
         merkle_tree_rent,
         queue_rent,
+        additional_bytes,
+        additional_bytes_rent,
     )?;
     process_initialize_nullifier_queue(
         ctx.accounts.nullifier_queue.to_account_info(),
@@ -188,6 +188,7 @@ mod migrate_state_test {
             tree_capacity: 2u64.pow(32),
             hashed_merkle_tree_pubkey: [0u8; 32],
             hashed_queue_pubkey: [0u8; 32],
+            ..Default::default()
         };
         let queue_pubkey = Pubkey::new_unique();
         let account_data: Vec<u8> = vec![
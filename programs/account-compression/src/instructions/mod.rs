@@ -59,3 +59,15 @@ pub use generic::*;
 
 pub mod resize_registered_program_account;
 pub use resize_registered_program_account::*;
+
+pub mod close_batched_state_merkle_tree;
+pub use close_batched_state_merkle_tree::*;
+
+pub mod close_batched_address_merkle_tree;
+pub use close_batched_address_merkle_tree::*;
+
+pub mod close_address_merkle_tree_and_queue;
+pub use close_address_merkle_tree_and_queue::*;
+
+pub mod transfer_network_fees;
+pub use transfer_network_fees::*;
@@ -54,10 +54,14 @@ impl<'info> GroupAccounts<'info> for RolloverStateMerkleTreeAndNullifierQueue<'i
 /// 4. Merkle tree is not already rolled over Actions:
 /// 1. mark Merkle tree as rolled over in this slot
 /// 2. initialize new Merkle tree and nullifier queue with the same parameters
+/// 3. reimburse the fee payer for rent of the new accounts,
+///    including rent for additional bytes (cpi context account)
+///    if a registered program rolls over the tree.
+///    The registry program creates the new cpi context account
+///    in the same instruction, a direct rollover creates no cpi context account.
 pub fn process_rollover_state_merkle_tree_nullifier_queue_pair<'a, 'b, 'c: 'info, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, RolloverStateMerkleTreeAndNullifierQueue<'info>>,
 ) -> Result<()> {
-    // TODO: rollover additional rent as well. (need to add a field to the metadata for this)
     let new_merkle_tree_account_info = ctx.accounts.new_state_merkle_tree.to_account_info();
     let merkle_tree_rent = check_account_balance_is_rent_exempt(
         &new_merkle_tree_account_info,
@@ -77,7 +81,7 @@ pub fn process_rollover_state_merkle_tree_nullifier_queue_pair<'a, 'b, 'c: 'info
     )
     .map_err(ProgramError::from)?;
 
-    let queue_metadata = {
+    let (queue_metadata, additional_bytes_reimbursement) = {
         let (merkle_tree_metadata, queue_metadata) = {
             let mut merkle_tree_account_loaded = ctx.accounts.old_state_merkle_tree.load_mut()?;
             let mut queue_account_loaded = ctx.accounts.old_nullifier_queue.load_mut()?;
@@ -118,6 +122,12 @@ pub fn process_rollover_state_merkle_tree_nullifier_queue_pair<'a, 'b, 'c: 'info
         {
             return err!(crate::errors::AccountCompressionErrorCode::NotReadyForRollover);
         }
+        let additional_bytes = merkle_tree_metadata.rollover_metadata.additional_bytes;
+        let additional_bytes_rent = if additional_bytes == 0 {
+            0
+        } else {
+            Rent::get()?.minimum_balance(additional_bytes as usize)
+        };
 
         process_initialize_state_merkle_tree(
             &ctx.accounts.new_state_merkle_tree,
@@ -147,9 +157,16 @@ pub fn process_rollover_state_merkle_tree_nullifier_queue_pair<'a, 'b, 'c: 'info
             Some(merkle_tree_metadata.rollover_metadata.close_threshold),
             merkle_tree_rent,
             queue_rent,
+            additional_bytes,
+            additional_bytes_rent,
         )?;
 
-        queue_metadata
+        let additional_bytes_reimbursement = if ctx.accounts.registered_program_pda.is_some() {
+            additional_bytes_rent
+        } else {
+            0
+        };
+        (queue_metadata, additional_bytes_reimbursement)
     };
     {
         let nullifier_queue_account = ctx.accounts.old_nullifier_queue.to_account_info();
@@ -176,7 +193,7 @@ pub fn process_rollover_state_merkle_tree_nullifier_queue_pair<'a, 'b, 'c: 'info
             queue_metadata.rollover_metadata.network_fee,
         )?;
     }
-    let lamports = merkle_tree_rent + queue_rent;
+    let lamports = merkle_tree_rent + queue_rent + additional_bytes_reimbursement;
 
     transfer_lamports(
         &ctx.accounts.old_state_merkle_tree.to_account_info(),
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use light_batched_merkle_tree::{
+    merkle_tree::BatchedMerkleTreeAccount, queue::BatchedQueueAccount,
+};
+
+use crate::{
+    errors::AccountCompressionErrorCode,
+    utils::{
+        check_signer_is_registered_or_authority::{
+            check_signer_is_registered_or_authority, GroupAccounts,
+        },
+        transfer_lamports::transfer_lamports,
+    },
+    RegisteredProgram,
+};
+
+#[derive(Accounts)]
+pub struct TransferNetworkFees<'info> {
+    pub authority: Signer<'info>,
+    pub registered_program_pda: Option<Account<'info, RegisteredProgram>>,
+    /// CHECK: in state_from_account_info or address_from_account_info.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: in output_from_account_info.
+    #[account(mut)]
+    pub output_queue: Option<AccountInfo<'info>>,
+    /// CHECK: receives the network fees.
+    #[account(mut)]
+    pub fee_recipient: AccountInfo<'info>,
+}
+
+impl<'info> GroupAccounts<'info> for TransferNetworkFees<'info> {
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+    fn get_registered_program_pda(&self) -> &Option<Account<'info, RegisteredProgram>> {
+        &self.registered_program_pda
+    }
+}
+
+/// Transfer collected network fees of a batched Merkle tree
+/// and its output queue to the fee recipient.
+/// 1. Check Merkle tree (and queue) account discriminator, tree type, and program ownership.
+/// 2. Check that signer is registered or authority.
+/// 3. Compute the lamports which are reserved for rent exemption and rollover.
+///     Rollover fees of every inserted leaf are kept
+///     since the rent of the next tree and queue is reimbursed from them.
+/// 4. Transfer the remaining lamports to the fee recipient.
+pub fn process_transfer_network_fees<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, TransferNetworkFees<'info>>,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let mut transfers = Vec::with_capacity(2);
+    {
+        // 1. Check Merkle tree account discriminator, tree type, and program ownership.
+        let (merkle_tree, is_state_tree) =
+            match BatchedMerkleTreeAccount::state_from_account_info(&ctx.accounts.merkle_tree) {
+                Ok(merkle_tree) => (merkle_tree, true),
+                Err(_) => (
+                    BatchedMerkleTreeAccount::address_from_account_info(&ctx.accounts.merkle_tree)
+                        .map_err(ProgramError::from)?,
+                    false,
+                ),
+            };
+        // 2. Check that signer is registered or authority.
+        check_signer_is_registered_or_authority::<TransferNetworkFees, BatchedMerkleTreeAccount>(
+            &ctx,
+            &merkle_tree,
+        )?;
+        // 3. Compute reserved lamports.
+        //     Rollover fees of state trees are paid to the output queue.
+        let reserved_rollover_fees = if is_state_tree {
+            0
+        } else {
+            merkle_tree.metadata.rollover_metadata.rollover_fee * merkle_tree.next_index
+        };
+        transfers.push((
+            &ctx.accounts.merkle_tree,
+            rent.minimum_balance(ctx.accounts.merkle_tree.data_len()) + reserved_rollover_fees,
+        ));
+
+        if let Some(output_queue_info) = ctx.accounts.output_queue.as_ref() {
+            if !is_state_tree {
+                return err!(AccountCompressionErrorCode::MerkleTreeAndQueueNotAssociated);
+            }
+            let output_queue = BatchedQueueAccount::output_from_account_info(output_queue_info)
+                .map_err(ProgramError::from)?;
+            if output_queue.metadata.associated_merkle_tree != ctx.accounts.merkle_tree.key().into()
+            {
+                return err!(AccountCompressionErrorCode::MerkleTreeAndQueueNotAssociated);
+            }
+            let reserved_rollover_fees = output_queue.metadata.rollover_metadata.rollover_fee
+                * output_queue.batch_metadata.next_index;
+            transfers.push((
+                output_queue_info,
+                rent.minimum_balance(output_queue_info.data_len()) + reserved_rollover_fees,
+            ));
+        }
+    }
+    // 4. Transfer network fees.
+    for (account, reserved_lamports) in transfers {
+        let network_fees = account.lamports().saturating_sub(reserved_lamports);
+        transfer_lamports(account, &ctx.accounts.fee_recipient, network_fees)?;
+    }
+    Ok(())
+}
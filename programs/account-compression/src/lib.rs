@@ -13,7 +13,9 @@ use errors::AccountCompressionErrorCode;
 use light_batched_merkle_tree::{
     initialize_address_tree::InitAddressTreeAccountsInstructionData,
     initialize_state_tree::InitStateTreeAccountsInstructionData,
-    merkle_tree::{InstructionDataBatchAppendInputs, InstructionDataBatchNullifyInputs},
+    merkle_tree::{
+        InstructionDataBatchAppendInputs, InstructionDataBatchNullifyInputs, NullifiedValue,
+    },
 };
 
 declare_id!("compr6CUsB5m2jS4Y3831ztGSTnDpnKJTKS95d64XVq");
@@ -30,6 +32,8 @@ solana_security_txt::security_txt! {
 #[program]
 pub mod account_compression {
 
+    use anchor_lang::solana_program::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+
     use super::*;
     use crate::processor::insert_into_queues::process_insert_into_queues;
 
@@ -138,8 +142,15 @@ pub mod account_compression {
         // so that it can be rolled over as well
         additional_bytes: u64,
     ) -> Result<()> {
-        if additional_bytes != 0 {
-            msg!("additional_bytes is not supported yet");
+        // Rent for additional bytes is included in the rollover fee
+        // and reimbursed when the tree is rolled over,
+        // hence any size of a Solana account is supported.
+        if additional_bytes > MAX_PERMITTED_DATA_LENGTH {
+            msg!(
+                "additional_bytes {} exceed the maximum account size {}",
+                additional_bytes,
+                MAX_PERMITTED_DATA_LENGTH
+            );
             return err!(AccountCompressionErrorCode::UnsupportedAdditionalBytes);
         }
         process_initialize_state_merkle_tree_and_nullifier_queue(
@@ -231,13 +242,23 @@ pub mod account_compression {
 
     /// Nullify a batch of leaves from the input queue
     /// to a batched Merkle tree with a zkp.
+    /// Data can be followed by the nullified values of the next zkp batch
+    /// of the previous batch, which are removed from its counting bloom filter.
     pub fn batch_nullify<'a, 'b, 'c: 'info, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, BatchNullify<'info>>,
         data: Vec<u8>,
     ) -> Result<()> {
-        let instruction_data = InstructionDataBatchNullifyInputs::try_from_slice(&data)
+        let data = &mut data.as_slice();
+        let instruction_data = InstructionDataBatchNullifyInputs::deserialize(data)
             .map_err(|_| AccountCompressionErrorCode::InputDeserializationFailed)?;
-        process_batch_nullify(&ctx, instruction_data)
+        // Nullified values are optional, end of data means no values.
+        let nullified_values = if data.is_empty() {
+            Vec::new()
+        } else {
+            Vec::<NullifiedValue>::try_from_slice(data)
+                .map_err(|_| AccountCompressionErrorCode::InputDeserializationFailed)?
+        };
+        process_batch_nullify(&ctx, instruction_data, nullified_values)
     }
 
     /// Append a batch of leaves from an output queue
@@ -285,6 +306,49 @@ pub mod account_compression {
         process_rollover_batched_state_merkle_tree(ctx, additional_bytes, network_fee)
     }
 
+    /// Close a rolled over batched state Merkle tree and its output queue.
+    /// The tree and queue must be empty, drained,
+    /// and close threshold slots must have passed since the rollover.
+    /// Closed accounts keep a closed discriminator, the remaining rent is
+    /// transferred to the tree owner or the rent beneficiary chosen by the
+    /// registered program of the owner group.
+    /// Concurrent (v1) state Merkle trees cannot be closed,
+    /// they don't track whether every leaf has been nullified.
+    pub fn close_batched_state_merkle_tree<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseBatchedStateMerkleTree<'info>>,
+    ) -> Result<()> {
+        process_close_batched_state_merkle_tree(ctx)
+    }
+
+    /// Close a rolled over batched address Merkle tree.
+    /// The address queue must be empty,
+    /// and close threshold slots must have passed since the rollover.
+    /// Rent is reimbursed like for batched state Merkle trees.
+    pub fn close_batched_address_merkle_tree<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseBatchedAddressMerkleTree<'info>>,
+    ) -> Result<()> {
+        process_close_batched_address_merkle_tree(ctx)
+    }
+
+    /// Close a rolled over indexed (v1) address Merkle tree and its queue.
+    /// Every address of the queue must be inserted into the tree,
+    /// and close threshold slots must have passed since the rollover.
+    /// Rent is reimbursed like for batched state Merkle trees.
+    pub fn close_address_merkle_tree_and_queue<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseAddressMerkleTreeAndQueue<'info>>,
+    ) -> Result<()> {
+        process_close_address_merkle_tree_and_queue(ctx)
+    }
+
+    /// Transfer collected network fees of a batched Merkle tree
+    /// and its output queue to the fee recipient.
+    /// Rent exemption and rollover fees of inserted leaves remain in the accounts.
+    pub fn transfer_network_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, TransferNetworkFees<'info>>,
+    ) -> Result<()> {
+        process_transfer_network_fees(ctx)
+    }
+
     /// Migrate state from a v1 state Merkle tree
     /// to a v2 state Merkle tree.
     pub fn migrate_state<'a, 'b, 'c: 'info, 'info>(
@@ -40,6 +40,7 @@ pub fn process_initialize_address_queue<'info>(
                 rollover_fee,
                 queue_rent,
                 merkle_tree_rent,
+                0,
                 rollover_threshold,
                 height,
             )
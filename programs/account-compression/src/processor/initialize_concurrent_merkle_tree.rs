@@ -24,6 +24,8 @@ pub fn process_initialize_state_merkle_tree(
     close_threshold: Option<u64>,
     merkle_tree_rent: u64,
     queue_rent: u64,
+    additional_bytes: u64,
+    additional_bytes_rent: u64,
 ) -> Result<()> {
     // Initialize new Merkle trees.
     {
@@ -31,15 +33,20 @@ pub fn process_initialize_state_merkle_tree(
 
         let rollover_fee = match rollover_threshold {
             Some(rollover_threshold) => {
+                // Rent of additional accounts (cpi context account)
+                // is collected so that these can be rolled over as well.
                 let rollover_fee =
                     compute_rollover_fee(rollover_threshold, *height, merkle_tree_rent)
                         .map_err(ProgramError::from)?
                         + compute_rollover_fee(rollover_threshold, *height, queue_rent)
+                            .map_err(ProgramError::from)?
+                        + compute_rollover_fee(rollover_threshold, *height, additional_bytes_rent)
                             .map_err(ProgramError::from)?;
                 check_rollover_fee_sufficient(
                     rollover_fee,
                     queue_rent,
                     merkle_tree_rent,
+                    additional_bytes_rent,
                     rollover_threshold,
                     *height,
                 )
@@ -62,7 +69,7 @@ pub fn process_initialize_state_merkle_tree(
                 rollover_threshold,
                 network_fee,
                 close_threshold,
-                None,
+                Some(additional_bytes),
             ),
             associated_queue,
         );
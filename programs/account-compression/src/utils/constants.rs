@@ -7,6 +7,12 @@ pub const CPI_AUTHORITY_PDA_SEED: &[u8] = b"cpi_authority";
 #[constant]
 pub const GROUP_AUTHORITY_SEED: &[u8] = b"group_authority";
 
+/// Discriminator of closed Merkle tree and queue accounts.
+/// Closed accounts are not reassigned, no tree or queue can be
+/// initialized at the address of a closed account.
+#[constant]
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"closedac";
+
 #[constant]
 pub const STATE_MERKLE_TREE_HEIGHT: u64 = 26;
 #[constant]
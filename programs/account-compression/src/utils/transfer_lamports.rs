@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::errors::AccountCompressionErrorCode;
+use crate::{errors::AccountCompressionErrorCode, utils::constants::CLOSED_ACCOUNT_DISCRIMINATOR};
 
 pub fn transfer_lamports<'info>(
     from: &AccountInfo<'info>,
@@ -32,3 +32,21 @@ pub fn transfer_lamports_cpi<'info>(
     anchor_lang::solana_program::program::invoke(&instruction, &[from.clone(), to.clone()])?;
     Ok(())
 }
+
+/// Closes the account and leaves a tombstone.
+/// The account is shrunk to the closed account discriminator and keeps the
+/// rent exemption for it, all other lamports are transferred to the recipient.
+/// The account stays owned by the account compression program,
+/// hence it cannot be recreated and initialized as a new tree or queue.
+pub fn close_account<'info>(account: &AccountInfo<'info>, to: &AccountInfo<'info>) -> Result<()> {
+    account.realloc(CLOSED_ACCOUNT_DISCRIMINATOR.len(), false)?;
+    account
+        .try_borrow_mut_data()?
+        .copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR);
+    let tombstone_rent = Rent::get()?.minimum_balance(CLOSED_ACCOUNT_DISCRIMINATOR.len());
+    let lamports = account
+        .lamports()
+        .checked_sub(tombstone_rent)
+        .ok_or(AccountCompressionErrorCode::IntegerOverflow)?;
+    transfer_lamports(account, to, lamports)
+}
@@ -1,9 +1,9 @@
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh};
-use zerocopy::{little_endian::U64, Ref};
+use light_zero_copy::{slice::ZeroCopySliceBorsh, ZeroCopy};
 
-#[derive(Debug, Default, Clone, PartialEq, AnchorSerialize, AnchorDeserialize)]
-pub struct BatchCompressInstructionDataBorsh {
+#[derive(Debug, Default, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct BatchCompressInstructionData {
+    #[zero_copy(ty = ZeroCopySliceBorsh<'a, light_compressed_account::pubkey::Pubkey>)]
     pub pubkeys: Vec<Pubkey>,
     // Some if one amount per pubkey.
     pub amounts: Option<Vec<u64>>,
@@ -14,45 +14,15 @@ pub struct BatchCompressInstructionDataBorsh {
     pub bump: u8,
 }
 
-pub struct BatchCompressInstructionData<'a> {
-    pub pubkeys: ZeroCopySliceBorsh<'a, light_compressed_account::pubkey::Pubkey>,
-    pub amounts: Option<ZeroCopySliceBorsh<'a, U64>>,
-    pub lamports: Option<Ref<&'a [u8], U64>>,
-    pub amount: Option<Ref<&'a [u8], U64>>,
-    pub index: u8,
-    pub bump: u8,
-}
-
-impl<'a> Deserialize<'a> for BatchCompressInstructionData<'a> {
-    type Output = Self;
-
-    fn zero_copy_at(bytes: &'a [u8]) -> std::result::Result<(Self, &'a [u8]), ZeroCopyError> {
-        let (pubkeys, bytes) = ZeroCopySliceBorsh::from_bytes_at(bytes)?;
-        let (amounts, bytes) = Option::<ZeroCopySliceBorsh<U64>>::zero_copy_at(bytes)?;
-        let (lamports, bytes) = Option::<U64>::zero_copy_at(bytes)?;
-        let (amount, bytes) = Option::<U64>::zero_copy_at(bytes)?;
-        let (index, bytes) = u8::zero_copy_at(bytes)?;
-        let (bump, bytes) = u8::zero_copy_at(bytes)?;
-        Ok((
-            Self {
-                pubkeys,
-                amounts,
-                lamports,
-                amount,
-                index,
-                bump,
-            },
-            bytes,
-        ))
-    }
-}
-
 #[cfg(test)]
 mod test {
+    use light_zero_copy::borsh::Deserialize;
+    use zerocopy::little_endian::U64;
+
     use super::*;
     #[test]
     fn test_batch_compress_instruction_data() {
-        let data = super::BatchCompressInstructionDataBorsh {
+        let data = super::BatchCompressInstructionData {
             pubkeys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
             amounts: Some(vec![1, 2]),
             lamports: Some(3),
@@ -62,7 +32,7 @@ mod test {
         };
         let mut vec = Vec::new();
         data.serialize(&mut vec).unwrap();
-        let (decoded_data, _) = super::BatchCompressInstructionData::zero_copy_at(&vec).unwrap();
+        let (decoded_data, _) = super::ZBatchCompressInstructionData::zero_copy_at(&vec).unwrap();
         assert_eq!(decoded_data.pubkeys.len(), 2);
         assert_eq!(decoded_data.amounts.as_ref().unwrap().len(), 2);
         assert_eq!(*decoded_data.lamports.unwrap(), U64::from(3));
@@ -79,7 +49,7 @@ mod test {
 
     #[test]
     fn test_batch_compress_instruction_data_none() {
-        let data = super::BatchCompressInstructionDataBorsh {
+        let data = super::BatchCompressInstructionData {
             pubkeys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
             amounts: Some(vec![1, 2]),
             amount: None,
@@ -89,7 +59,7 @@ mod test {
         };
         let mut vec = Vec::new();
         data.serialize(&mut vec).unwrap();
-        let (decoded_data, _) = super::BatchCompressInstructionData::zero_copy_at(&vec).unwrap();
+        let (decoded_data, _) = super::ZBatchCompressInstructionData::zero_copy_at(&vec).unwrap();
         assert_eq!(decoded_data.pubkeys.len(), 2);
         assert_eq!(decoded_data.amounts.as_ref().unwrap().len(), 2);
         assert!(decoded_data.lamports.is_none());
@@ -135,7 +105,7 @@ mod test {
             let index = rng.gen_range(0..=u8::MAX);
             let bump = rng.gen_range(0..=u8::MAX);
 
-            let data = super::BatchCompressInstructionDataBorsh {
+            let data = super::BatchCompressInstructionData {
                 pubkeys,
                 amounts,
                 lamports,
@@ -147,7 +117,7 @@ mod test {
             let mut vec = Vec::new();
             data.serialize(&mut vec).unwrap();
             let (decoded_data, _) =
-                super::BatchCompressInstructionData::zero_copy_at(&vec).unwrap();
+                super::ZBatchCompressInstructionData::zero_copy_at(&vec).unwrap();
 
             assert_eq!(decoded_data.pubkeys.len(), data.pubkeys.len());
             if let Some(amounts) = &data.amounts {
@@ -5,50 +5,74 @@ use light_compressed_account::{
     instruction_data::{
         compressed_proof::CompressedProof, cpi_context::CompressedCpiContext,
         data::OutputCompressedAccountWithPackedContext, with_readonly::InAccount,
+        zero_copy::ZCompressedCpiContext,
     },
 };
+use light_zero_copy::{borsh::Deserialize, ZeroCopy};
+use zerocopy::Ref;
 
 use crate::{
     constants::NOT_FROZEN,
+    delegation::{get_remaining_delegated_amount, set_delegated_amount},
+    mint_extensions::get_mint_extensions,
     process_transfer::{
         add_data_hash_to_input_compressed_accounts, cpi_execute_compressed_transaction_transfer,
         create_output_compressed_accounts, get_cpi_signer_seeds,
-        get_input_compressed_accounts_with_merkle_context_and_check_signer, DelegatedTransfer,
-        InputTokenDataWithContext,
+        get_input_compressed_accounts_with_merkle_context_and_check_signer, get_input_tlv,
+        DelegatedTransfer, InputTokenDataWithContext,
     },
     spl_compression::invoke_token_program_with_multiple_token_pool_accounts,
+    tlv::get_shared_input_tlv,
+    vesting::check_inputs_unlocked,
     BurnInstruction, ErrorCode,
 };
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedTokenInstructionDataBurn {
     pub proof: CompressedProof,
     pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
     pub cpi_context: Option<CompressedCpiContext>,
     pub burn_amount: u64,
     pub change_account_merkle_tree_index: u8,
     pub delegated_transfer: Option<DelegatedTransfer>,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
 }
 
 pub fn process_burn<'a, 'b, 'c, 'info: 'b + 'c>(
     ctx: Context<'a, 'b, 'c, 'info, BurnInstruction<'info>>,
     inputs: Vec<u8>,
 ) -> Result<()> {
-    let inputs: CompressedTokenInstructionDataBurn =
-        CompressedTokenInstructionDataBurn::deserialize(&mut inputs.as_slice())?;
+    let (inputs, _) =
+        ZCompressedTokenInstructionDataBurn::zero_copy_at(&inputs).map_err(ProgramError::from)?;
+    check_inputs_unlocked(
+        inputs
+            .input_token_data_with_context
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.amount.get(), get_input_tlv(&inputs.input_tlvs, i))),
+        Clock::get()?.slot,
+    )?;
     burn_spl_from_pool_pda(&ctx, &inputs)?;
     let mint = ctx.accounts.mint.key();
+    let mint_extensions = get_mint_extensions(&ctx.accounts.mint.to_account_info())?;
+    // The permanent delegate burns the inputs of delegated_transfer.owner.
+    let is_permanent_delegate = inputs.delegated_transfer.is_some()
+        && mint_extensions.is_permanent_delegate(&ctx.accounts.authority.key());
     let (compressed_input_accounts, output_compressed_accounts) =
         create_input_and_output_accounts_burn(
             &inputs,
             &ctx.accounts.authority.key(),
+            is_permanent_delegate,
             ctx.remaining_accounts,
             &mint,
         )?;
-    let proof = if inputs.proof == CompressedProof::default() {
+    let proof = if *inputs.proof == CompressedProof::default() {
         None
     } else {
-        Some(inputs.proof)
+        Some(*inputs.proof)
     };
     cpi_execute_compressed_transaction_transfer(
         ctx.accounts,
@@ -56,7 +80,10 @@ pub fn process_burn<'a, 'b, 'c, 'info: 'b + 'c>(
         output_compressed_accounts,
         false,
         proof,
-        inputs.cpi_context,
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts.light_system_program.to_account_info(),
         ctx.accounts.self_program.to_account_info(),
@@ -68,9 +95,9 @@ pub fn process_burn<'a, 'b, 'c, 'info: 'b + 'c>(
 #[inline(never)]
 pub fn burn_spl_from_pool_pda<'info>(
     ctx: &Context<'_, '_, '_, 'info, BurnInstruction<'info>>,
-    inputs: &CompressedTokenInstructionDataBurn,
+    inputs: &ZCompressedTokenInstructionDataBurn,
 ) -> Result<()> {
-    let amount = inputs.burn_amount;
+    let amount = inputs.burn_amount.get();
     let token_pool_pda = &ctx.accounts.token_pool_pda;
 
     invoke_token_program_with_multiple_token_pool_accounts::<true>(
@@ -116,9 +143,15 @@ pub fn spl_burn_cpi<'info>(
     Ok(())
 }
 
+/// If the authority is the permanent delegate of the mint it can burn
+/// compressed tokens of delegated_transfer.owner. The change account is owned
+/// by the owner and not delegated.
+/// Other delegates can burn up to the delegated amount of the inputs, see
+/// crate::delegation::get_delegated_amount.
 pub fn create_input_and_output_accounts_burn(
-    inputs: &CompressedTokenInstructionDataBurn,
+    inputs: &ZCompressedTokenInstructionDataBurn,
     authority: &Pubkey,
+    is_permanent_delegate: bool,
     remaining_accounts: &[AccountInfo<'_>],
     mint: &Pubkey,
 ) -> Result<(
@@ -128,28 +161,44 @@ pub fn create_input_and_output_accounts_burn(
     let (mut compressed_input_accounts, input_token_data, sum_lamports) =
         get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
             authority,
-            &inputs.delegated_transfer,
+            inputs.delegated_transfer.as_ref(),
+            is_permanent_delegate,
             remaining_accounts,
             &inputs.input_token_data_with_context,
+            &inputs.input_tlvs,
             mint,
         )?;
     let sum_inputs = input_token_data.iter().map(|x| x.amount).sum::<u64>();
-    let change_amount = match sum_inputs.checked_sub(inputs.burn_amount) {
+    let change_amount = match sum_inputs.checked_sub(inputs.burn_amount.get()) {
         Some(change_amount) => change_amount,
         None => return err!(ErrorCode::ArithmeticUnderflow),
     };
 
+    // Delegates can burn up to the delegated amount, the remaining delegated
+    // amount is kept by the delegated change account.
+    let remaining_delegated_amount = match inputs.delegated_transfer.as_ref() {
+        Some(_) if !is_permanent_delegate => Some(get_remaining_delegated_amount(
+            &input_token_data,
+            inputs.burn_amount.get(),
+        )?),
+        _ => None,
+    };
+
     let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
     let output_compressed_accounts = if change_amount > 0 || sum_lamports > 0 {
         let (is_delegate, authority, delegate) =
             if let Some(delegated_transfer) = inputs.delegated_transfer.as_ref() {
-                let mut vec = vec![false; 1];
-                if let Some(index) = delegated_transfer.delegate_change_account_index {
-                    vec[index as usize] = true;
+                if is_permanent_delegate {
+                    (None, delegated_transfer.owner(), None)
                 } else {
-                    return err!(crate::ErrorCode::InvalidDelegateIndex);
+                    let mut vec = vec![false; 1];
+                    if let Some(index) = delegated_transfer.delegate_change_account_index {
+                        vec[index as usize] = true;
+                    } else {
+                        return err!(crate::ErrorCode::InvalidDelegateIndex);
+                    }
+                    (Some(vec), delegated_transfer.owner(), Some(*authority))
                 }
-                (Some(vec), delegated_transfer.owner, Some(*authority))
             } else {
                 (None, *authority, None)
             };
@@ -160,7 +209,15 @@ pub fn create_input_and_output_accounts_burn(
         } else {
             None
         };
-        create_output_compressed_accounts(
+        // Extensions are preserved in the change account.
+        let tlv = get_shared_input_tlv(input_token_data.as_slice())?;
+        let tlv = match remaining_delegated_amount {
+            Some(remaining_delegated_amount) => {
+                set_delegated_amount(&tlv, change_amount, remaining_delegated_amount)?
+            }
+            None => tlv,
+        };
+        create_output_compressed_accounts::<NOT_FROZEN>(
             &mut output_compressed_accounts,
             *mint,
             &[authority; 1],
@@ -168,6 +225,7 @@ pub fn create_input_and_output_accounts_burn(
             is_delegate,
             &[change_amount],
             lamports,
+            Some(&[tlv]),
             &hashed_mint,
             &[inputs.change_account_merkle_tree_index],
             remaining_accounts,
@@ -201,7 +259,8 @@ pub mod sdk {
         process_transfer::{
             get_cpi_authority_pda,
             transfer_sdk::{
-                create_input_output_and_remaining_accounts, to_account_metas, TransferSdkError,
+                create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+                TransferSdkError,
             },
             DelegatedTransfer,
         },
@@ -262,6 +321,7 @@ pub mod sdk {
             change_account_merkle_tree_index: *outputs_merkle_tree_index as u8,
             delegated_transfer,
             burn_amount: inputs.burn_amount,
+            input_tlvs: get_input_tlvs(&inputs.input_token_data),
         };
         let remaining_accounts = to_account_metas(remaining_accounts);
         let mut serialized_ix_data = Vec::new();
@@ -325,6 +385,7 @@ mod test {
             create_expected_input_accounts, create_expected_token_output_accounts,
             get_rnd_input_token_data_with_contexts,
         },
+        tlv::{pack_tlv, TokenExtension},
         token_data::AccountState,
         TokenData,
     };
@@ -397,15 +458,11 @@ mod test {
                 burn_amount: std::cmp::min(50, test_amount),
                 change_account_merkle_tree_index: 2,
                 delegated_transfer: None,
+                input_tlvs: Vec::new(),
             };
             let (compressed_input_accounts, output_compressed_accounts) =
-                create_input_and_output_accounts_burn(
-                    &inputs,
-                    &authority,
-                    &remaining_accounts,
-                    &mint,
-                )
-                .unwrap();
+                create_burn_accounts(&inputs, &authority, false, &remaining_accounts, &mint)
+                    .unwrap();
             assert_eq!(compressed_input_accounts.len(), 1);
             let change_amount = test_amount.saturating_sub(inputs.burn_amount);
             assert_eq!(
@@ -432,6 +489,116 @@ mod test {
                 );
             }
         }
+        // Permanent delegate burns tokens of the owner, the change account is
+        // owned by the owner and not delegated.
+        let owner = Pubkey::new_unique();
+        let mut inputs = CompressedTokenInstructionDataBurn {
+            proof: CompressedProof::default(),
+            input_token_data_with_context: vec![InputTokenDataWithContext {
+                amount: 100,
+                merkle_context: PackedMerkleContext {
+                    merkle_tree_pubkey_index: 0,
+                    queue_pubkey_index: 1,
+                    leaf_index: 1,
+                    prove_by_index: false,
+                },
+                root_index: 0,
+                delegate_index: None,
+                lamports: None,
+                tlv: None,
+            }],
+            cpi_context: None,
+            burn_amount: 50,
+            change_account_merkle_tree_index: 2,
+            delegated_transfer: Some(DelegatedTransfer {
+                owner,
+                delegate_change_account_index: None,
+            }),
+            input_tlvs: Vec::new(),
+        };
+        let (_, output_compressed_accounts) =
+            create_burn_accounts(&inputs, &authority, true, &remaining_accounts, &mint).unwrap();
+        let expected_change_token_data = TokenData {
+            mint,
+            owner,
+            amount: 50,
+            delegate: None,
+            state: AccountState::Initialized,
+            tlv: None,
+        };
+        assert_eq!(
+            output_compressed_accounts,
+            create_expected_token_output_accounts(vec![expected_change_token_data], vec![2])
+        );
+        // Without delegated_transfer the permanent delegate burns its own
+        // tokens like any owner, see process_burn.
+        inputs.delegated_transfer = None;
+        let (_, output_compressed_accounts) =
+            create_burn_accounts(&inputs, &authority, false, &remaining_accounts, &mint).unwrap();
+        let expected_change_token_data = TokenData {
+            mint,
+            owner: authority,
+            amount: 50,
+            delegate: None,
+            state: AccountState::Initialized,
+            tlv: None,
+        };
+        assert_eq!(
+            output_compressed_accounts,
+            create_expected_token_output_accounts(vec![expected_change_token_data], vec![2])
+        );
+
+        // Delegate burns up to the delegated amount, the change account keeps
+        // the remaining delegated amount.
+        let delegate = nullifier_queue_pubkey;
+        let mut inputs = CompressedTokenInstructionDataBurn {
+            proof: CompressedProof::default(),
+            input_token_data_with_context: vec![InputTokenDataWithContext {
+                amount: 100,
+                merkle_context: PackedMerkleContext {
+                    merkle_tree_pubkey_index: 0,
+                    queue_pubkey_index: 1,
+                    leaf_index: 1,
+                    prove_by_index: false,
+                },
+                root_index: 0,
+                delegate_index: Some(1),
+                lamports: None,
+                tlv: None,
+            }],
+            cpi_context: None,
+            burn_amount: 20,
+            change_account_merkle_tree_index: 2,
+            delegated_transfer: Some(DelegatedTransfer {
+                owner,
+                delegate_change_account_index: Some(0),
+            }),
+            input_tlvs: vec![Some(
+                pack_tlv(&[TokenExtension::DelegatedAmount(30)]).unwrap(),
+            )],
+        };
+        let (_, output_compressed_accounts) =
+            create_burn_accounts(&inputs, &delegate, false, &remaining_accounts, &mint).unwrap();
+        let expected_change_token_data = TokenData {
+            mint,
+            owner,
+            amount: 80,
+            delegate: Some(delegate),
+            state: AccountState::Initialized,
+            tlv: Some(pack_tlv(&[TokenExtension::DelegatedAmount(10)]).unwrap()),
+        };
+        assert_eq!(
+            output_compressed_accounts,
+            create_expected_token_output_accounts(vec![expected_change_token_data], vec![2])
+        );
+        // Delegate burn exceeding the delegated amount fails.
+        inputs.burn_amount = 31;
+        let result = create_burn_accounts(&inputs, &delegate, false, &remaining_accounts, &mint);
+        let error_code = ErrorCode::DelegatedAmountExceeded as u32 + 6000;
+        assert!(matches!(
+            result.unwrap_err(),
+            anchor_lang::error::Error::AnchorError(error) if error.error_code_number == error_code
+        ));
     }
 
     #[test]
@@ -498,15 +665,11 @@ mod test {
                 burn_amount,
                 change_account_merkle_tree_index: 2,
                 delegated_transfer: None,
+                input_tlvs: Vec::new(),
             };
             let (compressed_input_accounts, output_compressed_accounts) =
-                create_input_and_output_accounts_burn(
-                    &inputs,
-                    &authority,
-                    &remaining_accounts,
-                    &mint,
-                )
-                .unwrap();
+                create_burn_accounts(&inputs, &authority, false, &remaining_accounts, &mint)
+                    .unwrap();
             let expected_input_accounts = create_expected_input_accounts(
                 &input_token_data_with_context,
                 &mint,
@@ -609,13 +772,10 @@ mod test {
                 burn_amount: 50,
                 change_account_merkle_tree_index: 2,
                 delegated_transfer: None,
+                input_tlvs: Vec::new(),
             };
-            let result = create_input_and_output_accounts_burn(
-                &inputs,
-                &authority,
-                &remaining_accounts,
-                &mint,
-            );
+            let result =
+                create_burn_accounts(&inputs, &authority, false, &remaining_accounts, &mint);
             let error_code = ErrorCode::ArithmeticUnderflow as u32 + 6000;
             assert!(matches!(
                 result.unwrap_err(),
@@ -632,15 +792,16 @@ mod test {
                 burn_amount: 50,
                 change_account_merkle_tree_index: 2,
                 delegated_transfer: None,
+                input_tlvs: Vec::new(),
             };
-            let (compressed_input_accounts, output_compressed_accounts) =
-                create_input_and_output_accounts_burn(
-                    &inputs,
-                    &invalid_authority,
-                    &remaining_accounts,
-                    &mint,
-                )
-                .unwrap();
+            let (compressed_input_accounts, output_compressed_accounts) = create_burn_accounts(
+                &inputs,
+                &invalid_authority,
+                false,
+                &remaining_accounts,
+                &mint,
+            )
+            .unwrap();
             let expected_input_accounts = create_expected_input_accounts(
                 &input_token_data_with_context,
                 &mint,
@@ -681,15 +842,16 @@ mod test {
                 burn_amount: 50,
                 change_account_merkle_tree_index: 2,
                 delegated_transfer: None,
+                input_tlvs: Vec::new(),
             };
-            let (compressed_input_accounts, output_compressed_accounts) =
-                create_input_and_output_accounts_burn(
-                    &inputs,
-                    &authority,
-                    &remaining_accounts,
-                    &invalid_mint,
-                )
-                .unwrap();
+            let (compressed_input_accounts, output_compressed_accounts) = create_burn_accounts(
+                &inputs,
+                &authority,
+                false,
+                &remaining_accounts,
+                &invalid_mint,
+            )
+            .unwrap();
             assert_eq!(compressed_input_accounts.len(), 1);
             assert_eq!(output_compressed_accounts.len(), 1);
             let expected_input_accounts = create_expected_input_accounts(
@@ -721,4 +883,27 @@ mod test {
             );
         }
     }
+
+    /// Serializes the instruction data to process it zero copy like the
+    /// program does.
+    fn create_burn_accounts(
+        inputs: &CompressedTokenInstructionDataBurn,
+        authority: &Pubkey,
+        is_permanent_delegate: bool,
+        remaining_accounts: &[AccountInfo<'_>],
+        mint: &Pubkey,
+    ) -> Result<(
+        Vec<InAccount>,
+        Vec<OutputCompressedAccountWithPackedContext>,
+    )> {
+        let bytes = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataBurn::zero_copy_at(&bytes).unwrap();
+        create_input_and_output_accounts_burn(
+            &inputs,
+            authority,
+            is_permanent_delegate,
+            remaining_accounts,
+            mint,
+        )
+    }
 }
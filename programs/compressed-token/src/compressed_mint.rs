@@ -0,0 +1,1449 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface;
+use light_compressed_account::{
+    address::derive_address,
+    compressed_account::{CompressedAccount, CompressedAccountData, PackedMerkleContext},
+    hash_to_bn254_field_size_be,
+    instruction_data::{
+        compressed_proof::CompressedProof,
+        data::{NewAddressParamsAssignedPacked, OutputCompressedAccountWithPackedContext},
+        with_readonly::InAccount,
+    },
+};
+use light_hasher::{errors::HasherError, Hasher, Poseidon};
+use light_zero_copy::borsh::Deserialize;
+
+use crate::{
+    burn::{
+        create_input_and_output_accounts_burn, CompressedTokenInstructionDataBurn,
+        ZCompressedTokenInstructionDataBurn,
+    },
+    constants::{
+        COMPRESSED_MINT_ADDRESS_MERKLE_TREE, COMPRESSED_MINT_DISCRIMINATOR, COMPRESSED_MINT_SEED,
+        NOT_FROZEN, POOL_SEED,
+    },
+    find_token_pool_pda_with_index,
+    process_transfer::{
+        cpi_execute_compressed_transaction_transfer,
+        cpi_execute_compressed_transaction_with_addresses, create_output_compressed_accounts,
+        get_cpi_signer_seeds, get_input_tlv,
+    },
+    vesting::check_inputs_unlocked,
+    CreateCompressedMintInstruction, DecompressMintInstruction, ErrorCode, GenericInstruction,
+};
+
+/// Size of an spl token mint account without extensions.
+const SPL_MINT_LEN: usize = 82;
+/// Size of an spl token account without extensions.
+const SPL_TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Mint of compressed tokens without an spl mint, stored in a compressed
+/// account with an address. The address is derived from spl_mint, so there is
+/// one compressed mint per mint pda.
+///
+/// 1. create_compressed_mint creates the compressed mint with zero supply.
+/// 2. mint_to_compressed and burn_compressed update the supply, no spl
+///    tokens are minted or burned.
+/// 3. decompress_mint creates the spl mint at spl_mint and a token pool
+///    holding the supply. Afterwards the mint is a regular spl mint and the
+///    compressed mint can no longer mint or burn.
+///
+/// Compressed token accounts cannot be frozen without an spl mint, hence
+/// compressed mints are created without a freeze authority.
+#[derive(Debug, Default, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedMint {
+    /// Mint pda, see find_compressed_mint_pda. Compressed token accounts of
+    /// this mint use it as mint.
+    pub spl_mint: Pubkey,
+    /// Supply of compressed tokens.
+    pub supply: u64,
+    pub decimals: u8,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    /// The supply is held by the spl mint and its token pool.
+    pub is_decompressed: bool,
+}
+
+/// Hashing schema: H(spl_mint, supply, decimals, mint_authority,
+/// freeze_authority, is_decompressed)
+///
+/// Pubkeys are hashed to field size, undefined authorities are hashed as
+/// zero bytes.
+impl CompressedMint {
+    pub fn hash(&self) -> std::result::Result<[u8; 32], HasherError> {
+        let hashed_spl_mint = hash_to_bn254_field_size_be(self.spl_mint.to_bytes().as_slice());
+        let mut supply_bytes = [0u8; 32];
+        supply_bytes[24..].copy_from_slice(self.supply.to_be_bytes().as_slice());
+        let mut decimals_bytes = [0u8; 32];
+        decimals_bytes[31] = self.decimals;
+        let hashed_mint_authority = self
+            .mint_authority
+            .map(|authority| hash_to_bn254_field_size_be(authority.to_bytes().as_slice()))
+            .unwrap_or_default();
+        let hashed_freeze_authority = self
+            .freeze_authority
+            .map(|authority| hash_to_bn254_field_size_be(authority.to_bytes().as_slice()))
+            .unwrap_or_default();
+        let mut is_decompressed_bytes = [0u8; 32];
+        is_decompressed_bytes[31] = self.is_decompressed as u8;
+        Poseidon::hashv(&[
+            hashed_spl_mint.as_slice(),
+            supply_bytes.as_slice(),
+            decimals_bytes.as_slice(),
+            hashed_mint_authority.as_slice(),
+            hashed_freeze_authority.as_slice(),
+            is_decompressed_bytes.as_slice(),
+        ])
+    }
+}
+
+/// Mint pda of a compressed mint, the spl mint is created at this address
+/// when the mint is decompressed.
+pub fn find_compressed_mint_pda(mint_signer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMPRESSED_MINT_SEED, mint_signer.as_ref()], &crate::ID)
+}
+
+/// Address of the compressed mint account in the compressed mint address
+/// Merkle tree.
+pub fn get_compressed_mint_address(spl_mint: &Pubkey) -> [u8; 32] {
+    derive_address(
+        &spl_mint.to_bytes(),
+        &COMPRESSED_MINT_ADDRESS_MERKLE_TREE.to_bytes(),
+        &crate::ID.to_bytes(),
+    )
+}
+
+/// Input compressed mint account. The updated compressed mint account is
+/// created in output_merkle_tree_index.
+#[derive(Debug, Clone, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedMintInputs {
+    pub merkle_context: PackedMerkleContext,
+    pub root_index: u16,
+    pub address: [u8; 32],
+    pub compressed_mint: CompressedMint,
+    /// Index in remaining accounts.
+    pub output_merkle_tree_index: u8,
+}
+
+/// Fails if the compressed mint is decompressed or authority is not its
+/// mint authority.
+pub fn check_mint_authority(compressed_mint: &CompressedMint, authority: &Pubkey) -> Result<()> {
+    if compressed_mint.is_decompressed {
+        return err!(ErrorCode::MintDecompressed);
+    }
+    if compressed_mint.mint_authority != Some(*authority) {
+        msg!(
+            "authority {:?} != mint authority {:?}",
+            authority,
+            compressed_mint.mint_authority
+        );
+        return err!(ErrorCode::InvalidAuthorityMint);
+    }
+    Ok(())
+}
+
+pub fn create_compressed_mint_output_account(
+    compressed_mint: &CompressedMint,
+    address: [u8; 32],
+    merkle_tree_index: u8,
+) -> Result<OutputCompressedAccountWithPackedContext> {
+    let data_hash = compressed_mint.hash().map_err(ProgramError::from)?;
+    let mut data = Vec::new();
+    compressed_mint
+        .serialize(&mut data)
+        .map_err(ProgramError::from)?;
+    Ok(OutputCompressedAccountWithPackedContext {
+        compressed_account: CompressedAccount {
+            owner: crate::ID,
+            lamports: 0,
+            address: Some(address),
+            data: Some(CompressedAccountData {
+                discriminator: COMPRESSED_MINT_DISCRIMINATOR,
+                data,
+                data_hash,
+            }),
+        },
+        merkle_tree_index,
+    })
+}
+
+/// Creates the input compressed mint account and the output compressed mint
+/// account with the updated compressed mint.
+pub fn create_input_and_output_compressed_mint_accounts(
+    inputs: &CompressedMintInputs,
+    compressed_mint: &CompressedMint,
+) -> Result<(InAccount, OutputCompressedAccountWithPackedContext)> {
+    let input_compressed_account = InAccount {
+        discriminator: COMPRESSED_MINT_DISCRIMINATOR,
+        data_hash: inputs.compressed_mint.hash().map_err(ProgramError::from)?,
+        merkle_context: inputs.merkle_context,
+        root_index: inputs.root_index,
+        lamports: 0,
+        address: Some(inputs.address),
+    };
+    let output_compressed_account = create_compressed_mint_output_account(
+        compressed_mint,
+        inputs.address,
+        inputs.output_merkle_tree_index,
+    )?;
+    Ok((input_compressed_account, output_compressed_account))
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedTokenInstructionDataCreateCompressedMint {
+    /// Non-inclusion proof of the compressed mint address.
+    pub proof: CompressedProof,
+    pub decimals: u8,
+    /// Must be None, see CompressedMint.
+    pub freeze_authority: Option<Pubkey>,
+    /// Index in remaining accounts, must be
+    /// COMPRESSED_MINT_ADDRESS_MERKLE_TREE.
+    pub address_merkle_tree_index: u8,
+    /// Index in remaining accounts.
+    pub address_queue_index: u8,
+    pub address_merkle_tree_root_index: u16,
+    /// Index in remaining accounts.
+    pub output_merkle_tree_index: u8,
+}
+
+/// Creates a compressed mint with zero supply. The authority becomes the
+/// mint authority.
+pub fn process_create_compressed_mint<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, CreateCompressedMintInstruction<'info>>,
+    inputs: CompressedTokenInstructionDataCreateCompressedMint,
+) -> Result<()> {
+    let (spl_mint, _) = find_compressed_mint_pda(&ctx.accounts.mint_signer.key());
+    let (new_address_params, output_compressed_account) = create_compressed_mint_account(
+        &inputs,
+        &spl_mint,
+        &ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+    )?;
+    cpi_execute_compressed_transaction_with_addresses(
+        ctx.accounts,
+        Vec::new(),
+        vec![output_compressed_account],
+        vec![new_address_params],
+        false,
+        Some(inputs.proof),
+        None,
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn create_compressed_mint_account(
+    inputs: &CompressedTokenInstructionDataCreateCompressedMint,
+    spl_mint: &Pubkey,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    NewAddressParamsAssignedPacked,
+    OutputCompressedAccountWithPackedContext,
+)> {
+    if inputs.freeze_authority.is_some() {
+        return err!(ErrorCode::CompressedMintFreezeAuthorityNotSupported);
+    }
+    let address_merkle_tree = remaining_accounts[inputs.address_merkle_tree_index as usize].key();
+    if address_merkle_tree != COMPRESSED_MINT_ADDRESS_MERKLE_TREE {
+        msg!(
+            "address Merkle tree {:?} != {:?}",
+            address_merkle_tree,
+            COMPRESSED_MINT_ADDRESS_MERKLE_TREE
+        );
+        return err!(ErrorCode::InvalidCompressedMintAddressMerkleTree);
+    }
+    let address = get_compressed_mint_address(spl_mint);
+    let new_address_params = NewAddressParamsAssignedPacked {
+        seed: spl_mint.to_bytes(),
+        address_queue_account_index: inputs.address_queue_index,
+        address_merkle_tree_account_index: inputs.address_merkle_tree_index,
+        address_merkle_tree_root_index: inputs.address_merkle_tree_root_index,
+        assigned_to_account: true,
+        assigned_account_index: 0,
+    };
+    let compressed_mint = CompressedMint {
+        spl_mint: *spl_mint,
+        supply: 0,
+        decimals: inputs.decimals,
+        mint_authority: Some(*authority),
+        freeze_authority: None,
+        is_decompressed: false,
+    };
+    let output_compressed_account = create_compressed_mint_output_account(
+        &compressed_mint,
+        address,
+        inputs.output_merkle_tree_index,
+    )?;
+    Ok((new_address_params, output_compressed_account))
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedTokenInstructionDataMintToCompressed {
+    pub proof: Option<CompressedProof>,
+    pub compressed_mint_inputs: CompressedMintInputs,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    /// Index in remaining accounts.
+    pub token_account_merkle_tree_index: u8,
+}
+
+/// Mints compressed tokens of a compressed mint, the supply of the compressed
+/// mint is increased by the minted amount.
+pub fn process_mint_to_compressed<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, GenericInstruction<'info>>,
+    inputs: CompressedTokenInstructionDataMintToCompressed,
+) -> Result<()> {
+    let (compressed_input_accounts, output_compressed_accounts) =
+        create_input_and_output_accounts_mint_to_compressed(
+            &inputs,
+            &ctx.accounts.authority.key(),
+            ctx.remaining_accounts,
+        )?;
+    cpi_execute_compressed_transaction_transfer(
+        ctx.accounts,
+        compressed_input_accounts,
+        output_compressed_accounts,
+        false,
+        inputs.proof,
+        None,
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn create_input_and_output_accounts_mint_to_compressed(
+    inputs: &CompressedTokenInstructionDataMintToCompressed,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    Vec<InAccount>,
+    Vec<OutputCompressedAccountWithPackedContext>,
+)> {
+    if inputs.recipients.len() != inputs.amounts.len() {
+        msg!(
+            "recipients.len() {} !=  {} amounts.len()",
+            inputs.recipients.len(),
+            inputs.amounts.len()
+        );
+        return err!(ErrorCode::PublicKeyAmountMissmatch);
+    } else if inputs.recipients.is_empty() {
+        return err!(ErrorCode::NoInputsProvided);
+    }
+    let compressed_mint_inputs = &inputs.compressed_mint_inputs;
+    check_mint_authority(&compressed_mint_inputs.compressed_mint, authority)?;
+
+    let mut mint_amount: u64 = 0;
+    for amount in inputs.amounts.iter() {
+        mint_amount = mint_amount
+            .checked_add(*amount)
+            .ok_or(ErrorCode::MintTooLarge)?;
+    }
+    let mut compressed_mint = compressed_mint_inputs.compressed_mint.clone();
+    compressed_mint.supply = compressed_mint
+        .supply
+        .checked_add(mint_amount)
+        .ok_or(ErrorCode::MintTooLarge)?;
+
+    let mint = compressed_mint.spl_mint;
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
+    let mut output_compressed_accounts =
+        vec![OutputCompressedAccountWithPackedContext::default(); inputs.recipients.len()];
+    create_output_compressed_accounts::<NOT_FROZEN>(
+        &mut output_compressed_accounts,
+        mint,
+        inputs.recipients.as_slice(),
+        None,
+        None,
+        inputs.amounts.as_slice(),
+        None::<Vec<Option<u64>>>,
+        None,
+        &hashed_mint,
+        &vec![inputs.token_account_merkle_tree_index; inputs.recipients.len()],
+        remaining_accounts,
+    )?;
+    let (input_mint_account, output_mint_account) =
+        create_input_and_output_compressed_mint_accounts(compressed_mint_inputs, &compressed_mint)?;
+    output_compressed_accounts.push(output_mint_account);
+    Ok((vec![input_mint_account], output_compressed_accounts))
+}
+
+/// Same layout as CompressedTokenInstructionDataBurn, including its input
+/// tlvs, followed by the compressed mint inputs.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedTokenInstructionDataBurnCompressed {
+    pub burn: CompressedTokenInstructionDataBurn,
+    pub compressed_mint_inputs: CompressedMintInputs,
+}
+
+/// Burns compressed tokens of a compressed mint, the supply of the compressed
+/// mint is reduced by the burned amount. Delegates can burn tokens like in
+/// crate::burn::process_burn.
+pub fn process_burn_compressed<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, GenericInstruction<'info>>,
+    inputs: Vec<u8>,
+) -> Result<()> {
+    let (burn_inputs, bytes) =
+        ZCompressedTokenInstructionDataBurn::zero_copy_at(&inputs).map_err(ProgramError::from)?;
+    let compressed_mint_inputs =
+        CompressedMintInputs::deserialize(&mut &bytes[..]).map_err(ProgramError::from)?;
+    check_inputs_unlocked(
+        burn_inputs
+            .input_token_data_with_context
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.amount.get(), get_input_tlv(&burn_inputs.input_tlvs, i))),
+        Clock::get()?.slot,
+    )?;
+    let (compressed_input_accounts, output_compressed_accounts) =
+        create_input_and_output_accounts_burn_compressed(
+            &burn_inputs,
+            &compressed_mint_inputs,
+            &ctx.accounts.authority.key(),
+            ctx.remaining_accounts,
+        )?;
+    let proof = if *burn_inputs.proof == CompressedProof::default() {
+        None
+    } else {
+        Some(*burn_inputs.proof)
+    };
+    cpi_execute_compressed_transaction_transfer(
+        ctx.accounts,
+        compressed_input_accounts,
+        output_compressed_accounts,
+        false,
+        proof,
+        burn_inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn create_input_and_output_accounts_burn_compressed(
+    burn_inputs: &ZCompressedTokenInstructionDataBurn,
+    compressed_mint_inputs: &CompressedMintInputs,
+    authority: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    Vec<InAccount>,
+    Vec<OutputCompressedAccountWithPackedContext>,
+)> {
+    if compressed_mint_inputs.compressed_mint.is_decompressed {
+        return err!(ErrorCode::MintDecompressed);
+    }
+    let mut compressed_mint = compressed_mint_inputs.compressed_mint.clone();
+    compressed_mint.supply = match compressed_mint
+        .supply
+        .checked_sub(burn_inputs.burn_amount.get())
+    {
+        Some(supply) => supply,
+        None => return err!(ErrorCode::ArithmeticUnderflow),
+    };
+    let (mut compressed_input_accounts, mut output_compressed_accounts) =
+        create_input_and_output_accounts_burn(
+            burn_inputs,
+            authority,
+            false,
+            remaining_accounts,
+            &compressed_mint.spl_mint,
+        )?;
+    let (input_mint_account, output_mint_account) =
+        create_input_and_output_compressed_mint_accounts(compressed_mint_inputs, &compressed_mint)?;
+    compressed_input_accounts.push(input_mint_account);
+    output_compressed_accounts.push(output_mint_account);
+    Ok((compressed_input_accounts, output_compressed_accounts))
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct CompressedTokenInstructionDataDecompressMint {
+    pub proof: Option<CompressedProof>,
+    pub compressed_mint_inputs: CompressedMintInputs,
+}
+
+/// Decompresses a compressed mint:
+/// 1. creates the spl mint at the mint pda
+/// 2. creates the token pool account of the mint
+/// 3. mints the compressed supply to the token pool account
+/// 4. transfers the mint authority to the mint authority of the compressed
+///    mint
+/// 5. marks the compressed mint as decompressed
+///
+/// Existing compressed token accounts are backed by the token pool and can
+/// be decompressed, new tokens are minted with mint_to.
+pub fn process_decompress_mint<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, DecompressMintInstruction<'info>>,
+    inputs: CompressedTokenInstructionDataDecompressMint,
+) -> Result<()> {
+    let compressed_mint_inputs = &inputs.compressed_mint_inputs;
+    let authority = ctx.accounts.authority.key();
+    check_mint_authority(&compressed_mint_inputs.compressed_mint, &authority)?;
+    let (spl_mint, mint_bump) = find_compressed_mint_pda(&ctx.accounts.mint_signer.key());
+    if spl_mint != compressed_mint_inputs.compressed_mint.spl_mint
+        || spl_mint != ctx.accounts.mint.key()
+    {
+        msg!(
+            "mint {:?} != mint pda {:?}",
+            ctx.accounts.mint.key(),
+            compressed_mint_inputs.compressed_mint.spl_mint
+        );
+        return err!(ErrorCode::InvalidMint);
+    }
+    let (token_pool_pda, token_pool_bump) = find_token_pool_pda_with_index(&spl_mint, 0);
+    if token_pool_pda != ctx.accounts.token_pool_pda.key() {
+        return err!(ErrorCode::InvalidTokenPoolPda);
+    }
+    let mut compressed_mint = compressed_mint_inputs.compressed_mint.clone();
+    compressed_mint.is_decompressed = true;
+    let (input_mint_account, output_mint_account) =
+        create_input_and_output_compressed_mint_accounts(compressed_mint_inputs, &compressed_mint)?;
+
+    create_spl_mint_and_token_pool(&ctx, &compressed_mint, mint_bump, token_pool_bump)?;
+
+    cpi_execute_compressed_transaction_transfer(
+        ctx.accounts,
+        vec![input_mint_account],
+        vec![output_mint_account],
+        false,
+        inputs.proof,
+        None,
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+/// Creates a pda account owned by owner like Anchor init.
+/// Anyone can transfer lamports to the pda before it is created,
+/// a pre-funded pda is topped up to rent exemption,
+/// allocated and assigned instead of created.
+fn create_pda_account<'info>(
+    ctx: &Context<'_, '_, '_, 'info, DecompressMintInstruction<'info>>,
+    account: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+) -> Result<()> {
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let fee_payer = ctx.accounts.fee_payer.to_account_info();
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                system_program::CreateAccount {
+                    from: fee_payer,
+                    to: account.clone(),
+                },
+                &[seeds],
+            ),
+            rent.minimum_balance(space),
+            space as u64,
+            owner,
+        );
+    }
+    let required_lamports = rent.minimum_balance(space).max(1).saturating_sub(lamports);
+    if required_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: fee_payer,
+                    to: account.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            &[seeds],
+        ),
+        owner,
+    )
+}
+
+#[inline(never)]
+fn create_spl_mint_and_token_pool<'info>(
+    ctx: &Context<'_, '_, '_, 'info, DecompressMintInstruction<'info>>,
+    compressed_mint: &CompressedMint,
+    mint_bump: u8,
+    token_pool_bump: u8,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let mint = ctx.accounts.mint.to_account_info();
+    let token_pool_pda = ctx.accounts.token_pool_pda.to_account_info();
+    let cpi_authority_pda = ctx.accounts.cpi_authority_pda.to_account_info();
+    let mint_signer = ctx.accounts.mint_signer.key();
+    let cpi_signer_seeds = get_cpi_signer_seeds();
+
+    let mint_bump = [mint_bump];
+    let mint_seeds = [COMPRESSED_MINT_SEED, mint_signer.as_ref(), &mint_bump];
+    create_pda_account(
+        ctx,
+        &mint,
+        &mint_seeds,
+        &rent,
+        SPL_MINT_LEN,
+        token_program.key,
+    )?;
+    // The cpi authority mints the supply to the token pool before the mint
+    // authority is transferred.
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            token_program.clone(),
+            token_interface::InitializeMint2 { mint: mint.clone() },
+        ),
+        compressed_mint.decimals,
+        cpi_authority_pda.key,
+        compressed_mint.freeze_authority.as_ref(),
+    )?;
+
+    let token_pool_bump = [token_pool_bump];
+    let token_pool_seeds = [
+        POOL_SEED,
+        compressed_mint.spl_mint.as_ref(),
+        &token_pool_bump,
+    ];
+    create_pda_account(
+        ctx,
+        &token_pool_pda,
+        &token_pool_seeds,
+        &rent,
+        SPL_TOKEN_ACCOUNT_LEN,
+        token_program.key,
+    )?;
+    token_interface::initialize_account3(CpiContext::new(
+        token_program.clone(),
+        token_interface::InitializeAccount3 {
+            account: token_pool_pda.clone(),
+            mint: mint.clone(),
+            authority: cpi_authority_pda.clone(),
+        },
+    ))?;
+
+    if compressed_mint.supply > 0 {
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token_interface::MintTo {
+                    mint: mint.clone(),
+                    to: token_pool_pda,
+                    authority: cpi_authority_pda.clone(),
+                },
+                &[&cpi_signer_seeds[..]],
+            ),
+            compressed_mint.supply,
+        )?;
+    }
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program,
+            token_interface::SetAuthority {
+                current_authority: cpi_authority_pda,
+                account_or_mint: mint,
+            },
+            &[&cpi_signer_seeds[..]],
+        ),
+        anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType::MintTokens,
+        compressed_mint.mint_authority,
+    )
+}
+
+#[cfg(not(target_os = "solana"))]
+pub mod sdk {
+    use std::{collections::HashMap, result::Result};
+
+    use anchor_lang::{AnchorSerialize, InstructionData, ToAccountMetas};
+    use light_compressed_account::compressed_account::{CompressedAccount, MerkleContext};
+    use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+    use super::*;
+    use crate::{
+        get_token_pool_pda,
+        process_transfer::{
+            get_cpi_authority_pda,
+            transfer_sdk::{
+                create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+                TransferSdkError,
+            },
+        },
+        token_data::TokenData,
+    };
+
+    fn get_or_insert_index(remaining_accounts: &mut HashMap<Pubkey, usize>, account: Pubkey) -> u8 {
+        let next_index = remaining_accounts.len();
+        *remaining_accounts.entry(account).or_insert(next_index) as u8
+    }
+
+    /// Packs the compressed mint account inputs, a root index of None proves
+    /// the account by index.
+    fn pack_compressed_mint_inputs(
+        remaining_accounts: &mut HashMap<Pubkey, usize>,
+        compressed_mint: CompressedMint,
+        address: [u8; 32],
+        merkle_context: &MerkleContext,
+        root_index: Option<u16>,
+        output_merkle_tree: Pubkey,
+    ) -> CompressedMintInputs {
+        CompressedMintInputs {
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: get_or_insert_index(
+                    remaining_accounts,
+                    merkle_context.merkle_tree_pubkey,
+                ),
+                queue_pubkey_index: get_or_insert_index(
+                    remaining_accounts,
+                    merkle_context.queue_pubkey,
+                ),
+                leaf_index: merkle_context.leaf_index,
+                prove_by_index: root_index.is_none(),
+            },
+            root_index: root_index.unwrap_or_default(),
+            address,
+            compressed_mint,
+            output_merkle_tree_index: get_or_insert_index(remaining_accounts, output_merkle_tree),
+        }
+    }
+
+    fn generic_accounts(
+        fee_payer: Pubkey,
+        authority: Pubkey,
+    ) -> crate::accounts::GenericInstruction {
+        crate::accounts::GenericInstruction {
+            fee_payer,
+            authority,
+            cpi_authority_pda: get_cpi_authority_pda().0,
+            light_system_program: light_system_program::ID,
+            registered_program_pda: light_system_program::utils::get_registered_program_pda(
+                &light_system_program::ID,
+            ),
+            noop_program: Pubkey::new_from_array(
+                account_compression::utils::constants::NOOP_PUBKEY,
+            ),
+            account_compression_authority: light_system_program::utils::get_cpi_authority_pda(
+                &light_system_program::ID,
+            ),
+            account_compression_program: account_compression::ID,
+            self_program: crate::ID,
+            system_program: solana_sdk::system_program::ID,
+        }
+    }
+
+    pub struct CreateCompressedMintInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub mint_signer: Pubkey,
+        pub decimals: u8,
+        pub freeze_authority: Option<Pubkey>,
+        pub proof: CompressedProof,
+        pub address_merkle_tree_root_index: u16,
+        /// Batched address Merkle tree, is also the address queue. The
+        /// program only accepts COMPRESSED_MINT_ADDRESS_MERKLE_TREE.
+        pub address_merkle_tree: Pubkey,
+        pub output_merkle_tree: Pubkey,
+    }
+
+    pub fn create_create_compressed_mint_instruction(
+        inputs: CreateCompressedMintInstructionInputs,
+    ) -> Instruction {
+        let mut remaining_accounts = HashMap::<Pubkey, usize>::new();
+        let address_merkle_tree_index =
+            get_or_insert_index(&mut remaining_accounts, inputs.address_merkle_tree);
+        let output_merkle_tree_index =
+            get_or_insert_index(&mut remaining_accounts, inputs.output_merkle_tree);
+        let instruction_data = crate::instruction::CreateCompressedMint {
+            inputs: CompressedTokenInstructionDataCreateCompressedMint {
+                proof: inputs.proof,
+                decimals: inputs.decimals,
+                freeze_authority: inputs.freeze_authority,
+                address_merkle_tree_index,
+                address_queue_index: address_merkle_tree_index,
+                address_merkle_tree_root_index: inputs.address_merkle_tree_root_index,
+                output_merkle_tree_index,
+            },
+        };
+        let accounts = crate::accounts::CreateCompressedMintInstruction {
+            fee_payer: inputs.fee_payer,
+            authority: inputs.authority,
+            mint_signer: inputs.mint_signer,
+            cpi_authority_pda: get_cpi_authority_pda().0,
+            light_system_program: light_system_program::ID,
+            registered_program_pda: light_system_program::utils::get_registered_program_pda(
+                &light_system_program::ID,
+            ),
+            noop_program: Pubkey::new_from_array(
+                account_compression::utils::constants::NOOP_PUBKEY,
+            ),
+            account_compression_authority: light_system_program::utils::get_cpi_authority_pda(
+                &light_system_program::ID,
+            ),
+            account_compression_program: account_compression::ID,
+            self_program: crate::ID,
+            system_program: solana_sdk::system_program::ID,
+        };
+        Instruction {
+            program_id: crate::ID,
+            accounts: [
+                accounts.to_account_metas(Some(true)),
+                to_account_metas(remaining_accounts),
+            ]
+            .concat(),
+            data: instruction_data.data(),
+        }
+    }
+
+    pub struct CreateMintToCompressedInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub compressed_mint: CompressedMint,
+        pub compressed_mint_address: [u8; 32],
+        pub compressed_mint_merkle_context: MerkleContext,
+        pub root_index: Option<u16>,
+        pub proof: Option<CompressedProof>,
+        pub recipients: Vec<Pubkey>,
+        pub amounts: Vec<u64>,
+        pub output_merkle_tree: Pubkey,
+    }
+
+    pub fn create_mint_to_compressed_instruction(
+        inputs: CreateMintToCompressedInstructionInputs,
+    ) -> Instruction {
+        let mut remaining_accounts = HashMap::<Pubkey, usize>::new();
+        let compressed_mint_inputs = pack_compressed_mint_inputs(
+            &mut remaining_accounts,
+            inputs.compressed_mint,
+            inputs.compressed_mint_address,
+            &inputs.compressed_mint_merkle_context,
+            inputs.root_index,
+            inputs.output_merkle_tree,
+        );
+        let instruction_data = crate::instruction::MintToCompressed {
+            inputs: CompressedTokenInstructionDataMintToCompressed {
+                proof: inputs.proof,
+                token_account_merkle_tree_index: compressed_mint_inputs.output_merkle_tree_index,
+                compressed_mint_inputs,
+                recipients: inputs.recipients,
+                amounts: inputs.amounts,
+            },
+        };
+        let accounts = generic_accounts(inputs.fee_payer, inputs.authority);
+        Instruction {
+            program_id: crate::ID,
+            accounts: [
+                accounts.to_account_metas(Some(true)),
+                to_account_metas(remaining_accounts),
+            ]
+            .concat(),
+            data: instruction_data.data(),
+        }
+    }
+
+    pub struct CreateBurnCompressedInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub root_indices: Vec<Option<u16>>,
+        pub proof: CompressedProof,
+        pub input_token_data: Vec<TokenData>,
+        pub input_compressed_accounts: Vec<CompressedAccount>,
+        pub input_merkle_contexts: Vec<MerkleContext>,
+        pub change_account_merkle_tree: Pubkey,
+        pub burn_amount: u64,
+        pub compressed_mint: CompressedMint,
+        pub compressed_mint_address: [u8; 32],
+        pub compressed_mint_merkle_context: MerkleContext,
+        pub compressed_mint_root_index: Option<u16>,
+    }
+
+    pub fn create_burn_compressed_instruction(
+        inputs: CreateBurnCompressedInstructionInputs,
+    ) -> Result<Instruction, TransferSdkError> {
+        let (mut remaining_accounts, input_token_data_with_context, _) =
+            create_input_output_and_remaining_accounts(
+                &[inputs.change_account_merkle_tree],
+                &inputs.input_token_data,
+                &inputs.input_compressed_accounts,
+                &inputs.input_merkle_contexts,
+                &inputs.root_indices,
+                &Vec::new(),
+            );
+        let change_account_merkle_tree_index =
+            match remaining_accounts.get(&inputs.change_account_merkle_tree) {
+                Some(change_account_merkle_tree_index) => *change_account_merkle_tree_index as u8,
+                None => return Err(TransferSdkError::AccountNotFound),
+            };
+        let compressed_mint_inputs = pack_compressed_mint_inputs(
+            &mut remaining_accounts,
+            inputs.compressed_mint,
+            inputs.compressed_mint_address,
+            &inputs.compressed_mint_merkle_context,
+            inputs.compressed_mint_root_index,
+            inputs.change_account_merkle_tree,
+        );
+        let inputs_struct = CompressedTokenInstructionDataBurnCompressed {
+            burn: CompressedTokenInstructionDataBurn {
+                proof: inputs.proof,
+                input_token_data_with_context,
+                cpi_context: None,
+                burn_amount: inputs.burn_amount,
+                change_account_merkle_tree_index,
+                delegated_transfer: None,
+                input_tlvs: get_input_tlvs(&inputs.input_token_data),
+            },
+            compressed_mint_inputs,
+        };
+        let mut serialized_ix_data = Vec::new();
+        CompressedTokenInstructionDataBurnCompressed::serialize(
+            &inputs_struct,
+            &mut serialized_ix_data,
+        )
+        .map_err(|_| TransferSdkError::SerializationError)?;
+        let instruction_data = crate::instruction::BurnCompressed {
+            inputs: serialized_ix_data,
+        };
+        let accounts = generic_accounts(inputs.fee_payer, inputs.authority);
+        Ok(Instruction {
+            program_id: crate::ID,
+            accounts: [
+                accounts.to_account_metas(Some(true)),
+                to_account_metas(remaining_accounts),
+            ]
+            .concat(),
+            data: instruction_data.data(),
+        })
+    }
+
+    pub struct CreateDecompressMintInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub mint_signer: Pubkey,
+        pub compressed_mint: CompressedMint,
+        pub compressed_mint_address: [u8; 32],
+        pub compressed_mint_merkle_context: MerkleContext,
+        pub root_index: Option<u16>,
+        pub proof: Option<CompressedProof>,
+        pub output_merkle_tree: Pubkey,
+        pub token_2022: bool,
+    }
+
+    pub fn create_decompress_mint_instruction(
+        inputs: CreateDecompressMintInstructionInputs,
+    ) -> Instruction {
+        let mut remaining_accounts = HashMap::<Pubkey, usize>::new();
+        let mint = inputs.compressed_mint.spl_mint;
+        let compressed_mint_inputs = pack_compressed_mint_inputs(
+            &mut remaining_accounts,
+            inputs.compressed_mint,
+            inputs.compressed_mint_address,
+            &inputs.compressed_mint_merkle_context,
+            inputs.root_index,
+            inputs.output_merkle_tree,
+        );
+        let instruction_data = crate::instruction::DecompressMint {
+            inputs: CompressedTokenInstructionDataDecompressMint {
+                proof: inputs.proof,
+                compressed_mint_inputs,
+            },
+        };
+        let token_program = if inputs.token_2022 {
+            anchor_spl::token_2022::ID
+        } else {
+            anchor_spl::token::ID
+        };
+        let accounts = crate::accounts::DecompressMintInstruction {
+            fee_payer: inputs.fee_payer,
+            authority: inputs.authority,
+            mint_signer: inputs.mint_signer,
+            mint,
+            token_pool_pda: get_token_pool_pda(&mint),
+            token_program,
+            cpi_authority_pda: get_cpi_authority_pda().0,
+            light_system_program: light_system_program::ID,
+            registered_program_pda: light_system_program::utils::get_registered_program_pda(
+                &light_system_program::ID,
+            ),
+            noop_program: Pubkey::new_from_array(
+                account_compression::utils::constants::NOOP_PUBKEY,
+            ),
+            account_compression_authority: light_system_program::utils::get_cpi_authority_pda(
+                &light_system_program::ID,
+            ),
+            account_compression_program: account_compression::ID,
+            self_program: crate::ID,
+            system_program: solana_sdk::system_program::ID,
+        };
+        Instruction {
+            program_id: crate::ID,
+            accounts: [
+                accounts.to_account_metas(Some(true)),
+                to_account_metas(remaining_accounts),
+            ]
+            .concat(),
+            data: instruction_data.data(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use account_compression::StateMerkleTreeAccount;
+    use anchor_lang::{solana_program::account_info::AccountInfo, Discriminator};
+
+    use super::*;
+    use crate::{
+        freeze::test_freeze::create_expected_token_output_accounts,
+        process_transfer::InputTokenDataWithContext, token_data::AccountState, TokenData,
+    };
+
+    fn compressed_mint_inputs(compressed_mint: CompressedMint) -> CompressedMintInputs {
+        CompressedMintInputs {
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: 0,
+                queue_pubkey_index: 1,
+                leaf_index: 3,
+                prove_by_index: false,
+            },
+            root_index: 7,
+            address: [1u8; 32],
+            compressed_mint,
+            output_merkle_tree_index: 2,
+        }
+    }
+
+    fn expected_mint_accounts(
+        inputs: &CompressedMintInputs,
+        compressed_mint: &CompressedMint,
+    ) -> (InAccount, OutputCompressedAccountWithPackedContext) {
+        (
+            InAccount {
+                discriminator: COMPRESSED_MINT_DISCRIMINATOR,
+                data_hash: inputs.compressed_mint.hash().unwrap(),
+                merkle_context: inputs.merkle_context,
+                root_index: inputs.root_index,
+                lamports: 0,
+                address: Some(inputs.address),
+            },
+            OutputCompressedAccountWithPackedContext {
+                compressed_account: CompressedAccount {
+                    owner: crate::ID,
+                    lamports: 0,
+                    address: Some(inputs.address),
+                    data: Some(CompressedAccountData {
+                        discriminator: COMPRESSED_MINT_DISCRIMINATOR,
+                        data: compressed_mint.try_to_vec().unwrap(),
+                        data_hash: compressed_mint.hash().unwrap(),
+                    }),
+                },
+                merkle_tree_index: 2,
+            },
+        )
+    }
+
+    fn assert_error(result: Result<impl std::fmt::Debug>, error: ErrorCode) {
+        let error_code = error as u32 + 6000;
+        assert!(matches!(
+            result.unwrap_err(),
+            anchor_lang::error::Error::AnchorError(error) if error.error_code_number == error_code
+        ));
+    }
+
+    #[test]
+    fn test_compressed_mint_hash() {
+        let compressed_mint = CompressedMint {
+            spl_mint: Pubkey::new_unique(),
+            supply: 100,
+            decimals: 6,
+            mint_authority: Some(Pubkey::new_unique()),
+            freeze_authority: None,
+            is_decompressed: false,
+        };
+        let hash = compressed_mint.hash().unwrap();
+        let mut previous_hashes = vec![hash];
+        let changed_mints = [
+            CompressedMint {
+                spl_mint: Pubkey::new_unique(),
+                ..compressed_mint.clone()
+            },
+            CompressedMint {
+                supply: 101,
+                ..compressed_mint.clone()
+            },
+            CompressedMint {
+                decimals: 9,
+                ..compressed_mint.clone()
+            },
+            CompressedMint {
+                mint_authority: None,
+                ..compressed_mint.clone()
+            },
+            CompressedMint {
+                freeze_authority: compressed_mint.mint_authority,
+                ..compressed_mint.clone()
+            },
+            CompressedMint {
+                is_decompressed: true,
+                ..compressed_mint.clone()
+            },
+        ];
+        for changed_mint in changed_mints.iter() {
+            let hash = changed_mint.hash().unwrap();
+            assert!(!previous_hashes.contains(&hash));
+            previous_hashes.push(hash);
+        }
+        assert_eq!(compressed_mint.hash().unwrap(), previous_hashes[0]);
+    }
+
+    #[test]
+    fn test_mint_to_and_burn_compressed() {
+        let merkle_tree_pubkey = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports = 0;
+        let mut merkle_tree_account_data = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let nullifier_queue_pubkey = Pubkey::new_unique();
+        let mut nullifier_queue_account_lamports = 0;
+        let mut nullifier_queue_account_data = Vec::new();
+        let merkle_tree_pubkey_1 = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports_1 = 0;
+        let mut merkle_tree_account_data_1 = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let remaining_accounts = vec![
+            AccountInfo::new(
+                &merkle_tree_pubkey,
+                false,
+                false,
+                &mut merkle_tree_account_lamports,
+                &mut merkle_tree_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &nullifier_queue_pubkey,
+                false,
+                false,
+                &mut nullifier_queue_account_lamports,
+                &mut nullifier_queue_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &merkle_tree_pubkey_1,
+                false,
+                false,
+                &mut merkle_tree_account_lamports_1,
+                &mut merkle_tree_account_data_1,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+        ];
+        let authority = Pubkey::new_unique();
+        let compressed_mint = CompressedMint {
+            spl_mint: find_compressed_mint_pda(&Pubkey::new_unique()).0,
+            supply: 100,
+            decimals: 6,
+            mint_authority: Some(authority),
+            freeze_authority: None,
+            is_decompressed: false,
+        };
+        let recipients = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        // 1. Functional: mint to compressed increases the supply.
+        let mut inputs = CompressedTokenInstructionDataMintToCompressed {
+            proof: None,
+            compressed_mint_inputs: compressed_mint_inputs(compressed_mint.clone()),
+            recipients: recipients.clone(),
+            amounts: vec![10, 20],
+            token_account_merkle_tree_index: 0,
+        };
+        let (input_compressed_accounts, output_compressed_accounts) =
+            create_input_and_output_accounts_mint_to_compressed(
+                &inputs,
+                &authority,
+                &remaining_accounts,
+            )
+            .unwrap();
+        let expected_compressed_mint = CompressedMint {
+            supply: 130,
+            ..compressed_mint.clone()
+        };
+        let (expected_input_account, expected_mint_account) =
+            expected_mint_accounts(&inputs.compressed_mint_inputs, &expected_compressed_mint);
+        let mut expected_output_accounts = create_expected_token_output_accounts(
+            recipients
+                .iter()
+                .zip([10, 20])
+                .map(|(owner, amount)| TokenData {
+                    mint: compressed_mint.spl_mint,
+                    owner: *owner,
+                    amount,
+                    delegate: None,
+                    state: AccountState::Initialized,
+                    tlv: None,
+                })
+                .collect(),
+            vec![0, 0],
+        );
+        expected_output_accounts.push(expected_mint_account);
+        assert_eq!(input_compressed_accounts, vec![expected_input_account]);
+        assert_eq!(output_compressed_accounts, expected_output_accounts);
+
+        // 2. Failing: authority is not the mint authority.
+        let result = create_input_and_output_accounts_mint_to_compressed(
+            &inputs,
+            &Pubkey::new_unique(),
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::InvalidAuthorityMint);
+
+        // 3. Failing: supply overflows.
+        inputs.amounts = vec![u64::MAX - 100, 1];
+        let result = create_input_and_output_accounts_mint_to_compressed(
+            &inputs,
+            &authority,
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::MintTooLarge);
+
+        // 4. Failing: recipients and amounts mismatch.
+        inputs.amounts = vec![10];
+        let result = create_input_and_output_accounts_mint_to_compressed(
+            &inputs,
+            &authority,
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::PublicKeyAmountMissmatch);
+
+        // 5. Failing: mint is decompressed.
+        inputs.amounts = vec![10, 20];
+        inputs
+            .compressed_mint_inputs
+            .compressed_mint
+            .is_decompressed = true;
+        let result = create_input_and_output_accounts_mint_to_compressed(
+            &inputs,
+            &authority,
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::MintDecompressed);
+
+        // 6. Functional: burn compressed reduces the supply.
+        let owner = Pubkey::new_unique();
+        let mut burn_inputs = CompressedTokenInstructionDataBurnCompressed {
+            burn: CompressedTokenInstructionDataBurn {
+                proof: CompressedProof::default(),
+                input_token_data_with_context: vec![InputTokenDataWithContext {
+                    amount: 100,
+                    merkle_context: PackedMerkleContext {
+                        merkle_tree_pubkey_index: 0,
+                        queue_pubkey_index: 1,
+                        leaf_index: 1,
+                        prove_by_index: false,
+                    },
+                    root_index: 0,
+                    delegate_index: None,
+                    lamports: None,
+                    tlv: None,
+                }],
+                cpi_context: None,
+                burn_amount: 40,
+                change_account_merkle_tree_index: 2,
+                delegated_transfer: None,
+                input_tlvs: Vec::new(),
+            },
+            compressed_mint_inputs: compressed_mint_inputs(compressed_mint.clone()),
+        };
+        let bytes = burn_inputs.try_to_vec().unwrap();
+        let (z_burn_inputs, remaining_bytes) =
+            ZCompressedTokenInstructionDataBurn::zero_copy_at(&bytes).unwrap();
+        assert_eq!(
+            CompressedMintInputs::deserialize(&mut &remaining_bytes[..]).unwrap(),
+            burn_inputs.compressed_mint_inputs
+        );
+        let (input_compressed_accounts, output_compressed_accounts) =
+            create_input_and_output_accounts_burn_compressed(
+                &z_burn_inputs,
+                &burn_inputs.compressed_mint_inputs,
+                &owner,
+                &remaining_accounts,
+            )
+            .unwrap();
+        let expected_compressed_mint = CompressedMint {
+            supply: 60,
+            ..compressed_mint.clone()
+        };
+        let (expected_input_account, expected_mint_account) = expected_mint_accounts(
+            &burn_inputs.compressed_mint_inputs,
+            &expected_compressed_mint,
+        );
+        let mut expected_output_accounts = create_expected_token_output_accounts(
+            vec![TokenData {
+                mint: compressed_mint.spl_mint,
+                owner,
+                amount: 60,
+                delegate: None,
+                state: AccountState::Initialized,
+                tlv: None,
+            }],
+            vec![2],
+        );
+        expected_output_accounts.push(expected_mint_account);
+        assert_eq!(input_compressed_accounts.len(), 2);
+        assert_eq!(input_compressed_accounts[1], expected_input_account);
+        assert_eq!(output_compressed_accounts, expected_output_accounts);
+
+        // 7. Failing: burn amount exceeds the supply.
+        burn_inputs.burn.burn_amount = 100;
+        burn_inputs.compressed_mint_inputs.compressed_mint.supply = 50;
+        let bytes = burn_inputs.burn.try_to_vec().unwrap();
+        let (z_burn_inputs, _) = ZCompressedTokenInstructionDataBurn::zero_copy_at(&bytes).unwrap();
+        let result = create_input_and_output_accounts_burn_compressed(
+            &z_burn_inputs,
+            &burn_inputs.compressed_mint_inputs,
+            &owner,
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::ArithmeticUnderflow);
+
+        // 8. Failing: mint is decompressed.
+        burn_inputs.compressed_mint_inputs.compressed_mint.supply = 100;
+        burn_inputs
+            .compressed_mint_inputs
+            .compressed_mint
+            .is_decompressed = true;
+        let result = create_input_and_output_accounts_burn_compressed(
+            &z_burn_inputs,
+            &burn_inputs.compressed_mint_inputs,
+            &owner,
+            &remaining_accounts,
+        );
+        assert_error(result, ErrorCode::MintDecompressed);
+    }
+
+    #[test]
+    fn test_create_compressed_mint_account() {
+        let address_merkle_tree_pubkey = COMPRESSED_MINT_ADDRESS_MERKLE_TREE;
+        let mut address_merkle_tree_lamports = 0;
+        let mut address_merkle_tree_data = Vec::new();
+        let invalid_address_merkle_tree_pubkey = Pubkey::new_unique();
+        let mut invalid_address_merkle_tree_lamports = 0;
+        let mut invalid_address_merkle_tree_data = Vec::new();
+        let remaining_accounts = vec![
+            AccountInfo::new(
+                &address_merkle_tree_pubkey,
+                false,
+                false,
+                &mut address_merkle_tree_lamports,
+                &mut address_merkle_tree_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &invalid_address_merkle_tree_pubkey,
+                false,
+                false,
+                &mut invalid_address_merkle_tree_lamports,
+                &mut invalid_address_merkle_tree_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+        ];
+        let authority = Pubkey::new_unique();
+        let (spl_mint, _) = find_compressed_mint_pda(&Pubkey::new_unique());
+        let inputs = CompressedTokenInstructionDataCreateCompressedMint {
+            proof: CompressedProof::default(),
+            decimals: 9,
+            freeze_authority: None,
+            address_merkle_tree_index: 0,
+            address_queue_index: 0,
+            address_merkle_tree_root_index: 5,
+            output_merkle_tree_index: 1,
+        };
+        // 1. Functional
+        {
+            let (new_address_params, output_compressed_account) =
+                create_compressed_mint_account(&inputs, &spl_mint, &authority, &remaining_accounts)
+                    .unwrap();
+            assert_eq!(
+                new_address_params,
+                NewAddressParamsAssignedPacked {
+                    seed: spl_mint.to_bytes(),
+                    address_queue_account_index: 0,
+                    address_merkle_tree_account_index: 0,
+                    address_merkle_tree_root_index: 5,
+                    assigned_to_account: true,
+                    assigned_account_index: 0,
+                }
+            );
+            let expected_compressed_mint = CompressedMint {
+                spl_mint,
+                supply: 0,
+                decimals: 9,
+                mint_authority: Some(authority),
+                freeze_authority: None,
+                is_decompressed: false,
+            };
+            assert_eq!(
+                output_compressed_account,
+                create_compressed_mint_output_account(
+                    &expected_compressed_mint,
+                    get_compressed_mint_address(&spl_mint),
+                    1,
+                )
+                .unwrap()
+            );
+            assert_eq!(
+                output_compressed_account.compressed_account.address,
+                Some(derive_address(
+                    &spl_mint.to_bytes(),
+                    &address_merkle_tree_pubkey.to_bytes(),
+                    &crate::ID.to_bytes(),
+                ))
+            );
+        }
+        // 2. Failing: address Merkle tree is not the compressed mint address
+        //    Merkle tree
+        {
+            let mut inputs = inputs.clone();
+            inputs.address_merkle_tree_index = 1;
+            inputs.address_queue_index = 1;
+            let result =
+                create_compressed_mint_account(&inputs, &spl_mint, &authority, &remaining_accounts);
+            assert_error(result, ErrorCode::InvalidCompressedMintAddressMerkleTree);
+        }
+        // 3. Failing: freeze authority
+        {
+            let mut inputs = inputs.clone();
+            inputs.freeze_authority = Some(Pubkey::new_unique());
+            let result =
+                create_compressed_mint_account(&inputs, &spl_mint, &authority, &remaining_accounts);
+            assert_error(result, ErrorCode::CompressedMintFreezeAuthorityNotSupported);
+        }
+    }
+}
@@ -1,8 +1,26 @@
+use anchor_lang::{prelude::Pubkey, solana_program::pubkey};
+
 // 2 in little endian
 pub const TOKEN_COMPRESSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = [2, 0, 0, 0, 0, 0, 0, 0];
+// 3 in little endian
+pub const COMPRESSED_MINT_DISCRIMINATOR: [u8; 8] = [3, 0, 0, 0, 0, 0, 0, 0];
 pub const BUMP_CPI_AUTHORITY: u8 = 254;
 pub const NOT_FROZEN: bool = false;
 pub const POOL_SEED: &[u8] = b"pool";
+pub const COMPRESSED_MINT_SEED: &[u8] = b"compressed_mint";
+/// Batched address Merkle tree of all compressed mint addresses. Addresses
+/// are unique per tree, hence one tree ensures one compressed mint per mint
+/// pda.
+///
+/// Compressed mints can be created until the tree is rolled over. A batched
+/// address tree of height 40 holds 2^40 addresses and is rolled over at its
+/// rollover threshold (95% by default). Addresses are only unique within a
+/// tree, a mint pda could be created again in a successor tree. A successor
+/// tree therefore requires a new compressed mint version with its own tree
+/// and mint pda seed, so that the mint pdas of different versions never
+/// collide.
+pub const COMPRESSED_MINT_ADDRESS_MERKLE_TREE: Pubkey =
+    pubkey!("EzKE84aVTkCUhDHLELqyJaq1Y7UVVmqxXqZjVHwHY3rK");
 
 /// Maximum number of pool accounts that can be created for each mint.
 pub const NUM_MAX_POOL_ACCOUNTS: u8 = 5;
@@ -4,8 +4,11 @@ use light_compressed_account::{
     instruction_data::{
         compressed_proof::CompressedProof, cpi_context::CompressedCpiContext,
         data::OutputCompressedAccountWithPackedContext, with_readonly::InAccount,
+        zero_copy::ZCompressedCpiContext,
     },
 };
+use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, ZeroCopy};
+use zerocopy::Ref;
 
 use crate::{
     constants::NOT_FROZEN,
@@ -15,16 +18,135 @@ use crate::{
         get_input_compressed_accounts_with_merkle_context_and_check_signer,
         InputTokenDataWithContext,
     },
+    tlv::{
+        add_extension, get_shared_input_tlv, remove_extension, unpack_tlv, TokenExtension,
+        TokenExtensionType,
+    },
+    token_data::TokenData,
     ErrorCode, GenericInstruction,
 };
 
-// TODO: add instruction which accepts an optional proof
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+/// Returns the amount the delegate of a token account can transfer or burn.
+/// Accounts without delegate have no delegated amount, delegated accounts
+/// without TokenExtensionType::DelegatedAmount are delegated completely.
+pub fn get_delegated_amount(token_data: &TokenData) -> Result<u64> {
+    if token_data.delegate.is_none() {
+        return Ok(0);
+    }
+    let delegated_amount = match token_data.tlv.as_ref() {
+        Some(tlv) => unpack_tlv(tlv)?
+            .into_iter()
+            .find_map(|extension| match extension {
+                TokenExtension::DelegatedAmount(delegated_amount) => Some(delegated_amount),
+                _ => None,
+            }),
+        None => None,
+    };
+    Ok(
+        delegated_amount.map_or(token_data.amount, |delegated_amount| {
+            delegated_amount.min(token_data.amount)
+        }),
+    )
+}
+
+/// Returns the delegated amount which remains after the delegate spent
+/// spent_amount of the inputs.
+pub fn get_remaining_delegated_amount(
+    input_token_data: &[TokenData],
+    spent_amount: u64,
+) -> Result<u64> {
+    let mut delegated_amount: u64 = 0;
+    for token_data in input_token_data.iter() {
+        delegated_amount = delegated_amount
+            .checked_add(get_delegated_amount(token_data)?)
+            .ok_or(ErrorCode::ComputeInputSumFailed)?;
+    }
+    match delegated_amount.checked_sub(spent_amount) {
+        Some(remaining_delegated_amount) => Ok(remaining_delegated_amount),
+        None => {
+            msg!(
+                "Delegate spends {} but the delegated amount is {}.",
+                spent_amount,
+                delegated_amount
+            );
+            err!(ErrorCode::DelegatedAmountExceeded)
+        }
+    }
+}
+
+/// Sets the delegated amount extension of a delegated output with amount.
+/// The extension is omitted if the delegated amount covers the amount.
+pub fn set_delegated_amount(
+    tlv: &Option<Vec<u8>>,
+    amount: u64,
+    delegated_amount: u64,
+) -> Result<Option<Vec<u8>>> {
+    let tlv = remove_extension(tlv, TokenExtensionType::DelegatedAmount)?;
+    if delegated_amount >= amount {
+        Ok(tlv)
+    } else {
+        Ok(Some(add_extension(
+            &tlv,
+            TokenExtension::DelegatedAmount(delegated_amount),
+        )?))
+    }
+}
+
+/// Checks that a delegated transfer spends at most the delegated amount of
+/// the inputs. The spent amount is the sum of inputs owned by the owner minus
+/// the sum of outputs owned by the owner, inputs of the delegate itself are
+/// not limited. The remaining delegated amount is set in the tlv of the
+/// delegated change output.
+pub fn check_delegated_transfer(
+    input_token_data: &[TokenData],
+    owner: &Pubkey,
+    delegate_change_account_index: Option<u8>,
+    output_owners: &[Pubkey],
+    output_amounts: &[u64],
+    output_tlvs: &mut [Option<Vec<u8>>],
+) -> Result<()> {
+    let mut sum_inputs: u64 = 0;
+    for token_data in input_token_data
+        .iter()
+        .filter(|token_data| token_data.owner == *owner)
+    {
+        sum_inputs = sum_inputs
+            .checked_add(token_data.amount)
+            .ok_or(ErrorCode::ComputeInputSumFailed)?;
+    }
+    let mut owner_outputs: u64 = 0;
+    for (output_owner, amount) in output_owners.iter().zip(output_amounts.iter()) {
+        if output_owner == owner {
+            owner_outputs = owner_outputs
+                .checked_add(*amount)
+                .ok_or(ErrorCode::ComputeOutputSumFailed)?;
+        }
+    }
+    let remaining_delegated_amount =
+        get_remaining_delegated_amount(input_token_data, sum_inputs.saturating_sub(owner_outputs))?;
+    if let Some(index) = delegate_change_account_index {
+        let index = index as usize;
+        let (tlv, amount) = match (output_tlvs.get_mut(index), output_amounts.get(index)) {
+            (Some(tlv), Some(amount)) => (tlv, *amount),
+            _ => return err!(ErrorCode::InvalidDelegateIndex),
+        };
+        *tlv = set_delegated_amount(tlv, amount, remaining_delegated_amount)?;
+    }
+    Ok(())
+}
+
+/// The zero copy view ZCompressedTokenInstructionDataApprove deserializes a
+/// default proof as None.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedTokenInstructionDataApprove {
+    #[zero_copy(ty = DefaultProofAsNone)]
     pub proof: CompressedProof,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub mint: Pubkey,
     pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
     pub cpi_context: Option<CompressedCpiContext>,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub delegate: Pubkey,
     pub delegated_amount: u64,
     /// Index in remaining accounts.
@@ -32,41 +154,158 @@ pub struct CompressedTokenInstructionDataApprove {
     /// Index in remaining accounts.
     pub change_account_merkle_tree_index: u8,
     pub delegate_lamports: Option<u64>,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+    /// If true all inputs are merged into one delegated output and the
+    /// delegate can transfer or burn up to delegated_amount of it, see
+    /// TokenExtensionType::DelegatedAmount. Else delegated_amount is split
+    /// into a completely delegated output. Clients which predate
+    /// with_allowance omit it.
+    #[zero_copy(trailing)]
+    pub with_allowance: bool,
+}
+
+/// Approve instruction data without a proof if all inputs are proven by
+/// index. Same layout as CompressedTokenInstructionDataApprove except for the
+/// proof, its zero copy view is converted into
+/// ZCompressedTokenInstructionDataApprove.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct CompressedTokenInstructionDataApproveWithOptionalProof {
+    #[zero_copy(ty = OptionalDefaultProofAsNone)]
+    pub proof: Option<CompressedProof>,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
+    pub mint: Pubkey,
+    pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
+    pub cpi_context: Option<CompressedCpiContext>,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
+    pub delegate: Pubkey,
+    pub delegated_amount: u64,
+    /// Index in remaining accounts.
+    pub delegate_merkle_tree_index: u8,
+    /// Index in remaining accounts.
+    pub change_account_merkle_tree_index: u8,
+    pub delegate_lamports: Option<u64>,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+    /// If true all inputs are merged into one delegated output and the
+    /// delegate can transfer or burn up to delegated_amount of it, see
+    /// TokenExtensionType::DelegatedAmount. Else delegated_amount is split
+    /// into a completely delegated output. Clients which predate
+    /// with_allowance omit it.
+    #[zero_copy(trailing)]
+    pub with_allowance: bool,
+}
+
+/// Deserializes a proof, a default proof is deserialized as None. Clients
+/// pass a default proof if all inputs are proven by index.
+pub struct DefaultProofAsNone;
+
+impl<'a> Deserialize<'a> for DefaultProofAsNone {
+    type Output = Option<Ref<&'a [u8], CompressedProof>>;
+    const MIN_SIZE: usize = std::mem::size_of::<CompressedProof>();
+
+    fn zero_copy_at(
+        bytes: &'a [u8],
+    ) -> std::result::Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        let (proof, bytes) = CompressedProof::zero_copy_at(bytes)?;
+        Ok((
+            Some(proof).filter(|proof| **proof != CompressedProof::default()),
+            bytes,
+        ))
+    }
+}
+
+/// Deserializes an optional proof, a default proof is deserialized as None.
+pub struct OptionalDefaultProofAsNone;
+
+impl<'a> Deserialize<'a> for OptionalDefaultProofAsNone {
+    type Output = Option<Ref<&'a [u8], CompressedProof>>;
+
+    fn zero_copy_at(
+        bytes: &'a [u8],
+    ) -> std::result::Result<(Self::Output, &'a [u8]), ZeroCopyError> {
+        let (proof, bytes) = Option::<CompressedProof>::zero_copy_at(bytes)?;
+        Ok((
+            proof.filter(|proof| **proof != CompressedProof::default()),
+            bytes,
+        ))
+    }
+}
+
+impl<'a> ZCompressedTokenInstructionDataApprove<'a> {
+    /// Deserializes CompressedTokenInstructionDataApproveWithOptionalProof.
+    pub fn zero_copy_at_with_optional_proof(
+        bytes: &'a [u8],
+    ) -> std::result::Result<(Self, &'a [u8]), ZeroCopyError> {
+        let (inputs, bytes) =
+            ZCompressedTokenInstructionDataApproveWithOptionalProof::zero_copy_at(bytes)?;
+        Ok((inputs.into(), bytes))
+    }
+}
+
+impl<'a> From<ZCompressedTokenInstructionDataApproveWithOptionalProof<'a>>
+    for ZCompressedTokenInstructionDataApprove<'a>
+{
+    fn from(inputs: ZCompressedTokenInstructionDataApproveWithOptionalProof<'a>) -> Self {
+        Self {
+            proof: inputs.proof,
+            mint: inputs.mint,
+            input_token_data_with_context: inputs.input_token_data_with_context,
+            cpi_context: inputs.cpi_context,
+            delegate: inputs.delegate,
+            delegated_amount: inputs.delegated_amount,
+            delegate_merkle_tree_index: inputs.delegate_merkle_tree_index,
+            change_account_merkle_tree_index: inputs.change_account_merkle_tree_index,
+            delegate_lamports: inputs.delegate_lamports,
+            with_allowance: inputs.with_allowance,
+            input_tlvs: inputs.input_tlvs,
+        }
+    }
 }
 
 /// Processes an approve instruction.
 /// - creates an output compressed acount which is delegated to the delegate.
 /// - creates a change account for the remaining amount (sum inputs - delegated amount).
+/// - with_allowance creates one delegated output for the sum of inputs
+///   instead, the delegate can spend up to the delegated amount.
 /// - ignores prior delegations.
 /// 1. unpack instruction data and input compressed accounts
 /// 2. calculate change amount
 /// 3. create output compressed accounts
 /// 4. pack token data into input compressed accounts
 /// 5. execute compressed transaction
-pub fn process_approve<'a, 'b, 'c, 'info: 'b + 'c>(
+///
+/// With WITH_OPTIONAL_PROOF the proof can be omitted if all inputs are proven by
+/// index.
+pub fn process_approve<'a, 'b, 'c, 'info: 'b + 'c, const WITH_OPTIONAL_PROOF: bool>(
     ctx: Context<'a, 'b, 'c, 'info, GenericInstruction<'info>>,
     inputs: Vec<u8>,
 ) -> Result<()> {
-    let inputs: CompressedTokenInstructionDataApprove =
-        CompressedTokenInstructionDataApprove::deserialize(&mut inputs.as_slice())?;
+    let (inputs, _) = if WITH_OPTIONAL_PROOF {
+        ZCompressedTokenInstructionDataApprove::zero_copy_at_with_optional_proof(&inputs)
+    } else {
+        ZCompressedTokenInstructionDataApprove::zero_copy_at(&inputs)
+    }
+    .map_err(ProgramError::from)?;
     let (compressed_input_accounts, output_compressed_accounts) =
         create_input_and_output_accounts_approve(
             &inputs,
             &ctx.accounts.authority.key(),
             ctx.remaining_accounts,
         )?;
-    let proof = if inputs.proof == CompressedProof::default() {
-        None
-    } else {
-        Some(inputs.proof)
-    };
     cpi_execute_compressed_transaction_transfer(
         ctx.accounts,
         compressed_input_accounts,
         output_compressed_accounts,
         false,
-        proof,
-        inputs.cpi_context,
+        inputs.proof.as_ref().map(|proof| **proof),
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts.light_system_program.to_account_info(),
         ctx.accounts.self_program.to_account_info(),
@@ -75,7 +314,7 @@ pub fn process_approve<'a, 'b, 'c, 'info: 'b + 'c>(
 }
 
 pub fn create_input_and_output_accounts_approve(
-    inputs: &CompressedTokenInstructionDataApprove,
+    inputs: &ZCompressedTokenInstructionDataApprove,
     authority: &Pubkey,
     remaining_accounts: &[AccountInfo<'_>],
 ) -> Result<(
@@ -85,27 +324,37 @@ pub fn create_input_and_output_accounts_approve(
     if inputs.input_token_data_with_context.is_empty() {
         return err!(ErrorCode::NoInputTokenAccountsProvided);
     }
+    let mint: Pubkey = (*inputs.mint).into();
     let (mut compressed_input_accounts, input_token_data, sum_lamports) =
         get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
             authority,
-            &None,
+            None,
+            false,
             remaining_accounts,
             &inputs.input_token_data_with_context,
-            &inputs.mint,
+            &inputs.input_tlvs,
+            &mint,
         )?;
+    let delegated_amount = inputs.delegated_amount.get();
+    let delegate_lamports = inputs
+        .delegate_lamports
+        .as_ref()
+        .map(|lamports| lamports.get());
     let sum_inputs = input_token_data.iter().map(|x| x.amount).sum::<u64>();
-    let change_amount = match sum_inputs.checked_sub(inputs.delegated_amount) {
+    let change_amount = match sum_inputs.checked_sub(delegated_amount) {
         Some(change_amount) => change_amount,
         None => return err!(ErrorCode::ArithmeticUnderflow),
     };
 
-    let delegated_lamports = inputs.delegate_lamports.unwrap_or(0);
+    let delegated_lamports = delegate_lamports.unwrap_or(0);
     let change_lamports = match sum_lamports.checked_sub(delegated_lamports) {
         Some(change_lamports) => change_lamports,
         None => return err!(ErrorCode::ArithmeticUnderflow),
     };
 
-    let hashed_mint = hash_to_bn254_field_size_be(&inputs.mint.to_bytes());
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
+    // Extensions are preserved in the delegated and the change account.
+    let tlv = get_shared_input_tlv(input_token_data.as_slice())?;
 
     let lamports = if sum_lamports != 0 {
         let change_lamports = if change_lamports != 0 {
@@ -113,25 +362,42 @@ pub fn create_input_and_output_accounts_approve(
         } else {
             None
         };
-        Some(vec![inputs.delegate_lamports, change_lamports])
+        Some(vec![delegate_lamports, change_lamports])
     } else {
         None
     };
-    // Only create outputs if the change amount is not zero.
     let (
         mut output_compressed_accounts,
         pubkeys,
         is_delegate,
         amounts,
         lamports,
+        tlvs,
         merkle_tree_indices,
-    ) = if change_amount > 0 || change_lamports > 0 {
+    ) = if inputs.with_allowance {
+        let lamports = if sum_lamports != 0 {
+            Some(vec![Some(sum_lamports)])
+        } else {
+            None
+        };
+        (
+            vec![OutputCompressedAccountWithPackedContext::default(); 1],
+            vec![*authority],
+            Some(vec![true]),
+            vec![sum_inputs],
+            lamports,
+            vec![set_delegated_amount(&tlv, sum_inputs, delegated_amount)?],
+            vec![inputs.delegate_merkle_tree_index],
+        )
+    } else if change_amount > 0 || change_lamports > 0 {
+        // Only create a change output if the change amount is not zero.
         (
             vec![OutputCompressedAccountWithPackedContext::default(); 2],
             vec![*authority, *authority],
             Some(vec![true, false]),
-            vec![inputs.delegated_amount, change_amount],
+            vec![delegated_amount, change_amount],
             lamports,
+            vec![tlv.clone(), tlv],
             vec![
                 inputs.delegate_merkle_tree_index,
                 inputs.change_account_merkle_tree_index,
@@ -142,19 +408,21 @@ pub fn create_input_and_output_accounts_approve(
             vec![OutputCompressedAccountWithPackedContext::default(); 1],
             vec![*authority],
             Some(vec![true]),
-            vec![inputs.delegated_amount],
+            vec![delegated_amount],
             lamports,
+            vec![tlv],
             vec![inputs.delegate_merkle_tree_index],
         )
     };
-    create_output_compressed_accounts(
+    create_output_compressed_accounts::<NOT_FROZEN>(
         &mut output_compressed_accounts,
-        inputs.mint,
+        mint,
         pubkeys.as_slice(),
-        Some(inputs.delegate),
+        Some((*inputs.delegate).into()),
         is_delegate,
         amounts.as_slice(),
         lamports,
+        Some(tlvs.as_slice()),
         &hashed_mint,
         &merkle_tree_indices,
         remaining_accounts,
@@ -168,39 +436,96 @@ pub fn create_input_and_output_accounts_approve(
     Ok((compressed_input_accounts, output_compressed_accounts))
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+/// The zero copy view ZCompressedTokenInstructionDataRevoke deserializes a
+/// default proof as None.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedTokenInstructionDataRevoke {
+    #[zero_copy(ty = DefaultProofAsNone)]
     pub proof: CompressedProof,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
+    pub mint: Pubkey,
+    pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
+    pub cpi_context: Option<CompressedCpiContext>,
+    pub output_account_merkle_tree_index: u8,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+}
+
+/// Revoke instruction data without a proof if all inputs are proven by
+/// index. Same layout as CompressedTokenInstructionDataRevoke except for the
+/// proof, its zero copy view is converted into
+/// ZCompressedTokenInstructionDataRevoke.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct CompressedTokenInstructionDataRevokeWithOptionalProof {
+    #[zero_copy(ty = OptionalDefaultProofAsNone)]
+    pub proof: Option<CompressedProof>,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub mint: Pubkey,
     pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
     pub cpi_context: Option<CompressedCpiContext>,
     pub output_account_merkle_tree_index: u8,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+}
+
+impl<'a> ZCompressedTokenInstructionDataRevoke<'a> {
+    /// Deserializes CompressedTokenInstructionDataRevokeWithOptionalProof.
+    pub fn zero_copy_at_with_optional_proof(
+        bytes: &'a [u8],
+    ) -> std::result::Result<(Self, &'a [u8]), ZeroCopyError> {
+        let (inputs, bytes) =
+            ZCompressedTokenInstructionDataRevokeWithOptionalProof::zero_copy_at(bytes)?;
+        Ok((inputs.into(), bytes))
+    }
 }
 
-pub fn process_revoke<'a, 'b, 'c, 'info: 'b + 'c>(
+impl<'a> From<ZCompressedTokenInstructionDataRevokeWithOptionalProof<'a>>
+    for ZCompressedTokenInstructionDataRevoke<'a>
+{
+    fn from(inputs: ZCompressedTokenInstructionDataRevokeWithOptionalProof<'a>) -> Self {
+        Self {
+            proof: inputs.proof,
+            mint: inputs.mint,
+            input_token_data_with_context: inputs.input_token_data_with_context,
+            cpi_context: inputs.cpi_context,
+            output_account_merkle_tree_index: inputs.output_account_merkle_tree_index,
+            input_tlvs: inputs.input_tlvs,
+        }
+    }
+}
+
+/// With WITH_OPTIONAL_PROOF the proof can be omitted if all inputs are proven by
+/// index.
+pub fn process_revoke<'a, 'b, 'c, 'info: 'b + 'c, const WITH_OPTIONAL_PROOF: bool>(
     ctx: Context<'a, 'b, 'c, 'info, GenericInstruction<'info>>,
     inputs: Vec<u8>,
 ) -> Result<()> {
-    let inputs: CompressedTokenInstructionDataRevoke =
-        CompressedTokenInstructionDataRevoke::deserialize(&mut inputs.as_slice())?;
+    let (inputs, _) = if WITH_OPTIONAL_PROOF {
+        ZCompressedTokenInstructionDataRevoke::zero_copy_at_with_optional_proof(&inputs)
+    } else {
+        ZCompressedTokenInstructionDataRevoke::zero_copy_at(&inputs)
+    }
+    .map_err(ProgramError::from)?;
     let (compressed_input_accounts, output_compressed_accounts) =
         create_input_and_output_accounts_revoke(
             &inputs,
             &ctx.accounts.authority.key(),
             ctx.remaining_accounts,
         )?;
-    let proof = if inputs.proof == CompressedProof::default() {
-        None
-    } else {
-        Some(inputs.proof)
-    };
     cpi_execute_compressed_transaction_transfer(
         ctx.accounts,
         compressed_input_accounts,
         output_compressed_accounts,
         false,
-        proof,
-        inputs.cpi_context,
+        inputs.proof.as_ref().map(|proof| **proof),
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts.light_system_program.to_account_info(),
         ctx.accounts.self_program.to_account_info(),
@@ -210,7 +535,7 @@ pub fn process_revoke<'a, 'b, 'c, 'info: 'b + 'c>(
 }
 
 pub fn create_input_and_output_accounts_revoke(
-    inputs: &CompressedTokenInstructionDataRevoke,
+    inputs: &ZCompressedTokenInstructionDataRevoke,
     authority: &Pubkey,
     remaining_accounts: &[AccountInfo<'_>],
 ) -> Result<(
@@ -220,13 +545,16 @@ pub fn create_input_and_output_accounts_revoke(
     if inputs.input_token_data_with_context.is_empty() {
         return err!(ErrorCode::NoInputTokenAccountsProvided);
     }
+    let mint: Pubkey = (*inputs.mint).into();
     let (mut compressed_input_accounts, input_token_data, sum_lamports) =
         get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
             authority,
-            &None,
+            None,
+            false,
             remaining_accounts,
             &inputs.input_token_data_with_context,
-            &inputs.mint,
+            &inputs.input_tlvs,
+            &mint,
         )?;
     let sum_inputs = input_token_data.iter().map(|x| x.amount).sum::<u64>();
     let lamports = if sum_lamports != 0 {
@@ -236,16 +564,18 @@ pub fn create_input_and_output_accounts_revoke(
     };
     let mut output_compressed_accounts =
         vec![OutputCompressedAccountWithPackedContext::default(); 1];
-    let hashed_mint = hash_to_bn254_field_size_be(&inputs.mint.to_bytes());
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
+    let tlv = get_shared_input_tlv(input_token_data.as_slice())?;
 
-    create_output_compressed_accounts(
+    create_output_compressed_accounts::<NOT_FROZEN>(
         &mut output_compressed_accounts,
-        inputs.mint,
+        mint,
         &[*authority; 1],
         None,
         None,
         &[sum_inputs],
         lamports,
+        Some(&[tlv]),
         &hashed_mint,
         &[inputs.output_account_merkle_tree_index],
         remaining_accounts,
@@ -273,7 +603,8 @@ pub mod sdk {
         process_transfer::{
             get_cpi_authority_pda,
             transfer_sdk::{
-                create_input_output_and_remaining_accounts, to_account_metas, TransferSdkError,
+                create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+                TransferSdkError,
             },
         },
         token_data::TokenData,
@@ -283,7 +614,8 @@ pub mod sdk {
         pub fee_payer: Pubkey,
         pub authority: Pubkey,
         pub root_indices: Vec<Option<u16>>,
-        pub proof: CompressedProof,
+        /// Without a proof all inputs must be proven by index.
+        pub proof: Option<CompressedProof>,
         pub input_token_data: Vec<TokenData>,
         pub input_compressed_accounts: Vec<CompressedAccount>,
         pub input_merkle_contexts: Vec<MerkleContext>,
@@ -293,8 +625,12 @@ pub mod sdk {
         pub delegated_compressed_account_merkle_tree: Pubkey,
         pub change_compressed_account_merkle_tree: Pubkey,
         pub delegate: Pubkey,
+        /// See CompressedTokenInstructionDataApprove::with_allowance.
+        pub with_allowance: bool,
     }
 
+    /// Creates an approve instruction, or an approve with optional proof
+    /// instruction if inputs.proof is None.
     pub fn create_approve_instruction(
         inputs: CreateApproveInstructionInputs,
     ) -> Result<Instruction, TransferSdkError> {
@@ -320,7 +656,7 @@ pub mod sdk {
                 Some(change_account_merkle_tree_index) => change_account_merkle_tree_index,
                 None => return Err(TransferSdkError::AccountNotFound),
             };
-        let inputs_struct = CompressedTokenInstructionDataApprove {
+        let inputs_struct = CompressedTokenInstructionDataApproveWithOptionalProof {
             proof: inputs.proof,
             mint: inputs.mint,
             input_token_data_with_context,
@@ -330,16 +666,46 @@ pub mod sdk {
             delegate_merkle_tree_index: *delegated_merkle_tree_index as u8,
             change_account_merkle_tree_index: *change_account_merkle_tree_index as u8,
             delegate_lamports: inputs.delegate_lamports,
+            with_allowance: inputs.with_allowance,
+            input_tlvs: get_input_tlvs(&inputs.input_token_data),
         };
         let remaining_accounts = to_account_metas(remaining_accounts);
         let mut serialized_ix_data = Vec::new();
-        CompressedTokenInstructionDataApprove::serialize(&inputs_struct, &mut serialized_ix_data)
-            .map_err(|_| TransferSdkError::SerializationError)?;
+        let data = match inputs_struct.proof {
+            Some(proof) => {
+                CompressedTokenInstructionDataApprove {
+                    proof,
+                    mint: inputs_struct.mint,
+                    input_token_data_with_context: inputs_struct.input_token_data_with_context,
+                    cpi_context: inputs_struct.cpi_context,
+                    delegate: inputs_struct.delegate,
+                    delegated_amount: inputs_struct.delegated_amount,
+                    delegate_merkle_tree_index: inputs_struct.delegate_merkle_tree_index,
+                    change_account_merkle_tree_index: inputs_struct
+                        .change_account_merkle_tree_index,
+                    delegate_lamports: inputs_struct.delegate_lamports,
+                    with_allowance: inputs_struct.with_allowance,
+                    input_tlvs: inputs_struct.input_tlvs,
+                }
+                .serialize(&mut serialized_ix_data)
+                .map_err(|_| TransferSdkError::SerializationError)?;
+                crate::instruction::Approve {
+                    inputs: serialized_ix_data,
+                }
+                .data()
+            }
+            None => {
+                inputs_struct
+                    .serialize(&mut serialized_ix_data)
+                    .map_err(|_| TransferSdkError::SerializationError)?;
+                crate::instruction::ApproveWithOptionalProof {
+                    inputs: serialized_ix_data,
+                }
+                .data()
+            }
+        };
 
         let (cpi_authority_pda, _) = get_cpi_authority_pda();
-        let instruction_data = crate::instruction::Approve {
-            inputs: serialized_ix_data,
-        };
 
         let accounts = crate::accounts::GenericInstruction {
             fee_payer: inputs.fee_payer,
@@ -364,7 +730,7 @@ pub mod sdk {
             program_id: crate::ID,
             accounts: [accounts.to_account_metas(Some(true)), remaining_accounts].concat(),
 
-            data: instruction_data.data(),
+            data,
         })
     }
 
@@ -372,7 +738,8 @@ pub mod sdk {
         pub fee_payer: Pubkey,
         pub authority: Pubkey,
         pub root_indices: Vec<Option<u16>>,
-        pub proof: CompressedProof,
+        /// Without a proof all inputs must be proven by index.
+        pub proof: Option<CompressedProof>,
         pub input_token_data: Vec<TokenData>,
         pub input_compressed_accounts: Vec<CompressedAccount>,
         pub input_merkle_contexts: Vec<MerkleContext>,
@@ -380,6 +747,8 @@ pub mod sdk {
         pub output_account_merkle_tree: Pubkey,
     }
 
+    /// Creates a revoke instruction, or a revoke with optional proof
+    /// instruction if inputs.proof is None.
     pub fn create_revoke_instruction(
         inputs: CreateRevokeInstructionInputs,
     ) -> Result<Instruction, TransferSdkError> {
@@ -398,22 +767,46 @@ pub mod sdk {
                 None => return Err(TransferSdkError::AccountNotFound),
             };
 
-        let inputs_struct = CompressedTokenInstructionDataRevoke {
+        let inputs_struct = CompressedTokenInstructionDataRevokeWithOptionalProof {
             proof: inputs.proof,
             mint: inputs.mint,
             input_token_data_with_context,
             cpi_context: None,
             output_account_merkle_tree_index: *output_account_merkle_tree_index as u8,
+            input_tlvs: get_input_tlvs(&inputs.input_token_data),
         };
         let remaining_accounts = to_account_metas(remaining_accounts);
         let mut serialized_ix_data = Vec::new();
-        CompressedTokenInstructionDataRevoke::serialize(&inputs_struct, &mut serialized_ix_data)
-            .map_err(|_| TransferSdkError::SerializationError)?;
+        let data = match inputs_struct.proof {
+            Some(proof) => {
+                CompressedTokenInstructionDataRevoke {
+                    proof,
+                    mint: inputs_struct.mint,
+                    input_token_data_with_context: inputs_struct.input_token_data_with_context,
+                    cpi_context: inputs_struct.cpi_context,
+                    output_account_merkle_tree_index: inputs_struct
+                        .output_account_merkle_tree_index,
+                    input_tlvs: inputs_struct.input_tlvs,
+                }
+                .serialize(&mut serialized_ix_data)
+                .map_err(|_| TransferSdkError::SerializationError)?;
+                crate::instruction::Revoke {
+                    inputs: serialized_ix_data,
+                }
+                .data()
+            }
+            None => {
+                inputs_struct
+                    .serialize(&mut serialized_ix_data)
+                    .map_err(|_| TransferSdkError::SerializationError)?;
+                crate::instruction::RevokeWithOptionalProof {
+                    inputs: serialized_ix_data,
+                }
+                .data()
+            }
+        };
 
         let (cpi_authority_pda, _) = get_cpi_authority_pda();
-        let instruction_data = crate::instruction::Revoke {
-            inputs: serialized_ix_data,
-        };
 
         let accounts = crate::accounts::GenericInstruction {
             fee_payer: inputs.fee_payer,
@@ -438,7 +831,7 @@ pub mod sdk {
             program_id: crate::ID,
             accounts: [accounts.to_account_metas(Some(true)), remaining_accounts].concat(),
 
-            data: instruction_data.data(),
+            data,
         })
     }
 }
@@ -451,8 +844,8 @@ mod test {
 
     use super::*;
     use crate::{
-        freeze::test_freeze::create_expected_token_output_accounts, token_data::AccountState,
-        TokenData,
+        freeze::test_freeze::create_expected_token_output_accounts,
+        process_transfer::get_input_tlv, tlv::pack_tlv, token_data::AccountState, TokenData,
     };
 
     // TODO: add randomized and edge case tests
@@ -533,14 +926,18 @@ mod test {
         let inputs = CompressedTokenInstructionDataApprove {
             proof: CompressedProof::default(),
             mint,
-            input_token_data_with_context,
+            input_token_data_with_context: input_token_data_with_context.clone(),
             cpi_context: None,
             delegate,
             delegated_amount: 50,
             delegate_merkle_tree_index: 0,
             change_account_merkle_tree_index: 2,
             delegate_lamports: None,
+            with_allowance: false,
+            input_tlvs: Vec::new(),
         };
+        let inputs = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataApprove::zero_copy_at(&inputs).unwrap();
         let (compressed_input_accounts, output_compressed_accounts) =
             create_input_and_output_accounts_approve(&inputs, &authority, &remaining_accounts)
                 .unwrap();
@@ -571,6 +968,46 @@ mod test {
             output_compressed_accounts,
             expected_compressed_output_accounts
         );
+
+        // With allowance all inputs are merged into one delegated output.
+        let inputs = CompressedTokenInstructionDataApprove {
+            proof: CompressedProof::default(),
+            mint,
+            input_token_data_with_context,
+            cpi_context: None,
+            delegate,
+            delegated_amount: 50,
+            delegate_merkle_tree_index: 0,
+            change_account_merkle_tree_index: 2,
+            delegate_lamports: None,
+            with_allowance: true,
+            input_tlvs: Vec::new(),
+        };
+        let inputs = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataApprove::zero_copy_at(&inputs).unwrap();
+        assert!(inputs.with_allowance);
+        let (compressed_input_accounts, output_compressed_accounts) =
+            create_input_and_output_accounts_approve(&inputs, &authority, &remaining_accounts)
+                .unwrap();
+        assert_eq!(compressed_input_accounts.len(), 2);
+        let expected_delegated_token_data = TokenData {
+            mint,
+            owner: authority,
+            amount: 201,
+            delegate: Some(delegate),
+            state: AccountState::Initialized,
+            tlv: Some(pack_tlv(&[TokenExtension::DelegatedAmount(50)]).unwrap()),
+        };
+        assert_eq!(
+            get_delegated_amount(&expected_delegated_token_data).unwrap(),
+            50
+        );
+        let expected_compressed_output_accounts =
+            create_expected_token_output_accounts(vec![expected_delegated_token_data], vec![0]);
+        assert_eq!(
+            output_compressed_accounts,
+            expected_compressed_output_accounts
+        );
     }
 
     #[test]
@@ -655,7 +1092,10 @@ mod test {
             input_token_data_with_context,
             cpi_context: None,
             output_account_merkle_tree_index: 2,
+            input_tlvs: Vec::new(),
         };
+        let inputs = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataRevoke::zero_copy_at(&inputs).unwrap();
         let (compressed_input_accounts, output_compressed_accounts) =
             create_input_and_output_accounts_revoke(&inputs, &authority, &remaining_accounts)
                 .unwrap();
@@ -714,7 +1154,10 @@ mod test {
             input_token_data_with_context,
             cpi_context: None,
             output_account_merkle_tree_index: 2,
+            input_tlvs: Vec::new(),
         };
+        let inputs = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataRevoke::zero_copy_at(&inputs).unwrap();
         let (compressed_input_accounts, output_compressed_accounts) =
             create_input_and_output_accounts_revoke(&inputs, &authority, &remaining_accounts)
                 .unwrap();
@@ -738,4 +1181,312 @@ mod test {
             expected_compressed_output_accounts
         );
     }
+
+    /// Both instruction data layouts deserialize into the same zero copy
+    /// struct, a default proof is deserialized as None.
+    #[test]
+    fn test_optional_proof_zero_copy() {
+        let mint = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let input_token_data_with_context = vec![InputTokenDataWithContext {
+            amount: 100,
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: 0,
+                queue_pubkey_index: 1,
+                leaf_index: 1,
+                prove_by_index: true,
+            },
+            root_index: 0,
+            delegate_index: None,
+            lamports: None,
+            tlv: None,
+        }];
+        let proof = CompressedProof {
+            a: [1; 32],
+            b: [2; 64],
+            c: [3; 32],
+        };
+        for optional_proof in [None, Some(CompressedProof::default()), Some(proof)] {
+            let expected_proof =
+                optional_proof.filter(|proof| *proof != CompressedProof::default());
+
+            let approve = CompressedTokenInstructionDataApproveWithOptionalProof {
+                proof: optional_proof,
+                mint,
+                input_token_data_with_context: input_token_data_with_context.clone(),
+                cpi_context: None,
+                delegate,
+                delegated_amount: 50,
+                delegate_merkle_tree_index: 0,
+                change_account_merkle_tree_index: 2,
+                delegate_lamports: Some(10),
+                with_allowance: false,
+                input_tlvs: Vec::new(),
+            };
+            let bytes = approve.try_to_vec().unwrap();
+            let (z_approve, remaining_bytes) =
+                ZCompressedTokenInstructionDataApprove::zero_copy_at_with_optional_proof(&bytes)
+                    .unwrap();
+            assert!(remaining_bytes.is_empty());
+            assert_eq!(
+                z_approve.proof.as_ref().map(|proof| **proof),
+                expected_proof
+            );
+            assert_eq!(Pubkey::from(*z_approve.delegate), delegate);
+            assert_eq!(z_approve.delegated_amount.get(), 50);
+            assert_eq!(z_approve.change_account_merkle_tree_index, 2);
+            assert_eq!(
+                z_approve.delegate_lamports.as_ref().map(|x| x.get()),
+                Some(10)
+            );
+
+            let revoke = CompressedTokenInstructionDataRevokeWithOptionalProof {
+                proof: optional_proof,
+                mint,
+                input_token_data_with_context: input_token_data_with_context.clone(),
+                cpi_context: None,
+                output_account_merkle_tree_index: 3,
+                input_tlvs: Vec::new(),
+            };
+            let bytes = revoke.try_to_vec().unwrap();
+            let (z_revoke, remaining_bytes) =
+                ZCompressedTokenInstructionDataRevoke::zero_copy_at_with_optional_proof(&bytes)
+                    .unwrap();
+            assert!(remaining_bytes.is_empty());
+            assert_eq!(z_revoke.proof.as_ref().map(|proof| **proof), expected_proof);
+            assert_eq!(z_revoke.output_account_merkle_tree_index, 3);
+
+            // The layouts with proof are deserialized the same way.
+            if let Some(proof) = optional_proof {
+                let approve = CompressedTokenInstructionDataApprove {
+                    proof,
+                    mint,
+                    input_token_data_with_context: input_token_data_with_context.clone(),
+                    cpi_context: None,
+                    delegate,
+                    delegated_amount: 50,
+                    delegate_merkle_tree_index: 0,
+                    change_account_merkle_tree_index: 2,
+                    delegate_lamports: Some(10),
+                    with_allowance: false,
+                    input_tlvs: Vec::new(),
+                };
+                let bytes = approve.try_to_vec().unwrap();
+                let (z_approve, remaining_bytes) =
+                    ZCompressedTokenInstructionDataApprove::zero_copy_at(&bytes).unwrap();
+                assert!(remaining_bytes.is_empty());
+                assert_eq!(
+                    z_approve.proof.as_ref().map(|proof| **proof),
+                    expected_proof
+                );
+
+                let revoke = CompressedTokenInstructionDataRevoke {
+                    proof,
+                    mint,
+                    input_token_data_with_context: input_token_data_with_context.clone(),
+                    cpi_context: None,
+                    output_account_merkle_tree_index: 3,
+                    input_tlvs: Vec::new(),
+                };
+                let bytes = revoke.try_to_vec().unwrap();
+                let (z_revoke, remaining_bytes) =
+                    ZCompressedTokenInstructionDataRevoke::zero_copy_at(&bytes).unwrap();
+                assert!(remaining_bytes.is_empty());
+                assert_eq!(z_revoke.proof.as_ref().map(|proof| **proof), expected_proof);
+            }
+        }
+    }
+
+    /// Approve instruction data of clients which predate with_allowance
+    /// ends with input_tlvs and is deserialized without allowance.
+    #[test]
+    fn test_approve_without_allowance_layout() {
+        let input_token_data_with_context = vec![InputTokenDataWithContext {
+            amount: 100,
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: 0,
+                queue_pubkey_index: 1,
+                leaf_index: 1,
+                prove_by_index: true,
+            },
+            root_index: 0,
+            delegate_index: None,
+            lamports: None,
+            tlv: None,
+        }];
+        let input_tlv = pack_tlv(&[TokenExtension::DelegatedAmount(30)]).unwrap();
+        let approve = CompressedTokenInstructionDataApproveWithOptionalProof {
+            proof: None,
+            mint: Pubkey::new_unique(),
+            input_token_data_with_context,
+            cpi_context: None,
+            delegate: Pubkey::new_unique(),
+            delegated_amount: 50,
+            delegate_merkle_tree_index: 0,
+            change_account_merkle_tree_index: 2,
+            delegate_lamports: None,
+            input_tlvs: vec![Some(input_tlv.clone())],
+            with_allowance: true,
+        };
+        let mut bytes = approve.try_to_vec().unwrap();
+        let (z_approve, remaining_bytes) =
+            ZCompressedTokenInstructionDataApprove::zero_copy_at_with_optional_proof(&bytes)
+                .unwrap();
+        assert!(remaining_bytes.is_empty());
+        assert!(z_approve.with_allowance);
+        assert_eq!(
+            get_input_tlv(&z_approve.input_tlvs, 0),
+            Some(input_tlv.as_slice())
+        );
+
+        // Old layout without the trailing with_allowance byte.
+        bytes.pop();
+        let (z_approve, remaining_bytes) =
+            ZCompressedTokenInstructionDataApprove::zero_copy_at_with_optional_proof(&bytes)
+                .unwrap();
+        assert!(remaining_bytes.is_empty());
+        assert!(!z_approve.with_allowance);
+        assert_eq!(
+            get_input_tlv(&z_approve.input_tlvs, 0),
+            Some(input_tlv.as_slice())
+        );
+
+        let approve = CompressedTokenInstructionDataApprove {
+            proof: CompressedProof::default(),
+            mint: approve.mint,
+            input_token_data_with_context: approve.input_token_data_with_context,
+            cpi_context: None,
+            delegate: approve.delegate,
+            delegated_amount: 50,
+            delegate_merkle_tree_index: 0,
+            change_account_merkle_tree_index: 2,
+            delegate_lamports: None,
+            input_tlvs: vec![Some(input_tlv.clone())],
+            with_allowance: true,
+        };
+        let mut bytes = approve.try_to_vec().unwrap();
+        bytes.pop();
+        let (z_approve, remaining_bytes) =
+            ZCompressedTokenInstructionDataApprove::zero_copy_at(&bytes).unwrap();
+        assert!(remaining_bytes.is_empty());
+        assert!(!z_approve.with_allowance);
+        assert_eq!(
+            get_input_tlv(&z_approve.input_tlvs, 0),
+            Some(input_tlv.as_slice())
+        );
+    }
+
+    #[test]
+    fn test_delegated_amount() {
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let token_data = |amount, delegated_amount: Option<u64>| TokenData {
+            mint: Pubkey::new_unique(),
+            owner,
+            amount,
+            delegate: Some(delegate),
+            state: AccountState::Initialized,
+            tlv: delegated_amount.map(|x| pack_tlv(&[TokenExtension::DelegatedAmount(x)]).unwrap()),
+        };
+        // Accounts without delegate have no delegated amount.
+        let mut not_delegated = token_data(100, Some(50));
+        not_delegated.delegate = None;
+        assert_eq!(get_delegated_amount(&not_delegated).unwrap(), 0);
+        // Accounts without extension are delegated completely.
+        assert_eq!(get_delegated_amount(&token_data(100, None)).unwrap(), 100);
+        assert_eq!(
+            get_delegated_amount(&token_data(100, Some(50))).unwrap(),
+            50
+        );
+        // The delegated amount is limited by the amount.
+        assert_eq!(
+            get_delegated_amount(&token_data(100, Some(150))).unwrap(),
+            100
+        );
+
+        // The extension is omitted if the delegated amount covers the amount.
+        let memo = Some(pack_tlv(&[TokenExtension::Memo(b"memo".to_vec())]).unwrap());
+        assert_eq!(set_delegated_amount(&memo, 100, 100).unwrap(), memo);
+        assert_eq!(
+            set_delegated_amount(&memo, 100, 40).unwrap(),
+            Some(
+                pack_tlv(&[
+                    TokenExtension::Memo(b"memo".to_vec()),
+                    TokenExtension::DelegatedAmount(40)
+                ])
+                .unwrap()
+            )
+        );
+        assert_eq!(
+            set_delegated_amount(&token_data(100, Some(50)).tlv, 100, 100).unwrap(),
+            None
+        );
+
+        let inputs = vec![token_data(100, Some(50)), token_data(30, None)];
+        assert_eq!(get_remaining_delegated_amount(&inputs, 80).unwrap(), 0);
+        assert_eq!(get_remaining_delegated_amount(&inputs, 20).unwrap(), 60);
+        assert_eq!(
+            get_remaining_delegated_amount(&inputs, 81),
+            Err(ErrorCode::DelegatedAmountExceeded.into())
+        );
+
+        // 1. Functional: delegate transfers 60 to a recipient, 70 remain in
+        //    the delegated change account with 20 delegated.
+        let recipient = Pubkey::new_unique();
+        let mut output_tlvs = vec![None, None];
+        check_delegated_transfer(
+            &inputs,
+            &owner,
+            Some(1),
+            &[recipient, owner],
+            &[60, 70],
+            &mut output_tlvs,
+        )
+        .unwrap();
+        assert_eq!(output_tlvs[0], None);
+        assert_eq!(
+            output_tlvs[1],
+            Some(pack_tlv(&[TokenExtension::DelegatedAmount(20)]).unwrap())
+        );
+        // 2. Functional: inputs of the delegate are not limited.
+        let mut delegate_input = token_data(1000, None);
+        delegate_input.owner = delegate;
+        delegate_input.delegate = None;
+        let mut output_tlvs = vec![None, None];
+        check_delegated_transfer(
+            &[inputs[0].clone(), delegate_input],
+            &owner,
+            Some(1),
+            &[recipient, owner],
+            &[1050, 50],
+            &mut output_tlvs,
+        )
+        .unwrap();
+        assert_eq!(
+            output_tlvs[1],
+            Some(pack_tlv(&[TokenExtension::DelegatedAmount(0)]).unwrap())
+        );
+        // 3. Failing: delegate transfers more than the delegated amount.
+        let mut output_tlvs = vec![None, None];
+        let result = check_delegated_transfer(
+            &inputs,
+            &owner,
+            Some(1),
+            &[recipient, owner],
+            &[81, 49],
+            &mut output_tlvs,
+        );
+        assert_eq!(result, Err(ErrorCode::DelegatedAmountExceeded.into()));
+        // 4. Failing: invalid delegate change account index.
+        let mut output_tlvs = vec![None, None];
+        let result = check_delegated_transfer(
+            &inputs,
+            &owner,
+            Some(2),
+            &[recipient, owner],
+            &[60, 70],
+            &mut output_tlvs,
+        );
+        assert_eq!(result, Err(ErrorCode::InvalidDelegateIndex.into()));
+    }
 }
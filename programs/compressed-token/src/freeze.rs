@@ -6,27 +6,37 @@ use light_compressed_account::{
     instruction_data::{
         compressed_proof::CompressedProof, cpi_context::CompressedCpiContext,
         data::OutputCompressedAccountWithPackedContext, with_readonly::InAccount,
+        zero_copy::ZCompressedCpiContext,
     },
 };
+use light_zero_copy::{borsh::Deserialize, ZeroCopy};
+use zerocopy::Ref;
 
 use crate::{
     constants::TOKEN_COMPRESSED_ACCOUNT_DISCRIMINATOR,
     process_transfer::{
         add_data_hash_to_input_compressed_accounts, cpi_execute_compressed_transaction_transfer,
         get_input_compressed_accounts_with_merkle_context_and_check_signer,
-        InputTokenDataWithContext, BATCHED_DISCRIMINATOR,
+        InputTokenDataWithContext, ZInputTokenDataWithContext, BATCHED_DISCRIMINATOR,
     },
     token_data::{AccountState, TokenData},
     FreezeInstruction,
 };
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+/// Freeze and thaw share the instruction data layout and the zero copy view
+/// ZCompressedTokenInstructionDataFreeze.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedTokenInstructionDataFreeze {
     pub proof: CompressedProof,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub owner: Pubkey,
     pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
     pub cpi_context: Option<CompressedCpiContext>,
     pub outputs_merkle_tree_index: u8,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
 }
 
 pub fn process_freeze_or_thaw<
@@ -40,8 +50,8 @@ pub fn process_freeze_or_thaw<
     ctx: Context<'a, 'b, 'c, 'info, FreezeInstruction<'info>>,
     inputs: Vec<u8>,
 ) -> Result<()> {
-    let inputs: CompressedTokenInstructionDataFreeze =
-        CompressedTokenInstructionDataFreeze::deserialize(&mut inputs.as_slice())?;
+    let (inputs, _) =
+        ZCompressedTokenInstructionDataFreeze::zero_copy_at(&inputs).map_err(ProgramError::from)?;
     let (compressed_input_accounts, output_compressed_accounts) =
         create_input_and_output_accounts_freeze_or_thaw::<FROZEN_INPUTS, FROZEN_OUTPUTS>(
             &inputs,
@@ -49,10 +59,10 @@ pub fn process_freeze_or_thaw<
             ctx.remaining_accounts,
         )?;
     // TODO: discuss
-    let proof = if inputs.proof == CompressedProof::default() {
+    let proof = if *inputs.proof == CompressedProof::default() {
         None
     } else {
-        Some(inputs.proof)
+        Some(*inputs.proof)
     };
     cpi_execute_compressed_transaction_transfer(
         ctx.accounts,
@@ -60,7 +70,10 @@ pub fn process_freeze_or_thaw<
         output_compressed_accounts,
         false,
         proof,
-        inputs.cpi_context,
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts.light_system_program.to_account_info(),
         ctx.accounts.self_program.to_account_info(),
@@ -72,7 +85,7 @@ pub fn create_input_and_output_accounts_freeze_or_thaw<
     const FROZEN_INPUTS: bool,
     const FROZEN_OUTPUTS: bool,
 >(
-    inputs: &CompressedTokenInstructionDataFreeze,
+    inputs: &ZCompressedTokenInstructionDataFreeze,
     mint: &Pubkey,
     remaining_accounts: &[AccountInfo<'_>],
 ) -> Result<(
@@ -82,16 +95,19 @@ pub fn create_input_and_output_accounts_freeze_or_thaw<
     if inputs.input_token_data_with_context.is_empty() {
         return err!(crate::ErrorCode::NoInputTokenAccountsProvided);
     }
+    let owner: Pubkey = (*inputs.owner).into();
     let (mut compressed_input_accounts, input_token_data, _) =
         get_input_compressed_accounts_with_merkle_context_and_check_signer::<FROZEN_INPUTS>(
             // The signer in this case is the freeze authority. The owner is not
             // required to sign for this instruction. Hence, we pass the owner
             // from a variable instead of an account to still reproduce value
             // token data hashes for the input accounts.
-            &inputs.owner,
-            &None,
+            &owner,
+            None,
+            false,
             remaining_accounts,
             &inputs.input_token_data_with_context,
+            &inputs.input_tlvs,
             mint,
         )?;
     let output_len = compressed_input_accounts.len();
@@ -100,13 +116,14 @@ pub fn create_input_and_output_accounts_freeze_or_thaw<
     let hashed_mint = hash_to_bn254_field_size_be(mint.to_bytes().as_slice());
     create_token_output_accounts::<FROZEN_OUTPUTS>(
         inputs.input_token_data_with_context.as_slice(),
+        input_token_data.as_slice(),
         remaining_accounts,
         mint,
         // The signer in this case is the freeze authority. The owner is not
         // required to sign for this instruction. Hence, we pass the owner
         // from a variable instead of an account to still reproduce value
         // token data hashes for the input accounts.
-        &inputs.owner,
+        &owner,
         &inputs.outputs_merkle_tree_index,
         &mut output_compressed_accounts,
     )?;
@@ -124,7 +141,8 @@ pub fn create_input_and_output_accounts_freeze_or_thaw<
 /// for a flexible number of delegates. create_output_compressed_accounts only
 /// supports one delegate.
 fn create_token_output_accounts<const IS_FROZEN: bool>(
-    input_token_data_with_context: &[InputTokenDataWithContext],
+    input_token_data_with_context: &[ZInputTokenDataWithContext],
+    input_token_data: &[TokenData],
     remaining_accounts: &[AccountInfo],
     mint: &Pubkey,
     owner: &Pubkey,
@@ -138,12 +156,17 @@ fn create_token_output_accounts<const IS_FROZEN: bool>(
         // +    8       amount
         // +    1 + 32  option + delegate (optional)
         // +    1       state
-        // +    1       tlv
+        // +    1       tlv (None)
+        // +    4 + n   vec len + tlv (optional)
         let capacity = if token_data_with_context.delegate_index.is_some() {
             107
         } else {
             75
-        };
+        } + input_token_data[i]
+            .tlv
+            .as_ref()
+            .map(|tlv| 4 + tlv.len())
+            .unwrap_or_default();
         let mut token_data_bytes = Vec::with_capacity(capacity);
         let delegate = token_data_with_context
             .delegate_index
@@ -157,10 +180,12 @@ fn create_token_output_accounts<const IS_FROZEN: bool>(
         let token_data = TokenData {
             mint: *mint,
             owner: *owner,
-            amount: token_data_with_context.amount,
+            amount: token_data_with_context.amount.get(),
             delegate,
             state,
-            tlv: None,
+            // Extensions are preserved, inputs tlv is validated in
+            // get_input_compressed_accounts_with_merkle_context_and_check_signer.
+            tlv: input_token_data[i].tlv.clone(),
         };
         token_data.serialize(&mut token_data_bytes)?;
 
@@ -185,7 +210,11 @@ fn create_token_output_accounts<const IS_FROZEN: bool>(
         output_compressed_accounts[i] = OutputCompressedAccountWithPackedContext {
             compressed_account: CompressedAccount {
                 owner: crate::ID,
-                lamports: token_data_with_context.lamports.unwrap_or(0),
+                lamports: token_data_with_context
+                    .lamports
+                    .as_ref()
+                    .map(|lamports| lamports.get())
+                    .unwrap_or(0),
                 data: Some(data),
                 address: None,
             },
@@ -202,6 +231,7 @@ pub struct CompressedTokenInstructionDataThaw {
     pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
     pub cpi_context: Option<CompressedCpiContext>,
     pub outputs_merkle_tree_index: u8,
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
 }
 
 #[cfg(not(target_os = "solana"))]
@@ -217,7 +247,8 @@ pub mod sdk {
     use super::CompressedTokenInstructionDataFreeze;
     use crate::{
         process_transfer::transfer_sdk::{
-            create_input_output_and_remaining_accounts, to_account_metas, TransferSdkError,
+            create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+            TransferSdkError,
         },
         token_data::TokenData,
     };
@@ -254,6 +285,7 @@ pub mod sdk {
             cpi_context: None,
             outputs_merkle_tree_index: *outputs_merkle_tree_index as u8,
             owner: inputs.input_token_data[0].owner,
+            input_tlvs: get_input_tlvs(&inputs.input_token_data),
         };
         let remaining_accounts = to_account_metas(remaining_accounts);
         let mut serialized_ix_data = Vec::new();
@@ -424,7 +456,10 @@ pub mod test_freeze {
                 input_token_data_with_context: input_token_data_with_context.clone(),
                 cpi_context: None,
                 outputs_merkle_tree_index: 3,
+                input_tlvs: Vec::new(),
             };
+            let inputs = inputs.try_to_vec().unwrap();
+            let (inputs, _) = ZCompressedTokenInstructionDataFreeze::zero_copy_at(&inputs).unwrap();
             let (compressed_input_accounts, output_compressed_accounts) =
                 create_input_and_output_accounts_freeze_or_thaw::<false, true>(
                     &inputs,
@@ -468,7 +503,10 @@ pub mod test_freeze {
                 input_token_data_with_context,
                 cpi_context: None,
                 outputs_merkle_tree_index: 3,
+                input_tlvs: Vec::new(),
             };
+            let inputs = inputs.try_to_vec().unwrap();
+            let (inputs, _) = ZCompressedTokenInstructionDataFreeze::zero_copy_at(&inputs).unwrap();
             let (compressed_input_accounts, output_compressed_accounts) =
                 create_input_and_output_accounts_freeze_or_thaw::<true, false>(
                     &inputs,
@@ -0,0 +1,152 @@
+use account_compression::{program::AccountCompression, utils::constants::CPI_AUTHORITY_PDA_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use light_system_program::{
+    account_traits::{InvokeAccounts, SignerAccounts},
+    program::LightSystemProgram,
+};
+
+use crate::program::LightCompressedToken;
+
+#[derive(Accounts)]
+pub struct CreateCompressedMintInstruction<'info> {
+    /// UNCHECKED: only pays fees.
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    /// CHECK: becomes the mint authority of the compressed mint.
+    pub authority: Signer<'info>,
+    /// CHECK:
+    /// Seed of the mint pda, see crate::compressed_mint::find_compressed_mint_pda.
+    /// Signs so that mint pdas cannot be claimed by others.
+    pub mint_signer: Signer<'info>,
+    /// CHECK: (seed constraint).
+    #[account(seeds = [CPI_AUTHORITY_PDA_SEED], bump,)]
+    pub cpi_authority_pda: UncheckedAccount<'info>,
+    pub light_system_program: Program<'info, LightSystemProgram>,
+    /// CHECK: (account compression program).
+    pub registered_program_pda: AccountInfo<'info>,
+    /// CHECK: (account compression program) when emitting event.
+    pub noop_program: UncheckedAccount<'info>,
+    /// CHECK: (different program) is used to cpi account compression program from light system program.
+    pub account_compression_authority: UncheckedAccount<'info>,
+    pub account_compression_program: Program<'info, AccountCompression>,
+    /// CHECK:(system program) used to derive cpi_authority_pda and check that
+    /// this program is the signer of the cpi.
+    pub self_program: Program<'info, LightCompressedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InvokeAccounts<'info> for CreateCompressedMintInstruction<'info> {
+    fn get_registered_program_pda(&self) -> &AccountInfo<'info> {
+        &self.registered_program_pda
+    }
+
+    fn get_noop_program(&self) -> &UncheckedAccount<'info> {
+        &self.noop_program
+    }
+
+    fn get_account_compression_authority(&self) -> &UncheckedAccount<'info> {
+        &self.account_compression_authority
+    }
+
+    fn get_account_compression_program(&self) -> &Program<'info, AccountCompression> {
+        &self.account_compression_program
+    }
+
+    fn get_system_program(&self) -> &Program<'info, System> {
+        &self.system_program
+    }
+
+    fn get_sol_pool_pda(&self) -> Option<&AccountInfo<'info>> {
+        None
+    }
+
+    fn get_decompression_recipient(&self) -> Option<&AccountInfo<'info>> {
+        None
+    }
+}
+
+impl<'info> SignerAccounts<'info> for CreateCompressedMintInstruction<'info> {
+    fn get_fee_payer(&self) -> &Signer<'info> {
+        &self.fee_payer
+    }
+
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+}
+
+#[derive(Accounts)]
+pub struct DecompressMintInstruction<'info> {
+    /// UNCHECKED: pays fees and rent of the mint and token pool accounts.
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    /// CHECK: is checked to be the mint authority of the compressed mint.
+    pub authority: Signer<'info>,
+    /// CHECK: is checked to derive the mint pda in ix.
+    pub mint_signer: UncheckedAccount<'info>,
+    /// CHECK: is checked to be the mint pda of the compressed mint in ix,
+    /// is created in ix.
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: is checked to be the token pool pda of the mint in ix, is
+    /// created in ix.
+    #[account(mut)]
+    pub token_pool_pda: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: (seed constraint).
+    #[account(seeds = [CPI_AUTHORITY_PDA_SEED], bump,)]
+    pub cpi_authority_pda: UncheckedAccount<'info>,
+    pub light_system_program: Program<'info, LightSystemProgram>,
+    /// CHECK: (account compression program).
+    pub registered_program_pda: AccountInfo<'info>,
+    /// CHECK: (account compression program) when emitting event.
+    pub noop_program: UncheckedAccount<'info>,
+    /// CHECK: (different program) is used to cpi account compression program from light system program.
+    pub account_compression_authority: UncheckedAccount<'info>,
+    pub account_compression_program: Program<'info, AccountCompression>,
+    /// CHECK:(system program) used to derive cpi_authority_pda and check that
+    /// this program is the signer of the cpi.
+    pub self_program: Program<'info, LightCompressedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InvokeAccounts<'info> for DecompressMintInstruction<'info> {
+    fn get_registered_program_pda(&self) -> &AccountInfo<'info> {
+        &self.registered_program_pda
+    }
+
+    fn get_noop_program(&self) -> &UncheckedAccount<'info> {
+        &self.noop_program
+    }
+
+    fn get_account_compression_authority(&self) -> &UncheckedAccount<'info> {
+        &self.account_compression_authority
+    }
+
+    fn get_account_compression_program(&self) -> &Program<'info, AccountCompression> {
+        &self.account_compression_program
+    }
+
+    fn get_system_program(&self) -> &Program<'info, System> {
+        &self.system_program
+    }
+
+    fn get_sol_pool_pda(&self) -> Option<&AccountInfo<'info>> {
+        None
+    }
+
+    fn get_decompression_recipient(&self) -> Option<&AccountInfo<'info>> {
+        None
+    }
+}
+
+impl<'info> SignerAccounts<'info> for DecompressMintInstruction<'info> {
+    fn get_fee_payer(&self) -> &Signer<'info> {
+        &self.fee_payer
+    }
+
+    fn get_authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+}
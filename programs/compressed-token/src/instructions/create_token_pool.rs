@@ -56,7 +56,7 @@ pub fn get_token_pool_pda_with_index(mint: &Pubkey, token_pool_index: u8) -> Pub
     find_token_pool_pda_with_index(mint, token_pool_index).0
 }
 
-const ALLOWED_EXTENSION_TYPES: [ExtensionType; 7] = [
+const ALLOWED_EXTENSION_TYPES: [ExtensionType; 11] = [
     ExtensionType::MetadataPointer,
     ExtensionType::TokenMetadata,
     ExtensionType::InterestBearingConfig,
@@ -64,6 +64,15 @@ const ALLOWED_EXTENSION_TYPES: [ExtensionType; 7] = [
     ExtensionType::GroupMemberPointer,
     ExtensionType::TokenGroup,
     ExtensionType::TokenGroupMember,
+    // Transfer fees are applied to compressed transfers, see crate::transfer_fee.
+    ExtensionType::TransferFeeConfig,
+    // Permanent delegate, pausable and default account state are enforced for
+    // compressed token accounts, see crate::mint_extensions. Token pool
+    // accounts of mints with default account state frozen have to be thawed
+    // by the freeze authority.
+    ExtensionType::PermanentDelegate,
+    ExtensionType::Pausable,
+    ExtensionType::DefaultAccountState,
 ];
 
 pub fn assert_mint_extensions(account_data: &[u8]) -> Result<()> {
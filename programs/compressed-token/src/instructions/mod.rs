@@ -1,11 +1,15 @@
 pub mod burn;
+pub mod compressed_mint;
 pub mod create_token_pool;
 pub mod freeze;
 pub mod generic;
+pub mod swap;
 pub mod transfer;
 
 pub use burn::*;
+pub use compressed_mint::*;
 pub use create_token_pool::*;
 pub use freeze::*;
 pub use generic::*;
+pub use swap::*;
 pub use transfer::*;
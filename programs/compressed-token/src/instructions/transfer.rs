@@ -1,6 +1,6 @@
 use account_compression::program::AccountCompression;
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use anchor_spl::token_interface::TokenInterface;
 use light_system_program::{
     self,
     account_traits::{InvokeAccounts, SignerAccounts},
@@ -32,10 +32,14 @@ pub struct TransferInstruction<'info> {
     /// CHECK:(system program) used to derive cpi_authority_pda and check that
     /// this program is the signer of the cpi.
     pub self_program: Program<'info, LightCompressedToken>,
+    /// CHECK: (derivation in ix) is derived from the mint, the token program
+    /// checks that it is a token account.
     #[account(mut)]
-    pub token_pool_pda: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_pool_pda: Option<AccountInfo<'info>>,
+    /// CHECK: (token program) is checked to be a token account in the spl
+    /// token cpi.
     #[account(mut, constraint= if token_pool_pda.is_some() {Ok(token_pool_pda.as_ref().unwrap().key() != compress_or_decompress_token_account.key())}else {err!(crate::ErrorCode::TokenPoolPdaUndefined)}? @crate::ErrorCode::IsTokenPoolPda)]
-    pub compress_or_decompress_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub compress_or_decompress_token_account: Option<AccountInfo<'info>>,
     pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
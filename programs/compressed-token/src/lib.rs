@@ -7,7 +7,11 @@ pub mod process_transfer;
 use process_compress_spl_token_account::process_compress_spl_token_account;
 pub mod spl_compression;
 pub use process_mint::*;
+pub mod mint_extensions;
+pub mod tlv;
 pub mod token_data;
+pub mod transfer_fee;
+pub mod vesting;
 pub use token_data::TokenData;
 pub mod delegation;
 pub mod freeze;
@@ -16,9 +20,10 @@ pub use instructions::*;
 pub mod burn;
 pub use burn::*;
 pub mod batch_compress;
+pub mod compressed_mint;
+pub mod swap;
 use light_compressed_account::instruction_data::cpi_context::CompressedCpiContext;
 
-use crate::process_transfer::CompressedTokenInstructionDataTransfer;
 declare_id!("cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m");
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -75,17 +80,19 @@ pub mod light_compressed_token {
     /// transferred to each output account to enable. A use case to add lamports
     /// to a compressed token account is to prevent spam. This is the only way
     /// to add lamports to a compressed token account.
+    /// The arguments public_keys, amounts and lamports are deserialized
+    /// zero copy, see MintToInstructionData.
     pub fn mint_to<'info>(
         ctx: Context<'_, '_, '_, 'info, MintToInstruction<'info>>,
-        public_keys: Vec<Pubkey>,
-        amounts: Vec<u64>,
-        lamports: Option<u64>,
+        inputs: MintToInstructionDataBytes,
     ) -> Result<()> {
+        let (inputs, _) =
+            ZMintToInstructionData::zero_copy_at(&inputs.0).map_err(ProgramError::from)?;
         process_mint_to_or_compress::<MINT_TO>(
             ctx,
-            public_keys.as_slice(),
-            amounts.as_slice(),
-            lamports,
+            inputs.public_keys.as_slice(),
+            inputs.amounts.as_slice(),
+            inputs.lamports.map(|x| (*x).into()),
             None,
             None,
         )
@@ -96,7 +103,7 @@ pub mod light_compressed_token {
         ctx: Context<'_, '_, '_, 'info, MintToInstruction<'info>>,
         inputs: Vec<u8>,
     ) -> Result<()> {
-        let (inputs, _) = batch_compress::BatchCompressInstructionData::zero_copy_at(&inputs)
+        let (inputs, _) = batch_compress::ZBatchCompressInstructionData::zero_copy_at(&inputs)
             .map_err(ProgramError::from)?;
         if inputs.amounts.is_some() && inputs.amount.is_some() {
             return Err(crate::ErrorCode::AmountsAndAmountProvided.into());
@@ -138,16 +145,18 @@ pub mod light_compressed_token {
     /// accounts specify less lamports than inputs the remaining lamports are
     /// transferred to an output compressed account. Signer must be owner or
     /// delegate. If a delegated token account is transferred the delegate is
-    /// not preserved.
+    /// not preserved. Token extensions (tlv) of outputs are defined in the
+    /// instruction data and validated, see tlv::validate_tlv. Time locked
+    /// inputs must be completely unlocked, see vesting::TimeLock.
     pub fn transfer<'info>(
         ctx: Context<'_, '_, '_, 'info, TransferInstruction<'info>>,
         inputs: Vec<u8>,
     ) -> Result<()> {
-        let mut inputs = inputs;
-        // Borsh ignores excess bytes -> push bool false for with_transaction_hash field.
-        inputs.extend_from_slice(&[0u8; 1]);
-        let inputs: CompressedTokenInstructionDataTransfer =
-            CompressedTokenInstructionDataTransfer::deserialize(&mut inputs.as_slice())?;
+        light_heap::bench_sbf_start!("t_deserialize");
+        let (inputs, _) =
+            process_transfer::ZCompressedTokenInstructionDataTransfer::zero_copy_at(&inputs)
+                .map_err(ProgramError::from)?;
+        light_heap::bench_sbf_end!("t_deserialize");
         process_transfer::process_transfer(ctx, inputs)
     }
 
@@ -161,7 +170,17 @@ pub mod light_compressed_token {
         ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
         inputs: Vec<u8>,
     ) -> Result<()> {
-        delegation::process_approve(ctx, inputs)
+        delegation::process_approve::<false>(ctx, inputs)
+    }
+
+    /// Same as approve, the proof is optional. Inputs which are proven by
+    /// index in an output queue require no proof, if all inputs are proven
+    /// by index the proof can be omitted.
+    pub fn approve_with_optional_proof<'info>(
+        ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
+        inputs: Vec<u8>,
+    ) -> Result<()> {
+        delegation::process_approve::<true>(ctx, inputs)
     }
 
     /// Revokes a delegation. The instruction merges all inputs into one output
@@ -170,7 +189,16 @@ pub mod light_compressed_token {
         ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
         inputs: Vec<u8>,
     ) -> Result<()> {
-        delegation::process_revoke(ctx, inputs)
+        delegation::process_revoke::<false>(ctx, inputs)
+    }
+
+    /// Same as revoke, the proof is optional. If all inputs are proven by
+    /// index the proof can be omitted.
+    pub fn revoke_with_optional_proof<'info>(
+        ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
+        inputs: Vec<u8>,
+    ) -> Result<()> {
+        delegation::process_revoke::<true>(ctx, inputs)
     }
 
     /// Freezes compressed token accounts. Inputs must not be frozen. Creates as
@@ -193,9 +221,22 @@ pub mod light_compressed_token {
         freeze::process_freeze_or_thaw::<true, NOT_FROZEN>(ctx, inputs)
     }
 
+    /// Releases the unlocked amount of time locked compressed token accounts,
+    /// see vesting::TimeLock. Inputs must have the same time lock. Creates an
+    /// output without time lock for the unlocked amount and, if tokens remain
+    /// locked, an output for the locked amount. Cannot be called by a
+    /// delegate. Delegates are not preserved.
+    pub fn split_vested<'info>(
+        ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
+        inputs: Vec<u8>,
+    ) -> Result<()> {
+        vesting::process_split_vested(ctx, inputs)
+    }
+
     /// Burns compressed tokens and spl tokens from the pool account. Delegates
     /// can burn tokens. The output compressed token account remains delegated.
-    /// Creates one output compressed token account.
+    /// Creates one output compressed token account. Time locked inputs must
+    /// be completely unlocked.
     pub fn burn<'info>(
         ctx: Context<'_, '_, '_, 'info, BurnInstruction<'info>>,
         inputs: Vec<u8>,
@@ -203,14 +244,71 @@ pub mod light_compressed_token {
         burn::process_burn(ctx, inputs)
     }
 
+    /// Swaps compressed tokens of two mints between the authority and the
+    /// counterparty atomically. Both must sign. Each party sends the amount
+    /// of its leg to the other party, remaining tokens and lamports of the
+    /// inputs are kept in a change account. Both legs are sum checked per
+    /// mint and settled with one proof in one compressed transaction. Cannot
+    /// be called by a delegate. Delegates are not preserved. Time locked
+    /// inputs must be completely unlocked.
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapInstruction<'info>>,
+        inputs: Vec<u8>,
+    ) -> Result<()> {
+        swap::process_swap(ctx, inputs)
+    }
+
+    /// Creates a compressed mint without an spl mint, see
+    /// compressed_mint::CompressedMint. The compressed mint account has an
+    /// address derived from the mint pda of the mint signer. The authority
+    /// becomes the mint authority.
+    pub fn create_compressed_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCompressedMintInstruction<'info>>,
+        inputs: compressed_mint::CompressedTokenInstructionDataCreateCompressedMint,
+    ) -> Result<()> {
+        compressed_mint::process_create_compressed_mint(ctx, inputs)
+    }
+
+    /// Mints compressed tokens of a compressed mint to a list of compressed
+    /// accounts. Increases the supply of the compressed mint, no spl tokens
+    /// are minted. Signer must be the mint authority.
+    pub fn mint_to_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
+        inputs: compressed_mint::CompressedTokenInstructionDataMintToCompressed,
+    ) -> Result<()> {
+        compressed_mint::process_mint_to_compressed(ctx, inputs)
+    }
+
+    /// Burns compressed tokens of a compressed mint. Reduces the supply of
+    /// the compressed mint, no spl tokens are burned. Delegates can burn
+    /// tokens like in burn.
+    pub fn burn_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, GenericInstruction<'info>>,
+        inputs: Vec<u8>,
+    ) -> Result<()> {
+        compressed_mint::process_burn_compressed(ctx, inputs)
+    }
+
+    /// Creates the spl mint of a compressed mint and a token pool holding
+    /// the compressed supply. The mint authority of the spl mint is the mint
+    /// authority of the compressed mint. Afterwards tokens are minted and
+    /// burned with mint_to and burn. Signer must be the mint authority.
+    pub fn decompress_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, DecompressMintInstruction<'info>>,
+        inputs: compressed_mint::CompressedTokenInstructionDataDecompressMint,
+    ) -> Result<()> {
+        compressed_mint::process_decompress_mint(ctx, inputs)
+    }
+
     /// This function is a stub to allow Anchor to include the input types in
     /// the IDL. It should not be included in production builds nor be called in
     /// practice.
     #[cfg(feature = "idl-build")]
     pub fn stub_idl_build<'info>(
         _ctx: Context<'_, '_, '_, 'info, TransferInstruction<'info>>,
-        _inputs1: CompressedTokenInstructionDataTransfer,
+        _inputs1: process_transfer::CompressedTokenInstructionDataTransfer,
         _inputs2: TokenData,
+        _inputs3: MintToInstructionData,
     ) -> Result<()> {
         Err(ErrorCode::InstructionNotCallable.into())
     }
@@ -276,4 +374,40 @@ pub enum ErrorCode {
     NoMatchingBumpFound,
     NoAmount,
     AmountsAndAmountProvided,
+    #[msg("Invalid token extension tlv data.")]
+    InvalidTlv,
+    #[msg("Unknown token extension type.")]
+    UnknownTokenExtension,
+    #[msg("Input token accounts have different token extensions.")]
+    TokenExtensionMismatch,
+    InvalidMint,
+    #[msg("Mint account is required for mints with transfer fee or pausable config.")]
+    MintAccountUndefined,
+    TransferFeeCalculationFailed,
+    #[msg("Mint with transfer fee has no withdraw withheld authority.")]
+    WithdrawWithheldAuthorityUndefined,
+    #[msg("Transfer fee output requires an output compressed account.")]
+    NoOutputCompressedAccounts,
+    #[msg("Mint is paused.")]
+    MintPaused,
+    #[msg("Permanent delegate transfers require the owner in delegated transfer.")]
+    PermanentDelegateOwnerUndefined,
+    #[msg("Token account is time locked.")]
+    TokenAccountLocked,
+    #[msg("Input token accounts have no time lock.")]
+    TimeLockUndefined,
+    #[msg("No tokens are unlocked yet.")]
+    NoUnlockedAmount,
+    #[msg("Swap legs must be of different mints.")]
+    SwapMintsNotDistinct,
+    #[msg("Swaps of mints with transfer fee or pausable config are not supported.")]
+    SwapMintExtensionNotSupported,
+    #[msg("Amount exceeds the delegated amount.")]
+    DelegatedAmountExceeded,
+    #[msg("Compressed mint is decompressed.")]
+    MintDecompressed,
+    #[msg("Compressed mints must be created in the compressed mint address Merkle tree.")]
+    InvalidCompressedMintAddressMerkleTree,
+    #[msg("Compressed mints cannot have a freeze authority.")]
+    CompressedMintFreezeAuthorityNotSupported,
 }
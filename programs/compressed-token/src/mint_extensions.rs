@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState, pausable::PausableConfig,
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFeeConfig,
+        BaseStateWithExtensions, PodStateWithExtensions,
+    },
+    pod::PodMint,
+    state::AccountState as SplAccountState,
+};
+
+use crate::{
+    tlv::{add_extension, has_extension, TokenExtension, TokenExtensionType},
+    token_data::TokenData,
+    ErrorCode,
+};
+
+/// Token 2022 mint extensions which change the behavior of compressed token
+/// accounts:
+/// 1. TransferFeeConfig, see crate::transfer_fee.
+/// 2. PermanentDelegate, the permanent delegate can transfer and burn
+///    compressed token accounts of any owner.
+/// 3. Pausable, transfers of compressed token accounts of a paused mint fail.
+///    mint_to and compression fail in the token 2022 program.
+/// 4. DefaultAccountState, compressed token accounts created by mint_to are
+///    frozen if the default account state is frozen. Compression does not
+///    freeze outputs since the compressed spl token account has already been
+///    thawed by the freeze authority. Note that token pool accounts are
+///    initialized frozen as well and have to be thawed by the freeze
+///    authority before tokens can be minted or compressed.
+///
+/// Compressed token accounts of transfer fee and pausable mints carry a tlv
+/// marker so that the mint account is required in every transfer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MintExtensions {
+    pub transfer_fee_config: Option<TransferFeeConfig>,
+    pub permanent_delegate: Option<Pubkey>,
+    /// Some(paused) if the mint is pausable.
+    pub paused: Option<bool>,
+    pub default_state_frozen: bool,
+}
+
+impl MintExtensions {
+    /// Spl token accounts of transfer fee and pausable mints can only be
+    /// transferred with transfer_checked.
+    pub fn requires_transfer_checked(&self) -> bool {
+        self.transfer_fee_config.is_some() || self.paused.is_some()
+    }
+
+    pub fn is_permanent_delegate(&self, authority: &Pubkey) -> bool {
+        self.permanent_delegate == Some(*authority)
+    }
+
+    pub fn check_not_paused(&self) -> Result<()> {
+        if self.paused == Some(true) {
+            msg!("Mint is paused.");
+            return err!(ErrorCode::MintPaused);
+        }
+        Ok(())
+    }
+
+    /// Adds the tlv markers of the mint extensions to an output tlv.
+    pub fn add_output_markers(&self, tlv: &Option<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+        let mut tlv = tlv.clone();
+        if self.transfer_fee_config.is_some() {
+            tlv = Some(add_extension(&tlv, TokenExtension::TransferFee)?);
+        }
+        if self.paused.is_some() {
+            tlv = Some(add_extension(&tlv, TokenExtension::Pausable)?);
+        }
+        Ok(tlv)
+    }
+}
+
+/// Parses the mint extensions of a token 2022 mint.
+/// Spl token mints have no extensions.
+pub fn get_mint_extensions(mint_account: &AccountInfo) -> Result<MintExtensions> {
+    if *mint_account.owner != spl_token_2022::ID {
+        return Ok(MintExtensions::default());
+    }
+    let mint_data = mint_account.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)
+        .map_err(|_| ErrorCode::InvalidMint)?;
+    let permanent_delegate = mint
+        .get_extension::<PermanentDelegate>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.delegate));
+    let paused = mint
+        .get_extension::<PausableConfig>()
+        .ok()
+        .map(|extension| bool::from(extension.paused));
+    let default_state_frozen = mint
+        .get_extension::<DefaultAccountState>()
+        .map(|extension| extension.state == SplAccountState::Frozen as u8)
+        .unwrap_or(false);
+    Ok(MintExtensions {
+        transfer_fee_config: mint.get_extension::<TransferFeeConfig>().ok().copied(),
+        permanent_delegate,
+        paused,
+        default_state_frozen,
+    })
+}
+
+/// Inputs with the pausable marker can only be transferred if the mint
+/// account is provided.
+pub fn check_input_pausable_markers(
+    input_token_data: &[TokenData],
+    mint_extensions: &MintExtensions,
+) -> Result<()> {
+    if mint_extensions.paused.is_some() {
+        return Ok(());
+    }
+    for token_data in input_token_data.iter() {
+        if has_extension(&token_data.tlv, TokenExtensionType::Pausable)? {
+            msg!("Mint account with pausable config is required.");
+            return err!(ErrorCode::MintAccountUndefined);
+        }
+    }
+    Ok(())
+}
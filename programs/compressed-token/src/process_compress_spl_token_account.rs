@@ -1,11 +1,15 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
 use light_compressed_account::instruction_data::cpi_context::CompressedCpiContext;
+use light_zero_copy::errors::ZeroCopyError;
+use zerocopy::{little_endian::U64, IntoBytes, Ref};
 
 use super::TransferInstruction;
 use crate::{
     process_transfer::{
-        process_transfer, CompressedTokenInstructionDataTransfer, PackedTokenTransferOutputData,
+        process_transfer, ZCompressedTokenInstructionDataTransfer, ZPackedTokenTransferOutputData,
     },
+    transfer_fee::{calculate_transfer_fee, find_mint_account, get_transfer_fee_config},
     ErrorCode,
 };
 
@@ -17,7 +21,7 @@ pub fn process_compress_spl_token_account<'info>(
 ) -> Result<()> {
     let compression_token_account =
         if let Some(token_account) = ctx.accounts.compress_or_decompress_token_account.as_ref() {
-            token_account
+            InterfaceAccount::<TokenAccount>::try_from(token_account)?
         } else {
             return err!(ErrorCode::CompressedPdaUndefinedForCompress);
         };
@@ -25,29 +29,67 @@ pub fn process_compress_spl_token_account<'info>(
         .amount
         .checked_sub(remaining_amount.unwrap_or_default())
         .ok_or(crate::ErrorCode::InsufficientTokenAccountBalance)?;
-    let compressed_output_account = PackedTokenTransferOutputData {
-        owner,
+    // Mints with transfer fee withhold the fee in the token pool account.
+    let transfer_fee_config =
+        match find_mint_account(ctx.remaining_accounts, &compression_token_account.mint) {
+            Some(mint_account) => get_transfer_fee_config(mint_account)?,
+            None => None,
+        };
+    let compressed_amount = match transfer_fee_config {
+        Some(transfer_fee_config) => {
+            compress_amount - calculate_transfer_fee(&transfer_fee_config, compress_amount)?
+        }
+        None => compress_amount,
+    };
+    // The instruction data is created in place from its fields
+    // instead of serializing it to deserialize it zero copy.
+    let owner = owner.to_bytes();
+    let mint = compression_token_account.mint.to_bytes();
+    let compressed_amount = U64::new(compressed_amount);
+    let compress_amount = U64::new(compress_amount);
+    let cpi_context = cpi_context.map(|cpi_context| {
+        [
+            cpi_context.set_context as u8,
+            cpi_context.first_set_context as u8,
+            cpi_context.cpi_context_account_index,
+        ]
+    });
+    let compressed_output_account = ZPackedTokenTransferOutputData {
+        owner: ref_from_bytes(owner.as_slice())?,
+        amount: ref_from_bytes(compressed_amount.as_bytes())?,
         lamports: None,
-        amount: compress_amount,
-        tlv: None,
         merkle_tree_index: 0,
+        tlv: None,
     };
-
-    let inputs = CompressedTokenInstructionDataTransfer {
+    let inputs = ZCompressedTokenInstructionDataTransfer {
         proof: None,
-        mint: compression_token_account.mint,
+        mint: ref_from_bytes(mint.as_slice())?,
         delegated_transfer: None,
-        is_compress: true,
         input_token_data_with_context: Vec::new(),
         output_compressed_accounts: vec![compressed_output_account],
-        cpi_context,
+        is_compress: true,
+        compress_or_decompress_amount: Some(ref_from_bytes(compress_amount.as_bytes())?),
+        cpi_context: cpi_context
+            .as_ref()
+            .map(|cpi_context| ref_from_bytes(cpi_context.as_slice()))
+            .transpose()?,
         lamports_change_account_merkle_tree_index: None,
-        compress_or_decompress_amount: Some(compress_amount),
         with_transaction_hash: false,
+        input_tlvs: Vec::new(),
     };
     process_transfer(ctx, inputs)
 }
 
+#[inline(always)]
+fn ref_from_bytes<T>(bytes: &[u8]) -> Result<Ref<&[u8], T>>
+where
+    T: zerocopy::FromBytes + zerocopy::KnownLayout + zerocopy::Immutable + zerocopy::Unaligned,
+{
+    Ok(Ref::from_bytes(bytes)
+        .map_err(ZeroCopyError::from)
+        .map_err(ProgramError::from)?)
+}
+
 #[cfg(not(target_os = "solana"))]
 pub mod sdk {
     use anchor_lang::{prelude::AccountMeta, InstructionData, ToAccountMetas};
@@ -5,13 +5,16 @@ use light_compressed_account::{
     instruction_data::data::OutputCompressedAccountWithPackedContext, pubkey::PubkeyTrait,
 };
 use light_system_program::program::LightSystemProgram;
-use light_zero_copy::num_trait::ZeroCopyNumTrait;
+use light_zero_copy::{num_trait::ZeroCopyNumTrait, slice::ZeroCopySliceBorsh, ZeroCopy};
 #[cfg(target_os = "solana")]
 use {
     crate::{
         check_spl_token_pool_derivation_with_index,
+        constants::NOT_FROZEN,
+        mint_extensions::{get_mint_extensions, MintExtensions},
         process_transfer::create_output_compressed_accounts,
-        process_transfer::get_cpi_signer_seeds, spl_compression::spl_token_transfer,
+        process_transfer::get_cpi_signer_seeds,
+        spl_compression::spl_token_transfer,
     },
     light_compressed_account::hash_to_bn254_field_size_be,
     light_heap::{bench_sbf_end, bench_sbf_start, GLOBAL_ALLOCATOR},
@@ -22,6 +25,46 @@ use crate::{check_spl_token_pool_derivation, program::LightCompressedToken};
 pub const COMPRESS: bool = false;
 pub const MINT_TO: bool = true;
 
+/// Arguments of mint_to, Anchor encodes them consecutively
+/// which is the Borsh layout of this struct.
+#[derive(Debug, Default, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct MintToInstructionData {
+    #[zero_copy(ty = ZeroCopySliceBorsh<'a, light_compressed_account::pubkey::Pubkey>)]
+    pub public_keys: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub lamports: Option<u64>,
+}
+
+/// Serialized MintToInstructionData without length prefix.
+/// Anchor reads the remaining instruction data into it without decoding,
+/// mint_to deserializes it zero copy.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MintToInstructionDataBytes(pub Vec<u8>);
+
+impl AnchorDeserialize for MintToInstructionDataBytes {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl AnchorSerialize for MintToInstructionDataBytes {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl From<&MintToInstructionData> for MintToInstructionDataBytes {
+    fn from(data: &MintToInstructionData) -> Self {
+        Self(data.try_to_vec().unwrap())
+    }
+}
+
+/// The IDL type of the arguments is MintToInstructionData.
+#[cfg(feature = "idl-build")]
+impl anchor_lang::idl::build::IdlBuild for MintToInstructionDataBytes {}
+
 /// Mints tokens from an spl token mint to a list of compressed accounts and
 /// stores minted tokens in spl token pool account.
 ///
@@ -57,9 +100,29 @@ pub fn process_mint_to_or_compress<'info, const IS_MINT_TO: bool>(
 
     #[cfg(target_os = "solana")]
     {
+        // Outputs of mints with transfer fee or pausable config carry tlv
+        // markers and outputs of mints with default account state frozen are
+        // frozen, see crate::mint_extensions.
+        let mint_extensions = match ctx.accounts.mint.as_ref() {
+            Some(mint) if IS_MINT_TO => get_mint_extensions(&mint.to_account_info())?,
+            _ => MintExtensions::default(),
+        };
+        let extension_tlv = mint_extensions.add_output_markers(&None)?;
         let option_compression_lamports = if lamports.unwrap_or(0) == 0 { 0 } else { 8 };
-        let inputs_len =
-            1 + 4 + 4 + 4 + amounts.len() * 162 + 1 + 1 + 1 + 1 + option_compression_lamports;
+        let tlv_len = extension_tlv
+            .as_ref()
+            .map(|tlv| 4 + tlv.len())
+            .unwrap_or_default();
+        let inputs_len = 1
+            + 4
+            + 4
+            + 4
+            + amounts.len() * (162 + tlv_len)
+            + 1
+            + 1
+            + 1
+            + 1
+            + option_compression_lamports;
         // inputs_len =
         //   1                          Option<Proof>
         // + 4                          Vec::new()
@@ -110,20 +173,40 @@ pub fn process_mint_to_or_compress<'info, const IS_MINT_TO: bool>(
         let mut output_compressed_accounts =
             vec![OutputCompressedAccountWithPackedContext::default(); recipient_pubkeys.len()];
         let lamports_vec = lamports.map(|_| vec![lamports; amounts.len()]);
-        create_output_compressed_accounts(
-            &mut output_compressed_accounts,
-            mint,
-            recipient_pubkeys,
-            None,
-            None,
-            &amounts,
-            lamports_vec,
-            &hashed_mint,
-            // We ensure that the Merkle tree account is the first
-            // remaining account in the cpi to the system program.
-            &vec![0; amounts.len()],
-            &[ctx.accounts.merkle_tree.to_account_info()],
-        )?;
+        let tlvs = extension_tlv.map(|tlv| vec![Some(tlv); amounts.len()]);
+        // We ensure that the Merkle tree account is the first
+        // remaining account in the cpi to the system program.
+        let merkle_tree_indices = vec![0; amounts.len()];
+        let remaining_accounts = [ctx.accounts.merkle_tree.to_account_info()];
+        if mint_extensions.default_state_frozen {
+            create_output_compressed_accounts::<true>(
+                &mut output_compressed_accounts,
+                mint,
+                recipient_pubkeys,
+                None,
+                None,
+                &amounts,
+                lamports_vec,
+                tlvs.as_deref(),
+                &hashed_mint,
+                &merkle_tree_indices,
+                &remaining_accounts,
+            )?;
+        } else {
+            create_output_compressed_accounts::<NOT_FROZEN>(
+                &mut output_compressed_accounts,
+                mint,
+                recipient_pubkeys,
+                None,
+                None,
+                &amounts,
+                lamports_vec,
+                tlvs.as_deref(),
+                &hashed_mint,
+                &merkle_tree_indices,
+                &remaining_accounts,
+            )?;
+        }
         bench_sbf_end!("tm_output_compressed_accounts");
 
         cpi_execute_compressed_transaction_mint_to(
@@ -479,9 +562,12 @@ pub mod mint_sdk {
         let token_pool_pda = get_token_pool_pda_with_index(mint, token_pool_index);
 
         let instruction_data = crate::instruction::MintTo {
-            amounts,
-            public_keys,
-            lamports,
+            inputs: (&MintToInstructionData {
+                public_keys,
+                amounts,
+                lamports,
+            })
+                .into(),
         };
         let sol_pool_pda = if lamports.is_some() {
             Some(get_sol_pool_pda())
@@ -669,4 +755,44 @@ mod test {
             assert_eq!(inputs, reference);
         }
     }
+
+    /// The mint_to instruction data is the Anchor encoding
+    /// of the arguments public_keys, amounts and lamports.
+    #[test]
+    fn test_mint_to_instruction_data_wire_format() {
+        use anchor_lang::{Discriminator, InstructionData};
+        use light_zero_copy::borsh::Deserialize;
+
+        let public_keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let amounts = vec![1u64, u64::MAX];
+        for lamports in [None, Some(3u64)] {
+            let data = crate::instruction::MintTo {
+                inputs: (&MintToInstructionData {
+                    public_keys: public_keys.clone(),
+                    amounts: amounts.clone(),
+                    lamports,
+                })
+                    .into(),
+            }
+            .data();
+            let mut reference = crate::instruction::MintTo::DISCRIMINATOR.to_vec();
+            public_keys.serialize(&mut reference).unwrap();
+            amounts.serialize(&mut reference).unwrap();
+            lamports.serialize(&mut reference).unwrap();
+            assert_eq!(data, reference);
+
+            let (inputs, remaining) = ZMintToInstructionData::zero_copy_at(
+                &data[crate::instruction::MintTo::DISCRIMINATOR.len()..],
+            )
+            .unwrap();
+            assert!(remaining.is_empty());
+            for (pubkey, z_pubkey) in public_keys.iter().zip(inputs.public_keys.iter()) {
+                assert_eq!(*pubkey, (*z_pubkey).into());
+            }
+            for (amount, z_amount) in amounts.iter().zip(inputs.amounts.iter()) {
+                assert_eq!(*amount, z_amount.get());
+            }
+            assert_eq!(lamports, inputs.lamports.map(|x| x.get()));
+        }
+    }
 }
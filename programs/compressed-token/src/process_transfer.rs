@@ -8,19 +8,32 @@ use light_compressed_account::{
     instruction_data::{
         compressed_proof::CompressedProof,
         cpi_context::CompressedCpiContext,
-        data::OutputCompressedAccountWithPackedContext,
+        data::{NewAddressParamsAssignedPacked, OutputCompressedAccountWithPackedContext},
         with_readonly::{InAccount, InstructionDataInvokeCpiWithReadOnly},
+        zero_copy::{ZCompressedCpiContext, ZPackedMerkleContext},
     },
     pubkey::PubkeyTrait,
 };
 use light_heap::{bench_sbf_end, bench_sbf_start};
 use light_system_program::account_traits::{InvokeAccounts, SignerAccounts};
-use light_zero_copy::num_trait::ZeroCopyNumTrait;
+use light_zero_copy::{
+    init_mut::ZeroCopyNew, num_trait::ZeroCopyNumTrait, slice::ZeroCopySliceBorsh, ZeroCopy,
+};
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use zerocopy::{little_endian::U64, Ref};
 
 use crate::{
     constants::{BUMP_CPI_AUTHORITY, NOT_FROZEN, TOKEN_COMPRESSED_ACCOUNT_DISCRIMINATOR},
-    spl_compression::process_compression_or_decompression,
-    token_data::{AccountState, TokenData},
+    delegation::check_delegated_transfer,
+    mint_extensions::{check_input_pausable_markers, get_mint_extensions, MintExtensions},
+    spl_compression::{check_token_account_owners, process_compression_or_decompression},
+    tlv::{check_output_tlvs, has_extension, hash_tlv, validate_tlv, TokenExtensionType},
+    token_data::{AccountState, TokenData, TokenDataConfig},
+    transfer_fee::{
+        add_transfer_fee_extension, calculate_transfer_fee, check_token_pool_transfer_fee,
+        find_mint_account, get_fee_collection_owner, has_transfer_fee_extension,
+    },
+    vesting::check_inputs_unlocked,
     ErrorCode, TransferInstruction,
 };
 
@@ -38,7 +51,7 @@ use crate::{
 #[inline(always)]
 pub fn process_transfer<'a, 'b, 'c, 'info: 'b + 'c>(
     ctx: Context<'a, 'b, 'c, 'info, TransferInstruction<'info>>,
-    inputs: CompressedTokenInstructionDataTransfer,
+    inputs: ZCompressedTokenInstructionDataTransfer,
 ) -> Result<()> {
     bench_sbf_start!("t_context_and_check_sig");
     if inputs.input_token_data_with_context.is_empty()
@@ -46,44 +59,140 @@ pub fn process_transfer<'a, 'b, 'c, 'info: 'b + 'c>(
     {
         return err!(crate::ErrorCode::NoInputTokenAccountsProvided);
     }
+    let mint = inputs.mint();
+    let mint_account = find_mint_account(ctx.remaining_accounts, &mint);
+    let mint_extensions = match mint_account {
+        Some(mint_account) => get_mint_extensions(mint_account)?,
+        None => MintExtensions::default(),
+    };
+    mint_extensions.check_not_paused()?;
+    if inputs.compress_or_decompress_amount.is_some() {
+        check_token_account_owners(ctx.accounts)?;
+        check_token_pool_transfer_fee(
+            ctx.accounts.token_pool_pda.as_ref(),
+            mint_extensions.transfer_fee_config.as_ref(),
+        )?;
+    }
+    // The permanent delegate transfers the inputs of delegated_transfer.owner.
+    let is_permanent_delegate = inputs.delegated_transfer.is_some()
+        && mint_extensions.is_permanent_delegate(&ctx.accounts.authority.key());
     let (mut compressed_input_accounts, input_token_data, input_lamports) =
         get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
             &ctx.accounts.authority.key(),
-            &inputs.delegated_transfer,
+            inputs.delegated_transfer.as_ref(),
+            is_permanent_delegate,
             ctx.remaining_accounts,
             &inputs.input_token_data_with_context,
-            &inputs.mint,
+            &inputs.input_tlvs,
+            &mint,
+        )?;
+    check_input_pausable_markers(&input_token_data, &mint_extensions)?;
+    if !input_token_data.is_empty() {
+        check_inputs_unlocked(
+            input_token_data
+                .iter()
+                .map(|x| (x.amount, x.tlv.as_deref())),
+            Clock::get()?.slot,
         )?;
+    }
     bench_sbf_end!("t_context_and_check_sig");
+    let mut output_owners = inputs
+        .output_compressed_accounts
+        .iter()
+        .map(|data| (*data.owner).into())
+        .collect::<Vec<Pubkey>>();
+    let mut output_amounts = inputs
+        .output_compressed_accounts
+        .iter()
+        .map(|data| data.amount.get())
+        .collect::<Vec<u64>>();
+    let mut output_lamports = inputs
+        .output_compressed_accounts
+        .iter()
+        .map(|data| data.lamports.as_ref().map(|lamports| lamports.get()))
+        .collect::<Vec<Option<u64>>>();
+    let mut output_tlvs = inputs
+        .output_compressed_accounts
+        .iter()
+        .map(|data| match data.tlv() {
+            Some(tlv) => {
+                validate_tlv(tlv)?;
+                let tlv = Some(tlv.to_vec());
+                // The delegated amount is only set by the program.
+                if has_extension(&tlv, TokenExtensionType::DelegatedAmount)? {
+                    msg!("Delegated amount extension cannot be set in transfer outputs.");
+                    return err!(ErrorCode::InvalidTlv);
+                }
+                Ok(tlv)
+            }
+            None => Ok(None),
+        })
+        .collect::<Result<Vec<Option<Vec<u8>>>>>()?;
+    check_output_tlvs(&input_token_data, &output_tlvs)?;
+    let mut output_merkle_tree_indices = inputs
+        .output_compressed_accounts
+        .iter()
+        .map(|data| data.merkle_tree_index)
+        .collect::<Vec<u8>>();
+    let compress_or_decompress_amount = add_transfer_fee_output(
+        &inputs,
+        &input_token_data,
+        mint_extensions.transfer_fee_config.as_ref(),
+        &ctx.accounts.authority.key(),
+        &mut output_owners,
+        &mut output_amounts,
+        &mut output_lamports,
+        &mut output_tlvs,
+        &mut output_merkle_tree_indices,
+    )?;
+    for tlv in output_tlvs.iter_mut() {
+        *tlv = mint_extensions.add_output_markers(tlv)?;
+    }
+    // Delegates can spend up to the delegated amount, permanent delegates
+    // are not limited.
+    if let Some(delegated_transfer) = inputs.delegated_transfer.as_ref() {
+        if !is_permanent_delegate {
+            check_delegated_transfer(
+                &input_token_data,
+                &delegated_transfer.owner(),
+                delegated_transfer.delegate_change_account_index,
+                &output_owners,
+                &output_amounts,
+                &mut output_tlvs,
+            )?;
+        }
+    }
     bench_sbf_start!("t_sum_check");
     sum_check(
         &input_token_data,
-        &inputs
-            .output_compressed_accounts
-            .iter()
-            .map(|data| data.amount)
-            .collect::<Vec<u64>>(),
-        inputs.compress_or_decompress_amount.as_ref(),
+        &output_amounts,
+        compress_or_decompress_amount.as_ref(),
         inputs.is_compress,
     )?;
     bench_sbf_end!("t_sum_check");
     bench_sbf_start!("t_process_compression");
     if inputs.compress_or_decompress_amount.is_some() {
-        process_compression_or_decompression(&inputs, &ctx)?;
+        let mint_account = if mint_extensions.requires_transfer_checked() {
+            mint_account
+        } else {
+            None
+        };
+        process_compression_or_decompression(&inputs, &ctx, mint_account)?;
     }
     bench_sbf_end!("t_process_compression");
     bench_sbf_start!("t_create_output_compressed_accounts");
-    let hashed_mint = hash_to_bn254_field_size_be(&inputs.mint.to_bytes());
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
 
-    let mut output_compressed_accounts = vec![
-        OutputCompressedAccountWithPackedContext::default();
-        inputs.output_compressed_accounts.len()
-    ];
+    let mut output_compressed_accounts =
+        vec![OutputCompressedAccountWithPackedContext::default(); output_owners.len()];
 
     // If delegate is signer of the transaction determine whether there is a
     // change account which remains delegated and mark its position.
-    let (is_delegate, delegate) = if let Some(delegated_transfer) = inputs.delegated_transfer {
-        let mut vec = vec![false; inputs.output_compressed_accounts.len()];
+    // Change accounts of permanent delegate transfers are not delegated.
+    let (is_delegate, delegate) = if is_permanent_delegate {
+        (None, None)
+    } else if let Some(delegated_transfer) = inputs.delegated_transfer.as_ref() {
+        let mut vec = vec![false; output_owners.len()];
         if let Some(index) = delegated_transfer.delegate_change_account_index {
             vec[index as usize] = true;
             (Some(vec), Some(ctx.accounts.authority.key()))
@@ -93,41 +202,17 @@ pub fn process_transfer<'a, 'b, 'c, 'info: 'b + 'c>(
     } else {
         (None, None)
     };
-    inputs.output_compressed_accounts.iter().for_each(|data| {
-        if data.tlv.is_some() {
-            unimplemented!("Tlv is unimplemented");
-        }
-    });
-    let output_lamports = create_output_compressed_accounts(
+    let output_lamports = create_output_compressed_accounts::<NOT_FROZEN>(
         &mut output_compressed_accounts,
-        inputs.mint,
-        inputs
-            .output_compressed_accounts
-            .iter()
-            .map(|data| data.owner)
-            .collect::<Vec<Pubkey>>()
-            .as_slice(),
+        mint,
+        output_owners.as_slice(),
         delegate,
         is_delegate,
-        inputs
-            .output_compressed_accounts
-            .iter()
-            .map(|data: &PackedTokenTransferOutputData| data.amount)
-            .collect::<Vec<u64>>()
-            .as_slice(),
-        Some(
-            inputs
-                .output_compressed_accounts
-                .iter()
-                .map(|data: &PackedTokenTransferOutputData| data.lamports)
-                .collect::<Vec<Option<u64>>>(),
-        ),
+        output_amounts.as_slice(),
+        Some(output_lamports),
+        Some(output_tlvs.as_slice()),
         &hashed_mint,
-        &inputs
-            .output_compressed_accounts
-            .iter()
-            .map(|data| data.merkle_tree_index)
-            .collect::<Vec<u8>>(),
+        &output_merkle_tree_indices,
         ctx.remaining_accounts,
     )?;
     bench_sbf_end!("t_create_output_compressed_accounts");
@@ -169,14 +254,90 @@ pub fn process_transfer<'a, 'b, 'c, 'info: 'b + 'c>(
         compressed_input_accounts,
         output_compressed_accounts,
         inputs.with_transaction_hash,
-        inputs.proof,
-        inputs.cpi_context,
+        inputs.proof.as_ref().map(|proof| **proof),
+        inputs.cpi_context(),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts.light_system_program.to_account_info(),
         ctx.accounts.self_program.to_account_info(),
         ctx.remaining_accounts,
     )
 }
+
+/// Applies the transfer fee of mints with a token 2022 TransferFeeConfig,
+/// see crate::transfer_fee.
+/// 1. Inputs with the transfer fee marker require the mint account.
+/// 2. All outputs receive the transfer fee marker.
+/// 3. The fee is charged on the sum of outputs owned by other owners than the
+///    sender and added as fee output owned by the withdraw withheld authority.
+/// 4. Compressed amount is net of the fee withheld in the token pool account,
+///    outputs to other owners up to the net compressed amount are not charged.
+///
+/// Returns the compress or decompress amount for the sum check.
+#[allow(clippy::too_many_arguments)]
+pub fn add_transfer_fee_output(
+    inputs: &ZCompressedTokenInstructionDataTransfer,
+    input_token_data: &[TokenData],
+    transfer_fee_config: Option<&TransferFeeConfig>,
+    authority: &Pubkey,
+    output_owners: &mut Vec<Pubkey>,
+    output_amounts: &mut Vec<u64>,
+    output_lamports: &mut Vec<Option<u64>>,
+    output_tlvs: &mut Vec<Option<Vec<u8>>>,
+    output_merkle_tree_indices: &mut Vec<u8>,
+) -> Result<Option<u64>> {
+    let transfer_fee_config = match transfer_fee_config {
+        Some(transfer_fee_config) => transfer_fee_config,
+        None => {
+            for token_data in input_token_data.iter() {
+                if has_transfer_fee_extension(&token_data.tlv)? {
+                    msg!("Mint account with transfer fee config is required.");
+                    return err!(ErrorCode::MintAccountUndefined);
+                }
+            }
+            return Ok(inputs.compress_or_decompress_amount());
+        }
+    };
+    for tlv in output_tlvs.iter_mut() {
+        *tlv = add_transfer_fee_extension(tlv)?;
+    }
+    let sender = match inputs.delegated_transfer.as_ref() {
+        Some(delegated_transfer) => delegated_transfer.owner(),
+        None => *authority,
+    };
+    let mut transferred_amount: u64 = 0;
+    for (owner, amount) in output_owners.iter().zip(output_amounts.iter()) {
+        if *owner != sender {
+            transferred_amount = transferred_amount
+                .checked_add(*amount)
+                .ok_or(ErrorCode::ComputeOutputSumFailed)?;
+        }
+    }
+    // The token pool account receives the compressed amount net of the fee.
+    let compress_or_decompress_amount = match inputs.compress_or_decompress_amount() {
+        Some(amount) if inputs.is_compress => {
+            let compression_fee = calculate_transfer_fee(transfer_fee_config, amount)?;
+            // Compressed tokens paid the fee when they were transferred to
+            // the token pool account, outputs funded by the compression are
+            // not charged twice.
+            transferred_amount = transferred_amount.saturating_sub(amount - compression_fee);
+            Some(amount - compression_fee)
+        }
+        amount => amount,
+    };
+    let fee = calculate_transfer_fee(transfer_fee_config, transferred_amount)?;
+    if fee > 0 {
+        let merkle_tree_index = *output_merkle_tree_indices
+            .first()
+            .ok_or(ErrorCode::NoOutputCompressedAccounts)?;
+        output_owners.push(get_fee_collection_owner(transfer_fee_config)?);
+        output_amounts.push(fee);
+        output_lamports.push(None);
+        output_tlvs.push(add_transfer_fee_extension(&None)?);
+        output_merkle_tree_indices.push(merkle_tree_index);
+    }
+    Ok(compress_or_decompress_amount)
+}
+
 pub const BATCHED_DISCRIMINATOR: &[u8] = b"BatchMta";
 pub const OUTPUT_QUEUE_DISCRIMINATOR: &[u8] = b"queueacc";
 
@@ -186,8 +347,10 @@ pub const OUTPUT_QUEUE_DISCRIMINATOR: &[u8] = b"queueacc";
 /// 2. Create, hash and serialize token data.
 /// 3. Create compressed account data.
 /// 4. Repeat for every pubkey.
+///
+/// Outputs are frozen if FROZEN_OUTPUTS, see crate::mint_extensions.
 #[allow(clippy::too_many_arguments)]
-pub fn create_output_compressed_accounts(
+pub fn create_output_compressed_accounts<const FROZEN_OUTPUTS: bool>(
     output_compressed_accounts: &mut [OutputCompressedAccountWithPackedContext],
     mint_pubkey: impl PubkeyTrait,
     pubkeys: &[impl PubkeyTrait],
@@ -195,6 +358,7 @@ pub fn create_output_compressed_accounts(
     is_delegate: Option<Vec<bool>>,
     amounts: &[impl ZeroCopyNumTrait],
     lamports: Option<Vec<Option<impl ZeroCopyNumTrait>>>,
+    tlvs: Option<&[Option<Vec<u8>>]>,
     hashed_mint: &[u8; 32],
     merkle_tree_indices: &[u8],
     remaining_accounts: &[AccountInfo<'_>],
@@ -218,26 +382,34 @@ pub fn create_output_compressed_accounts(
         } else {
             (None, None)
         };
-        // 107/75 =
-        //      32      mint
-        // +    32      owner
-        // +    8       amount
-        // +    1 + 32  option + delegate (optional)
-        // +    1       state
-        // +    1       tlv (None)
-        let capacity = if delegate.is_some() { 107 } else { 75 };
-        let mut token_data_bytes = Vec::with_capacity(capacity);
-        // 1,000 CU token data and serialize
-        let token_data = TokenData {
-            mint: (mint_pubkey).to_anchor_pubkey(),
-            owner: (*owner).to_anchor_pubkey(),
-            amount: (*amount).into(),
-            delegate,
-            state: AccountState::Initialized,
-            tlv: None,
+        let tlv = tlvs.and_then(|tlvs| tlvs[i].as_ref());
+        // Write token data in borsh layout without serialization.
+        // The buffer becomes the data of the output compressed account,
+        // CompressedAccountData owns its data hence the allocation.
+        let token_data_config = TokenDataConfig {
+            delegate: delegate.is_some(),
+            tlv: tlv.map(|tlv| tlv.len() as u32),
         };
-        // TODO: remove serialization, just write bytes.
-        token_data.serialize(&mut token_data_bytes).unwrap();
+        let mut token_data_bytes = vec![0u8; TokenData::byte_len(&token_data_config)];
+        {
+            let (mut token_data, _) =
+                TokenData::new_zero_copy(&mut token_data_bytes, token_data_config)
+                    .map_err(ProgramError::from)?;
+            *token_data.mint = mint_pubkey.to_anchor_pubkey().to_bytes();
+            *token_data.owner = (*owner).to_anchor_pubkey().to_bytes();
+            *token_data.amount = U64::new((*amount).into());
+            if let (Some(z_delegate), Some(delegate)) = (token_data.delegate.as_mut(), delegate) {
+                **z_delegate = delegate.to_bytes();
+            }
+            *token_data.state = if FROZEN_OUTPUTS {
+                AccountState::Frozen as u8
+            } else {
+                AccountState::Initialized as u8
+            };
+            if let (Some(z_tlv), Some(tlv)) = (token_data.tlv.as_mut(), tlv) {
+                z_tlv.as_mut_slice().copy_from_slice(tlv);
+            }
+        }
         bench_sbf_start!("token_data_hash");
         let hashed_owner = hash_to_bn254_field_size_be(owner.trait_to_bytes().as_slice());
 
@@ -266,11 +438,13 @@ pub fn create_output_compressed_accounts(
             }
         }?;
 
-        let data_hash = TokenData::hash_with_hashed_values(
+        let hashed_tlv = tlv.map(|tlv| hash_tlv(tlv.as_slice()));
+        let data_hash = TokenData::hash_inputs_with_hashed_values_and_tlv::<FROZEN_OUTPUTS>(
             hashed_mint,
             &hashed_owner,
             &amount_bytes,
             &hashed_delegate,
+            &hashed_tlv.as_ref(),
         )
         .map_err(ProgramError::from)?;
         let data = CompressedAccountData {
@@ -354,23 +528,19 @@ pub fn add_data_hash_to_input_compressed_accounts<const FROZEN_INPUTS: bool>(
         } else {
             None
         };
-        compressed_account_with_context.data_hash = if !FROZEN_INPUTS {
-            TokenData::hash_with_hashed_values(
-                hashed_mint,
-                &hashed_owner,
-                &amount_bytes,
-                &hashed_delegate,
-            )
-            .map_err(ProgramError::from)?
-        } else {
-            TokenData::hash_frozen_with_hashed_values(
+        let hashed_tlv = input_token_data[i]
+            .tlv
+            .as_ref()
+            .map(|tlv| hash_tlv(tlv.as_slice()));
+        compressed_account_with_context.data_hash =
+            TokenData::hash_inputs_with_hashed_values_and_tlv::<FROZEN_INPUTS>(
                 hashed_mint,
                 &hashed_owner,
                 &amount_bytes,
                 &hashed_delegate,
+                &hashed_tlv.as_ref(),
             )
-            .map_err(ProgramError::from)?
-        };
+            .map_err(ProgramError::from)?;
     }
     Ok(())
 }
@@ -398,6 +568,41 @@ pub fn cpi_execute_compressed_transaction_transfer<
     _system_program_account_info: AccountInfo<'info>,
     _invoking_program_account_info: AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    cpi_execute_compressed_transaction_with_addresses(
+        ctx,
+        input_compressed_accounts,
+        output_compressed_accounts,
+        Vec::new(),
+        with_transaction_hash,
+        proof,
+        cpi_context,
+        cpi_authority_pda,
+        _system_program_account_info,
+        _invoking_program_account_info,
+        remaining_accounts,
+    )
+}
+
+/// Same as cpi_execute_compressed_transaction_transfer, additionally creates
+/// new addresses, see crate::compressed_mint.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_execute_compressed_transaction_with_addresses<
+    'info,
+    A: InvokeAccounts<'info> + SignerAccounts<'info>,
+>(
+    ctx: &A,
+    input_compressed_accounts: Vec<InAccount>,
+    output_compressed_accounts: Vec<OutputCompressedAccountWithPackedContext>,
+    new_address_params: Vec<NewAddressParamsAssignedPacked>,
+    with_transaction_hash: bool,
+    proof: Option<CompressedProof>,
+    cpi_context: Option<CompressedCpiContext>,
+    cpi_authority_pda: AccountInfo<'info>,
+    _system_program_account_info: AccountInfo<'info>,
+    _invoking_program_account_info: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     bench_sbf_start!("t_cpi_prep");
 
@@ -424,9 +629,10 @@ pub fn cpi_execute_compressed_transaction_transfer<
         input_compressed_accounts,
         output_compressed_accounts,
         proof,
-        new_address_params: Vec::new(),
+        new_address_params,
         compress_or_decompress_lamports: 0,
         is_compress: false,
+        decompression_recipients: Vec::new(),
     };
     let mut inputs = Vec::new();
     InstructionDataInvokeCpiWithReadOnly::serialize(&inputs_struct, &mut inputs)
@@ -578,20 +784,23 @@ pub fn sum_check(
     }
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct InputTokenDataWithContext {
     pub amount: u64,
     pub delegate_index: Option<u8>,
+    #[zero_copy(ty = ZPackedMerkleContext)]
     pub merkle_context: PackedMerkleContext,
     pub root_index: u16,
     pub lamports: Option<u64>,
-    /// Placeholder for TokenExtension tlv data (unimplemented)
+    /// Placeholder for TokenExtension tlv data, must be None. Input tlvs are
+    /// passed as trailing instruction data, see ZInputTlvs.
     pub tlv: Option<Vec<u8>>,
 }
 
 /// Struct to provide the owner when the delegate is signer of the transaction.
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct DelegatedTransfer {
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub owner: Pubkey,
     /// Index of change compressed account in output compressed accounts. In
     /// case that the delegate didn't spend the complete delegated compressed
@@ -600,9 +809,13 @@ pub struct DelegatedTransfer {
     pub delegate_change_account_index: Option<u8>,
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+/// Instruction data is read in place by the zero copy view
+/// ZCompressedTokenInstructionDataTransfer instead of being copied into heap
+/// allocated Borsh structs.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct CompressedTokenInstructionDataTransfer {
     pub proof: Option<CompressedProof>,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub mint: Pubkey,
     /// Is required if the signer is delegate,
     /// -> delegate is authority account,
@@ -612,16 +825,69 @@ pub struct CompressedTokenInstructionDataTransfer {
     pub output_compressed_accounts: Vec<PackedTokenTransferOutputData>,
     pub is_compress: bool,
     pub compress_or_decompress_amount: Option<u64>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
     pub cpi_context: Option<CompressedCpiContext>,
     pub lamports_change_account_merkle_tree_index: Option<u8>,
+    /// Clients which predate with_transaction_hash omit it.
+    #[zero_copy(trailing)]
     pub with_transaction_hash: bool,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+}
+
+/// Token extension tlv data of the inputs of an instruction, parallel to
+/// input_token_data_with_context, see crate::tlv. Empty if no input has
+/// extensions. Optional trailing instruction data, clients which predate
+/// input tlvs omit it, see `#[zero_copy(trailing)]`.
+pub type ZInputTlvs<'a> = Vec<Option<ZeroCopySliceBorsh<'a, u8>>>;
+
+/// Returns the tlv of the input at index, None if input_tlvs is empty.
+#[inline]
+pub fn get_input_tlv<'a>(
+    input_tlvs: &'a [Option<ZeroCopySliceBorsh<'_, u8>>],
+    index: usize,
+) -> Option<&'a [u8]> {
+    input_tlvs
+        .get(index)
+        .and_then(|tlv| tlv.as_ref())
+        .map(|tlv| tlv.as_slice())
+}
+
+impl ZDelegatedTransfer<'_> {
+    pub fn owner(&self) -> Pubkey {
+        (*self.owner).into()
+    }
+}
+
+impl ZCompressedTokenInstructionDataTransfer<'_> {
+    pub fn mint(&self) -> Pubkey {
+        (*self.mint).into()
+    }
+
+    pub fn compress_or_decompress_amount(&self) -> Option<u64> {
+        self.compress_or_decompress_amount
+            .as_ref()
+            .map(|amount| amount.get())
+    }
+
+    pub fn cpi_context(&self) -> Option<CompressedCpiContext> {
+        self.cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into())
+    }
 }
 
+/// If the signer is the permanent delegate of the mint all inputs are owned by
+/// signer_is_delegate.owner and the delegate signer check is skipped.
+/// input_tlvs are the token extensions of the inputs, see ZInputTlvs.
 pub fn get_input_compressed_accounts_with_merkle_context_and_check_signer<const IS_FROZEN: bool>(
     signer: &Pubkey,
-    signer_is_delegate: &Option<DelegatedTransfer>,
+    signer_is_delegate: Option<&ZDelegatedTransfer>,
+    signer_is_permanent_delegate: bool,
     remaining_accounts: &[AccountInfo<'_>],
-    input_token_data_with_context: &[InputTokenDataWithContext],
+    input_token_data_with_context: &[ZInputTokenDataWithContext],
+    input_tlvs: &[Option<ZeroCopySliceBorsh<'_, u8>>],
     mint: &Pubkey,
 ) -> Result<(Vec<InAccount>, Vec<TokenData>, u64)> {
     // Collect the total number of lamports to check whether inputs and outputs
@@ -633,17 +899,31 @@ pub fn get_input_compressed_accounts_with_merkle_context_and_check_signer<const
     let mut input_token_data_vec: Vec<TokenData> =
         Vec::with_capacity(input_token_data_with_context.len());
 
-    for input_token_data in input_token_data_with_context.iter() {
-        let owner = if input_token_data.delegate_index.is_none() {
+    if signer_is_permanent_delegate && signer_is_delegate.is_none() {
+        return err!(ErrorCode::PermanentDelegateOwnerUndefined);
+    }
+    if !input_tlvs.is_empty() && input_tlvs.len() != input_token_data_with_context.len() {
+        msg!(
+            "input tlvs len {} != inputs len {}",
+            input_tlvs.len(),
+            input_token_data_with_context.len()
+        );
+        return err!(ErrorCode::InvalidTlv);
+    }
+    for (i, input_token_data) in input_token_data_with_context.iter().enumerate() {
+        let owner = if signer_is_permanent_delegate {
+            signer_is_delegate.unwrap().owner()
+        } else if input_token_data.delegate_index.is_none() {
             *signer
         } else if let Some(signer_is_delegate) = signer_is_delegate {
-            signer_is_delegate.owner
+            signer_is_delegate.owner()
         } else {
             *signer
         };
         // This is a check for convenience to throw a meaningful error.
         // The actual security results from the proof verification.
-        if signer_is_delegate.is_some()
+        if !signer_is_permanent_delegate
+            && signer_is_delegate.is_some()
             && input_token_data.delegate_index.is_some()
             && *signer
                 != remaining_accounts[input_token_data.delegate_index.unwrap() as usize].key()
@@ -661,10 +941,14 @@ pub fn get_input_compressed_accounts_with_merkle_context_and_check_signer<const
         }
 
         let compressed_account = InAccount {
-            lamports: input_token_data.lamports.unwrap_or_default(),
+            lamports: input_token_data
+                .lamports
+                .as_ref()
+                .map(|lamports| lamports.get())
+                .unwrap_or_default(),
             discriminator: TOKEN_COMPRESSED_ACCOUNT_DISCRIMINATOR,
-            merkle_context: input_token_data.merkle_context,
-            root_index: input_token_data.root_index,
+            merkle_context: (*input_token_data.merkle_context).into(),
+            root_index: input_token_data.root_index.get(),
             data_hash: [0u8; 32],
             address: None,
         };
@@ -675,17 +959,22 @@ pub fn get_input_compressed_accounts_with_merkle_context_and_check_signer<const
             AccountState::Initialized
         };
         if input_token_data.tlv.is_some() {
-            unimplemented!("Tlv is unimplemented.");
+            msg!("Input tlvs must be passed in input_tlvs.");
+            return err!(ErrorCode::InvalidTlv);
+        }
+        let tlv = get_input_tlv(input_tlvs, i);
+        if let Some(tlv) = tlv {
+            validate_tlv(tlv)?;
         }
         let token_data = TokenData {
             mint: *mint,
             owner,
-            amount: input_token_data.amount,
+            amount: input_token_data.amount.get(),
             delegate: input_token_data.delegate_index.map(|_| {
                 remaining_accounts[input_token_data.delegate_index.unwrap() as usize].key()
             }),
             state,
-            tlv: None,
+            tlv: tlv.map(|tlv| tlv.to_vec()),
         };
         input_token_data_vec.push(token_data);
         input_compressed_accounts_with_merkle_context.push(compressed_account);
@@ -697,16 +986,23 @@ pub fn get_input_compressed_accounts_with_merkle_context_and_check_signer<const
     ))
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
 pub struct PackedTokenTransferOutputData {
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
     pub owner: Pubkey,
     pub amount: u64,
     pub lamports: Option<u64>,
     pub merkle_tree_index: u8,
-    /// Placeholder for TokenExtension tlv data (unimplemented)
+    /// Token extension tlv data of the output account, see crate::tlv.
     pub tlv: Option<Vec<u8>>,
 }
 
+impl ZPackedTokenTransferOutputData<'_> {
+    pub fn tlv(&self) -> Option<&[u8]> {
+        self.tlv.as_ref().map(|tlv| tlv.as_slice())
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
 pub struct TokenTransferOutputData {
     pub owner: Pubkey,
@@ -968,11 +1264,28 @@ pub mod transfer_sdk {
             cpi_context: None,
             lamports_change_account_merkle_tree_index,
             with_transaction_hash,
+            input_tlvs: get_input_tlvs(input_token_data),
         };
 
         (remaining_accounts, inputs_struct)
     }
 
+    /// Returns the input tlvs of the instruction data,
+    /// empty if no input has token extensions.
+    pub fn get_input_tlvs(input_token_data: &[TokenData]) -> Vec<Option<Vec<u8>>> {
+        if input_token_data
+            .iter()
+            .all(|token_data| token_data.tlv.is_none())
+        {
+            Vec::new()
+        } else {
+            input_token_data
+                .iter()
+                .map(|token_data| token_data.tlv.clone())
+                .collect()
+        }
+    }
+
     pub fn create_input_output_and_remaining_accounts(
         additional_accounts: &[Pubkey],
         input_token_data: &[TokenData],
@@ -1108,6 +1421,8 @@ pub mod transfer_sdk {
 
 #[cfg(test)]
 mod test {
+    use light_zero_copy::borsh::Deserialize;
+
     use super::*;
     use crate::token_data::AccountState;
 
@@ -1145,6 +1460,199 @@ mod test {
         sum_check_test(&[100, 50], &[], Some(150 + 1), false).unwrap_err();
     }
 
+    #[test]
+    fn test_transfer_instruction_data_zero_copy() {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let rnd_option_u64 = |rng: &mut rand::rngs::ThreadRng| {
+            if rng.gen_bool(0.5) {
+                Some(rng.gen())
+            } else {
+                None
+            }
+        };
+        let rnd_tlv = |rng: &mut rand::rngs::ThreadRng| {
+            if rng.gen_bool(0.5) {
+                Some((0..rng.gen_range(0..64)).map(|_| rng.gen()).collect())
+            } else {
+                None
+            }
+        };
+        for _ in 0..1000 {
+            let num_inputs = rng.gen_range(0..8);
+            let data = CompressedTokenInstructionDataTransfer {
+                proof: if rng.gen_bool(0.5) {
+                    Some(CompressedProof {
+                        a: [rng.gen(); 32],
+                        b: [rng.gen(); 64],
+                        c: [rng.gen(); 32],
+                    })
+                } else {
+                    None
+                },
+                mint: Pubkey::new_unique(),
+                delegated_transfer: if rng.gen_bool(0.5) {
+                    Some(DelegatedTransfer {
+                        owner: Pubkey::new_unique(),
+                        delegate_change_account_index: Some(rng.gen()),
+                    })
+                } else {
+                    None
+                },
+                input_token_data_with_context: (0..num_inputs)
+                    .map(|_| InputTokenDataWithContext {
+                        amount: rng.gen(),
+                        delegate_index: if rng.gen_bool(0.5) {
+                            Some(rng.gen())
+                        } else {
+                            None
+                        },
+                        merkle_context: PackedMerkleContext {
+                            merkle_tree_pubkey_index: rng.gen(),
+                            queue_pubkey_index: rng.gen(),
+                            leaf_index: rng.gen(),
+                            prove_by_index: rng.gen(),
+                        },
+                        root_index: rng.gen(),
+                        lamports: rnd_option_u64(&mut rng),
+                        tlv: None,
+                    })
+                    .collect(),
+                output_compressed_accounts: (0..rng.gen_range(0..8))
+                    .map(|_| PackedTokenTransferOutputData {
+                        owner: Pubkey::new_unique(),
+                        amount: rng.gen(),
+                        lamports: rnd_option_u64(&mut rng),
+                        merkle_tree_index: rng.gen(),
+                        tlv: rnd_tlv(&mut rng),
+                    })
+                    .collect(),
+                is_compress: rng.gen(),
+                compress_or_decompress_amount: rnd_option_u64(&mut rng),
+                cpi_context: if rng.gen_bool(0.5) {
+                    Some(CompressedCpiContext {
+                        set_context: rng.gen(),
+                        first_set_context: rng.gen(),
+                        cpi_context_account_index: rng.gen(),
+                    })
+                } else {
+                    None
+                },
+                lamports_change_account_merkle_tree_index: Some(rng.gen()),
+                with_transaction_hash: rng.gen(),
+                input_tlvs: if rng.gen_bool(0.5) {
+                    (0..num_inputs).map(|_| rnd_tlv(&mut rng)).collect()
+                } else {
+                    Vec::new()
+                },
+            };
+            let mut bytes = data.try_to_vec().unwrap();
+            // Clients which predate input tlvs omit them.
+            if data.input_tlvs.is_empty() && rng.gen_bool(0.5) {
+                bytes.truncate(bytes.len() - 4);
+                // Clients which predate with_transaction_hash omit the last byte.
+                if !data.with_transaction_hash && rng.gen_bool(0.5) {
+                    bytes.pop();
+                }
+            }
+            let (z_data, remaining_bytes) =
+                ZCompressedTokenInstructionDataTransfer::zero_copy_at(&bytes).unwrap();
+            assert!(remaining_bytes.is_empty());
+            assert_eq!(z_data.proof.as_ref().map(|proof| **proof), data.proof);
+            assert_eq!(z_data.mint(), data.mint);
+            assert_eq!(
+                z_data
+                    .delegated_transfer
+                    .as_ref()
+                    .map(|x| (x.owner(), x.delegate_change_account_index)),
+                data.delegated_transfer
+                    .as_ref()
+                    .map(|x| (x.owner, x.delegate_change_account_index))
+            );
+            assert_eq!(
+                z_data.input_token_data_with_context.len(),
+                data.input_token_data_with_context.len()
+            );
+            for (z_input, input) in z_data
+                .input_token_data_with_context
+                .iter()
+                .zip(data.input_token_data_with_context.iter())
+            {
+                assert_eq!(z_input.amount.get(), input.amount);
+                assert_eq!(z_input.delegate_index, input.delegate_index);
+                assert_eq!(
+                    PackedMerkleContext::from(*z_input.merkle_context),
+                    input.merkle_context
+                );
+                assert_eq!(z_input.root_index.get(), input.root_index);
+                assert_eq!(z_input.lamports.as_ref().map(|x| x.get()), input.lamports);
+                assert!(z_input.tlv.is_none());
+            }
+            assert_eq!(
+                z_data
+                    .input_tlvs
+                    .iter()
+                    .map(|tlv| tlv.as_ref().map(|tlv| tlv.to_vec()))
+                    .collect::<Vec<_>>(),
+                data.input_tlvs
+            );
+            assert_eq!(
+                z_data.output_compressed_accounts.len(),
+                data.output_compressed_accounts.len()
+            );
+            for (z_output, output) in z_data
+                .output_compressed_accounts
+                .iter()
+                .zip(data.output_compressed_accounts.iter())
+            {
+                assert_eq!(Pubkey::from(*z_output.owner), output.owner);
+                assert_eq!(z_output.amount.get(), output.amount);
+                assert_eq!(z_output.lamports.as_ref().map(|x| x.get()), output.lamports);
+                assert_eq!(z_output.merkle_tree_index, output.merkle_tree_index);
+                assert_eq!(z_output.tlv(), output.tlv.as_deref());
+            }
+            assert_eq!(z_data.is_compress, data.is_compress);
+            assert_eq!(
+                z_data.compress_or_decompress_amount(),
+                data.compress_or_decompress_amount
+            );
+            assert_eq!(z_data.cpi_context(), data.cpi_context);
+            assert_eq!(
+                z_data.lamports_change_account_merkle_tree_index,
+                data.lamports_change_account_merkle_tree_index
+            );
+            assert_eq!(z_data.with_transaction_hash, data.with_transaction_hash);
+        }
+    }
+
+    #[test]
+    fn test_transfer_instruction_data_non_canonical_bool() {
+        let data = CompressedTokenInstructionDataTransfer {
+            proof: None,
+            mint: Pubkey::new_unique(),
+            delegated_transfer: None,
+            input_token_data_with_context: Vec::new(),
+            output_compressed_accounts: Vec::new(),
+            is_compress: true,
+            compress_or_decompress_amount: Some(1),
+            cpi_context: None,
+            lamports_change_account_merkle_tree_index: None,
+            with_transaction_hash: true,
+            input_tlvs: Vec::new(),
+        };
+        let bytes = data.try_to_vec().unwrap();
+        // proof option, mint, delegated transfer option, 2 vec lengths
+        let is_compress_offset = 1 + 32 + 1 + 4 + 4;
+        // with_transaction_hash, input tlvs vec length
+        let with_transaction_hash_offset = bytes.len() - 1 - 4;
+        for offset in [is_compress_offset, with_transaction_hash_offset] {
+            assert_eq!(bytes[offset], 1);
+            let mut bytes = bytes.clone();
+            bytes[offset] = 2;
+            assert!(ZCompressedTokenInstructionDataTransfer::zero_copy_at(&bytes).is_err());
+        }
+    }
+
     fn sum_check_test(
         input_amounts: &[u64],
         output_amounts: &[u64],
@@ -5,21 +5,48 @@ use anchor_spl::{token::TokenAccount, token_interface};
 use crate::{
     check_spl_token_pool_derivation,
     constants::{NUM_MAX_POOL_ACCOUNTS, POOL_SEED},
-    process_transfer::{get_cpi_signer_seeds, CompressedTokenInstructionDataTransfer},
+    process_transfer::{get_cpi_signer_seeds, ZCompressedTokenInstructionDataTransfer},
+    transfer_fee::get_mint_decimals,
     ErrorCode, TransferInstruction,
 };
 
+/// The mint account is only required for token 2022 mints with a
+/// TransferFeeConfig, see crate::transfer_fee. If it is provided spl tokens
+/// are transferred with transfer_checked.
 pub fn process_compression_or_decompression<'info>(
-    inputs: &CompressedTokenInstructionDataTransfer,
+    inputs: &ZCompressedTokenInstructionDataTransfer,
     ctx: &Context<'_, '_, '_, 'info, TransferInstruction<'info>>,
+    mint: Option<&AccountInfo<'info>>,
 ) -> Result<()> {
     if inputs.is_compress {
-        compress_spl_tokens(inputs, ctx)
+        compress_spl_tokens(inputs, ctx, mint)
     } else {
-        decompress_spl_tokens(inputs, ctx)
+        decompress_spl_tokens(inputs, ctx, mint)
     }
 }
 
+/// The token pool and compress or decompress token accounts are account
+/// infos, hence their owner is checked manually.
+pub fn check_token_account_owners(accounts: &TransferInstruction<'_>) -> Result<()> {
+    for account in [
+        accounts.token_pool_pda.as_ref(),
+        accounts.compress_or_decompress_token_account.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if *account.owner != anchor_spl::token::ID && *account.owner != spl_token_2022::ID {
+            msg!(
+                "Token account {:?} owner {:?}",
+                account.key(),
+                account.owner
+            );
+            return err!(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+        }
+    }
+    Ok(())
+}
+
 pub fn check_spl_token_pool_derivation_with_index(
     mint_bytes: &[u8],
     token_pool_pubkey: &Pubkey,
@@ -51,8 +78,9 @@ pub fn is_valid_token_pool_pda(
 }
 
 pub fn decompress_spl_tokens<'info>(
-    inputs: &CompressedTokenInstructionDataTransfer,
+    inputs: &ZCompressedTokenInstructionDataTransfer,
     ctx: &Context<'_, '_, '_, 'info, TransferInstruction<'info>>,
+    mint: Option<&AccountInfo<'info>>,
 ) -> Result<()> {
     let recipient = match ctx.accounts.compress_or_decompress_token_account.as_ref() {
         Some(compression_recipient) => compression_recipient.to_account_info(),
@@ -62,14 +90,14 @@ pub fn decompress_spl_tokens<'info>(
         Some(token_pool_pda) => token_pool_pda.to_account_info(),
         None => return err!(ErrorCode::CompressedPdaUndefinedForDecompress),
     };
-    let amount = match inputs.compress_or_decompress_amount {
+    let amount = match inputs.compress_or_decompress_amount() {
         Some(amount) => amount,
         None => return err!(ErrorCode::DeCompressAmountUndefinedForDecompress),
     };
     invoke_token_program_with_multiple_token_pool_accounts::<false>(
         ctx.remaining_accounts,
-        &inputs.mint.key().to_bytes(),
-        None,
+        &inputs.mint.to_bytes(),
+        mint.cloned(),
         Some(recipient),
         ctx.accounts.cpi_authority_pda.to_account_info(),
         ctx.accounts
@@ -93,6 +121,7 @@ pub fn decompress_spl_tokens<'info>(
 /// 6. Return error if the token pool account is not derived
 ///     from any combination of mint and bump.
 /// 7. Burn or transfer the amount from the token pool account.
+///    Transfers use transfer_checked if the mint account is provided.
 /// 8. Remove bump from the list of bumps.
 /// 9. Reduce the amount by the transferred or burned amount.
 /// 10. Continue until the amount is zero.
@@ -139,6 +168,16 @@ pub fn invoke_token_program_with_multiple_token_pool_accounts<'info, const IS_BU
                         action_amount,
                         token_pool_amount,
                     )?;
+                } else if let Some(mint) = mint.as_ref() {
+                    crate::spl_compression::spl_token_transfer_checked_cpi_with_signer(
+                        token_pool_pda.to_account_info(),
+                        mint.to_account_info(),
+                        recipient.clone().unwrap(),
+                        cpi_authority_pda.to_account_info(),
+                        token_program.to_account_info(),
+                        action_amount,
+                        get_mint_decimals(mint)?,
+                    )?;
                 } else {
                     crate::spl_compression::spl_token_transfer_cpi_with_signer(
                         token_pool_pda.to_account_info(),
@@ -179,34 +218,51 @@ pub fn invoke_token_program_with_multiple_token_pool_accounts<'info, const IS_BU
 }
 
 pub fn compress_spl_tokens<'info>(
-    inputs: &CompressedTokenInstructionDataTransfer,
+    inputs: &ZCompressedTokenInstructionDataTransfer,
     ctx: &Context<'_, '_, '_, 'info, TransferInstruction<'info>>,
+    mint: Option<&AccountInfo<'info>>,
 ) -> Result<()> {
     let recipient_token_pool = match ctx.accounts.token_pool_pda.as_ref() {
         Some(token_pool_pda) => token_pool_pda.to_account_info(),
         None => return err!(ErrorCode::CompressedPdaUndefinedForCompress),
     };
-    let amount = match inputs.compress_or_decompress_amount {
+    let amount = match inputs.compress_or_decompress_amount() {
         Some(amount) => amount,
         None => return err!(ErrorCode::DeCompressAmountUndefinedForCompress),
     };
 
-    check_spl_token_pool_derivation(&recipient_token_pool.key(), &inputs.mint)?;
-    spl_token_transfer(
-        ctx.accounts
-            .compress_or_decompress_token_account
-            .as_ref()
-            .unwrap()
-            .to_account_info(),
-        recipient_token_pool.to_account_info(),
-        ctx.accounts.authority.to_account_info(),
-        ctx.accounts
-            .token_program
-            .as_ref()
-            .unwrap()
-            .to_account_info(),
-        amount,
-    )
+    check_spl_token_pool_derivation(&recipient_token_pool.key(), &inputs.mint())?;
+    let from = ctx
+        .accounts
+        .compress_or_decompress_token_account
+        .as_ref()
+        .unwrap()
+        .to_account_info();
+    let token_program = ctx
+        .accounts
+        .token_program
+        .as_ref()
+        .unwrap()
+        .to_account_info();
+    if let Some(mint) = mint {
+        spl_token_transfer_checked(
+            from,
+            mint.to_account_info(),
+            recipient_token_pool.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            token_program,
+            amount,
+            get_mint_decimals(mint)?,
+        )
+    } else {
+        spl_token_transfer(
+            from,
+            recipient_token_pool.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            token_program,
+            amount,
+        )
+    }
 }
 
 /// Invoke the spl token burn instruction with cpi authority pda as signer.
@@ -230,6 +286,60 @@ pub fn spl_token_transfer_cpi_with_signer<'info>(
     anchor_spl::token_interface::transfer(cpi_ctx, amount)
 }
 
+/// Invoke the spl token transfer checked instruction with cpi authority pda as
+/// signer. Used to decompress spl tokens of mints with transfer fees.
+#[allow(clippy::too_many_arguments)]
+pub fn spl_token_transfer_checked_cpi_with_signer<'info>(
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let signer_seeds = get_cpi_signer_seeds();
+    let signer_seeds_ref = &[&signer_seeds[..]];
+
+    let accounts = token_interface::TransferChecked {
+        from,
+        mint,
+        to,
+        authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, signer_seeds_ref);
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, amount, decimals)
+}
+
+/// Invoke the spl token transfer checked instruction with transaction signer.
+/// Used to compress spl tokens of mints with transfer fees.
+pub fn spl_token_transfer_checked<'info>(
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let instruction = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        from.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+
+    anchor_lang::solana_program::program::invoke(
+        &instruction,
+        &[from, mint, to, authority, token_program],
+    )?;
+    Ok(())
+}
+
 /// Invoke the spl token transfer instruction with transaction signer.
 /// Used to compress spl tokens.
 pub fn spl_token_transfer<'info>(
@@ -0,0 +1,808 @@
+use anchor_lang::prelude::*;
+use light_compressed_account::{
+    hash_to_bn254_field_size_be,
+    instruction_data::{
+        compressed_proof::CompressedProof, cpi_context::CompressedCpiContext,
+        data::OutputCompressedAccountWithPackedContext, with_readonly::InAccount,
+        zero_copy::ZCompressedCpiContext,
+    },
+};
+use light_zero_copy::{borsh::Deserialize, ZeroCopy};
+use zerocopy::Ref;
+
+use crate::{
+    constants::NOT_FROZEN,
+    mint_extensions::get_mint_extensions,
+    process_transfer::{
+        add_data_hash_to_input_compressed_accounts, cpi_execute_compressed_transaction_transfer,
+        create_output_compressed_accounts,
+        get_input_compressed_accounts_with_merkle_context_and_check_signer, get_input_tlv,
+        sum_check, InputTokenDataWithContext,
+    },
+    tlv::{get_shared_input_tlv, has_extension, TokenExtensionType},
+    transfer_fee::find_mint_account,
+    vesting::check_inputs_unlocked,
+    ErrorCode, SwapInstruction,
+};
+
+/// Tokens of one mint which the owner of the inputs sends to the other party
+/// of the swap.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct CompressedTokenSwapLeg {
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
+    pub mint: Pubkey,
+    pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    /// Amount sent to the other party.
+    pub amount: u64,
+    /// Index in remaining accounts.
+    pub recipient_merkle_tree_index: u8,
+    /// Index in remaining accounts.
+    pub change_account_merkle_tree_index: u8,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+}
+
+impl ZCompressedTokenSwapLeg<'_> {
+    pub fn mint(&self) -> Pubkey {
+        (*self.mint).into()
+    }
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct CompressedTokenInstructionDataSwap {
+    /// One proof for the inputs of both legs.
+    pub proof: Option<CompressedProof>,
+    /// Inputs owned by the authority, sent to the counterparty.
+    pub authority_leg: CompressedTokenSwapLeg,
+    /// Inputs owned by the counterparty, sent to the authority.
+    pub counterparty_leg: CompressedTokenSwapLeg,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
+    pub cpi_context: Option<CompressedCpiContext>,
+}
+
+/// Processes a swap instruction.
+/// - the authority sends authority_leg.amount of its mint to the counterparty.
+/// - the counterparty sends counterparty_leg.amount of its mint to the
+///   authority.
+/// - both legs are settled in one compressed transaction, either both
+///   transfers succeed or none.
+/// 1. unpack instruction data and check that inputs are unlocked
+/// 2. create input and output compressed accounts of both legs
+/// 3. execute compressed transaction
+pub fn process_swap<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapInstruction<'info>>,
+    inputs: Vec<u8>,
+) -> Result<()> {
+    let (inputs, _) =
+        ZCompressedTokenInstructionDataSwap::zero_copy_at(&inputs).map_err(ProgramError::from)?;
+    check_inputs_unlocked(
+        [&inputs.authority_leg, &inputs.counterparty_leg]
+            .into_iter()
+            .flat_map(|leg| {
+                leg.input_token_data_with_context
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| (x.amount.get(), get_input_tlv(&leg.input_tlvs, i)))
+            }),
+        Clock::get()?.slot,
+    )?;
+    let (compressed_input_accounts, output_compressed_accounts) =
+        create_input_and_output_accounts_swap(
+            &inputs,
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.counterparty.key(),
+            ctx.remaining_accounts,
+        )?;
+    cpi_execute_compressed_transaction_transfer(
+        ctx.accounts,
+        compressed_input_accounts,
+        output_compressed_accounts,
+        false,
+        inputs.proof.as_ref().map(|proof| **proof),
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+/// Creates the input and output compressed accounts of both legs. Inputs of
+/// the authority leg come first, followed by the inputs of the counterparty
+/// leg, outputs are ordered the same way.
+pub fn create_input_and_output_accounts_swap(
+    inputs: &ZCompressedTokenInstructionDataSwap,
+    authority: &Pubkey,
+    counterparty: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    Vec<InAccount>,
+    Vec<OutputCompressedAccountWithPackedContext>,
+)> {
+    if inputs.authority_leg.mint() == inputs.counterparty_leg.mint() {
+        return err!(ErrorCode::SwapMintsNotDistinct);
+    }
+    let (mut compressed_input_accounts, mut output_compressed_accounts) = create_swap_leg_accounts(
+        &inputs.authority_leg,
+        authority,
+        counterparty,
+        remaining_accounts,
+    )?;
+    let (counterparty_input_accounts, counterparty_output_accounts) = create_swap_leg_accounts(
+        &inputs.counterparty_leg,
+        counterparty,
+        authority,
+        remaining_accounts,
+    )?;
+    compressed_input_accounts.extend(counterparty_input_accounts);
+    output_compressed_accounts.extend(counterparty_output_accounts);
+    Ok((compressed_input_accounts, output_compressed_accounts))
+}
+
+/// Creates the accounts of one swap leg:
+/// 1. an output with leg.amount owned by the recipient,
+/// 2. a change output owned by the owner if tokens or lamports remain.
+///
+/// Inputs and outputs of the leg are sum checked. Input lamports are kept
+/// in the change output. Both outputs preserve the extensions of the inputs
+/// except the delegated amount, see get_shared_input_tlv. The mint account
+/// of the leg has to be passed in remaining accounts, mints with transfer
+/// fee or pausable config are not supported.
+fn create_swap_leg_accounts(
+    leg: &ZCompressedTokenSwapLeg,
+    owner: &Pubkey,
+    recipient: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    Vec<InAccount>,
+    Vec<OutputCompressedAccountWithPackedContext>,
+)> {
+    if leg.input_token_data_with_context.is_empty() {
+        return err!(ErrorCode::NoInputTokenAccountsProvided);
+    }
+    let mint = leg.mint();
+    check_swap_mint(remaining_accounts, &mint)?;
+    let (mut compressed_input_accounts, input_token_data, sum_lamports) =
+        get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
+            owner,
+            None,
+            false,
+            remaining_accounts,
+            &leg.input_token_data_with_context,
+            &leg.input_tlvs,
+            &mint,
+        )?;
+    for token_data in input_token_data.iter() {
+        if has_extension(&token_data.tlv, TokenExtensionType::TransferFee)?
+            || has_extension(&token_data.tlv, TokenExtensionType::Pausable)?
+        {
+            return err!(ErrorCode::SwapMintExtensionNotSupported);
+        }
+    }
+    let amount = leg.amount.get();
+    let mut sum_inputs: u64 = 0;
+    for token_data in input_token_data.iter() {
+        sum_inputs = sum_inputs
+            .checked_add(token_data.amount)
+            .ok_or(ErrorCode::ComputeInputSumFailed)?;
+    }
+    // Outputs preserve the extensions of the inputs except the delegated
+    // amount, swap outputs are not delegated.
+    let tlv = get_shared_input_tlv(input_token_data.as_slice())?;
+    let change_amount = match sum_inputs.checked_sub(amount) {
+        Some(change_amount) => change_amount,
+        None => return err!(ErrorCode::ArithmeticUnderflow),
+    };
+    let (pubkeys, amounts, lamports, tlvs, merkle_tree_indices) =
+        if change_amount > 0 || sum_lamports > 0 {
+            let lamports = if sum_lamports > 0 {
+                Some(vec![None, Some(sum_lamports)])
+            } else {
+                None
+            };
+            (
+                vec![*recipient, *owner],
+                vec![amount, change_amount],
+                lamports,
+                vec![tlv.clone(), tlv],
+                vec![
+                    leg.recipient_merkle_tree_index,
+                    leg.change_account_merkle_tree_index,
+                ],
+            )
+        } else {
+            (
+                vec![*recipient],
+                vec![amount],
+                None,
+                vec![tlv],
+                vec![leg.recipient_merkle_tree_index],
+            )
+        };
+    sum_check(&input_token_data, &amounts, None, false)?;
+
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
+    let mut output_compressed_accounts =
+        vec![OutputCompressedAccountWithPackedContext::default(); amounts.len()];
+    create_output_compressed_accounts::<NOT_FROZEN>(
+        &mut output_compressed_accounts,
+        mint,
+        pubkeys.as_slice(),
+        None,
+        None,
+        amounts.as_slice(),
+        lamports,
+        Some(tlvs.as_slice()),
+        &hashed_mint,
+        &merkle_tree_indices,
+        remaining_accounts,
+    )?;
+    add_data_hash_to_input_compressed_accounts::<NOT_FROZEN>(
+        &mut compressed_input_accounts,
+        input_token_data.as_slice(),
+        &hashed_mint,
+        remaining_accounts,
+    )?;
+    Ok((compressed_input_accounts, output_compressed_accounts))
+}
+
+/// Checks that the mint account is provided, is an spl token or token 2022
+/// mint, and has neither a transfer fee nor a pausable config.
+fn check_swap_mint(remaining_accounts: &[AccountInfo<'_>], mint: &Pubkey) -> Result<()> {
+    let mint_account = match find_mint_account(remaining_accounts, mint) {
+        Some(mint_account) => mint_account,
+        None => {
+            msg!("Mint account {:?} is missing.", mint);
+            return err!(ErrorCode::MintAccountUndefined);
+        }
+    };
+    if *mint_account.owner != anchor_spl::token::ID && *mint_account.owner != spl_token_2022::ID {
+        msg!("Mint account {:?} owner {:?}", mint, mint_account.owner);
+        return err!(ErrorCode::InvalidMint);
+    }
+    if get_mint_extensions(mint_account)?.requires_transfer_checked() {
+        return err!(ErrorCode::SwapMintExtensionNotSupported);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "solana"))]
+pub mod sdk {
+
+    use std::result::Result;
+
+    use anchor_lang::{AnchorSerialize, InstructionData, ToAccountMetas};
+    use light_compressed_account::compressed_account::{CompressedAccount, MerkleContext};
+    use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+    use super::*;
+    use crate::{
+        process_transfer::{
+            get_cpi_authority_pda,
+            transfer_sdk::{
+                create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+                TransferSdkError,
+            },
+        },
+        token_data::TokenData,
+    };
+
+    /// Input compressed accounts, merkle contexts and root indices are
+    /// ordered authority inputs first, followed by counterparty inputs.
+    pub struct CreateSwapInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub counterparty: Pubkey,
+        pub root_indices: Vec<Option<u16>>,
+        pub proof: Option<CompressedProof>,
+        pub authority_input_token_data: Vec<TokenData>,
+        pub counterparty_input_token_data: Vec<TokenData>,
+        pub input_compressed_accounts: Vec<CompressedAccount>,
+        pub input_merkle_contexts: Vec<MerkleContext>,
+        pub authority_mint: Pubkey,
+        pub counterparty_mint: Pubkey,
+        /// Amount of the authority mint sent to the counterparty.
+        pub authority_amount: u64,
+        /// Amount of the counterparty mint sent to the authority.
+        pub counterparty_amount: u64,
+        pub output_merkle_tree: Pubkey,
+    }
+
+    pub fn create_swap_instruction(
+        inputs: CreateSwapInstructionInputs,
+    ) -> Result<Instruction, TransferSdkError> {
+        let input_token_data = [
+            inputs.authority_input_token_data.as_slice(),
+            inputs.counterparty_input_token_data.as_slice(),
+        ]
+        .concat();
+        let (remaining_accounts, mut input_token_data_with_context, _) =
+            create_input_output_and_remaining_accounts(
+                &[inputs.output_merkle_tree],
+                &input_token_data,
+                &inputs.input_compressed_accounts,
+                &inputs.input_merkle_contexts,
+                &inputs.root_indices,
+                &Vec::new(),
+            );
+        let output_merkle_tree_index = match remaining_accounts.get(&inputs.output_merkle_tree) {
+            Some(output_merkle_tree_index) => *output_merkle_tree_index as u8,
+            None => return Err(TransferSdkError::AccountNotFound),
+        };
+        let counterparty_input_token_data_with_context =
+            input_token_data_with_context.split_off(inputs.authority_input_token_data.len());
+        let inputs_struct = CompressedTokenInstructionDataSwap {
+            proof: inputs.proof,
+            authority_leg: CompressedTokenSwapLeg {
+                mint: inputs.authority_mint,
+                input_token_data_with_context,
+                amount: inputs.authority_amount,
+                recipient_merkle_tree_index: output_merkle_tree_index,
+                change_account_merkle_tree_index: output_merkle_tree_index,
+                input_tlvs: get_input_tlvs(&inputs.authority_input_token_data),
+            },
+            counterparty_leg: CompressedTokenSwapLeg {
+                mint: inputs.counterparty_mint,
+                input_token_data_with_context: counterparty_input_token_data_with_context,
+                amount: inputs.counterparty_amount,
+                recipient_merkle_tree_index: output_merkle_tree_index,
+                change_account_merkle_tree_index: output_merkle_tree_index,
+                input_tlvs: get_input_tlvs(&inputs.counterparty_input_token_data),
+            },
+            cpi_context: None,
+        };
+        let mut remaining_accounts = to_account_metas(remaining_accounts);
+        // The program checks the extensions of both mints.
+        for mint in [inputs.authority_mint, inputs.counterparty_mint] {
+            remaining_accounts.push(AccountMeta::new_readonly(mint, false));
+        }
+        let mut serialized_ix_data = Vec::new();
+        CompressedTokenInstructionDataSwap::serialize(&inputs_struct, &mut serialized_ix_data)
+            .map_err(|_| TransferSdkError::SerializationError)?;
+
+        let (cpi_authority_pda, _) = get_cpi_authority_pda();
+        let instruction_data = crate::instruction::Swap {
+            inputs: serialized_ix_data,
+        };
+
+        let accounts = crate::accounts::SwapInstruction {
+            fee_payer: inputs.fee_payer,
+            authority: inputs.authority,
+            counterparty: inputs.counterparty,
+            cpi_authority_pda,
+            light_system_program: light_system_program::ID,
+            registered_program_pda: light_system_program::utils::get_registered_program_pda(
+                &light_system_program::ID,
+            ),
+            noop_program: Pubkey::new_from_array(
+                account_compression::utils::constants::NOOP_PUBKEY,
+            ),
+            account_compression_authority: light_system_program::utils::get_cpi_authority_pda(
+                &light_system_program::ID,
+            ),
+            account_compression_program: account_compression::ID,
+            self_program: crate::ID,
+            system_program: solana_sdk::system_program::ID,
+        };
+
+        Ok(Instruction {
+            program_id: crate::ID,
+            accounts: [accounts.to_account_metas(Some(true)), remaining_accounts].concat(),
+
+            data: instruction_data.data(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use account_compression::StateMerkleTreeAccount;
+    use anchor_lang::{solana_program::account_info::AccountInfo, Discriminator};
+    use light_compressed_account::compressed_account::PackedMerkleContext;
+
+    use super::*;
+    use crate::{
+        freeze::test_freeze::create_expected_token_output_accounts,
+        tlv::{pack_tlv, TokenExtension},
+        token_data::AccountState,
+        TokenData,
+    };
+
+    fn input_token_data_with_context(
+        amount: u64,
+        leaf_index: u32,
+        lamports: Option<u64>,
+        tlv: Option<Vec<u8>>,
+    ) -> InputTokenDataWithContext {
+        InputTokenDataWithContext {
+            amount,
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: 0,
+                queue_pubkey_index: 1,
+                leaf_index,
+                prove_by_index: false,
+            },
+            root_index: 0,
+            delegate_index: None,
+            lamports,
+            tlv,
+        }
+    }
+
+    /// The tlvs of the inputs are passed as input tlvs of the leg.
+    fn swap_leg(
+        mint: Pubkey,
+        mut input_token_data_with_context: Vec<InputTokenDataWithContext>,
+        amount: u64,
+    ) -> CompressedTokenSwapLeg {
+        let input_tlvs = input_token_data_with_context
+            .iter_mut()
+            .map(|x| x.tlv.take())
+            .collect();
+        CompressedTokenSwapLeg {
+            mint,
+            input_token_data_with_context,
+            amount,
+            recipient_merkle_tree_index: 0,
+            change_account_merkle_tree_index: 2,
+            input_tlvs,
+        }
+    }
+
+    fn swap(
+        authority_leg: CompressedTokenSwapLeg,
+        counterparty_leg: CompressedTokenSwapLeg,
+        authority: &Pubkey,
+        counterparty: &Pubkey,
+        remaining_accounts: &[AccountInfo<'_>],
+    ) -> Result<(
+        Vec<InAccount>,
+        Vec<OutputCompressedAccountWithPackedContext>,
+    )> {
+        let inputs = CompressedTokenInstructionDataSwap {
+            proof: Some(CompressedProof::default()),
+            authority_leg,
+            counterparty_leg,
+            cpi_context: None,
+        };
+        let inputs = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataSwap::zero_copy_at(&inputs).unwrap();
+        create_input_and_output_accounts_swap(&inputs, authority, counterparty, remaining_accounts)
+    }
+
+    #[test]
+    fn test_swap() {
+        let merkle_tree_pubkey = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports = 0;
+        let mut merkle_tree_account_data = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let nullifier_queue_pubkey = Pubkey::new_unique();
+        let mut nullifier_queue_account_lamports = 0;
+        let mut nullifier_queue_account_data = Vec::new();
+        let merkle_tree_pubkey_1 = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports_1 = 0;
+        let mut merkle_tree_account_data_1 = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let mint_a = Pubkey::new_unique();
+        let mut mint_a_lamports = 0;
+        let mut mint_a_data = Vec::new();
+        let mint_b = Pubkey::new_unique();
+        let mut mint_b_lamports = 0;
+        let mut mint_b_data = Vec::new();
+        let invalid_mint = Pubkey::new_unique();
+        let mut invalid_mint_lamports = 0;
+        let mut invalid_mint_data = Vec::new();
+        let remaining_accounts = vec![
+            AccountInfo::new(
+                &merkle_tree_pubkey,
+                false,
+                false,
+                &mut merkle_tree_account_lamports,
+                &mut merkle_tree_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &nullifier_queue_pubkey,
+                false,
+                false,
+                &mut nullifier_queue_account_lamports,
+                &mut nullifier_queue_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &merkle_tree_pubkey_1,
+                false,
+                false,
+                &mut merkle_tree_account_lamports_1,
+                &mut merkle_tree_account_data_1,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &mint_a,
+                false,
+                false,
+                &mut mint_a_lamports,
+                &mut mint_a_data,
+                &anchor_spl::token::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &mint_b,
+                false,
+                false,
+                &mut mint_b_lamports,
+                &mut mint_b_data,
+                &anchor_spl::token::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &invalid_mint,
+                false,
+                false,
+                &mut invalid_mint_lamports,
+                &mut invalid_mint_data,
+                &crate::ID,
+                false,
+                0,
+            ),
+        ];
+        let authority = Pubkey::new_unique();
+        let counterparty = Pubkey::new_unique();
+
+        // 1. Functional: authority keeps change, counterparty swaps its
+        //    whole balance and keeps its lamports in the change account.
+        {
+            let (compressed_input_accounts, output_compressed_accounts) = swap(
+                swap_leg(
+                    mint_a,
+                    vec![
+                        input_token_data_with_context(100, 1, None, None),
+                        input_token_data_with_context(101, 2, None, None),
+                    ],
+                    150,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, Some(1000), None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            )
+            .unwrap();
+            assert_eq!(compressed_input_accounts.len(), 3);
+            assert_eq!(output_compressed_accounts.len(), 4);
+            let token_data = |mint, owner, amount| TokenData {
+                mint,
+                owner,
+                amount,
+                delegate: None,
+                state: AccountState::Initialized,
+                tlv: None,
+            };
+            let mut expected_compressed_output_accounts = create_expected_token_output_accounts(
+                vec![
+                    token_data(mint_a, counterparty, 150),
+                    token_data(mint_a, authority, 51),
+                    token_data(mint_b, authority, 300),
+                    token_data(mint_b, counterparty, 0),
+                ],
+                vec![0, 2, 0, 2],
+            );
+            expected_compressed_output_accounts[3]
+                .compressed_account
+                .lamports = 1000;
+            assert_eq!(
+                output_compressed_accounts,
+                expected_compressed_output_accounts
+            );
+        }
+        // 2. Functional: no change accounts if both parties swap their whole
+        //    balance.
+        {
+            let (compressed_input_accounts, output_compressed_accounts) = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            )
+            .unwrap();
+            assert_eq!(compressed_input_accounts.len(), 2);
+            let token_data = |mint, owner, amount| TokenData {
+                mint,
+                owner,
+                amount,
+                delegate: None,
+                state: AccountState::Initialized,
+                tlv: None,
+            };
+            let expected_compressed_output_accounts = create_expected_token_output_accounts(
+                vec![
+                    token_data(mint_a, counterparty, 100),
+                    token_data(mint_b, authority, 300),
+                ],
+                vec![0, 0],
+            );
+            assert_eq!(
+                output_compressed_accounts,
+                expected_compressed_output_accounts
+            );
+        }
+        // 3. Failing: legs of the same mint
+        {
+            let result = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::SwapMintsNotDistinct.into()));
+        }
+        // 4. Failing: amount exceeds the inputs of the counterparty leg
+        {
+            let result = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    301,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::ArithmeticUnderflow.into()));
+        }
+        // 5. Failing: counterparty leg without inputs
+        {
+            let result = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(mint_b, Vec::new(), 0),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::NoInputTokenAccountsProvided.into()));
+        }
+        // 6. Failing: inputs of a transfer fee mint
+        {
+            let tlv = pack_tlv(&[TokenExtension::TransferFee]).unwrap();
+            let result = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, Some(tlv))],
+                    100,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::SwapMintExtensionNotSupported.into()));
+        }
+        // 7. Failing: mint account of the counterparty leg is missing
+        {
+            let result = swap(
+                swap_leg(
+                    mint_a,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(
+                    Pubkey::new_unique(),
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::MintAccountUndefined.into()));
+        }
+        // 8. Failing: mint account is not owned by a token program
+        {
+            let result = swap(
+                swap_leg(
+                    invalid_mint,
+                    vec![input_token_data_with_context(100, 1, None, None)],
+                    100,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            );
+            assert_eq!(result, Err(ErrorCode::InvalidMint.into()));
+        }
+        // 9. Functional: recipient and change outputs preserve the extensions
+        //    of the inputs without the delegated amount.
+        {
+            let memo = TokenExtension::Memo(vec![1, 2, 3]);
+            let tlv = pack_tlv(&[memo.clone()]).unwrap();
+            let delegated_tlv = pack_tlv(&[memo, TokenExtension::DelegatedAmount(50)]).unwrap();
+            let (_, output_compressed_accounts) = swap(
+                swap_leg(
+                    mint_a,
+                    vec![
+                        input_token_data_with_context(100, 1, None, Some(tlv.clone())),
+                        input_token_data_with_context(101, 2, None, Some(delegated_tlv)),
+                    ],
+                    150,
+                ),
+                swap_leg(
+                    mint_b,
+                    vec![input_token_data_with_context(300, 3, None, None)],
+                    300,
+                ),
+                &authority,
+                &counterparty,
+                &remaining_accounts,
+            )
+            .unwrap();
+            let token_data = |mint, owner, amount, tlv| TokenData {
+                mint,
+                owner,
+                amount,
+                delegate: None,
+                state: AccountState::Initialized,
+                tlv,
+            };
+            let expected_compressed_output_accounts = create_expected_token_output_accounts(
+                vec![
+                    token_data(mint_a, counterparty, 150, Some(tlv.clone())),
+                    token_data(mint_a, authority, 51, Some(tlv)),
+                    token_data(mint_b, authority, 300, None),
+                ],
+                vec![0, 2, 0],
+            );
+            assert_eq!(
+                output_compressed_accounts,
+                expected_compressed_output_accounts
+            );
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use anchor_lang::prelude::*;
+use light_compressed_account::hashv_to_bn254_field_size_be;
+
+use crate::{token_data::TokenData, vesting::TimeLock, ErrorCode};
+
+/// Maximum length of the serialized tlv data of a compressed token account.
+pub const MAX_TLV_LEN: usize = 512;
+/// Maximum length of a memo extension value.
+pub const MAX_MEMO_LEN: usize = 256;
+/// Length of an entry header: u16 extension type + u16 value length.
+pub const TLV_ENTRY_HEADER_LEN: usize = 4;
+/// Domain separator of the tlv hash. The tlv hash has its own position in the
+/// token data hash, see TokenData::hash_inputs_with_hashed_values_and_tlv.
+pub const TLV_HASH_DOMAIN: &[u8] = b"tlv";
+
+/// Token extensions which can be stored in the tlv data of a compressed token
+/// account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u16)]
+pub enum TokenExtensionType {
+    /// Utf-8 memo attached to the account.
+    Memo = 1,
+    /// Transfer hook program id followed by opaque hook metadata.
+    /// The compressed token program stores the metadata but does not invoke
+    /// the hook program.
+    TransferHook = 2,
+    /// Marks accounts of mints with a token 2022 TransferFeeConfig. The value
+    /// is empty. The marker is set by mint_to and compression and preserved
+    /// by every instruction so that transfers of marked accounts always
+    /// charge the transfer fee, see crate::transfer_fee.
+    TransferFee = 3,
+    /// Marks accounts of mints with a token 2022 PausableConfig. The value is
+    /// empty. Transfers of marked accounts require the mint account and fail
+    /// while the mint is paused, see crate::mint_extensions.
+    Pausable = 4,
+    /// Start and end slot of a linear vesting schedule, u64 le each.
+    /// Transfers and burns of accounts which are not completely unlocked
+    /// fail, see crate::vesting.
+    TimeLock = 5,
+    /// Amount the delegate can transfer or burn, u64 le. Set by approve
+    /// with allowance and on delegated change accounts of delegated transfers
+    /// and burns. Delegated accounts without the extension are delegated
+    /// completely, see crate::delegation::get_delegated_amount.
+    DelegatedAmount = 6,
+}
+
+impl TryFrom<u16> for TokenExtensionType {
+    type Error = ErrorCode;
+
+    fn try_from(value: u16) -> std::result::Result<Self, Self::Error> {
+        match value {
+            1 => Ok(TokenExtensionType::Memo),
+            2 => Ok(TokenExtensionType::TransferHook),
+            3 => Ok(TokenExtensionType::TransferFee),
+            4 => Ok(TokenExtensionType::Pausable),
+            5 => Ok(TokenExtensionType::TimeLock),
+            6 => Ok(TokenExtensionType::DelegatedAmount),
+            _ => Err(ErrorCode::UnknownTokenExtension),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenExtension {
+    Memo(Vec<u8>),
+    TransferHook { program_id: Pubkey, data: Vec<u8> },
+    TransferFee,
+    Pausable,
+    TimeLock(TimeLock),
+    DelegatedAmount(u64),
+}
+
+impl TokenExtension {
+    pub fn extension_type(&self) -> TokenExtensionType {
+        match self {
+            TokenExtension::Memo(_) => TokenExtensionType::Memo,
+            TokenExtension::TransferHook { .. } => TokenExtensionType::TransferHook,
+            TokenExtension::TransferFee => TokenExtensionType::TransferFee,
+            TokenExtension::Pausable => TokenExtensionType::Pausable,
+            TokenExtension::TimeLock(_) => TokenExtensionType::TimeLock,
+            TokenExtension::DelegatedAmount(_) => TokenExtensionType::DelegatedAmount,
+        }
+    }
+}
+
+/// Tlv encoding:
+/// 1. every entry is `[extension_type: u16 le][len: u16 le][value; len]`
+/// 2. entries are sorted by strictly increasing extension type, hence every
+///    extension can exist at most once and every set of extensions has
+///    exactly one encoding
+/// 3. the complete tlv is not empty and at most MAX_TLV_LEN bytes,
+///    token data without extensions uses `tlv: None`.
+pub fn validate_tlv(tlv: &[u8]) -> Result<()> {
+    if tlv.is_empty() || tlv.len() > MAX_TLV_LEN {
+        msg!("Invalid tlv length {}", tlv.len());
+        return err!(ErrorCode::InvalidTlv);
+    }
+    let mut offset = 0;
+    let mut previous_type = 0u16;
+    while offset < tlv.len() {
+        let (extension_type, value) = read_entry(tlv, &mut offset)?;
+        if extension_type <= previous_type {
+            msg!("Tlv extensions must be sorted and unique.");
+            return err!(ErrorCode::InvalidTlv);
+        }
+        previous_type = extension_type;
+        validate_extension_value(TokenExtensionType::try_from(extension_type)?, value)?;
+    }
+    Ok(())
+}
+
+fn read_entry<'a>(tlv: &'a [u8], offset: &mut usize) -> Result<(u16, &'a [u8])> {
+    let header_end = *offset + TLV_ENTRY_HEADER_LEN;
+    if header_end > tlv.len() {
+        return err!(ErrorCode::InvalidTlv);
+    }
+    let extension_type = u16::from_le_bytes([tlv[*offset], tlv[*offset + 1]]);
+    let len = u16::from_le_bytes([tlv[*offset + 2], tlv[*offset + 3]]) as usize;
+    let value_end = header_end + len;
+    if value_end > tlv.len() {
+        return err!(ErrorCode::InvalidTlv);
+    }
+    *offset = value_end;
+    Ok((extension_type, &tlv[header_end..value_end]))
+}
+
+fn validate_extension_value(extension_type: TokenExtensionType, value: &[u8]) -> Result<()> {
+    match extension_type {
+        TokenExtensionType::Memo => {
+            if value.is_empty() || value.len() > MAX_MEMO_LEN {
+                msg!("Invalid memo length {}", value.len());
+                return err!(ErrorCode::InvalidTlv);
+            }
+            if std::str::from_utf8(value).is_err() {
+                msg!("Memo is not valid utf-8.");
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+        TokenExtensionType::TransferHook => {
+            if value.len() < 32 {
+                msg!("Transfer hook extension is missing the program id.");
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+        TokenExtensionType::TransferFee => {
+            if !value.is_empty() {
+                msg!("Transfer fee extension must be empty.");
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+        TokenExtensionType::Pausable => {
+            if !value.is_empty() {
+                msg!("Pausable extension must be empty.");
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+        TokenExtensionType::TimeLock => {
+            if value.len() != 16 {
+                msg!("Invalid time lock extension length {}", value.len());
+                return err!(ErrorCode::InvalidTlv);
+            }
+            let time_lock = TimeLock::from_bytes(value);
+            if time_lock.start_slot > time_lock.end_slot {
+                msg!("Time lock start slot is greater than end slot.");
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+        TokenExtensionType::DelegatedAmount => {
+            if value.len() != 8 {
+                msg!("Invalid delegated amount extension length {}", value.len());
+                return err!(ErrorCode::InvalidTlv);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether a valid tlv contains an extension of extension_type.
+pub fn has_extension(tlv: &Option<Vec<u8>>, extension_type: TokenExtensionType) -> Result<bool> {
+    let tlv = match tlv {
+        Some(tlv) => tlv,
+        None => return Ok(false),
+    };
+    let mut offset = 0;
+    while offset < tlv.len() {
+        let (entry_type, _) = read_entry(tlv, &mut offset)?;
+        if entry_type == extension_type as u16 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Adds an extension to a tlv, the extension is not added twice.
+pub fn add_extension(tlv: &Option<Vec<u8>>, extension: TokenExtension) -> Result<Vec<u8>> {
+    let mut extensions = match tlv {
+        Some(tlv) => unpack_tlv(tlv)?,
+        None => Vec::new(),
+    };
+    if !extensions
+        .iter()
+        .any(|item| item.extension_type() == extension.extension_type())
+    {
+        extensions.push(extension);
+    }
+    pack_tlv(&extensions)
+}
+
+/// Removes an extension from a tlv.
+/// Returns None if no other extensions remain.
+pub fn remove_extension(
+    tlv: &Option<Vec<u8>>,
+    extension_type: TokenExtensionType,
+) -> Result<Option<Vec<u8>>> {
+    let extensions = match tlv {
+        Some(tlv) => unpack_tlv(tlv)?,
+        None => return Ok(None),
+    };
+    let extensions = extensions
+        .into_iter()
+        .filter(|item| item.extension_type() != extension_type)
+        .collect::<Vec<_>>();
+    if extensions.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(pack_tlv(&extensions)?))
+    }
+}
+
+/// Validates an optional tlv, `None` is always valid.
+pub fn validate_option_tlv(tlv: &Option<Vec<u8>>) -> Result<()> {
+    match tlv {
+        Some(tlv) => validate_tlv(tlv),
+        None => Ok(()),
+    }
+}
+
+/// Hash of the tlv data which is included in the token data hash.
+/// H_keccak_to_field(TLV_HASH_DOMAIN, tlv)
+pub fn hash_tlv(tlv: &[u8]) -> [u8; 32] {
+    hashv_to_bn254_field_size_be(&[TLV_HASH_DOMAIN, tlv])
+}
+
+/// Returns the tlv shared by all input token accounts.
+/// Instructions which merge inputs into one output (approve, revoke, burn)
+/// preserve extensions, hence all inputs must carry the same extensions.
+/// The delegated amount belongs to the delegation of an account, it is
+/// ignored and not part of the shared tlv.
+pub fn get_shared_input_tlv(input_token_data: &[TokenData]) -> Result<Option<Vec<u8>>> {
+    let tlv = match input_token_data.first() {
+        Some(token_data) => remove_extension(&token_data.tlv, TokenExtensionType::DelegatedAmount)?,
+        None => None,
+    };
+    for token_data in input_token_data.iter().skip(1) {
+        if remove_extension(&token_data.tlv, TokenExtensionType::DelegatedAmount)? != tlv {
+            msg!("All input token accounts must have the same tlv extensions.");
+            return err!(ErrorCode::TokenExtensionMismatch);
+        }
+    }
+    Ok(tlv)
+}
+
+/// Removes the extensions which are set by the program and not by the owner:
+/// marker extensions are derived from the mint account and the delegated
+/// amount belongs to the delegation of an account.
+fn remove_program_extensions(tlv: &Option<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+    let tlv = remove_extension(tlv, TokenExtensionType::TransferFee)?;
+    let tlv = remove_extension(&tlv, TokenExtensionType::Pausable)?;
+    remove_extension(&tlv, TokenExtensionType::DelegatedAmount)
+}
+
+/// Checks that transfer outputs preserve the extensions of the inputs.
+/// All inputs must carry the same extensions and every output must carry
+/// exactly these extensions. Extensions set by the program are ignored, see
+/// remove_program_extensions. Outputs of compressions without inputs can set
+/// any valid tlv.
+pub fn check_output_tlvs(
+    input_token_data: &[TokenData],
+    output_tlvs: &[Option<Vec<u8>>],
+) -> Result<()> {
+    if input_token_data.is_empty() {
+        return Ok(());
+    }
+    let input_tlv = remove_program_extensions(&get_shared_input_tlv(input_token_data)?)?;
+    for output_tlv in output_tlvs.iter() {
+        if remove_program_extensions(output_tlv)? != input_tlv {
+            msg!("Output tlv extensions must match the input tlv extensions.");
+            return err!(ErrorCode::TokenExtensionMismatch);
+        }
+    }
+    Ok(())
+}
+
+/// Serializes extensions into the canonical tlv encoding.
+pub fn pack_tlv(extensions: &[TokenExtension]) -> Result<Vec<u8>> {
+    let mut extensions = extensions.iter().collect::<Vec<_>>();
+    extensions.sort_by_key(|extension| extension.extension_type() as u16);
+    let mut tlv = Vec::new();
+    for extension in extensions {
+        tlv.extend_from_slice(&(extension.extension_type() as u16).to_le_bytes());
+        match extension {
+            TokenExtension::Memo(memo) => {
+                tlv.extend_from_slice(&(memo.len() as u16).to_le_bytes());
+                tlv.extend_from_slice(memo);
+            }
+            TokenExtension::TransferHook { program_id, data } => {
+                tlv.extend_from_slice(&((32 + data.len()) as u16).to_le_bytes());
+                tlv.extend_from_slice(program_id.as_ref());
+                tlv.extend_from_slice(data);
+            }
+            TokenExtension::TransferFee | TokenExtension::Pausable => {
+                tlv.extend_from_slice(&0u16.to_le_bytes());
+            }
+            TokenExtension::TimeLock(time_lock) => {
+                tlv.extend_from_slice(&16u16.to_le_bytes());
+                tlv.extend_from_slice(&time_lock.to_bytes());
+            }
+            TokenExtension::DelegatedAmount(delegated_amount) => {
+                tlv.extend_from_slice(&8u16.to_le_bytes());
+                tlv.extend_from_slice(&delegated_amount.to_le_bytes());
+            }
+        }
+    }
+    validate_tlv(&tlv)?;
+    Ok(tlv)
+}
+
+/// Deserializes and validates tlv data into extensions.
+pub fn unpack_tlv(tlv: &[u8]) -> Result<Vec<TokenExtension>> {
+    validate_tlv(tlv)?;
+    let mut extensions = Vec::new();
+    let mut offset = 0;
+    while offset < tlv.len() {
+        let (extension_type, value) = read_entry(tlv, &mut offset)?;
+        let extension = match TokenExtensionType::try_from(extension_type)? {
+            TokenExtensionType::Memo => TokenExtension::Memo(value.to_vec()),
+            TokenExtensionType::TransferHook => TokenExtension::TransferHook {
+                program_id: Pubkey::try_from(&value[..32]).unwrap(),
+                data: value[32..].to_vec(),
+            },
+            TokenExtensionType::TransferFee => TokenExtension::TransferFee,
+            TokenExtensionType::Pausable => TokenExtension::Pausable,
+            TokenExtensionType::TimeLock => TokenExtension::TimeLock(TimeLock::from_bytes(value)),
+            TokenExtensionType::DelegatedAmount => {
+                TokenExtension::DelegatedAmount(u64::from_le_bytes(value.try_into().unwrap()))
+            }
+        };
+        extensions.push(extension);
+    }
+    Ok(extensions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_tlv() {
+        let extensions = vec![
+            TokenExtension::TransferHook {
+                program_id: Pubkey::new_unique(),
+                data: vec![1, 2, 3],
+            },
+            TokenExtension::Memo(b"invoice 42".to_vec()),
+        ];
+        let tlv = pack_tlv(&extensions).unwrap();
+        let unpacked = unpack_tlv(&tlv).unwrap();
+        // Packing sorts extensions by type.
+        assert_eq!(unpacked[0], extensions[1]);
+        assert_eq!(unpacked[1], extensions[0]);
+        assert_eq!(pack_tlv(&unpacked).unwrap(), tlv);
+    }
+
+    #[test]
+    fn test_add_extension() {
+        let tlv = add_extension(&None, TokenExtension::TransferFee).unwrap();
+        assert_eq!(tlv, vec![3, 0, 0, 0]);
+        let tlv = add_extension(&Some(tlv), TokenExtension::Memo(b"a".to_vec())).unwrap();
+        assert_eq!(tlv, vec![1, 0, 1, 0, b'a', 3, 0, 0, 0]);
+        let tlv = Some(tlv);
+        assert!(has_extension(&tlv, TokenExtensionType::TransferFee).unwrap());
+        assert!(!has_extension(&tlv, TokenExtensionType::TransferHook).unwrap());
+        // Adding an existing extension is a noop.
+        assert_eq!(
+            add_extension(&tlv, TokenExtension::TransferFee).unwrap(),
+            tlv.clone().unwrap()
+        );
+        let tlv = add_extension(&tlv, TokenExtension::Pausable).unwrap();
+        assert_eq!(tlv, vec![1, 0, 1, 0, b'a', 3, 0, 0, 0, 4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_remove_extension() {
+        let time_lock = TimeLock {
+            start_slot: 1,
+            end_slot: 2,
+        };
+        let tlv = add_extension(&None, TokenExtension::TimeLock(time_lock)).unwrap();
+        let mut expected_tlv = vec![5, 0, 16, 0];
+        expected_tlv.extend_from_slice(&1u64.to_le_bytes());
+        expected_tlv.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(tlv, expected_tlv);
+        assert_eq!(
+            unpack_tlv(&tlv).unwrap(),
+            vec![TokenExtension::TimeLock(time_lock)]
+        );
+        let tlv = add_extension(&Some(tlv), TokenExtension::TransferFee).unwrap();
+        let tlv = remove_extension(&Some(tlv), TokenExtensionType::TimeLock).unwrap();
+        assert_eq!(tlv, Some(vec![3, 0, 0, 0]));
+        let tlv = remove_extension(&tlv, TokenExtensionType::TransferFee).unwrap();
+        assert_eq!(tlv, None);
+        assert_eq!(
+            remove_extension(&None, TokenExtensionType::TimeLock).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_tlv_failing() {
+        // empty
+        assert!(validate_tlv(&[]).is_err());
+        // truncated header
+        assert!(validate_tlv(&[1, 0, 1]).is_err());
+        // value longer than data
+        assert!(validate_tlv(&[1, 0, 2, 0, b'a']).is_err());
+        // unknown extension type
+        assert!(validate_tlv(&[7, 0, 1, 0, b'a']).is_err());
+        // transfer fee marker with value
+        assert!(validate_tlv(&[3, 0, 1, 0, 1]).is_err());
+        // pausable marker with value
+        assert!(validate_tlv(&[4, 0, 1, 0, 1]).is_err());
+        // time lock with invalid length
+        assert!(validate_tlv(&[5, 0, 8, 0, 1, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        // time lock start slot greater than end slot
+        let mut tlv = vec![5, 0, 16, 0];
+        tlv.extend_from_slice(&2u64.to_le_bytes());
+        tlv.extend_from_slice(&1u64.to_le_bytes());
+        assert!(validate_tlv(&tlv).is_err());
+        // delegated amount with invalid length
+        assert!(validate_tlv(&[6, 0, 4, 0, 1, 0, 0, 0]).is_err());
+        // empty memo
+        assert!(validate_tlv(&[1, 0, 0, 0]).is_err());
+        // invalid utf-8 memo
+        assert!(validate_tlv(&[1, 0, 1, 0, 0xff]).is_err());
+        // transfer hook without program id
+        assert!(validate_tlv(&[2, 0, 1, 0, 1]).is_err());
+        // duplicate extension
+        assert!(validate_tlv(&[1, 0, 1, 0, b'a', 1, 0, 1, 0, b'b']).is_err());
+        // unsorted extensions
+        let mut tlv = vec![2, 0, 32, 0];
+        tlv.extend_from_slice(&[1u8; 32]);
+        tlv.extend_from_slice(&[1, 0, 1, 0, b'a']);
+        assert!(validate_tlv(&tlv).is_err());
+        // too large
+        let memo = TokenExtension::Memo(vec![b'a'; MAX_MEMO_LEN + 1]);
+        assert!(pack_tlv(&[memo]).is_err());
+        // valid
+        assert!(validate_tlv(&[1, 0, 1, 0, b'a']).is_ok());
+    }
+
+    #[test]
+    fn test_check_output_tlvs() {
+        let token_data = |tlv: Option<Vec<u8>>| TokenData {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 100,
+            delegate: None,
+            state: crate::token_data::AccountState::Initialized,
+            tlv,
+        };
+        let memo = TokenExtension::Memo(b"memo".to_vec());
+        let memo_tlv = Some(pack_tlv(&[memo.clone()]).unwrap());
+        let marked_memo_tlv = Some(pack_tlv(&[memo.clone(), TokenExtension::TransferFee]).unwrap());
+        let inputs = vec![
+            token_data(marked_memo_tlv.clone()),
+            token_data(marked_memo_tlv.clone()),
+        ];
+        // Outputs carry the input extensions, markers are ignored.
+        assert!(check_output_tlvs(
+            &inputs,
+            &[
+                memo_tlv.clone(),
+                marked_memo_tlv,
+                Some(pack_tlv(&[memo.clone(), TokenExtension::Pausable]).unwrap()),
+            ]
+        )
+        .is_ok());
+        // Dropped extension.
+        assert_eq!(
+            check_output_tlvs(&inputs, &[memo_tlv.clone(), None]),
+            Err(ErrorCode::TokenExtensionMismatch.into())
+        );
+        // Changed extension.
+        let other_memo_tlv = Some(pack_tlv(&[TokenExtension::Memo(b"other".to_vec())]).unwrap());
+        assert_eq!(
+            check_output_tlvs(&inputs, &[other_memo_tlv.clone()]),
+            Err(ErrorCode::TokenExtensionMismatch.into())
+        );
+        // Added extension.
+        assert_eq!(
+            check_output_tlvs(&[token_data(None)], &[memo_tlv]),
+            Err(ErrorCode::TokenExtensionMismatch.into())
+        );
+        // Compressions without inputs can set any tlv.
+        assert!(check_output_tlvs(&[], &[other_memo_tlv, None]).is_ok());
+    }
+
+    #[test]
+    fn test_get_shared_input_tlv() {
+        let token_data = |tlv: Option<Vec<u8>>| TokenData {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 100,
+            delegate: Some(Pubkey::new_unique()),
+            state: crate::token_data::AccountState::Initialized,
+            tlv,
+        };
+        let memo = TokenExtension::Memo(b"memo".to_vec());
+        let memo_tlv = Some(pack_tlv(&[memo.clone()]).unwrap());
+        // The delegated amount is not shared.
+        let inputs = vec![
+            token_data(Some(
+                pack_tlv(&[memo.clone(), TokenExtension::DelegatedAmount(1)]).unwrap(),
+            )),
+            token_data(memo_tlv.clone()),
+        ];
+        assert_eq!(get_shared_input_tlv(&inputs).unwrap(), memo_tlv);
+        let inputs = vec![
+            token_data(Some(
+                pack_tlv(&[TokenExtension::DelegatedAmount(1)]).unwrap(),
+            )),
+            token_data(None),
+        ];
+        assert_eq!(get_shared_input_tlv(&inputs).unwrap(), None);
+        // Other extensions must match.
+        let inputs = vec![token_data(memo_tlv), token_data(None)];
+        assert_eq!(
+            get_shared_input_tlv(&inputs),
+            Err(ErrorCode::TokenExtensionMismatch.into())
+        );
+    }
+}
@@ -5,6 +5,12 @@ use anchor_lang::{
 };
 use light_compressed_account::hash_to_bn254_field_size_be;
 use light_hasher::{errors::HasherError, Hasher, Poseidon};
+use light_zero_copy::{
+    errors::ZeroCopyError, init_mut::ZeroCopyNew, slice_mut::ZeroCopySliceMutBorsh,
+};
+use zerocopy::{little_endian::U64, Ref};
+
+use crate::tlv::hash_tlv;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
 #[repr(u8)]
@@ -21,22 +27,27 @@ pub struct TokenData {
     pub owner: Pubkey,
     /// The amount of tokens this account holds.
     pub amount: u64,
-    /// If `delegate` is `Some` then `delegated_amount` represents
-    /// the amount authorized by the delegate
+    /// If `delegate` is `Some` the delegate can transfer and burn up to the
+    /// delegated amount, see crate::delegation::get_delegated_amount. The
+    /// delegated amount is stored in the tlv, accounts without delegated
+    /// amount extension are delegated completely.
     pub delegate: Option<Pubkey>,
     /// The account's state
     pub state: AccountState,
-    /// Placeholder for TokenExtension tlv data (unimplemented)
+    /// Token extension tlv data, see crate::tlv for the encoding.
     pub tlv: Option<Vec<u8>>,
 }
 
 /// Hashing schema: H(mint, owner, amount, delegate, delegated_amount,
-/// is_native, state)
+/// is_native, state, tlv)
 ///
-/// delegate, delegated_amount, is_native and state have dynamic positions.
+/// delegate, delegated_amount, is_native, state and tlv have dynamic positions.
 /// Always hash mint, owner and amount If delegate hash delegate and
 /// delegated_amount together. If is native hash is_native else is omitted.
 /// If frozen hash AccountState::Frozen else is omitted.
+/// If tlv is some hash H_keccak_to_field("tlv", tlv) last else is omitted.
+/// The delegated amount of accounts with a DelegatedAmount extension is
+/// hashed as part of the tlv.
 ///
 /// Security: to prevent the possibility that different fields with the same
 /// value to result in the same hash we add a prefix to the delegated amount, is
@@ -87,15 +98,53 @@ impl TokenData {
         owner: &[u8; 32],
         amount_bytes: &[u8],
         hashed_delegate: &Option<&[u8; 32]>,
+    ) -> std::result::Result<[u8; 32], HasherError> {
+        Self::hash_inputs_with_hashed_values_and_tlv::<FROZEN_INPUTS>(
+            mint,
+            owner,
+            amount_bytes,
+            hashed_delegate,
+            &None,
+        )
+    }
+
+    /// Same as hash_inputs_with_hashed_values but additionally hashes the
+    /// tlv hash (see crate::tlv::hash_tlv) of accounts with token extensions.
+    ///
+    /// Token data with tlv is hashed with a fixed number of inputs:
+    /// H(mint, owner, amount, delegate or zero, state, tlv hash).
+    /// Token data without tlv is hashed with at most five inputs, hence the
+    /// tlv hash cannot take the position of a delegate or state.
+    pub fn hash_inputs_with_hashed_values_and_tlv<const FROZEN_INPUTS: bool>(
+        mint: &[u8; 32],
+        owner: &[u8; 32],
+        amount_bytes: &[u8],
+        hashed_delegate: &Option<&[u8; 32]>,
+        hashed_tlv: &Option<&[u8; 32]>,
     ) -> std::result::Result<[u8; 32], HasherError> {
         let mut hash_inputs = vec![mint.as_slice(), owner.as_slice(), amount_bytes];
-        if let Some(hashed_delegate) = hashed_delegate {
-            hash_inputs.push(hashed_delegate.as_slice());
-        }
         let mut state_bytes = [0u8; 32];
         if FROZEN_INPUTS {
             state_bytes[31] = AccountState::Frozen as u8;
-            hash_inputs.push(&state_bytes[..]);
+        }
+        match hashed_tlv {
+            Some(hashed_tlv) => {
+                hash_inputs.push(
+                    hashed_delegate
+                        .map(|hashed_delegate| hashed_delegate.as_slice())
+                        .unwrap_or(&[0u8; 32]),
+                );
+                hash_inputs.push(&state_bytes[..]);
+                hash_inputs.push(hashed_tlv.as_slice());
+            }
+            None => {
+                if let Some(hashed_delegate) = hashed_delegate {
+                    hash_inputs.push(hashed_delegate.as_slice());
+                }
+                if FROZEN_INPUTS {
+                    hash_inputs.push(&state_bytes[..]);
+                }
+            }
         }
         Poseidon::hashv(hash_inputs.as_slice())
     }
@@ -131,24 +180,86 @@ impl TokenData {
         } else {
             None
         };
+        let hashed_tlv = self.tlv.as_ref().map(|tlv| hash_tlv(tlv.as_slice()));
         if self.state != AccountState::Initialized {
-            Self::hash_inputs_with_hashed_values::<true>(
+            Self::hash_inputs_with_hashed_values_and_tlv::<true>(
                 &hashed_mint,
                 &hashed_owner,
                 &amount_bytes,
                 &hashed_delegate_option,
+                &hashed_tlv.as_ref(),
             )
         } else {
-            Self::hash_inputs_with_hashed_values::<false>(
+            Self::hash_inputs_with_hashed_values_and_tlv::<false>(
                 &hashed_mint,
                 &hashed_owner,
                 &amount_bytes,
                 &hashed_delegate_option,
+                &hashed_tlv.as_ref(),
             )
         }
     }
 }
 
+/// Sizes of the optional fields of TokenData.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TokenDataConfig {
+    pub delegate: bool,
+    /// Tlv length in bytes.
+    pub tlv: Option<u32>,
+}
+
+/// Mutable zero copy view of borsh serialized TokenData.
+#[derive(Debug)]
+pub struct ZTokenDataMut<'a> {
+    pub mint: Ref<&'a mut [u8], [u8; 32]>,
+    pub owner: Ref<&'a mut [u8], [u8; 32]>,
+    pub amount: Ref<&'a mut [u8], U64>,
+    pub delegate: Option<Ref<&'a mut [u8], [u8; 32]>>,
+    pub state: Ref<&'a mut [u8], u8>,
+    pub tlv: Option<ZeroCopySliceMutBorsh<'a, u8>>,
+}
+
+impl<'a> ZeroCopyNew<'a> for TokenData {
+    type ZeroCopyConfig = TokenDataConfig;
+    type Output = ZTokenDataMut<'a>;
+
+    fn byte_len(config: &Self::ZeroCopyConfig) -> usize {
+        Ref::<&mut [u8], [u8; 32]>::byte_len(&())
+            + Ref::<&mut [u8], [u8; 32]>::byte_len(&())
+            + U64::byte_len(&())
+            + Option::<Ref<&mut [u8], [u8; 32]>>::byte_len(&config.delegate.then_some(()))
+            + u8::byte_len(&())
+            + Option::<ZeroCopySliceMutBorsh<u8>>::byte_len(&config.tlv)
+    }
+
+    fn new_zero_copy(
+        bytes: &'a mut [u8],
+        config: Self::ZeroCopyConfig,
+    ) -> Result<(Self::Output, &'a mut [u8]), ZeroCopyError> {
+        let (mint, bytes) = Ref::<&mut [u8], [u8; 32]>::new_zero_copy(bytes, ())?;
+        let (owner, bytes) = Ref::<&mut [u8], [u8; 32]>::new_zero_copy(bytes, ())?;
+        let (amount, bytes) = U64::new_zero_copy(bytes, ())?;
+        let (delegate, bytes) = Option::<Ref<&mut [u8], [u8; 32]>>::new_zero_copy(
+            bytes,
+            config.delegate.then_some(()),
+        )?;
+        let (state, bytes) = u8::new_zero_copy(bytes, ())?;
+        let (tlv, bytes) = Option::<ZeroCopySliceMutBorsh<u8>>::new_zero_copy(bytes, config.tlv)?;
+        Ok((
+            ZTokenDataMut {
+                mint,
+                owner,
+                amount,
+                delegate,
+                state,
+                tlv,
+            },
+            bytes,
+        ))
+    }
+}
+
 #[cfg(test)]
 pub mod test {
 
@@ -433,6 +544,117 @@ pub mod test {
         token_data.delegate = Some(delegate);
         let hash10 = token_data.hash_legacy().unwrap();
         assert_to_previous_hashes(hash10, &mut vec_previous_hashes);
+        // with tlv
+        token_data.tlv = Some(vec![1, 0, 1, 0, b'a']);
+        let hash11 = token_data.hash_legacy().unwrap();
+        assert_to_previous_hashes(hash11, &mut vec_previous_hashes);
+        // different tlv
+        token_data.tlv = Some(vec![1, 0, 1, 0, b'b']);
+        let hash12 = token_data.hash_legacy().unwrap();
+        assert_to_previous_hashes(hash12, &mut vec_previous_hashes);
+        // tlv hash in the position of the delegate
+        token_data.delegate = None;
+        token_data.state = AccountState::Initialized;
+        let hash13 = token_data.hash_legacy().unwrap();
+        assert_to_previous_hashes(hash13, &mut vec_previous_hashes);
+    }
+
+    #[test]
+    fn test_tlv_hash_equivalence() {
+        let token_data = TokenData {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 100,
+            delegate: Some(Pubkey::new_unique()),
+            state: AccountState::Frozen,
+            tlv: Some(vec![1, 0, 4, 0, b'm', b'e', b'm', b'o']),
+        };
+        let hashed_mint = hash_to_bn254_field_size_be(token_data.mint.to_bytes().as_slice());
+        let hashed_owner = hash_to_bn254_field_size_be(token_data.owner.to_bytes().as_slice());
+        let hashed_delegate =
+            hash_to_bn254_field_size_be(token_data.delegate.unwrap().to_bytes().as_slice());
+        let hashed_tlv = hash_tlv(token_data.tlv.as_ref().unwrap());
+        let mut amount_bytes = [0u8; 32];
+        amount_bytes[24..].copy_from_slice(token_data.amount.to_be_bytes().as_slice());
+        let hash = TokenData::hash_inputs_with_hashed_values_and_tlv::<true>(
+            &hashed_mint,
+            &hashed_owner,
+            &amount_bytes,
+            &Some(&hashed_delegate),
+            &Some(&hashed_tlv),
+        )
+        .unwrap();
+        assert_eq!(hash, token_data.hash().unwrap());
+    }
+
+    /// A tlv of 29 bytes has a 32 byte hash preimage `TLV_HASH_DOMAIN || tlv`,
+    /// the same preimage as a delegate pubkey with these bytes. The token
+    /// data hash must still differ.
+    #[test]
+    fn test_tlv_hash_is_not_a_delegate() {
+        use crate::{
+            tlv::{pack_tlv, TokenExtension, TLV_HASH_DOMAIN},
+            vesting::TimeLock,
+        };
+
+        let tlvs = [
+            pack_tlv(&[TokenExtension::Memo(b"hello, world! hello, all!".to_vec())]).unwrap(),
+            pack_tlv(&[
+                TokenExtension::Memo(b"hello".to_vec()),
+                TokenExtension::TimeLock(TimeLock {
+                    start_slot: 1,
+                    end_slot: 2,
+                }),
+            ])
+            .unwrap(),
+            pack_tlv(&[
+                TokenExtension::Memo(b"hello, world!".to_vec()),
+                TokenExtension::DelegatedAmount(1),
+            ])
+            .unwrap(),
+        ];
+        for tlv in tlvs {
+            assert_eq!(tlv.len(), 29);
+            let delegate = Pubkey::new_from_array(
+                [TLV_HASH_DOMAIN, tlv.as_slice()]
+                    .concat()
+                    .try_into()
+                    .unwrap(),
+            );
+            // The inner hashes collide.
+            assert_eq!(
+                hash_tlv(&tlv),
+                hash_to_bn254_field_size_be(delegate.to_bytes().as_slice())
+            );
+            for state in [AccountState::Initialized, AccountState::Frozen] {
+                let with_tlv = TokenData {
+                    mint: Pubkey::new_unique(),
+                    owner: Pubkey::new_unique(),
+                    amount: 100,
+                    delegate: None,
+                    state,
+                    tlv: Some(tlv.clone()),
+                };
+                let with_delegate = TokenData {
+                    delegate: Some(delegate),
+                    tlv: None,
+                    ..with_tlv.clone()
+                };
+                assert_ne!(with_tlv.hash().unwrap(), with_delegate.hash().unwrap());
+                assert_ne!(
+                    with_tlv.hash_legacy().unwrap(),
+                    with_delegate.hash_legacy().unwrap()
+                );
+                let with_tlv_and_delegate = TokenData {
+                    delegate: Some(delegate),
+                    ..with_tlv.clone()
+                };
+                assert_ne!(
+                    with_tlv_and_delegate.hash().unwrap(),
+                    with_tlv.hash().unwrap()
+                );
+            }
+        }
     }
 
     fn assert_to_previous_hashes(hash: [u8; 32], previous_hashes: &mut Vec<[u8; 32]>) {
@@ -442,4 +664,48 @@ pub mod test {
         println!("len previous hashes: {}", previous_hashes.len());
         previous_hashes.push(hash);
     }
+
+    #[test]
+    fn test_token_data_new_zero_copy() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let token_data = TokenData {
+                mint: Pubkey::new_unique(),
+                owner: Pubkey::new_unique(),
+                amount: rng.gen(),
+                delegate: rng.gen::<bool>().then(Pubkey::new_unique),
+                state: if rng.gen() {
+                    AccountState::Frozen
+                } else {
+                    AccountState::Initialized
+                },
+                tlv: rng
+                    .gen::<bool>()
+                    .then(|| (0..rng.gen_range(0..20)).map(|_| rng.gen()).collect()),
+            };
+            let config = TokenDataConfig {
+                delegate: token_data.delegate.is_some(),
+                tlv: token_data.tlv.as_ref().map(|tlv| tlv.len() as u32),
+            };
+            let mut bytes = vec![0u8; TokenData::byte_len(&config)];
+            {
+                let (mut z_token_data, remaining_bytes) =
+                    TokenData::new_zero_copy(&mut bytes, config).unwrap();
+                assert!(remaining_bytes.is_empty());
+                *z_token_data.mint = token_data.mint.to_bytes();
+                *z_token_data.owner = token_data.owner.to_bytes();
+                *z_token_data.amount = token_data.amount.into();
+                if let Some(delegate) = z_token_data.delegate.as_mut() {
+                    **delegate = token_data.delegate.unwrap().to_bytes();
+                }
+                *z_token_data.state = token_data.state as u8;
+                if let Some(tlv) = z_token_data.tlv.as_mut() {
+                    tlv.as_mut_slice()
+                        .copy_from_slice(token_data.tlv.as_ref().unwrap());
+                }
+            }
+            assert_eq!(bytes, token_data.try_to_vec().unwrap());
+            assert_eq!(TokenData::deserialize(&mut &bytes[..]).unwrap(), token_data);
+        }
+    }
 }
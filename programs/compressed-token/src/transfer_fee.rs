@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, PodStateWithExtensions,
+    },
+    pod::{PodAccount, PodMint},
+};
+
+use crate::{
+    tlv::{add_extension, has_extension, TokenExtension, TokenExtensionType},
+    ErrorCode,
+};
+
+/// Compressed token accounts of mints with a token 2022 TransferFeeConfig:
+/// 1. carry the TokenExtensionType::TransferFee tlv marker, the marker is
+///    added by mint_to and compression and cannot be removed.
+/// 2. compressed transfers charge the epoch transfer fee on the amount
+///    transferred to other owners and create a fee compressed account owned
+///    by the withdraw withheld authority of the mint.
+/// 3. compression credits the amount net of the fee withheld in the token
+///    pool account, decompression withholds the fee in the recipient account.
+///
+/// The mint account has to be passed in remaining accounts after any
+/// additional token pool accounts.
+pub fn find_mint_account<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    mint: &Pubkey,
+) -> Option<&'a AccountInfo<'info>> {
+    remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == *mint)
+}
+
+/// Returns the transfer fee config of a token 2022 mint.
+/// Returns None for spl token mints and token 2022 mints without fee.
+pub fn get_transfer_fee_config(mint_account: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint_account.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let mint_data = mint_account.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)
+        .map_err(|_| ErrorCode::InvalidMint)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Compressions and decompressions of mints with a TransferFeeConfig require
+/// the mint account, otherwise the compressed amount would not be net of the
+/// fee. Token accounts of these mints carry the TransferFeeAmount extension,
+/// hence the token pool account identifies them without the mint account.
+pub fn check_token_pool_transfer_fee(
+    token_pool_pda: Option<&AccountInfo>,
+    transfer_fee_config: Option<&TransferFeeConfig>,
+) -> Result<()> {
+    let token_pool_pda = match token_pool_pda {
+        Some(token_pool_pda) if transfer_fee_config.is_none() => token_pool_pda,
+        _ => return Ok(()),
+    };
+    if *token_pool_pda.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let token_pool_data = token_pool_pda.try_borrow_data()?;
+    let token_pool = PodStateWithExtensions::<PodAccount>::unpack(&token_pool_data)
+        .map_err(|_| ErrorCode::InvalidTokenPoolPda)?;
+    if token_pool.get_extension::<TransferFeeAmount>().is_ok() {
+        msg!("Mint account with transfer fee config is required.");
+        return err!(ErrorCode::MintAccountUndefined);
+    }
+    Ok(())
+}
+
+pub fn get_mint_decimals(mint_account: &AccountInfo) -> Result<u8> {
+    let mint_data = mint_account.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)
+        .map_err(|_| ErrorCode::InvalidMint)?;
+    Ok(mint.base.decimals)
+}
+
+/// Calculates the transfer fee of the current epoch.
+pub fn calculate_transfer_fee(config: &TransferFeeConfig, amount: u64) -> Result<u64> {
+    let epoch = Clock::get()?.epoch;
+    config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::TransferFeeCalculationFailed.into())
+}
+
+/// Owner of fee compressed accounts.
+pub fn get_fee_collection_owner(config: &TransferFeeConfig) -> Result<Pubkey> {
+    Option::<Pubkey>::from(config.withdraw_withheld_authority)
+        .ok_or(ErrorCode::WithdrawWithheldAuthorityUndefined.into())
+}
+
+pub fn has_transfer_fee_extension(tlv: &Option<Vec<u8>>) -> Result<bool> {
+    has_extension(tlv, TokenExtensionType::TransferFee)
+}
+
+/// Adds the transfer fee marker to an output tlv.
+pub fn add_transfer_fee_extension(tlv: &Option<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+    Ok(Some(add_extension(tlv, TokenExtension::TransferFee)?))
+}
@@ -0,0 +1,664 @@
+use anchor_lang::prelude::*;
+use light_compressed_account::{
+    hash_to_bn254_field_size_be,
+    instruction_data::{
+        compressed_proof::CompressedProof, cpi_context::CompressedCpiContext,
+        data::OutputCompressedAccountWithPackedContext, with_readonly::InAccount,
+        zero_copy::ZCompressedCpiContext,
+    },
+};
+use light_zero_copy::{borsh::Deserialize, ZeroCopy};
+use zerocopy::Ref;
+
+use crate::{
+    constants::NOT_FROZEN,
+    mint_extensions::{check_input_pausable_markers, MintExtensions},
+    process_transfer::{
+        add_data_hash_to_input_compressed_accounts, cpi_execute_compressed_transaction_transfer,
+        create_output_compressed_accounts,
+        get_input_compressed_accounts_with_merkle_context_and_check_signer,
+        InputTokenDataWithContext,
+    },
+    tlv::{
+        add_extension, get_shared_input_tlv, remove_extension, unpack_tlv, TokenExtension,
+        TokenExtensionType,
+    },
+    ErrorCode, GenericInstruction,
+};
+
+/// Vesting schedule of a compressed token account, stored in the
+/// TokenExtensionType::TimeLock tlv entry and hashed with the token data.
+/// The complete amount is locked until start_slot and unlocks linearly until
+/// end_slot. A time lock with start_slot == end_slot unlocks the complete
+/// amount at once.
+///
+/// 1. transfer and burn fail for inputs with a time lock which is not
+///    completely unlocked.
+/// 2. split_vested releases the unlocked amount into an account without time
+///    lock. The locked amount remains in an account with a time lock from the
+///    current slot to the same end_slot.
+/// 3. approve, revoke, freeze and thaw preserve time locks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeLock {
+    pub start_slot: u64,
+    pub end_slot: u64,
+}
+
+impl TimeLock {
+    /// Expects a value of a validated tlv entry.
+    pub fn from_bytes(value: &[u8]) -> Self {
+        Self {
+            start_slot: u64::from_le_bytes(value[..8].try_into().unwrap()),
+            end_slot: u64::from_le_bytes(value[8..16].try_into().unwrap()),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.start_slot.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.end_slot.to_le_bytes());
+        bytes
+    }
+
+    pub fn is_unlocked(&self, current_slot: u64) -> bool {
+        current_slot >= self.end_slot
+    }
+
+    /// Locked part of amount at current_slot. Rounds up so that splitting
+    /// an account repeatedly never unlocks tokens ahead of the schedule.
+    pub fn locked_amount(&self, amount: u64, current_slot: u64) -> u64 {
+        if self.is_unlocked(current_slot) {
+            0
+        } else if current_slot <= self.start_slot {
+            amount
+        } else {
+            let remaining_slots = (self.end_slot - current_slot) as u128;
+            let vesting_slots = (self.end_slot - self.start_slot) as u128;
+            (amount as u128 * remaining_slots).div_ceil(vesting_slots) as u64
+        }
+    }
+}
+
+/// Returns the time lock of a valid tlv.
+pub fn get_time_lock(tlv: Option<&[u8]>) -> Result<Option<TimeLock>> {
+    let tlv = match tlv {
+        Some(tlv) => tlv,
+        None => return Ok(None),
+    };
+    Ok(unpack_tlv(tlv)?
+        .into_iter()
+        .find_map(|extension| match extension {
+            TokenExtension::TimeLock(time_lock) => Some(time_lock),
+            _ => None,
+        }))
+}
+
+/// Fails if any input with a non zero amount is not completely unlocked.
+pub fn check_inputs_unlocked<'a>(
+    inputs: impl IntoIterator<Item = (u64, Option<&'a [u8]>)>,
+    current_slot: u64,
+) -> Result<()> {
+    for (amount, tlv) in inputs {
+        if amount == 0 {
+            continue;
+        }
+        if let Some(time_lock) = get_time_lock(tlv)? {
+            if !time_lock.is_unlocked(current_slot) {
+                msg!(
+                    "Token account is locked until slot {}, current slot {}",
+                    time_lock.end_slot,
+                    current_slot
+                );
+                return err!(ErrorCode::TokenAccountLocked);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, ZeroCopy)]
+pub struct CompressedTokenInstructionDataSplitVested {
+    pub proof: CompressedProof,
+    #[zero_copy(ty = light_compressed_account::pubkey::Pubkey)]
+    pub mint: Pubkey,
+    pub input_token_data_with_context: Vec<InputTokenDataWithContext>,
+    #[zero_copy(ty = Option<Ref<&'a [u8], ZCompressedCpiContext>>)]
+    pub cpi_context: Option<CompressedCpiContext>,
+    /// Index in remaining accounts.
+    pub unlocked_account_merkle_tree_index: u8,
+    /// Index in remaining accounts.
+    pub locked_account_merkle_tree_index: u8,
+    /// Token extension tlv data of the inputs, see ZInputTlvs.
+    #[zero_copy(trailing)]
+    pub input_tlvs: Vec<Option<Vec<u8>>>,
+}
+
+/// Processes a split vested instruction.
+/// - inputs must be owned by the authority and have the same time lock.
+/// - creates an output compressed account without time lock for the unlocked
+///   amount, the output receives all lamports.
+/// - creates an output compressed account for the locked amount if the time
+///   lock has not ended.
+/// - other extensions are preserved, delegates are not preserved.
+/// - inputs with the pausable marker are not supported.
+pub fn process_split_vested<'a, 'b, 'c, 'info: 'b + 'c>(
+    ctx: Context<'a, 'b, 'c, 'info, GenericInstruction<'info>>,
+    inputs: Vec<u8>,
+) -> Result<()> {
+    let (inputs, _) = ZCompressedTokenInstructionDataSplitVested::zero_copy_at(&inputs)
+        .map_err(ProgramError::from)?;
+    let (compressed_input_accounts, output_compressed_accounts) =
+        create_input_and_output_accounts_split_vested(
+            &inputs,
+            &ctx.accounts.authority.key(),
+            Clock::get()?.slot,
+            ctx.remaining_accounts,
+        )?;
+    let proof = if *inputs.proof == CompressedProof::default() {
+        None
+    } else {
+        Some(*inputs.proof)
+    };
+    cpi_execute_compressed_transaction_transfer(
+        ctx.accounts,
+        compressed_input_accounts,
+        output_compressed_accounts,
+        false,
+        proof,
+        inputs
+            .cpi_context
+            .as_ref()
+            .map(|cpi_context| (&**cpi_context).into()),
+        ctx.accounts.cpi_authority_pda.to_account_info(),
+        ctx.accounts.light_system_program.to_account_info(),
+        ctx.accounts.self_program.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn create_input_and_output_accounts_split_vested(
+    inputs: &ZCompressedTokenInstructionDataSplitVested,
+    authority: &Pubkey,
+    current_slot: u64,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<(
+    Vec<InAccount>,
+    Vec<OutputCompressedAccountWithPackedContext>,
+)> {
+    if inputs.input_token_data_with_context.is_empty() {
+        return err!(ErrorCode::NoInputTokenAccountsProvided);
+    }
+    let mint: Pubkey = (*inputs.mint).into();
+    let (mut compressed_input_accounts, input_token_data, sum_lamports) =
+        get_input_compressed_accounts_with_merkle_context_and_check_signer::<NOT_FROZEN>(
+            authority,
+            None,
+            false,
+            remaining_accounts,
+            &inputs.input_token_data_with_context,
+            &inputs.input_tlvs,
+            &mint,
+        )?;
+    // split_vested has no mint account, inputs of pausable mints are rejected
+    // like in transfer without mint account.
+    check_input_pausable_markers(&input_token_data, &MintExtensions::default())?;
+    let mut sum_inputs: u64 = 0;
+    for token_data in input_token_data.iter() {
+        sum_inputs = sum_inputs
+            .checked_add(token_data.amount)
+            .ok_or(ErrorCode::ComputeInputSumFailed)?;
+    }
+    let tlv = get_shared_input_tlv(input_token_data.as_slice())?;
+    let time_lock = match get_time_lock(tlv.as_deref())? {
+        Some(time_lock) => time_lock,
+        None => return err!(ErrorCode::TimeLockUndefined),
+    };
+    let locked_amount = time_lock.locked_amount(sum_inputs, current_slot);
+    let unlocked_amount = sum_inputs - locked_amount;
+    if unlocked_amount == 0 {
+        return err!(ErrorCode::NoUnlockedAmount);
+    }
+
+    let unlocked_tlv = remove_extension(&tlv, TokenExtensionType::TimeLock)?;
+    let (mut output_compressed_accounts, amounts, tlvs, merkle_tree_indices) = if locked_amount > 0
+    {
+        let locked_tlv = add_extension(
+            &unlocked_tlv,
+            TokenExtension::TimeLock(TimeLock {
+                start_slot: current_slot,
+                end_slot: time_lock.end_slot,
+            }),
+        )?;
+        (
+            vec![OutputCompressedAccountWithPackedContext::default(); 2],
+            vec![unlocked_amount, locked_amount],
+            vec![unlocked_tlv, Some(locked_tlv)],
+            vec![
+                inputs.unlocked_account_merkle_tree_index,
+                inputs.locked_account_merkle_tree_index,
+            ],
+        )
+    } else {
+        (
+            vec![OutputCompressedAccountWithPackedContext::default(); 1],
+            vec![unlocked_amount],
+            vec![unlocked_tlv],
+            vec![inputs.unlocked_account_merkle_tree_index],
+        )
+    };
+    let lamports = if sum_lamports != 0 {
+        let mut lamports = vec![None; amounts.len()];
+        lamports[0] = Some(sum_lamports);
+        Some(lamports)
+    } else {
+        None
+    };
+    let hashed_mint = hash_to_bn254_field_size_be(&mint.to_bytes());
+    create_output_compressed_accounts::<NOT_FROZEN>(
+        &mut output_compressed_accounts,
+        mint,
+        vec![*authority; amounts.len()].as_slice(),
+        None,
+        None,
+        amounts.as_slice(),
+        lamports,
+        Some(tlvs.as_slice()),
+        &hashed_mint,
+        &merkle_tree_indices,
+        remaining_accounts,
+    )?;
+    add_data_hash_to_input_compressed_accounts::<NOT_FROZEN>(
+        &mut compressed_input_accounts,
+        input_token_data.as_slice(),
+        &hashed_mint,
+        remaining_accounts,
+    )?;
+    Ok((compressed_input_accounts, output_compressed_accounts))
+}
+
+#[cfg(not(target_os = "solana"))]
+pub mod sdk {
+
+    use std::result::Result;
+
+    use anchor_lang::{AnchorSerialize, InstructionData, ToAccountMetas};
+    use light_compressed_account::compressed_account::{CompressedAccount, MerkleContext};
+    use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+    use super::*;
+    use crate::{
+        process_transfer::{
+            get_cpi_authority_pda,
+            transfer_sdk::{
+                create_input_output_and_remaining_accounts, get_input_tlvs, to_account_metas,
+                TransferSdkError,
+            },
+        },
+        token_data::TokenData,
+    };
+
+    pub struct CreateSplitVestedInstructionInputs {
+        pub fee_payer: Pubkey,
+        pub authority: Pubkey,
+        pub root_indices: Vec<Option<u16>>,
+        pub proof: CompressedProof,
+        pub input_token_data: Vec<TokenData>,
+        pub input_compressed_accounts: Vec<CompressedAccount>,
+        pub input_merkle_contexts: Vec<MerkleContext>,
+        pub mint: Pubkey,
+        pub unlocked_account_merkle_tree: Pubkey,
+        pub locked_account_merkle_tree: Pubkey,
+    }
+
+    pub fn create_split_vested_instruction(
+        inputs: CreateSplitVestedInstructionInputs,
+    ) -> Result<Instruction, TransferSdkError> {
+        let (remaining_accounts, input_token_data_with_context, _) =
+            create_input_output_and_remaining_accounts(
+                &[
+                    inputs.unlocked_account_merkle_tree,
+                    inputs.locked_account_merkle_tree,
+                ],
+                &inputs.input_token_data,
+                &inputs.input_compressed_accounts,
+                &inputs.input_merkle_contexts,
+                &inputs.root_indices,
+                &Vec::new(),
+            );
+        let unlocked_account_merkle_tree_index =
+            match remaining_accounts.get(&inputs.unlocked_account_merkle_tree) {
+                Some(unlocked_account_merkle_tree_index) => unlocked_account_merkle_tree_index,
+                None => return Err(TransferSdkError::AccountNotFound),
+            };
+        let locked_account_merkle_tree_index =
+            match remaining_accounts.get(&inputs.locked_account_merkle_tree) {
+                Some(locked_account_merkle_tree_index) => locked_account_merkle_tree_index,
+                None => return Err(TransferSdkError::AccountNotFound),
+            };
+
+        let inputs_struct = CompressedTokenInstructionDataSplitVested {
+            proof: inputs.proof,
+            mint: inputs.mint,
+            input_token_data_with_context,
+            cpi_context: None,
+            unlocked_account_merkle_tree_index: *unlocked_account_merkle_tree_index as u8,
+            locked_account_merkle_tree_index: *locked_account_merkle_tree_index as u8,
+            input_tlvs: get_input_tlvs(&inputs.input_token_data),
+        };
+        let remaining_accounts = to_account_metas(remaining_accounts);
+        let mut serialized_ix_data = Vec::new();
+        CompressedTokenInstructionDataSplitVested::serialize(
+            &inputs_struct,
+            &mut serialized_ix_data,
+        )
+        .map_err(|_| TransferSdkError::SerializationError)?;
+
+        let (cpi_authority_pda, _) = get_cpi_authority_pda();
+        let instruction_data = crate::instruction::SplitVested {
+            inputs: serialized_ix_data,
+        };
+
+        let accounts = crate::accounts::GenericInstruction {
+            fee_payer: inputs.fee_payer,
+            authority: inputs.authority,
+            cpi_authority_pda,
+            light_system_program: light_system_program::ID,
+            registered_program_pda: light_system_program::utils::get_registered_program_pda(
+                &light_system_program::ID,
+            ),
+            noop_program: Pubkey::new_from_array(
+                account_compression::utils::constants::NOOP_PUBKEY,
+            ),
+            account_compression_authority: light_system_program::utils::get_cpi_authority_pda(
+                &light_system_program::ID,
+            ),
+            account_compression_program: account_compression::ID,
+            self_program: crate::ID,
+            system_program: solana_sdk::system_program::ID,
+        };
+
+        Ok(Instruction {
+            program_id: crate::ID,
+            accounts: [accounts.to_account_metas(Some(true)), remaining_accounts].concat(),
+
+            data: instruction_data.data(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use account_compression::StateMerkleTreeAccount;
+    use anchor_lang::{solana_program::account_info::AccountInfo, Discriminator};
+    use light_compressed_account::compressed_account::PackedMerkleContext;
+
+    use super::*;
+    use crate::{
+        freeze::test_freeze::create_expected_token_output_accounts, tlv::pack_tlv,
+        token_data::AccountState, TokenData,
+    };
+
+    #[test]
+    fn test_locked_amount() {
+        let cliff = TimeLock {
+            start_slot: 100,
+            end_slot: 100,
+        };
+        assert_eq!(cliff.locked_amount(1000, 0), 1000);
+        assert_eq!(cliff.locked_amount(1000, 99), 1000);
+        assert_eq!(cliff.locked_amount(1000, 100), 0);
+        assert!(!cliff.is_unlocked(99));
+        assert!(cliff.is_unlocked(100));
+
+        let linear = TimeLock {
+            start_slot: 100,
+            end_slot: 200,
+        };
+        assert_eq!(linear.locked_amount(1000, 50), 1000);
+        assert_eq!(linear.locked_amount(1000, 100), 1000);
+        assert_eq!(linear.locked_amount(1000, 125), 750);
+        assert_eq!(linear.locked_amount(1000, 199), 10);
+        assert_eq!(linear.locked_amount(1000, 200), 0);
+        // Rounds up.
+        assert_eq!(linear.locked_amount(1, 199), 1);
+        assert_eq!(
+            linear.locked_amount(u64::MAX, 101),
+            18_262_276_632_972_456_099
+        );
+    }
+
+    /// Splitting at every slot never unlocks tokens ahead of the schedule.
+    #[test]
+    fn test_repeated_split_follows_schedule() {
+        let amount = 1_000_003;
+        let time_lock = TimeLock {
+            start_slot: 5,
+            end_slot: 777,
+        };
+        let mut locked_amount = amount;
+        let mut split_time_lock = time_lock;
+        for slot in 0..=time_lock.end_slot {
+            let remaining_locked_amount = split_time_lock.locked_amount(locked_amount, slot);
+            assert!(remaining_locked_amount >= time_lock.locked_amount(amount, slot));
+            // split_vested fails if nothing is unlocked.
+            if remaining_locked_amount < locked_amount {
+                locked_amount = remaining_locked_amount;
+                split_time_lock.start_slot = slot;
+            }
+        }
+        assert_eq!(locked_amount, 0);
+    }
+
+    #[test]
+    fn test_check_inputs_unlocked() {
+        let tlv = Some(
+            add_extension(
+                &None,
+                TokenExtension::TimeLock(TimeLock {
+                    start_slot: 10,
+                    end_slot: 20,
+                }),
+            )
+            .unwrap(),
+        );
+        check_inputs_unlocked([(100, None), (100, tlv.as_deref())], 20).unwrap();
+        check_inputs_unlocked([(100, None), (0, tlv.as_deref())], 19).unwrap();
+        assert_eq!(
+            check_inputs_unlocked([(100, None), (100, tlv.as_deref())], 19),
+            Err(ErrorCode::TokenAccountLocked.into())
+        );
+    }
+
+    #[test]
+    fn test_split_vested() {
+        let merkle_tree_pubkey = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports = 0;
+        let mut merkle_tree_account_data = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let nullifier_queue_pubkey = Pubkey::new_unique();
+        let mut nullifier_queue_account_lamports = 0;
+        let mut nullifier_queue_account_data = Vec::new();
+        let merkle_tree_pubkey_1 = Pubkey::new_unique();
+        let mut merkle_tree_account_lamports_1 = 0;
+        let mut merkle_tree_account_data_1 = StateMerkleTreeAccount::DISCRIMINATOR.to_vec();
+        let remaining_accounts = vec![
+            AccountInfo::new(
+                &merkle_tree_pubkey,
+                false,
+                false,
+                &mut merkle_tree_account_lamports,
+                &mut merkle_tree_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &nullifier_queue_pubkey,
+                false,
+                false,
+                &mut nullifier_queue_account_lamports,
+                &mut nullifier_queue_account_data,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+            AccountInfo::new(
+                &merkle_tree_pubkey_1,
+                false,
+                false,
+                &mut merkle_tree_account_lamports_1,
+                &mut merkle_tree_account_data_1,
+                &account_compression::ID,
+                false,
+                0,
+            ),
+        ];
+        let authority = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let time_lock = TimeLock {
+            start_slot: 100,
+            end_slot: 200,
+        };
+        let tlv = Some(pack_time_lock_with_memo(time_lock, b"vesting".to_vec()));
+        let input_token_data_with_context = |lamports: Option<u64>| {
+            (1..3)
+                .map(|leaf_index| InputTokenDataWithContext {
+                    amount: 500,
+                    merkle_context: PackedMerkleContext {
+                        merkle_tree_pubkey_index: 0,
+                        queue_pubkey_index: 1,
+                        leaf_index,
+                        prove_by_index: false,
+                    },
+                    root_index: 0,
+                    delegate_index: None,
+                    lamports,
+                    tlv: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let inputs = CompressedTokenInstructionDataSplitVested {
+            proof: CompressedProof::default(),
+            mint,
+            input_token_data_with_context: input_token_data_with_context(None),
+            cpi_context: None,
+            unlocked_account_merkle_tree_index: 0,
+            locked_account_merkle_tree_index: 2,
+            input_tlvs: vec![tlv.clone(); 2],
+        };
+
+        // 1. Nothing is unlocked before the start slot.
+        let result = split_vested(&inputs, &authority, 100, &remaining_accounts);
+        assert_eq!(result, Err(ErrorCode::NoUnlockedAmount.into()));
+
+        // 2. A quarter of the vesting period has passed.
+        let (compressed_input_accounts, output_compressed_accounts) =
+            split_vested(&inputs, &authority, 125, &remaining_accounts).unwrap();
+        assert_eq!(compressed_input_accounts.len(), 2);
+        let expected_unlocked_token_data = TokenData {
+            mint,
+            owner: authority,
+            amount: 250,
+            delegate: None,
+            state: AccountState::Initialized,
+            tlv: Some(pack_tlv(&[TokenExtension::Memo(b"vesting".to_vec())]).unwrap()),
+        };
+        let expected_locked_token_data = TokenData {
+            mint,
+            owner: authority,
+            amount: 750,
+            delegate: None,
+            state: AccountState::Initialized,
+            tlv: Some(pack_time_lock_with_memo(
+                TimeLock {
+                    start_slot: 125,
+                    end_slot: 200,
+                },
+                b"vesting".to_vec(),
+            )),
+        };
+        let expected_compressed_output_accounts = create_expected_token_output_accounts(
+            vec![expected_unlocked_token_data, expected_locked_token_data],
+            vec![0, 2],
+        );
+        assert_eq!(
+            output_compressed_accounts,
+            expected_compressed_output_accounts
+        );
+
+        // 3. After the end slot everything is unlocked, lamports are
+        //    transferred to the unlocked account.
+        let inputs = CompressedTokenInstructionDataSplitVested {
+            input_token_data_with_context: input_token_data_with_context(Some(1)),
+            ..inputs
+        };
+        let (_, output_compressed_accounts) =
+            split_vested(&inputs, &authority, 200, &remaining_accounts).unwrap();
+        let expected_unlocked_token_data = TokenData {
+            mint,
+            owner: authority,
+            amount: 1000,
+            delegate: None,
+            state: AccountState::Initialized,
+            tlv: Some(pack_tlv(&[TokenExtension::Memo(b"vesting".to_vec())]).unwrap()),
+        };
+        let mut expected_compressed_output_accounts =
+            create_expected_token_output_accounts(vec![expected_unlocked_token_data], vec![0]);
+        expected_compressed_output_accounts[0]
+            .compressed_account
+            .lamports = 2;
+        assert_eq!(
+            output_compressed_accounts,
+            expected_compressed_output_accounts
+        );
+
+        // 4. Inputs without time lock fail.
+        let mut inputs = inputs;
+        inputs.input_tlvs = Vec::new();
+        let result = split_vested(&inputs, &authority, 200, &remaining_accounts);
+        assert_eq!(result, Err(ErrorCode::TimeLockUndefined.into()));
+
+        // 5. Inputs with the pausable marker fail, the mint account is
+        //    required to check whether the mint is paused.
+        inputs.input_tlvs = vec![
+            Some(
+                pack_tlv(&[
+                    TokenExtension::TimeLock(time_lock),
+                    TokenExtension::Pausable,
+                ])
+                .unwrap()
+            );
+            2
+        ];
+        let result = split_vested(&inputs, &authority, 200, &remaining_accounts);
+        assert_eq!(result, Err(ErrorCode::MintAccountUndefined.into()));
+    }
+
+    /// Serializes the instruction data to process it zero copy like the
+    /// program does.
+    fn split_vested(
+        inputs: &CompressedTokenInstructionDataSplitVested,
+        authority: &Pubkey,
+        current_slot: u64,
+        remaining_accounts: &[AccountInfo<'_>],
+    ) -> Result<(
+        Vec<InAccount>,
+        Vec<OutputCompressedAccountWithPackedContext>,
+    )> {
+        let bytes = inputs.try_to_vec().unwrap();
+        let (inputs, _) = ZCompressedTokenInstructionDataSplitVested::zero_copy_at(&bytes).unwrap();
+        create_input_and_output_accounts_split_vested(
+            &inputs,
+            authority,
+            current_slot,
+            remaining_accounts,
+        )
+    }
+
+    fn pack_time_lock_with_memo(time_lock: TimeLock, memo: Vec<u8>) -> Vec<u8> {
+        pack_tlv(&[
+            TokenExtension::TimeLock(time_lock),
+            TokenExtension::Memo(memo),
+        ])
+        .unwrap()
+    }
+}
@@ -0,0 +1,86 @@
+use account_compression::{program::AccountCompression, utils::constants::CPI_AUTHORITY_PDA_SEED};
+use anchor_lang::prelude::*;
+
+use crate::protocol_config::state::ProtocolConfigPda;
+
+#[derive(Accounts)]
+pub struct CloseBatchedStateMerkleTree<'info> {
+    /// CHECK: only the protocol authority can close trees.
+    /// Receives the rent of the closed accounts.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub protocol_config_pda: Account<'info, ProtocolConfigPda>,
+    /// CHECK: in account compression program.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: in account compression program.
+    #[account(mut)]
+    pub output_queue: AccountInfo<'info>,
+    /// CHECK: (account compression program) access control.
+    pub registered_program_pda: AccountInfo<'info>,
+    /// CHECK: (seed constraints) used to invoke account compression program via cpi.
+    /// Receives the rent from the account compression program.
+    #[account(mut, seeds = [CPI_AUTHORITY_PDA_SEED], bump)]
+    pub cpi_authority: AccountInfo<'info>,
+    pub account_compression_program: Program<'info, AccountCompression>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_close_batched_state_merkle_tree(
+    ctx: &Context<CloseBatchedStateMerkleTree>,
+    bump: u8,
+) -> Result<()> {
+    let bump = &[bump];
+    let seeds = [CPI_AUTHORITY_PDA_SEED, bump];
+    let signer_seeds = &[&seeds[..]];
+    let accounts = account_compression::cpi::accounts::CloseBatchedStateMerkleTree {
+        authority: ctx.accounts.cpi_authority.to_account_info(),
+        registered_program_pda: Some(ctx.accounts.registered_program_pda.clone()),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        output_queue: ctx.accounts.output_queue.to_account_info(),
+        rent_recipient: ctx.accounts.cpi_authority.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.account_compression_program.to_account_info(),
+        accounts,
+        signer_seeds,
+    );
+    let cpi_authority_lamports = ctx.accounts.cpi_authority.lamports();
+    account_compression::cpi::close_batched_state_merkle_tree(cpi_ctx)?;
+    forward_rent_to_authority(
+        &ctx.accounts.cpi_authority,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        cpi_authority_lamports,
+        signer_seeds,
+    )
+}
+
+/// The account compression program reimburses the rent of closed trees to the
+/// signer, which is the cpi authority for trees of the protocol group.
+/// Forwards the rent from the cpi authority to the protocol authority.
+pub fn forward_rent_to_authority<'info>(
+    cpi_authority: &AccountInfo<'info>,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    cpi_authority_lamports: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = cpi_authority
+        .lamports()
+        .checked_sub(cpi_authority_lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: cpi_authority.to_account_info(),
+                to: authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rent,
+    )
+}
@@ -0,0 +1,78 @@
+use account_compression::{program::AccountCompression, utils::constants::CPI_AUTHORITY_PDA_SEED};
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::EPOCH_REWARD_VAULT_SEED, epoch::claim_rewards::EpochRewardVault,
+    errors::RegistryError, protocol_config::state::ProtocolConfigPda,
+};
+
+#[derive(Accounts)]
+#[instruction(bump: u8, epoch: u64)]
+pub struct CollectNetworkFees<'info> {
+    /// Anyone can collect network fees.
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    pub protocol_config_pda: Account<'info, ProtocolConfigPda>,
+    /// Instruction checks that epoch is the current active epoch.
+    #[account(init_if_needed, seeds = [EPOCH_REWARD_VAULT_SEED, epoch.to_le_bytes().as_slice()], bump, space = EpochRewardVault::LEN, payer = fee_payer)]
+    pub reward_vault: Account<'info, EpochRewardVault>,
+    /// CHECK: in account compression program.
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: in account compression program.
+    #[account(mut)]
+    pub output_queue: Option<AccountInfo<'info>>,
+    /// CHECK: (account compression program) access control.
+    pub registered_program_pda: AccountInfo<'info>,
+    /// CHECK: (seed constraints) used to invoke account compression program via cpi.
+    #[account(seeds = [CPI_AUTHORITY_PDA_SEED], bump)]
+    pub cpi_authority: AccountInfo<'info>,
+    pub account_compression_program: Program<'info, AccountCompression>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Collect network fees:
+/// 1. Check that epoch is the current active epoch
+/// 2. Transfer network fees of the tree and queue to the reward vault
+///     via cpi to the account compression program
+/// 3. Add the transferred lamports to the total rewards of the epoch
+pub fn process_collect_network_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, CollectNetworkFees<'info>>,
+    bump: u8,
+    epoch: u64,
+) -> Result<()> {
+    let current_solana_slot = Clock::get()?.slot;
+    let protocol_config = ctx.accounts.protocol_config_pda.config;
+    if protocol_config.get_current_active_epoch(current_solana_slot)? != epoch {
+        return err!(RegistryError::InvalidEpoch);
+    }
+
+    let bump = &[bump];
+    let seeds = [CPI_AUTHORITY_PDA_SEED, bump];
+    let signer_seeds = &[&seeds[..]];
+    let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+    let accounts = account_compression::cpi::accounts::TransferNetworkFees {
+        authority: ctx.accounts.cpi_authority.to_account_info(),
+        registered_program_pda: Some(ctx.accounts.registered_program_pda.clone()),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        output_queue: ctx.accounts.output_queue.clone(),
+        fee_recipient: reward_vault_info.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.account_compression_program.to_account_info(),
+        accounts,
+        signer_seeds,
+    );
+    let reward_vault_lamports = reward_vault_info.lamports();
+    account_compression::cpi::transfer_network_fees(cpi_ctx)?;
+    let network_fees = reward_vault_info
+        .lamports()
+        .checked_sub(reward_vault_lamports)
+        .ok_or(RegistryError::ArithmeticUnderflow)?;
+    ctx.accounts.reward_vault.add_rewards(
+        &protocol_config,
+        epoch,
+        network_fees,
+        current_solana_slot,
+    )
+}
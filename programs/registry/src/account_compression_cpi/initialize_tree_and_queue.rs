@@ -64,7 +64,8 @@ pub fn process_initialize_state_merkle_tree(
         forester,
         merkle_tree_config,
         queue_config,
-        0,
+        // The cpi context account is rolled over with the tree.
+        ctx.accounts.protocol_config_pda.config.cpi_context_size,
     )
 }
 
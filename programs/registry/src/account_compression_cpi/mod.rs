@@ -1,6 +1,10 @@
 pub mod batch_append;
 pub mod batch_nullify;
 pub mod batch_update_address_tree;
+pub mod close_address_tree_and_queue;
+pub mod close_batched_address_tree;
+pub mod close_batched_state_tree;
+pub mod collect_network_fees;
 pub mod initialize_batched_address_tree;
 pub mod initialize_batched_state_tree;
 pub mod initialize_tree_and_queue;
@@ -12,7 +12,8 @@ use light_system_program::program::LightSystemProgram;
 use solana_sdk::instruction::Instruction;
 
 use crate::utils::{
-    get_cpi_authority_pda, get_forester_epoch_pda_from_authority, get_protocol_config_pda_address,
+    get_cpi_authority_pda, get_epoch_reward_vault_address, get_forester_epoch_pda_from_authority,
+    get_protocol_config_pda_address,
 };
 pub struct CreateNullifyInstructionInputs {
     pub authority: Pubkey,
@@ -543,3 +544,103 @@ pub fn create_rollover_batch_address_tree_instruction(
         data: instruction_data.data(),
     }
 }
+
+pub fn create_close_batched_state_tree_instruction(
+    authority: Pubkey,
+    merkle_tree: Pubkey,
+    output_queue: Pubkey,
+) -> Instruction {
+    let registered_program_pda = get_registered_program_pda(&crate::ID);
+    let (cpi_authority_pda, bump) = get_cpi_authority_pda();
+    let accounts = crate::accounts::CloseBatchedStateMerkleTree {
+        authority,
+        protocol_config_pda: get_protocol_config_pda_address().0,
+        merkle_tree,
+        output_queue,
+        registered_program_pda,
+        cpi_authority: cpi_authority_pda,
+        account_compression_program: account_compression::ID,
+        system_program: solana_sdk::system_program::id(),
+    };
+    let instruction_data = crate::instruction::CloseBatchedStateMerkleTree { bump };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
+
+pub fn create_close_batched_address_tree_instruction(
+    authority: Pubkey,
+    merkle_tree: Pubkey,
+) -> Instruction {
+    let registered_program_pda = get_registered_program_pda(&crate::ID);
+    let (cpi_authority_pda, bump) = get_cpi_authority_pda();
+    let accounts = crate::accounts::CloseBatchedAddressMerkleTree {
+        authority,
+        protocol_config_pda: get_protocol_config_pda_address().0,
+        merkle_tree,
+        registered_program_pda,
+        cpi_authority: cpi_authority_pda,
+        account_compression_program: account_compression::ID,
+        system_program: solana_sdk::system_program::id(),
+    };
+    let instruction_data = crate::instruction::CloseBatchedAddressMerkleTree { bump };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
+
+pub fn create_close_address_tree_and_queue_instruction(
+    authority: Pubkey,
+    merkle_tree: Pubkey,
+    queue: Pubkey,
+) -> Instruction {
+    let registered_program_pda = get_registered_program_pda(&crate::ID);
+    let (cpi_authority_pda, bump) = get_cpi_authority_pda();
+    let accounts = crate::accounts::CloseAddressMerkleTreeAndQueue {
+        authority,
+        protocol_config_pda: get_protocol_config_pda_address().0,
+        merkle_tree,
+        queue,
+        registered_program_pda,
+        cpi_authority: cpi_authority_pda,
+        account_compression_program: account_compression::ID,
+        system_program: solana_sdk::system_program::id(),
+    };
+    let instruction_data = crate::instruction::CloseAddressMerkleTreeAndQueue { bump };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
+
+pub fn create_collect_network_fees_instruction(
+    fee_payer: Pubkey,
+    merkle_tree: Pubkey,
+    output_queue: Option<Pubkey>,
+    epoch: u64,
+) -> Instruction {
+    let registered_program_pda = get_registered_program_pda(&crate::ID);
+    let (cpi_authority_pda, bump) = get_cpi_authority_pda();
+    let accounts = crate::accounts::CollectNetworkFees {
+        fee_payer,
+        protocol_config_pda: get_protocol_config_pda_address().0,
+        reward_vault: get_epoch_reward_vault_address(epoch),
+        merkle_tree,
+        output_queue,
+        registered_program_pda,
+        cpi_authority: cpi_authority_pda,
+        account_compression_program: account_compression::ID,
+        system_program: solana_sdk::system_program::id(),
+    };
+    let instruction_data = crate::instruction::CollectNetworkFees { bump, epoch };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
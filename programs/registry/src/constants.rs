@@ -10,3 +10,11 @@ pub const PROTOCOL_CONFIG_PDA_SEED: &[u8] = b"authority";
 
 #[constant]
 pub const DEFAULT_WORK_V1: u64 = 1;
+
+#[constant]
+pub const EPOCH_REWARD_VAULT_SEED: &[u8] = b"epoch_reward_vault";
+
+/// Number of light slots per epoch in which forester work is recorded,
+/// see ForesterEpochPda::work_light_slots.
+#[constant]
+pub const MAX_RECORDED_LIGHT_SLOTS: u64 = 1024;
@@ -0,0 +1,424 @@
+use anchor_lang::prelude::*;
+
+use super::register_epoch::{EpochPda, ForesterEpochPda};
+use crate::{
+    constants::FORESTER_EPOCH_SEED, errors::RegistryError, selection::forester::ForesterPda,
+};
+
+#[derive(Accounts)]
+pub struct ChallengeForester<'info> {
+    /// Anyone can challenge a forester.
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub forester_pda: Account<'info, ForesterPda>,
+    #[account(mut, seeds = [FORESTER_EPOCH_SEED, forester_pda.key().to_bytes().as_slice(), forester_epoch_pda.epoch.to_le_bytes().as_slice()], bump)]
+    pub forester_epoch_pda: Account<'info, ForesterEpochPda>,
+    #[account(constraint = epoch_pda.epoch == forester_epoch_pda.epoch @ RegistryError::InvalidEpochAccount)]
+    pub epoch_pda: Account<'info, EpochPda>,
+    /// CHECK: is checked to be a batched state or address Merkle tree in ix.
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: is checked to be the output queue of the Merkle tree in ix.
+    pub output_queue: Option<AccountInfo<'info>>,
+}
+
+/// Challenge forester:
+/// 1. Check that the epoch is in the challenge window,
+///     the report work phase after the active phase.
+///     Rewards can only be claimed once the challenge window has ended.
+///     Fails if the slashing penalty of the epoch is 0,
+///     a challenge would mark the forester slashed without any penalty.
+/// 2. Check that the forester has not been slashed in this epoch
+/// 3. Check that the light slot is in the active phase and
+///     the forester was eligible for the tree in the light slot
+/// 4. Check that work was pending for the whole light slot
+///     and has not been processed since
+/// 5. Check that the forester did not perform any work in the light slot,
+///     partial progress is not slashed.
+///     Work is recorded per light slot in check_forester,
+///     light slots beyond the recorded range cannot be challenged.
+///     Work is not recorded per tree, work on any tree in the light slot
+///     prevents challenges for every other tree in that light slot.
+/// 6. Reduce the active weight of the forester by the slashing penalty
+///     of its epoch weight, the reduced weight applies in future epochs
+///
+/// Returns the slashed weight.
+pub fn challenge_forester_instruction(
+    forester_pda: &mut ForesterPda,
+    forester_epoch_pda: &mut ForesterEpochPda,
+    epoch_pda: &EpochPda,
+    tree_pubkey: &Pubkey,
+    light_slot: u64,
+    pending_since_slot: u64,
+    current_slot: u64,
+) -> Result<u64> {
+    let protocol_config = epoch_pda.protocol_config;
+    protocol_config.is_report_work_phase(current_slot, epoch_pda.epoch)?;
+    if protocol_config.forester_slashing_penalty == 0 {
+        return err!(RegistryError::ForesterSlashingDisabled);
+    }
+    if forester_epoch_pda.is_slashed {
+        return err!(RegistryError::ForesterAlreadySlashed);
+    }
+    let light_slot_start =
+        forester_epoch_pda.epoch_active_phase_start_slot + light_slot * protocol_config.slot_length;
+    let light_slot_end = light_slot_start + protocol_config.slot_length;
+    let active_phase_end =
+        forester_epoch_pda.epoch_active_phase_start_slot + protocol_config.active_phase_length;
+    if light_slot_end > active_phase_end {
+        msg!("Light slot {} is not in the active phase.", light_slot);
+        return err!(RegistryError::InvalidForesterChallenge);
+    }
+    let forester_slot = ForesterEpochPda::get_eligible_forester_index(
+        light_slot,
+        tree_pubkey,
+        epoch_pda.registered_weight,
+        epoch_pda.epoch,
+    )?;
+    if !forester_epoch_pda.is_eligible(forester_slot) {
+        return err!(RegistryError::ForesterNotEligible);
+    }
+    if pending_since_slot > light_slot_start {
+        msg!(
+            "Work is pending since slot {}, light slot starts at slot {}.",
+            pending_since_slot,
+            light_slot_start
+        );
+        return err!(RegistryError::InvalidForesterChallenge);
+    }
+    match forester_epoch_pda.has_work_in_light_slot(light_slot) {
+        Some(false) => {}
+        Some(true) => {
+            msg!("Forester performed work in light slot {}.", light_slot);
+            return err!(RegistryError::InvalidForesterChallenge);
+        }
+        None => {
+            msg!("Work in light slot {} is not recorded.", light_slot);
+            return err!(RegistryError::InvalidForesterChallenge);
+        }
+    }
+
+    let slashed_weight = (forester_epoch_pda.weight as u128
+        * protocol_config.forester_slashing_penalty as u128
+        / 100) as u64;
+    forester_pda.active_weight = forester_pda.active_weight.saturating_sub(slashed_weight);
+    forester_epoch_pda.is_slashed = true;
+    Ok(slashed_weight)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        epoch::claim_rewards::{claim_forester_rewards_instruction, EpochRewardVault},
+        protocol_config::state::ProtocolConfig,
+        selection::forester::ForesterConfig,
+    };
+
+    const SLOT_LENGTH: u64 = 10;
+    const ACTIVE_PHASE_START_SLOT: u64 = 10;
+
+    fn setup() -> (ForesterPda, ForesterEpochPda, EpochPda) {
+        let protocol_config = ProtocolConfig {
+            genesis_slot: 0,
+            registration_phase_length: 10,
+            active_phase_length: 100,
+            report_work_phase_length: 10,
+            min_weight: 1,
+            slot_length: SLOT_LENGTH,
+            forester_slashing_penalty: 10,
+            ..Default::default()
+        };
+        let forester_pda = ForesterPda {
+            active_weight: 1000,
+            ..Default::default()
+        };
+        let forester_epoch_pda = ForesterEpochPda {
+            authority: Pubkey::new_unique(),
+            config: ForesterConfig::default(),
+            epoch: 0,
+            weight: 1000,
+            forester_index: 0,
+            epoch_active_phase_start_slot: ACTIVE_PHASE_START_SLOT,
+            total_epoch_weight: Some(1000),
+            protocol_config,
+            ..Default::default()
+        };
+        let epoch_pda = EpochPda {
+            epoch: 0,
+            protocol_config,
+            total_work: 0,
+            registered_weight: 1000,
+        };
+        (forester_pda, forester_epoch_pda, epoch_pda)
+    }
+
+    #[test]
+    fn test_challenge_forester() {
+        let tree_pubkey = Pubkey::new_unique();
+        // Report work phase of epoch 0.
+        let current_slot = ACTIVE_PHASE_START_SLOT + 100;
+
+        // 1. Functional
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            let slashed_weight = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT + 2 * SLOT_LENGTH,
+                current_slot,
+            )
+            .unwrap();
+            assert_eq!(slashed_weight, 100);
+            assert_eq!(forester_pda.active_weight, 900);
+            assert!(forester_epoch_pda.is_slashed);
+            // 2. Failing: already slashed
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::ForesterAlreadySlashed.into()));
+            assert_eq!(forester_pda.active_weight, 900);
+        }
+        // 3. Failing: work was not pending for the whole light slot
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT + 2 * SLOT_LENGTH + 1,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::InvalidForesterChallenge.into()));
+        }
+        // 4. Failing: light slot not in active phase
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                10,
+                ACTIVE_PHASE_START_SLOT,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::InvalidForesterChallenge.into()));
+        }
+        // 5. Failing: active phase has not ended
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT,
+                ACTIVE_PHASE_START_SLOT + 50,
+            );
+            assert_eq!(result, Err(RegistryError::NotInActivePhase.into()));
+        }
+        // 6. Failing: challenge window has ended
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT,
+                ACTIVE_PHASE_START_SLOT + 110,
+            );
+            assert_eq!(result, Err(RegistryError::NotInReportWorkPhase.into()));
+            assert_eq!(forester_pda.active_weight, 1000);
+        }
+        // 7. Failing: forester not eligible
+        {
+            let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+            forester_epoch_pda.weight = 500;
+            forester_epoch_pda.forester_index = 500;
+            let not_eligible_light_slot = (0..10)
+                .find(|light_slot| {
+                    let index = ForesterEpochPda::get_eligible_forester_index(
+                        *light_slot,
+                        &tree_pubkey,
+                        1000,
+                        0,
+                    )
+                    .unwrap();
+                    !forester_epoch_pda.is_eligible(index)
+                })
+                .unwrap();
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                not_eligible_light_slot,
+                ACTIVE_PHASE_START_SLOT,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::ForesterNotEligible.into()));
+            assert_eq!(forester_pda.active_weight, 1000);
+        }
+        // 8. Failing: slashing penalty is not set
+        {
+            let (mut forester_pda, mut forester_epoch_pda, mut epoch_pda) = setup();
+            epoch_pda.protocol_config.forester_slashing_penalty = 0;
+            let result = challenge_forester_instruction(
+                &mut forester_pda,
+                &mut forester_epoch_pda,
+                &epoch_pda,
+                &tree_pubkey,
+                2,
+                ACTIVE_PHASE_START_SLOT,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::ForesterSlashingDisabled.into()));
+            assert!(!forester_epoch_pda.is_slashed);
+        }
+    }
+
+    /// A forester which processed part of the pending work in its light slot
+    /// is not slashed, even though work is still pending.
+    #[test]
+    fn test_challenge_forester_partial_progress() {
+        let tree_pubkey = Pubkey::new_unique();
+        let current_slot = ACTIVE_PHASE_START_SLOT + 100;
+        let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+        // Forester processed a batch in light slot 2, the next batch is still pending.
+        let work_slot = ACTIVE_PHASE_START_SLOT + 2 * SLOT_LENGTH + 5;
+        let authority = forester_epoch_pda.authority;
+        ForesterEpochPda::check_forester(
+            &mut forester_epoch_pda,
+            &authority,
+            &tree_pubkey,
+            work_slot,
+            1,
+        )
+        .unwrap();
+        assert_eq!(forester_epoch_pda.has_work_in_light_slot(2), Some(true));
+        let result = challenge_forester_instruction(
+            &mut forester_pda,
+            &mut forester_epoch_pda,
+            &epoch_pda,
+            &tree_pubkey,
+            2,
+            ACTIVE_PHASE_START_SLOT,
+            current_slot,
+        );
+        assert_eq!(result, Err(RegistryError::InvalidForesterChallenge.into()));
+        assert_eq!(forester_pda.active_weight, 1000);
+        assert!(!forester_epoch_pda.is_slashed);
+        // Work in light slot 2 is no evidence for light slot 3.
+        assert_eq!(forester_epoch_pda.has_work_in_light_slot(3), Some(false));
+        let slashed_weight = challenge_forester_instruction(
+            &mut forester_pda,
+            &mut forester_epoch_pda,
+            &epoch_pda,
+            &tree_pubkey,
+            3,
+            ACTIVE_PHASE_START_SLOT,
+            current_slot,
+        )
+        .unwrap();
+        assert_eq!(slashed_weight, 100);
+    }
+
+    /// Work is recorded per light slot, not per tree.
+    /// Work on one tree prevents challenges for another tree
+    /// with pending work in the same light slot.
+    #[test]
+    fn test_challenge_forester_work_is_not_tracked_per_tree() {
+        let worked_tree_pubkey = Pubkey::new_unique();
+        let idle_tree_pubkey = Pubkey::new_unique();
+        let current_slot = ACTIVE_PHASE_START_SLOT + 100;
+        let (mut forester_pda, mut forester_epoch_pda, epoch_pda) = setup();
+        let work_slot = ACTIVE_PHASE_START_SLOT + 2 * SLOT_LENGTH;
+        let authority = forester_epoch_pda.authority;
+        ForesterEpochPda::check_forester(
+            &mut forester_epoch_pda,
+            &authority,
+            &worked_tree_pubkey,
+            work_slot,
+            1,
+        )
+        .unwrap();
+        let result = challenge_forester_instruction(
+            &mut forester_pda,
+            &mut forester_epoch_pda,
+            &epoch_pda,
+            &idle_tree_pubkey,
+            2,
+            ACTIVE_PHASE_START_SLOT,
+            current_slot,
+        );
+        assert_eq!(result, Err(RegistryError::InvalidForesterChallenge.into()));
+        assert!(!forester_epoch_pda.is_slashed);
+        // The idle tree can be challenged in light slots without any work.
+        challenge_forester_instruction(
+            &mut forester_pda,
+            &mut forester_epoch_pda,
+            &epoch_pda,
+            &idle_tree_pubkey,
+            3,
+            ACTIVE_PHASE_START_SLOT,
+            current_slot,
+        )
+        .unwrap();
+        assert!(forester_epoch_pda.is_slashed);
+    }
+
+    /// A forester cannot claim rewards, which closes its epoch account,
+    /// before the challenge window has ended.
+    #[test]
+    fn test_challenge_forester_claim_then_challenge() {
+        let tree_pubkey = Pubkey::new_unique();
+        let (mut forester_pda, mut forester_epoch_pda, mut epoch_pda) = setup();
+        forester_epoch_pda.has_reported_work = true;
+        epoch_pda.total_work = 1;
+        let mut reward_vault = EpochRewardVault::default();
+        let report_work_phase_start = ACTIVE_PHASE_START_SLOT + 100;
+        let post_phase_start =
+            report_work_phase_start + epoch_pda.protocol_config.report_work_phase_length;
+        for current_slot in report_work_phase_start..post_phase_start {
+            let result = claim_forester_rewards_instruction(
+                &forester_epoch_pda,
+                &epoch_pda,
+                &mut reward_vault,
+                current_slot,
+            );
+            assert_eq!(result, Err(RegistryError::EpochStillInProgress.into()));
+        }
+        // The challenge window has ended once rewards can be claimed.
+        claim_forester_rewards_instruction(
+            &forester_epoch_pda,
+            &epoch_pda,
+            &mut reward_vault,
+            post_phase_start,
+        )
+        .unwrap();
+        let result = challenge_forester_instruction(
+            &mut forester_pda,
+            &mut forester_epoch_pda,
+            &epoch_pda,
+            &tree_pubkey,
+            2,
+            ACTIVE_PHASE_START_SLOT,
+            post_phase_start,
+        );
+        assert_eq!(result, Err(RegistryError::NotInReportWorkPhase.into()));
+    }
+}
@@ -0,0 +1,261 @@
+use aligned_sized::aligned_sized;
+use anchor_lang::prelude::*;
+
+use super::register_epoch::{EpochPda, ForesterEpochPda};
+use crate::{
+    constants::EPOCH_REWARD_VAULT_SEED,
+    errors::RegistryError,
+    protocol_config::state::{ProtocolConfig, ProtocolConfigPda},
+};
+
+/// Holds the rewards of an epoch in lamports.
+/// Network fees of batched trees are transferred to the vault of the current
+/// active epoch with collect_network_fees,
+/// additional rewards can be funded with fund_epoch_reward_vault.
+#[account]
+#[aligned_sized(anchor)]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EpochRewardVault {
+    pub epoch: u64,
+    /// Rewards which are distributed to foresters of the epoch.
+    /// Is final once the active phase of the epoch has ended.
+    pub total_rewards: u64,
+    pub claimed_rewards: u64,
+}
+
+impl EpochRewardVault {
+    /// Adds rewards to the vault of an epoch.
+    /// Rewards can only be added until the active phase of the epoch has ended,
+    /// so that total rewards are final before foresters claim.
+    pub fn add_rewards(
+        &mut self,
+        protocol_config: &ProtocolConfig,
+        epoch: u64,
+        rewards: u64,
+        current_slot: u64,
+    ) -> Result<()> {
+        if protocol_config.is_post_epoch(current_slot, epoch).is_ok() {
+            msg!("Rewards of epoch {} are final.", epoch);
+            return err!(RegistryError::EpochRewardsFinalized);
+        }
+        self.epoch = epoch;
+        self.total_rewards = self
+            .total_rewards
+            .checked_add(rewards)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct FundEpochRewardVault<'info> {
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(init_if_needed, seeds = [EPOCH_REWARD_VAULT_SEED, epoch.to_le_bytes().as_slice()], bump, space = EpochRewardVault::LEN, payer = fee_payer)]
+    pub reward_vault: Account<'info, EpochRewardVault>,
+    pub protocol_config_pda: Account<'info, ProtocolConfigPda>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimForesterRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Is closed, rent is reimbursed to the authority.
+    /// Claims are only possible after the challenge window of the epoch
+    /// (report work phase) has ended, hence no slashing state is lost.
+    #[account(mut, has_one = authority, close = authority)]
+    pub forester_epoch_pda: Account<'info, ForesterEpochPda>,
+    #[account(constraint = epoch_pda.epoch == forester_epoch_pda.epoch @ RegistryError::InvalidEpochAccount)]
+    pub epoch_pda: Account<'info, EpochPda>,
+    #[account(mut, seeds = [EPOCH_REWARD_VAULT_SEED, epoch_pda.epoch.to_le_bytes().as_slice()], bump)]
+    pub reward_vault: Account<'info, EpochRewardVault>,
+}
+
+/// Claim forester rewards:
+/// 1. Check that the epoch is in the post phase,
+///     the report work phase has ended so that total work is final
+///     and foresters can no longer be challenged
+/// 2. Check that forester has reported work
+/// 3. Compute rewards pro rata to work_counter / total_work,
+///     total rewards are final since the active phase has ended
+///
+/// Returns the rewards in lamports.
+/// The forester epoch account is closed so that rewards can only be claimed once.
+pub fn claim_forester_rewards_instruction(
+    forester_epoch_pda: &ForesterEpochPda,
+    epoch_pda: &EpochPda,
+    reward_vault: &mut EpochRewardVault,
+    current_slot: u64,
+) -> Result<u64> {
+    epoch_pda
+        .protocol_config
+        .is_post_epoch(current_slot, epoch_pda.epoch)?;
+    if epoch_pda
+        .protocol_config
+        .is_report_work_phase(current_slot, epoch_pda.epoch)
+        .is_ok()
+    {
+        return err!(RegistryError::EpochStillInProgress);
+    }
+    if !forester_epoch_pda.has_reported_work {
+        return err!(RegistryError::ForesterWorkNotReported);
+    }
+    let total_rewards = reward_vault.total_rewards;
+    let rewards = if epoch_pda.total_work == 0 {
+        0
+    } else {
+        (total_rewards as u128 * forester_epoch_pda.work_counter as u128
+            / epoch_pda.total_work as u128) as u64
+    };
+    reward_vault.claimed_rewards = reward_vault
+        .claimed_rewards
+        .checked_add(rewards)
+        .filter(|claimed_rewards| *claimed_rewards <= total_rewards)
+        .ok_or(RegistryError::InsufficientRewardVaultBalance)?;
+    Ok(rewards)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn setup(total_work: u64) -> (EpochPda, u64) {
+        let protocol_config = ProtocolConfig::default();
+        let epoch_pda = EpochPda {
+            epoch: 0,
+            protocol_config,
+            total_work,
+            registered_weight: 0,
+        };
+        // First slot of the post phase of epoch 0.
+        let post_slot = protocol_config.genesis_slot
+            + protocol_config.registration_phase_length
+            + protocol_config.active_phase_length
+            + protocol_config.report_work_phase_length;
+        (epoch_pda, post_slot)
+    }
+
+    fn forester_epoch_pda(work_counter: u64, has_reported_work: bool) -> ForesterEpochPda {
+        ForesterEpochPda {
+            work_counter,
+            has_reported_work,
+            ..Default::default()
+        }
+    }
+
+    fn reward_vault(total_rewards: u64) -> EpochRewardVault {
+        EpochRewardVault {
+            epoch: 0,
+            total_rewards,
+            claimed_rewards: 0,
+        }
+    }
+
+    #[test]
+    fn test_add_rewards() {
+        let (epoch_pda, post_slot) = setup(100);
+        let protocol_config = epoch_pda.protocol_config;
+        let active_phase_end = post_slot - protocol_config.report_work_phase_length;
+        let mut reward_vault = EpochRewardVault::default();
+        // Rewards can be added before and during the active phase.
+        reward_vault
+            .add_rewards(&protocol_config, 0, 1_000, 0)
+            .unwrap();
+        reward_vault
+            .add_rewards(&protocol_config, 0, 2_000, active_phase_end - 1)
+            .unwrap();
+        assert_eq!(reward_vault.total_rewards, 3_000);
+        // Rewards are final once the active phase has ended.
+        for slot in [active_phase_end, post_slot] {
+            let result = reward_vault.add_rewards(&protocol_config, 0, 1_000, slot);
+            assert_eq!(result, Err(RegistryError::EpochRewardsFinalized.into()));
+        }
+        assert_eq!(reward_vault.total_rewards, 3_000);
+    }
+
+    #[test]
+    fn test_claim_forester_rewards() {
+        let (epoch_pda, post_slot) = setup(100);
+        let total_rewards = 1_000_000;
+        let mut reward_vault = reward_vault(total_rewards);
+
+        let rewards = claim_forester_rewards_instruction(
+            &forester_epoch_pda(25, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot,
+        )
+        .unwrap();
+        assert_eq!(rewards, 250_000);
+        assert_eq!(reward_vault.total_rewards, total_rewards);
+        assert_eq!(reward_vault.claimed_rewards, 250_000);
+
+        // Total rewards don't change with claims.
+        let rewards = claim_forester_rewards_instruction(
+            &forester_epoch_pda(75, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot,
+        )
+        .unwrap();
+        assert_eq!(rewards, 750_000);
+        assert_eq!(reward_vault.claimed_rewards, total_rewards);
+    }
+
+    #[test]
+    fn test_claim_forester_rewards_failing() {
+        let (epoch_pda, post_slot) = setup(100);
+        let mut reward_vault = reward_vault(1_000_000);
+        // Epoch is still active.
+        let result = claim_forester_rewards_instruction(
+            &forester_epoch_pda(25, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot - epoch_pda.protocol_config.report_work_phase_length - 1,
+        );
+        assert_eq!(result, Err(RegistryError::InvalidEpoch.into()));
+        // Report work phase, foresters can still be challenged.
+        let result = claim_forester_rewards_instruction(
+            &forester_epoch_pda(25, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot - 1,
+        );
+        assert_eq!(result, Err(RegistryError::EpochStillInProgress.into()));
+        // Work not reported.
+        let result = claim_forester_rewards_instruction(
+            &forester_epoch_pda(25, false),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot,
+        );
+        assert_eq!(result, Err(RegistryError::ForesterWorkNotReported.into()));
+        // Work counter exceeds total work.
+        let result = claim_forester_rewards_instruction(
+            &forester_epoch_pda(101, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot,
+        );
+        assert_eq!(
+            result,
+            Err(RegistryError::InsufficientRewardVaultBalance.into())
+        );
+    }
+
+    #[test]
+    fn test_claim_forester_rewards_no_work() {
+        let (epoch_pda, post_slot) = setup(0);
+        let mut reward_vault = reward_vault(1_000_000);
+        let rewards = claim_forester_rewards_instruction(
+            &forester_epoch_pda(0, true),
+            &epoch_pda,
+            &mut reward_vault,
+            post_slot,
+        )
+        .unwrap();
+        assert_eq!(rewards, 0);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod challenge_forester;
+pub mod claim_rewards;
 pub mod finalize_registration;
 pub mod register_epoch;
 pub mod report_work;
@@ -2,7 +2,7 @@ use aligned_sized::aligned_sized;
 use anchor_lang::{prelude::*, solana_program::pubkey::Pubkey};
 
 use crate::{
-    constants::FORESTER_EPOCH_SEED,
+    constants::{FORESTER_EPOCH_SEED, MAX_RECORDED_LIGHT_SLOTS},
     errors::RegistryError,
     protocol_config::state::{ProtocolConfig, ProtocolConfigPda},
     selection::forester::{ForesterConfig, ForesterPda},
@@ -41,6 +41,14 @@ pub struct ForesterEpochPda {
     pub protocol_config: ProtocolConfig,
     /// Incremented every time finalize registration is called.
     pub finalize_counter: u64,
+    /// A forester can be slashed once per epoch.
+    pub is_slashed: bool,
+    /// Bitmap of light slots in which the forester performed work.
+    /// Is evidence against challenges, see challenge_forester.
+    /// Work is recorded per light slot, not per tree. Work on any tree
+    /// in a light slot protects the forester against challenges for
+    /// all trees it was eligible for in that light slot.
+    pub work_light_slots: [u64; 16],
 }
 
 impl ForesterEpochPda {
@@ -106,10 +114,29 @@ impl ForesterEpochPda {
         }
     }
 
+    /// Marks the light slot as worked in.
+    /// Light slots beyond the bitmap are not recorded.
+    pub fn set_work_light_slot(&mut self, light_slot: u64) {
+        if light_slot < MAX_RECORDED_LIGHT_SLOTS {
+            self.work_light_slots[(light_slot / 64) as usize] |= 1 << (light_slot % 64);
+        }
+    }
+
+    /// Returns whether the forester performed work in the light slot,
+    /// None if the light slot is beyond the bitmap.
+    pub fn has_work_in_light_slot(&self, light_slot: u64) -> Option<bool> {
+        if light_slot < MAX_RECORDED_LIGHT_SLOTS {
+            Some(self.work_light_slots[(light_slot / 64) as usize] & (1 << (light_slot % 64)) != 0)
+        } else {
+            None
+        }
+    }
+
     /// Checks forester:
     /// - signer
     /// - eligibility
     /// - increments work counter
+    /// - records work in the current light slot
     pub fn check_forester(
         forester_epoch_pda: &mut ForesterEpochPda,
         authority: &Pubkey,
@@ -127,6 +154,8 @@ impl ForesterEpochPda {
         }
         forester_epoch_pda.check_eligibility(current_solana_slot, queue_pubkey)?;
         forester_epoch_pda.work_counter += num_work_items;
+        let current_light_slot = forester_epoch_pda.get_current_light_slot(current_solana_slot)?;
+        forester_epoch_pda.set_work_light_slot(current_light_slot);
         Ok(())
     }
 
@@ -223,6 +252,8 @@ pub fn process_register_for_epoch(
         total_epoch_weight: None,
         protocol_config: epoch_pda.protocol_config,
         finalize_counter: 0,
+        is_slashed: false,
+        work_light_slots: [0; 16],
     };
     forester_epoch_pda.clone_from(&initialized_forester_epoch_pda);
     epoch_pda.registered_weight += forester_pda.active_weight;
@@ -257,6 +288,8 @@ mod test {
             epoch_active_phase_start_slot,
             total_epoch_weight: Some(total_epoch_weight),
             finalize_counter: 0,
+            is_slashed: false,
+            work_light_slots: [0; 16],
             protocol_config: ProtocolConfig {
                 genesis_slot: 0,
                 registration_phase_length: 1,
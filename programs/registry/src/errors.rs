@@ -28,4 +28,11 @@ pub enum RegistryError {
     GetCurrentActiveEpochFailed,
     ForesterUndefined,
     ForesterDefined,
+    ForesterWorkNotReported,
+    InsufficientRewardVaultBalance,
+    EpochRewardsFinalized,
+    ForesterAlreadySlashed,
+    InvalidForesterChallenge,
+    NoPendingQueueWork,
+    ForesterSlashingDisabled,
 }
@@ -10,14 +10,18 @@ pub mod account_compression_cpi;
 pub mod errors;
 pub use account_compression_cpi::{
     batch_append::*, batch_nullify::*, batch_update_address_tree::*,
-    initialize_batched_address_tree::*, initialize_batched_state_tree::*,
+    close_address_tree_and_queue::*, close_batched_address_tree::*, close_batched_state_tree::*,
+    collect_network_fees::*, initialize_batched_address_tree::*, initialize_batched_state_tree::*,
     initialize_tree_and_queue::*, migrate_state::*, nullify::*, register_program::*,
     rollover_batched_address_tree::*, rollover_batched_state_tree::*, rollover_state_tree::*,
     update_address_tree::*,
 };
 pub use protocol_config::{initialize::*, update::*};
 
-pub use crate::epoch::{finalize_registration::*, register_epoch::*, report_work::*};
+pub use crate::epoch::{
+    challenge_forester::*, claim_rewards::*, finalize_registration::*, register_epoch::*,
+    report_work::*,
+};
 pub mod constants;
 pub mod epoch;
 pub mod protocol_config;
@@ -269,6 +273,136 @@ pub mod light_registry {
         Ok(())
     }
 
+    /// Transfers lamports to the reward vault of an epoch.
+    /// Rewards are distributed to foresters of the epoch
+    /// pro rata to their reported work.
+    /// The vault can be funded until the active phase of the epoch has ended.
+    pub fn fund_epoch_reward_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundEpochRewardVault<'info>>,
+        epoch: u64,
+        amount: u64,
+    ) -> Result<()> {
+        let current_solana_slot = anchor_lang::solana_program::clock::Clock::get()?.slot;
+        ctx.accounts.reward_vault.add_rewards(
+            &ctx.accounts.protocol_config_pda.config,
+            epoch,
+            amount,
+            current_solana_slot,
+        )?;
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.fee_payer.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+
+    /// Claims forester rewards of an epoch in the post phase.
+    /// Rewards are pro rata to work_counter / total_work.
+    /// Closes the forester epoch account.
+    pub fn claim_forester_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimForesterRewards<'info>>,
+    ) -> Result<()> {
+        let current_solana_slot = anchor_lang::solana_program::clock::Clock::get()?.slot;
+        let rewards = claim_forester_rewards_instruction(
+            &ctx.accounts.forester_epoch_pda,
+            &ctx.accounts.epoch_pda,
+            &mut ctx.accounts.reward_vault,
+            current_solana_slot,
+        )?;
+        let reward_vault_info = ctx.accounts.reward_vault.to_account_info();
+        **reward_vault_info.try_borrow_mut_lamports()? = reward_vault_info
+            .lamports()
+            .checked_sub(rewards)
+            .ok_or(RegistryError::InsufficientRewardVaultBalance)?;
+        let authority_info = ctx.accounts.authority.to_account_info();
+        **authority_info.try_borrow_mut_lamports()? = authority_info
+            .lamports()
+            .checked_add(rewards)
+            .ok_or(RegistryError::InsufficientRewardVaultBalance)?;
+        Ok(())
+    }
+
+    /// Transfers collected network fees of a batched tree
+    /// and its output queue to the reward vault of the current active epoch.
+    /// Can be called by anyone.
+    pub fn collect_network_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectNetworkFees<'info>>,
+        bump: u8,
+        epoch: u64,
+    ) -> Result<()> {
+        process_collect_network_fees(ctx, bump, epoch)
+    }
+
+    /// Slashes a forester which did not process pending work of a batched
+    /// tree in a light slot it was eligible for.
+    /// Can be called by anyone in the report work phase of the epoch.
+    /// Rewards can only be claimed after the report work phase,
+    /// hence a forester cannot escape a challenge by claiming first.
+    /// Work is pending if the pending batch of the input or output queue
+    /// has been ready since before the light slot and is still not inserted.
+    /// Foresters which performed any work in the light slot are not slashed.
+    /// Fails while the forester slashing penalty of the protocol config is 0.
+    pub fn challenge_forester<'info>(
+        ctx: Context<'_, '_, '_, 'info, ChallengeForester<'info>>,
+        light_slot: u64,
+    ) -> Result<()> {
+        let current_solana_slot = anchor_lang::solana_program::clock::Clock::get()?.slot;
+        let tree_pubkey = ctx.accounts.merkle_tree.key();
+        let (metadata, mut pending_batch_ready_slot) =
+            match BatchedMerkleTreeAccount::state_from_account_info(&ctx.accounts.merkle_tree) {
+                Ok(merkle_tree) => (
+                    merkle_tree.metadata,
+                    merkle_tree.queue_batches.get_pending_batch_ready_slot(),
+                ),
+                Err(_) => {
+                    let merkle_tree = BatchedMerkleTreeAccount::address_from_account_info(
+                        &ctx.accounts.merkle_tree,
+                    )
+                    .map_err(ProgramError::from)?;
+                    (
+                        merkle_tree.metadata,
+                        merkle_tree.queue_batches.get_pending_batch_ready_slot(),
+                    )
+                }
+            };
+        // Only trees serviced by light foresters can be challenged.
+        if metadata.rollover_metadata.network_fee == 0 {
+            return err!(RegistryError::InvalidNetworkFee);
+        }
+        if let Some(output_queue) = ctx.accounts.output_queue.as_ref() {
+            let output_queue = BatchedQueueAccount::output_from_account_info(output_queue)
+                .map_err(ProgramError::from)?;
+            if output_queue.metadata.associated_merkle_tree != tree_pubkey.into() {
+                return err!(RegistryError::InvalidForesterChallenge);
+            }
+            // The earliest slot proves the longest period of pending work.
+            pending_batch_ready_slot = match (
+                pending_batch_ready_slot,
+                output_queue.batch_metadata.get_pending_batch_ready_slot(),
+            ) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+        let pending_batch_ready_slot =
+            pending_batch_ready_slot.ok_or(RegistryError::NoPendingQueueWork)?;
+        challenge_forester_instruction(
+            &mut ctx.accounts.forester_pda,
+            &mut ctx.accounts.forester_epoch_pda,
+            &ctx.accounts.epoch_pda,
+            &tree_pubkey,
+            light_slot,
+            pending_batch_ready_slot,
+            current_solana_slot,
+        )?;
+        Ok(())
+    }
+
     pub fn initialize_address_merkle_tree(
         ctx: Context<InitializeMerkleTreeAndQueue>,
         bump: u8,
@@ -482,6 +616,12 @@ pub mod light_registry {
             if network_fee != ctx.accounts.protocol_config_pda.config.network_fee {
                 return err!(RegistryError::InvalidNetworkFee);
             }
+            if params.network_fee_curve
+                != ctx.accounts.protocol_config_pda.config.network_fee_curve()
+            {
+                msg!("Network fee curve must match the protocol config.");
+                return err!(RegistryError::InvalidNetworkFee);
+            }
             if params.forester.is_some() {
                 msg!("Forester pubkey must not be defined for trees serviced by light foresters.");
                 return err!(RegistryError::ForesterDefined);
@@ -641,6 +781,33 @@ pub mod light_registry {
         )
     }
 
+    /// Closes a rolled over batched state Merkle tree and its output queue.
+    /// Only the protocol authority can close trees, it receives the rent.
+    pub fn close_batched_state_merkle_tree<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseBatchedStateMerkleTree<'info>>,
+        bump: u8,
+    ) -> Result<()> {
+        process_close_batched_state_merkle_tree(&ctx, bump)
+    }
+
+    /// Closes a rolled over batched address Merkle tree.
+    /// Only the protocol authority can close trees, it receives the rent.
+    pub fn close_batched_address_merkle_tree<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseBatchedAddressMerkleTree<'info>>,
+        bump: u8,
+    ) -> Result<()> {
+        process_close_batched_address_merkle_tree(&ctx, bump)
+    }
+
+    /// Closes a rolled over indexed (v1) address Merkle tree and its queue.
+    /// Only the protocol authority can close trees, it receives the rent.
+    pub fn close_address_merkle_tree_and_queue<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseAddressMerkleTreeAndQueue<'info>>,
+        bump: u8,
+    ) -> Result<()> {
+        process_close_address_merkle_tree_and_queue(&ctx, bump)
+    }
+
     pub fn migrate_state<'info>(
         ctx: Context<'_, '_, '_, 'info, MigrateState<'info>>,
         bump: u8,
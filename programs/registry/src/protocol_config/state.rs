@@ -1,5 +1,6 @@
 use aligned_sized::aligned_sized;
 use anchor_lang::prelude::*;
+use light_merkle_tree_metadata::fee::NetworkFeeCurve;
 
 use crate::errors::RegistryError;
 
@@ -39,11 +40,21 @@ pub struct ProtocolConfig {
     pub finalize_counter_limit: u64,
     /// Placeholder for future protocol updates.
     pub place_holder: Pubkey,
-    pub place_holder_a: u64,
-    pub place_holder_b: u64,
-    pub place_holder_c: u64,
-    pub place_holder_d: u64,
-    pub place_holder_e: u64,
+    /// Network fee curve of batched state trees keyed on queue fill level,
+    /// see `NetworkFeeCurve`. Disabled if the max fee is zero.
+    /// New batched state trees store this curve in their metadata.
+    pub network_fee_curve_min_fee: u64,
+    pub network_fee_curve_max_fee: u64,
+    /// Fill level in percent.
+    pub network_fee_curve_low_fill_threshold: u64,
+    /// Fill level in percent.
+    pub network_fee_curve_high_fill_threshold: u64,
+    /// Percentage of its epoch weight a forester loses
+    /// when it is slashed for missed light slots.
+    /// Reuses a former zeroed placeholder, hence deployed configs read 0
+    /// until the penalty is set with update_protocol_config.
+    /// Foresters cannot be challenged while the penalty is 0.
+    pub forester_slashing_penalty: u64,
     pub place_holder_f: u64,
 }
 
@@ -60,11 +71,11 @@ impl Default for ProtocolConfig {
             cpi_context_size: 20 * 1024 + 8,
             finalize_counter_limit: 100,
             place_holder: Pubkey::default(),
-            place_holder_a: 0,
-            place_holder_b: 0,
-            place_holder_c: 0,
-            place_holder_d: 0,
-            place_holder_e: 0,
+            network_fee_curve_min_fee: 0,
+            network_fee_curve_max_fee: 0,
+            network_fee_curve_low_fill_threshold: 0,
+            network_fee_curve_high_fill_threshold: 0,
+            forester_slashing_penalty: 10,
             place_holder_f: 0,
         }
     }
@@ -83,11 +94,11 @@ impl ProtocolConfig {
             cpi_context_size: 20 * 1024 + 8,
             finalize_counter_limit: 100,
             place_holder: Pubkey::default(),
-            place_holder_a: 0,
-            place_holder_b: 0,
-            place_holder_c: 0,
-            place_holder_d: 0,
-            place_holder_e: 0,
+            network_fee_curve_min_fee: 0,
+            network_fee_curve_max_fee: 0,
+            network_fee_curve_low_fill_threshold: 0,
+            network_fee_curve_high_fill_threshold: 0,
+            forester_slashing_penalty: 10,
             place_holder_f: 0,
         }
     }
@@ -149,6 +160,15 @@ pub enum EpochState {
 /// - Post 2: 3200 - inf
 ///
 impl ProtocolConfig {
+    pub fn network_fee_curve(&self) -> NetworkFeeCurve {
+        NetworkFeeCurve {
+            min_fee: self.network_fee_curve_min_fee,
+            max_fee: self.network_fee_curve_max_fee,
+            low_fill_threshold: self.network_fee_curve_low_fill_threshold,
+            high_fill_threshold: self.network_fee_curve_high_fill_threshold,
+        }
+    }
+
     /// Current epoch including registration phase.
     pub fn get_latest_register_epoch(&self, slot: u64) -> Result<u64> {
         let slot = slot
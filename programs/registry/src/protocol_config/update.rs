@@ -43,5 +43,16 @@ pub fn check_protocol_config(protocol_config: ProtocolConfig) -> Result<()> {
         );
         return err!(RegistryError::InvalidConfigUpdate);
     }
+    if protocol_config.forester_slashing_penalty > 100 {
+        msg!(
+            "Forester slashing penalty must be less or equal than 100. {}",
+            protocol_config.forester_slashing_penalty
+        );
+        return err!(RegistryError::InvalidConfigUpdate);
+    }
+    if protocol_config.network_fee_curve().validate().is_err() {
+        msg!("Invalid network fee curve.");
+        return err!(RegistryError::InvalidConfigUpdate);
+    }
     Ok(())
 }
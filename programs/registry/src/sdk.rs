@@ -9,8 +9,9 @@ use solana_sdk::{
 use crate::{
     protocol_config::state::ProtocolConfig,
     utils::{
-        get_cpi_authority_pda, get_epoch_pda_address, get_forester_epoch_pda_from_authority,
-        get_forester_epoch_pda_from_derivation, get_forester_pda, get_protocol_config_pda_address,
+        get_cpi_authority_pda, get_epoch_pda_address, get_epoch_reward_vault_address,
+        get_forester_epoch_pda_from_authority, get_forester_epoch_pda_from_derivation,
+        get_forester_pda, get_protocol_config_pda_address,
     },
     ForesterConfig,
 };
@@ -276,3 +277,73 @@ pub fn create_report_work_instruction(
         data: instruction_data.data(),
     }
 }
+
+pub fn create_fund_epoch_reward_vault_instruction(
+    fee_payer: &Pubkey,
+    epoch: u64,
+    amount: u64,
+) -> Instruction {
+    let reward_vault = get_epoch_reward_vault_address(epoch);
+    let instruction_data = crate::instruction::FundEpochRewardVault { epoch, amount };
+    let accounts = crate::accounts::FundEpochRewardVault {
+        fee_payer: *fee_payer,
+        reward_vault,
+        protocol_config_pda: get_protocol_config_pda_address().0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
+
+pub fn create_challenge_forester_instruction(
+    authority: &Pubkey,
+    forester_authority: &Pubkey,
+    epoch: u64,
+    merkle_tree: &Pubkey,
+    output_queue: Option<Pubkey>,
+    light_slot: u64,
+) -> Instruction {
+    let (forester_pda, _bump) = get_forester_pda(forester_authority);
+    let (forester_epoch_pda, _bump) =
+        get_forester_epoch_pda_from_authority(forester_authority, epoch);
+    let epoch_pda = get_epoch_pda_address(epoch);
+    let instruction_data = crate::instruction::ChallengeForester { light_slot };
+    let accounts = crate::accounts::ChallengeForester {
+        authority: *authority,
+        forester_pda,
+        forester_epoch_pda,
+        epoch_pda,
+        merkle_tree: *merkle_tree,
+        output_queue,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
+
+pub fn create_claim_forester_rewards_instruction(
+    authority: &Pubkey,
+    derivation: &Pubkey,
+    epoch: u64,
+) -> Instruction {
+    let (forester_epoch_pda, _bump) = get_forester_epoch_pda_from_authority(derivation, epoch);
+    let epoch_pda = get_epoch_pda_address(epoch);
+    let reward_vault = get_epoch_reward_vault_address(epoch);
+    let instruction_data = crate::instruction::ClaimForesterRewards {};
+    let accounts = crate::accounts::ClaimForesterRewards {
+        authority: *authority,
+        forester_epoch_pda,
+        epoch_pda,
+        reward_vault,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(Some(true)),
+        data: instruction_data.data(),
+    }
+}
@@ -1,6 +1,8 @@
 use anchor_lang::solana_program::pubkey::Pubkey;
 
-use crate::constants::{FORESTER_EPOCH_SEED, FORESTER_SEED, PROTOCOL_CONFIG_PDA_SEED};
+use crate::constants::{
+    EPOCH_REWARD_VAULT_SEED, FORESTER_EPOCH_SEED, FORESTER_SEED, PROTOCOL_CONFIG_PDA_SEED,
+};
 
 pub fn get_protocol_config_pda_address() -> (Pubkey, u8) {
     Pubkey::find_program_address(&[PROTOCOL_CONFIG_PDA_SEED], &crate::ID)
@@ -42,3 +44,11 @@ pub fn get_forester_pda(authority: &Pubkey) -> (Pubkey, u8) {
 pub fn get_epoch_pda_address(epoch: u64) -> Pubkey {
     Pubkey::find_program_address(&[&epoch.to_le_bytes()], &crate::ID).0
 }
+
+pub fn get_epoch_reward_vault_address(epoch: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[EPOCH_REWARD_VAULT_SEED, epoch.to_le_bytes().as_slice()],
+        &crate::ID,
+    )
+    .0
+}
@@ -3,10 +3,14 @@ use light_compressed_account::{
     hash_to_bn254_field_size_be,
     instruction_data::{
         cpi_context::CompressedCpiContext,
-        data::OutputCompressedAccountWithPackedContext,
+        data::{
+            CpiContextNewAddressParamsAssignedPacked, OutputCompressedAccountWithPackedContext,
+        },
         invoke_cpi::InstructionDataInvokeCpi,
         traits::{InputAccount, InstructionData, NewAddress, OutputAccount},
-        zero_copy::{ZPackedReadOnlyAddress, ZPackedReadOnlyCompressedAccount},
+        zero_copy::{
+            ZPackedDecompressionRecipient, ZPackedReadOnlyAddress, ZPackedReadOnlyCompressedAccount,
+        },
     },
 };
 use pinocchio::{account_info::AccountInfo, instruction::AccountMeta, pubkey::Pubkey};
@@ -24,7 +28,8 @@ pub struct SystemContext<'info> {
     pub hashed_pubkeys: Vec<(Pubkey, [u8; 32])>,
     // Addresses for deduplication.
     // Try to find a way without storing the addresses.
-    pub addresses: Vec<Option<[u8; 32]>>,
+    // Addresses staged in a cpi context are stored with the staging program.
+    pub addresses: Vec<(Option<[u8; 32]>, Option<Pubkey>)>,
     // Index of account and fee to be paid.
     pub rollover_fee_payments: Vec<(u8, u64)>,
     pub address_fee_is_set: bool,
@@ -64,6 +69,22 @@ impl SystemContext<'_> {
         }
     }
 
+    /// Removes the address of an output compressed account
+    /// from the existing and new addresses.
+    /// An address staged in a cpi context can only be used
+    /// by output compressed accounts owned by the program that staged it.
+    pub fn use_output_address(&mut self, address: [u8; 32], owner: &Pubkey) -> Result<()> {
+        let position = self
+            .addresses
+            .iter()
+            .position(|(x, address_owner)| {
+                *x == Some(address) && address_owner.map_or(true, |x| x == *owner)
+            })
+            .ok_or(SystemProgramError::InvalidAddress)?;
+        self.addresses.remove(position);
+        Ok(())
+    }
+
     pub fn get_or_hash_pubkey(&mut self, pubkey: Pubkey) -> [u8; 32] {
         let hashed_pubkey = self
             .hashed_pubkeys
@@ -189,7 +210,7 @@ impl<'a, 'b, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
             if self.outputs_len > MAX_OUTPUT_ACCOUNTS {
                 return Err(SystemProgramError::TooManyOutputAccounts.into());
             }
-            self.address_len += cpi_context.context[0].new_address_params.len();
+            self.address_len += cpi_context.new_addresses.len();
             self.input_len += cpi_context.context[0]
                 .input_compressed_accounts_with_merkle_context
                 .len();
@@ -245,6 +266,12 @@ impl<'a, 'b, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
         self.instruction_data.with_transaction_hash()
     }
 
+    /// Number of output accounts of the instruction,
+    /// outputs of the cpi context follow these.
+    pub fn instruction_outputs_len(&self) -> usize {
+        self.instruction_data.output_accounts().len()
+    }
+
     pub fn get_output_account(&'b self, index: usize) -> Option<&'b (dyn OutputAccount<'a> + 'b)> {
         let ix_outputs_len = self.instruction_data.output_accounts().len();
         if index >= ix_outputs_len {
@@ -295,7 +322,7 @@ impl<'a, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
         if let Some(cpi_context) = &self.cpi_context {
             chain_new_addresses(
                 self.instruction_data.new_addresses(),
-                cpi_context.context[0].new_addresses(),
+                cpi_context.new_addresses.as_slice(),
             )
         } else {
             let empty_slice = &[];
@@ -326,10 +353,28 @@ impl<'a, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
         }
     }
 
+    /// Copies inputs, outputs and new addresses to append them to the cpi context.
+    /// New addresses are stored with the invoking program
+    /// so that these are derived with it when the cpi context is executed.
+    /// Assigned account indices are converted to indices
+    /// of the output compressed accounts in the cpi context,
+    /// which already contains num_cpi_context_outputs outputs.
+    /// Decompression recipients cannot be stored in the cpi context
+    /// and are rejected instead of being dropped.
     pub fn into_instruction_data_invoke_cpi(
         &self,
+        num_cpi_context_outputs: usize,
         cpi_account_data: &mut InstructionDataInvokeCpi,
-    ) {
+        cpi_context_new_addresses: &mut Vec<CpiContextNewAddressParamsAssignedPacked>,
+        invoking_program: &Pubkey,
+    ) -> Result<()> {
+        if self
+            .instruction_data
+            .decompression_recipients()
+            .is_some_and(|recipients| !recipients.is_empty())
+        {
+            return Err(SystemProgramError::DecompressionRecipientsInCpiContext.into());
+        }
         for input in self.instruction_data.input_accounts() {
             if input.skip() {
                 continue;
@@ -368,9 +413,34 @@ impl<'a, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
                 .push(output_account);
         }
 
-        if !self.instruction_data.new_addresses().is_empty() {
-            unimplemented!("Address assignment cannot be guaranteed with cpi context.");
+        let outputs = self.instruction_data.output_accounts();
+        for new_address in self.instruction_data.new_addresses() {
+            let assigned_account_index = match new_address.assigned_compressed_account_index() {
+                Some(index) => {
+                    if outputs.get(index).filter(|x| !x.skip()).is_none() {
+                        return Err(SystemProgramError::NewAddressAssignedIndexOutOfBounds.into());
+                    }
+                    // Skipped outputs are not copied into the cpi context.
+                    let index = num_cpi_context_outputs
+                        + outputs[..index].iter().filter(|x| !x.skip()).count();
+                    Some(
+                        u8::try_from(index)
+                            .map_err(|_| SystemProgramError::NewAddressAssignedIndexOutOfBounds)?,
+                    )
+                }
+                None => None,
+            };
+            cpi_context_new_addresses.push(CpiContextNewAddressParamsAssignedPacked {
+                owner: *invoking_program,
+                seed: new_address.seed(),
+                address_queue_account_index: new_address.address_queue_index(),
+                address_merkle_tree_account_index: new_address.address_merkle_tree_account_index(),
+                address_merkle_tree_root_index: new_address.address_merkle_tree_root_index(),
+                assigned_to_account: assigned_account_index.is_some(),
+                assigned_account_index: assigned_account_index.unwrap_or_default(),
+            });
         }
+        Ok(())
     }
 
     pub fn cpi_context(&self) -> Option<CompressedCpiContext> {
@@ -384,6 +454,10 @@ impl<'a, T: InstructionData<'a>> WrappedInstructionData<'a, T> {
     pub fn read_only_accounts(&self) -> Option<&[ZPackedReadOnlyCompressedAccount]> {
         self.instruction_data.read_only_accounts()
     }
+
+    pub fn decompression_recipients(&self) -> Option<&[ZPackedDecompressionRecipient]> {
+        self.instruction_data.decompression_recipients()
+    }
 }
 
 pub fn chain_outputs<'a, 'b: 'a>(
@@ -108,6 +108,16 @@ pub enum SystemProgramError {
     InvalidTreeHeight,
     #[error("TooManyOutputAccounts")]
     TooManyOutputAccounts,
+    #[error("DecompressionRecipientIndexOutOfBounds")]
+    DecompressionRecipientIndexOutOfBounds,
+    #[error(
+        "Decompression recipients and compress_or_decompress_lamports are mutually exclusive."
+    )]
+    DecompressionRecipientsWithCompressOrDecompressLamports,
+    #[error("Decompression recipients are not supported in cpi context invocations.")]
+    DecompressionRecipientsInCpiContext,
+    #[error("Cpi context account is too small for the cpi context data.")]
+    CpiContextAccountTooSmall,
 }
 
 impl From<SystemProgramError> for ProgramError {
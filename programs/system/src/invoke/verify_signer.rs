@@ -11,7 +11,7 @@ pub fn input_compressed_accounts_signer_check(
         .iter()
         .try_for_each(
             |compressed_account_with_context: &ZPackedCompressedAccountWithMerkleContext| {
-                if light_compressed_account::pubkey::Pubkey::from(*authority) == compressed_account_with_context.compressed_account.owner
+                if light_compressed_account::pubkey::Pubkey::from(*authority) == *compressed_account_with_context.compressed_account.owner
                     && compressed_account_with_context
                         .compressed_account
                         .data
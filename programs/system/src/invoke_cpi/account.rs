@@ -4,15 +4,20 @@ use aligned_sized::aligned_sized;
 use borsh::{BorshDeserialize, BorshSerialize};
 use light_account_checks::discriminator::Discriminator;
 use light_compressed_account::instruction_data::{
+    data::CpiContextNewAddressParamsAssignedPacked,
     invoke_cpi::InstructionDataInvokeCpi,
     zero_copy::{
+        ZCompressedCpiContext, ZCpiContextNewAddressParamsAssignedPacked,
         ZInstructionDataInvokeCpi, ZNewAddressParamsPacked,
         ZOutputCompressedAccountWithPackedContext, ZPackedCompressedAccountWithMerkleContext,
     },
 };
 use light_zero_copy::{borsh::Deserialize, errors::ZeroCopyError, slice::ZeroCopySliceBorsh};
 use pinocchio::{account_info::AccountInfo, pubkey::Pubkey};
-use zerocopy::{little_endian::U32, Ref};
+use zerocopy::{
+    little_endian::{U32, U64},
+    Ref,
+};
 
 use crate::CPI_CONTEXT_ACCOUNT_DISCRIMINATOR;
 
@@ -22,14 +27,38 @@ use crate::CPI_CONTEXT_ACCOUNT_DISCRIMINATOR;
 /// and executed as a single transaction.
 /// This enables to use input compressed accounts that are owned by multiple programs,
 /// with one zero-knowledge proof.
+/// New addresses are stored separately from the context
+/// since these are derived with the program that staged them.
 #[aligned_sized(anchor)]
-#[derive(Debug, PartialEq, Default, BorshSerialize, BorshDeserialize, Clone)]
+#[derive(Debug, PartialEq, Default, BorshSerialize, Clone)]
 #[repr(C)]
 pub struct CpiContextAccount {
     pub fee_payer: Pubkey,
     pub associated_merkle_tree: Pubkey,
     // Offset 72
     pub context: Vec<InstructionDataInvokeCpi>,
+    pub new_addresses: Vec<CpiContextNewAddressParamsAssignedPacked>,
+}
+
+impl BorshDeserialize for CpiContextAccount {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let fee_payer = Pubkey::deserialize_reader(reader)?;
+        let associated_merkle_tree = Pubkey::deserialize_reader(reader)?;
+        let context = Vec::<InstructionDataInvokeCpi>::deserialize_reader(reader)?;
+        // Clearing the cpi context account only zeroes the context length,
+        // new addresses are only valid if the context is set.
+        let new_addresses = if context.is_empty() {
+            Vec::new()
+        } else {
+            Vec::<CpiContextNewAddressParamsAssignedPacked>::deserialize_reader(reader)?
+        };
+        Ok(Self {
+            fee_payer,
+            associated_merkle_tree,
+            context,
+            new_addresses,
+        })
+    }
 }
 
 impl Discriminator for CpiContextAccount {
@@ -42,6 +71,7 @@ pub struct ZCpiContextAccount<'a> {
     pub fee_payer: Ref<&'a mut [u8], light_compressed_account::pubkey::Pubkey>,
     pub associated_merkle_tree: Ref<&'a mut [u8], light_compressed_account::pubkey::Pubkey>,
     pub context: Vec<ZInstructionDataInvokeCpi<'a>>,
+    pub new_addresses: ZeroCopySliceBorsh<'a, ZCpiContextNewAddressParamsAssignedPacked>,
 }
 
 pub fn deserialize_cpi_context_account<'a>(
@@ -59,7 +89,7 @@ pub fn deserialize_cpi_context_account<'a>(
 
     let (len, data) = Ref::<&'a mut [u8], U32>::from_prefix(data)?;
 
-    let (context, offsets) = if *len > U32::from(1) {
+    let (context, new_addresses, offsets) = if *len > U32::from(1) {
         return Err(ZeroCopyError::InvalidCapacity);
     } else if *len == 1 {
         // Skip proof option byte.
@@ -73,6 +103,13 @@ pub fn deserialize_cpi_context_account<'a>(
         let (output_compressed_accounts, bytes) =
             Vec::<ZOutputCompressedAccountWithPackedContext>::zero_copy_at(bytes)?;
         let output_accounts_end_offset = data_len - bytes.len();
+        // Skip relay fee, compress_or_decompress_lamports, is_compress and cpi context.
+        let (_, bytes) = Option::<Ref<&'a [u8], U64>>::zero_copy_at(bytes)?;
+        let (_, bytes) = Option::<Ref<&'a [u8], U64>>::zero_copy_at(bytes)?;
+        let (_, bytes) = u8::zero_copy_at(bytes)?;
+        let (_, bytes) = Option::<Ref<&'a [u8], ZCompressedCpiContext>>::zero_copy_at(bytes)?;
+        let (new_addresses, _) =
+            ZeroCopySliceBorsh::<ZCpiContextNewAddressParamsAssignedPacked>::from_bytes_at(bytes)?;
 
         let context = vec![ZInstructionDataInvokeCpi {
             new_address_params,
@@ -87,10 +124,15 @@ pub fn deserialize_cpi_context_account<'a>(
         }];
         (
             context,
+            new_addresses,
             (output_accounts_start_offset, output_accounts_end_offset),
         )
     } else {
-        (vec![], (0, 0))
+        let (new_addresses, _) =
+            ZeroCopySliceBorsh::<ZCpiContextNewAddressParamsAssignedPacked>::from_bytes_at(
+                &[0u8; 4],
+            )?;
+        (vec![], new_addresses, (0, 0))
     };
 
     Ok((
@@ -98,6 +140,7 @@ pub fn deserialize_cpi_context_account<'a>(
             fee_payer,
             associated_merkle_tree,
             context,
+            new_addresses,
         },
         offsets,
     ))
@@ -1,11 +1,28 @@
+use borsh::BorshSerialize;
 use light_account_checks::discriminator::Discriminator;
 use light_batched_merkle_tree::queue::BatchedQueueAccount;
 use light_compressed_account::instruction_data::{
-    invoke_cpi::InstructionDataInvokeCpi, traits::InstructionData,
+    compressed_proof::CompressedProof,
+    data::CpiContextNewAddressParamsAssignedPacked,
+    invoke_cpi::InstructionDataInvokeCpi,
+    traits::InstructionData,
+    zero_copy::{
+        ZCompressedCpiContext, ZCpiContextNewAddressParamsAssignedPacked, ZNewAddressParamsPacked,
+        ZOutputCompressedAccountWithPackedContext, ZPackedCompressedAccountWithMerkleContext,
+    },
+};
+use light_zero_copy::{
+    borsh::{Deserialize, DeserializeMut},
+    errors::ZeroCopyError,
+    slice::ZeroCopySliceBorsh,
 };
 use pinocchio::{account_info::AccountInfo, msg, pubkey::Pubkey};
+use zerocopy::{
+    little_endian::{U32, U64},
+    Ref,
+};
 
-use super::account::{deserialize_cpi_context_account, CpiContextAccount, ZCpiContextAccount};
+use super::account::{deserialize_cpi_context_account, ZCpiContextAccount};
 use crate::{context::WrappedInstructionData, errors::SystemProgramError, Result};
 
 /// Diff:
@@ -37,6 +54,7 @@ pub fn process_cpi_context<'a, 'info, T: InstructionData<'a>>(
     mut instruction_data: WrappedInstructionData<'a, T>,
     cpi_context_account_info: Option<&'info AccountInfo>,
     fee_payer: Pubkey,
+    invoking_program: Pubkey,
     remaining_accounts: &[AccountInfo],
 ) -> Result<Option<(usize, WrappedInstructionData<'a, T>)>> {
     let cpi_context = &instruction_data.cpi_context();
@@ -60,7 +78,12 @@ pub fn process_cpi_context<'a, 'info, T: InstructionData<'a>>(
         )?;
 
         if cpi_context.set_context || cpi_context.first_set_context {
-            set_cpi_context(fee_payer, cpi_context_account_info, instruction_data)?;
+            set_cpi_context(
+                fee_payer,
+                invoking_program,
+                cpi_context_account_info,
+                instruction_data,
+            )?;
             return Ok(None);
         } else {
             if cpi_context_account.context.is_empty() {
@@ -85,6 +108,7 @@ pub fn process_cpi_context<'a, 'info, T: InstructionData<'a>>(
 
 pub fn set_cpi_context<'a, 'info, T: InstructionData<'a>>(
     fee_payer: Pubkey,
+    invoking_program: Pubkey,
     cpi_context_account_info: &'info AccountInfo,
     instruction_data: WrappedInstructionData<'a, T>,
 ) -> Result<()> {
@@ -101,32 +125,142 @@ pub fn set_cpi_context<'a, 'info, T: InstructionData<'a>>(
     // cpi context, compress_or_decompress_lamports,
     // relay_fee
     // 2. Subsequent invocations check the proof and fee payer
-    use borsh::{BorshDeserialize, BorshSerialize};
-    let cpi_context_account = {
-        let data = cpi_context_account_info.try_borrow_data()?;
-        let mut cpi_context_account = CpiContextAccount::deserialize(&mut &data[8..]).unwrap();
-        if instruction_data.cpi_context().unwrap().first_set_context {
-            cpi_context_account.context.clear();
-            cpi_context_account.fee_payer = fee_payer;
-
-            let mut new_cpi_context_data = InstructionDataInvokeCpi::default();
-            instruction_data.into_instruction_data_invoke_cpi(&mut new_cpi_context_data);
-            cpi_context_account.context.push(new_cpi_context_data);
-        } else if cpi_context_account.fee_payer == fee_payer
-            && !cpi_context_account.context.is_empty()
-        {
-            instruction_data.into_instruction_data_invoke_cpi(&mut cpi_context_account.context[0]);
-        } else {
-            msg!(format!(" {:?} != {:?}", fee_payer, cpi_context_account.fee_payer).as_str());
+    let mut account_data = cpi_context_account_info.try_borrow_mut_data()?;
+    // The cpi context is updated in place, inputs, outputs and new addresses
+    // of the invocation are appended to the vectors stored in the account.
+    let (mut cpi_context_fee_payer, data) =
+        Ref::<&mut [u8], Pubkey>::zero_copy_at_mut(&mut account_data[8..])?;
+    let (_associated_merkle_tree, data) = Ref::<&mut [u8], Pubkey>::zero_copy_at_mut(data)?;
+    if instruction_data.cpi_context().unwrap().first_set_context {
+        *cpi_context_fee_payer = fee_payer;
+        // Clearing the cpi context account only zeroes the context length,
+        // an empty context and new addresses are written.
+        let mut bytes = &mut data[..];
+        vec![InstructionDataInvokeCpi::default()]
+            .serialize(&mut bytes)
+            .map_err(|_| SystemProgramError::CpiContextAccountTooSmall)?;
+        Vec::<CpiContextNewAddressParamsAssignedPacked>::new()
+            .serialize(&mut bytes)
+            .map_err(|_| SystemProgramError::CpiContextAccountTooSmall)?;
+    } else {
+        let (context_len, _) = Ref::<&[u8], U32>::zero_copy_at(data)?;
+        if *cpi_context_fee_payer != fee_payer || context_len.get() == 0 {
+            msg!(format!(" {:?} != {:?}", fee_payer, *cpi_context_fee_payer).as_str());
             return Err(SystemProgramError::CpiContextFeePayerMismatch.into());
         }
-        cpi_context_account
-    };
-    let mut data = cpi_context_account_info.try_borrow_mut_data()?;
-    cpi_context_account.serialize(&mut &mut data[8..]).unwrap();
+    }
+    let offsets = CpiContextOffsets::new(data).map_err(|_| SystemProgramError::InvalidAccount)?;
+    let mut context = InstructionDataInvokeCpi::default();
+    let mut new_addresses = Vec::new();
+    instruction_data.into_instruction_data_invoke_cpi(
+        offsets.num_outputs,
+        &mut context,
+        &mut new_addresses,
+        &invoking_program,
+    )?;
+    // Vectors are extended back to front,
+    // so that the offsets of the preceding vectors stay valid.
+    let end = insert_vec_elements(
+        data,
+        offsets.new_addresses_len_offset,
+        offsets.end,
+        offsets.end,
+        &new_addresses,
+    )?;
+    let end = insert_vec_elements(
+        data,
+        offsets.outputs_len_offset,
+        offsets.outputs_end,
+        end,
+        &context.output_compressed_accounts,
+    )?;
+    insert_vec_elements(
+        data,
+        offsets.inputs_len_offset,
+        offsets.inputs_end,
+        end,
+        &context.input_compressed_accounts_with_merkle_context,
+    )?;
     Ok(())
 }
 
+/// Offsets of the vectors stored in the cpi context account,
+/// relative to the account data after the associated merkle tree.
+struct CpiContextOffsets {
+    inputs_len_offset: usize,
+    inputs_end: usize,
+    num_outputs: usize,
+    outputs_len_offset: usize,
+    outputs_end: usize,
+    new_addresses_len_offset: usize,
+    /// End of the used account data.
+    end: usize,
+}
+
+impl CpiContextOffsets {
+    fn new(data: &[u8]) -> std::result::Result<Self, ZeroCopyError> {
+        let offset = |bytes: &[u8]| data.len() - bytes.len();
+        let (context_len, bytes) = Ref::<&[u8], U32>::zero_copy_at(data)?;
+        if *context_len != U32::new(1) {
+            return Err(ZeroCopyError::InvalidCapacity);
+        }
+        let (_, bytes) = Option::<CompressedProof>::zero_copy_at(bytes)?;
+        let (_, bytes) = ZeroCopySliceBorsh::<ZNewAddressParamsPacked>::from_bytes_at(bytes)?;
+        let inputs_len_offset = offset(bytes);
+        let (_, bytes) = Vec::<ZPackedCompressedAccountWithMerkleContext>::zero_copy_at(bytes)?;
+        let inputs_end = offset(bytes);
+        let (outputs, bytes) =
+            Vec::<ZOutputCompressedAccountWithPackedContext>::zero_copy_at(bytes)?;
+        let outputs_end = offset(bytes);
+        // Skip relay fee, compress_or_decompress_lamports, is_compress and cpi context.
+        let (_, bytes) = Option::<Ref<&[u8], U64>>::zero_copy_at(bytes)?;
+        let (_, bytes) = Option::<Ref<&[u8], U64>>::zero_copy_at(bytes)?;
+        let (_, bytes) = u8::zero_copy_at(bytes)?;
+        let (_, bytes) = Option::<Ref<&[u8], ZCompressedCpiContext>>::zero_copy_at(bytes)?;
+        let new_addresses_len_offset = offset(bytes);
+        let (_, bytes) =
+            ZeroCopySliceBorsh::<ZCpiContextNewAddressParamsAssignedPacked>::from_bytes_at(bytes)?;
+        Ok(Self {
+            inputs_len_offset,
+            inputs_end,
+            num_outputs: outputs.len(),
+            outputs_len_offset: inputs_end,
+            outputs_end,
+            new_addresses_len_offset,
+            end: offset(bytes),
+        })
+    }
+}
+
+/// Inserts the elements at offset of the used account data data[..end]
+/// and increments the length of their vector at len_offset.
+/// Returns the new end of the used account data.
+fn insert_vec_elements<E: BorshSerialize>(
+    data: &mut [u8],
+    len_offset: usize,
+    offset: usize,
+    end: usize,
+    elements: &[E],
+) -> Result<usize> {
+    if elements.is_empty() {
+        return Ok(end);
+    }
+    // Serialize the elements into the unused account data
+    // and rotate them to the offset.
+    let mut unused_data = &mut data[end..];
+    let unused_data_len = unused_data.len();
+    for element in elements {
+        element
+            .serialize(&mut unused_data)
+            .map_err(|_| SystemProgramError::CpiContextAccountTooSmall)?;
+    }
+    let num_bytes = unused_data_len - unused_data.len();
+    data[offset..end + num_bytes].rotate_right(num_bytes);
+    let (mut len, _) = Ref::<&mut [u8], U32>::zero_copy_at_mut(&mut data[len_offset..])?;
+    *len = U32::new(len.get() + elements.len() as u32);
+    Ok(end + num_bytes)
+}
+
 /// Copy CPI context outputs to the provided buffer.
 /// This way we ensure that all data involved in the instruction is emitted in this transaction.
 /// This prevents an edge case where users misuse the cpi context over multiple transactions
@@ -216,25 +350,39 @@ fn validate_cpi_context_associated_with_merkle_tree<'a, 'info, T: InstructionDat
 /// Functional process cpi context:
 /// 1. Set context
 /// 2. Combine (with malicious input in cpi context account)
+/// 3. Combine new addresses of multiple programs
 #[cfg(test)]
 mod tests {
 
-    use borsh::BorshSerialize;
+    use borsh::{BorshDeserialize, BorshSerialize};
     use light_account_checks::test_account_info::pinocchio::get_account_info;
     use light_compressed_account::{
         compressed_account::{
             CompressedAccount, PackedCompressedAccountWithMerkleContext, PackedMerkleContext,
         },
         instruction_data::{
-            cpi_context::CompressedCpiContext, data::OutputCompressedAccountWithPackedContext,
-            invoke_cpi::InstructionDataInvokeCpi, zero_copy::ZInstructionDataInvokeCpi,
+            cpi_context::CompressedCpiContext,
+            data::{
+                CpiContextNewAddressParamsAssignedPacked, NewAddressParamsAssignedPacked,
+                NewAddressParamsPacked, OutputCompressedAccountWithPackedContext,
+            },
+            invoke_cpi::InstructionDataInvokeCpi,
+            with_readonly::{
+                InstructionDataInvokeCpiWithReadOnly, ZInstructionDataInvokeCpiWithReadOnly,
+            },
+            zero_copy::ZInstructionDataInvokeCpi,
         },
     };
     use light_zero_copy::borsh::Deserialize;
     use pinocchio::pubkey::Pubkey;
 
     use super::*;
-    use crate::invoke_cpi::processor::clear_cpi_context_account;
+    use crate::{
+        context::SystemContext,
+        invoke_cpi::{account::CpiContextAccount, processor::clear_cpi_context_account},
+    };
+
+    const TEST_INVOKING_PROGRAM: Pubkey = [1u8; 32];
 
     fn clean_input_data(instruction_data: &mut InstructionDataInvokeCpi) {
         instruction_data.cpi_context = None;
@@ -250,6 +398,7 @@ mod tests {
             fee_payer: solana_pubkey::Pubkey::new_unique().to_bytes(),
             associated_merkle_tree,
             context: vec![],
+            new_addresses: vec![],
         };
         get_account_info(
             solana_pubkey::Pubkey::new_unique().to_bytes(),
@@ -345,7 +494,12 @@ mod tests {
         let input_bytes = instruction_data.try_to_vec().unwrap();
         let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-        let result = set_cpi_context(fee_payer, &cpi_context_account, w_instruction_data);
+        let result = set_cpi_context(
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &cpi_context_account,
+            w_instruction_data,
+        );
         // assert
         {
             assert!(result.is_ok());
@@ -372,14 +526,25 @@ mod tests {
             let input_bytes = first_instruction_data.try_to_vec().unwrap();
             let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
             let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-            set_cpi_context(fee_payer, &cpi_context_account, w_instruction_data).unwrap();
+            set_cpi_context(
+                fee_payer,
+                TEST_INVOKING_PROGRAM,
+                &cpi_context_account,
+                w_instruction_data,
+            )
+            .unwrap();
         }
         let inputs_subsequent = create_test_instruction_data(false, true, 2);
         let mut input_bytes = Vec::new();
         inputs_subsequent.serialize(&mut input_bytes).unwrap();
         let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-        let result = set_cpi_context(fee_payer, &cpi_context_account, w_instruction_data);
+        let result = set_cpi_context(
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &cpi_context_account,
+            w_instruction_data,
+        );
         // assert
         {
             assert!(result.is_ok());
@@ -415,7 +580,13 @@ mod tests {
             let input_bytes = first_instruction_data.try_to_vec().unwrap();
             let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
             let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-            set_cpi_context(fee_payer, &cpi_context_account, w_instruction_data).unwrap();
+            set_cpi_context(
+                fee_payer,
+                TEST_INVOKING_PROGRAM,
+                &cpi_context_account,
+                w_instruction_data,
+            )
+            .unwrap();
         }
 
         let different_fee_payer = solana_pubkey::Pubkey::new_unique().to_bytes();
@@ -426,6 +597,7 @@ mod tests {
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
         let result = set_cpi_context(
             different_fee_payer,
+            TEST_INVOKING_PROGRAM,
             &cpi_context_account,
             w_instruction_data,
         );
@@ -435,6 +607,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_cpi_context_account_too_small() {
+        let fee_payer = solana_pubkey::Pubkey::new_unique().to_bytes();
+        let data = CpiContextAccount {
+            fee_payer: solana_pubkey::Pubkey::new_unique().to_bytes(),
+            associated_merkle_tree: solana_pubkey::Pubkey::new_unique().to_bytes(),
+            context: vec![],
+            new_addresses: vec![],
+        };
+        // No space for the context is left after the empty vectors.
+        let cpi_context_account = get_account_info(
+            solana_pubkey::Pubkey::new_unique().to_bytes(),
+            crate::ID,
+            false,
+            true,
+            false,
+            [
+                CpiContextAccount::DISCRIMINATOR_SLICE.to_vec(),
+                data.try_to_vec().unwrap(),
+            ]
+            .concat(),
+        );
+        let inputs_first = create_test_instruction_data(true, true, 1);
+        let input_bytes = inputs_first.try_to_vec().unwrap();
+        let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
+        let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
+        let result = set_cpi_context(
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &cpi_context_account,
+            w_instruction_data,
+        );
+        assert_eq!(
+            result,
+            Err(SystemProgramError::CpiContextAccountTooSmall.into())
+        );
+    }
+
     #[test]
     fn test_set_cpi_context_without_first_context() {
         let fee_payer = solana_pubkey::Pubkey::new_unique().to_bytes();
@@ -444,7 +654,12 @@ mod tests {
         inputs_first.serialize(&mut input_bytes).unwrap();
         let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-        let result = set_cpi_context(fee_payer, &cpi_context_account, w_instruction_data);
+        let result = set_cpi_context(
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &cpi_context_account,
+            w_instruction_data,
+        );
         assert_eq!(
             result,
             Err(SystemProgramError::CpiContextFeePayerMismatch.into())
@@ -462,8 +677,14 @@ mod tests {
         let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
 
-        let result = process_cpi_context(w_instruction_data, cpi_context_account, fee_payer, &[])
-            .unwrap_err();
+        let result = process_cpi_context(
+            w_instruction_data,
+            cpi_context_account,
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &[],
+        )
+        .unwrap_err();
         assert_eq!(
             result,
             SystemProgramError::CpiContextAccountUndefined.into()
@@ -480,8 +701,14 @@ mod tests {
         instruction_data.serialize(&mut input_bytes).unwrap();
         let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
         let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
-        let result = process_cpi_context(w_instruction_data, cpi_context_account, fee_payer, &[])
-            .unwrap_err();
+        let result = process_cpi_context(
+            w_instruction_data,
+            cpi_context_account,
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            &[],
+        )
+        .unwrap_err();
         assert_eq!(
             result,
             SystemProgramError::CpiContextAccountUndefined.into()
@@ -506,6 +733,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             &[],
         )
         .unwrap_err();
@@ -529,6 +757,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             &[],
         )
         .unwrap_err();
@@ -551,6 +780,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         )
         .unwrap_err();
@@ -577,6 +807,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         )
         .unwrap_err();
@@ -600,6 +831,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         )
         .unwrap_err();
@@ -626,6 +858,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         );
         assert!(result.is_ok());
@@ -639,6 +872,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             invalid_fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         )
         .unwrap_err();
@@ -664,6 +898,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         );
         // assert
@@ -695,6 +930,7 @@ mod tests {
                 fee_payer: Pubkey::default(),
                 associated_merkle_tree: merkle_tree_pubkey,
                 context: vec![malicious_inputs],
+                new_addresses: vec![],
             };
             let input_data = cpi_context_content.try_to_vec().unwrap();
             let input_data_len = input_data.len();
@@ -712,6 +948,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         );
         {
@@ -739,6 +976,7 @@ mod tests {
                 w_instruction_data,
                 Some(&cpi_context_account),
                 fee_payer,
+                TEST_INVOKING_PROGRAM,
                 remaining_accounts,
             );
             // assert
@@ -774,6 +1012,7 @@ mod tests {
             w_instruction_data,
             Some(&cpi_context_account),
             fee_payer,
+            TEST_INVOKING_PROGRAM,
             remaining_accounts,
         );
         assert!(result.is_ok());
@@ -802,4 +1041,195 @@ mod tests {
         assert_eq!(cpi_context.fee_payer.to_bytes(), Pubkey::default());
         assert_eq!(cpi_context.context.len(), 0);
     }
+
+    #[test]
+    fn test_process_cpi_context_new_addresses() {
+        let fee_payer = solana_pubkey::Pubkey::new_unique().to_bytes();
+        let program_a = solana_pubkey::Pubkey::new_unique().to_bytes();
+        let program_b = solana_pubkey::Pubkey::new_unique().to_bytes();
+        let merkle_tree_account_info = get_merkle_tree_account_info();
+        let cpi_context_account =
+            create_test_cpi_context_account(Some(*merkle_tree_account_info.key()));
+        let remaining_accounts = &[merkle_tree_account_info];
+        let address_a = NewAddressParamsPacked {
+            seed: [1u8; 32],
+            address_queue_account_index: 1,
+            address_merkle_tree_account_index: 1,
+            address_merkle_tree_root_index: 1,
+        };
+        // 1. Program a stages an unassigned address.
+        {
+            let instruction_data = InstructionDataInvokeCpi {
+                new_address_params: vec![address_a],
+                ..create_test_instruction_data(true, true, 1)
+            };
+            let input_bytes = instruction_data.try_to_vec().unwrap();
+            let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
+            let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
+            set_cpi_context(
+                fee_payer,
+                program_a,
+                &cpi_context_account,
+                w_instruction_data,
+            )
+            .unwrap();
+        }
+        let output =
+            create_test_instruction_data(false, true, 2).output_compressed_accounts[0].clone();
+        let mut instruction_data_b = InstructionDataInvokeCpiWithReadOnly {
+            invoking_program_id: program_b.into(),
+            with_cpi_context: true,
+            cpi_context: CompressedCpiContext {
+                first_set_context: false,
+                set_context: true,
+                cpi_context_account_index: 0,
+            },
+            new_address_params: vec![NewAddressParamsAssignedPacked::new(
+                NewAddressParamsPacked {
+                    seed: [2u8; 32],
+                    ..address_a
+                },
+                Some(2),
+            )],
+            output_compressed_accounts: vec![output.clone(), output],
+            ..Default::default()
+        };
+        // 2. Failing: program b assigns the address to an output that doesn't exist.
+        {
+            let input_bytes = instruction_data_b.try_to_vec().unwrap();
+            let (z_inputs, _) =
+                ZInstructionDataInvokeCpiWithReadOnly::zero_copy_at(&input_bytes).unwrap();
+            let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
+            let result = set_cpi_context(
+                fee_payer,
+                program_b,
+                &cpi_context_account,
+                w_instruction_data,
+            );
+            assert_eq!(
+                result,
+                Err(SystemProgramError::NewAddressAssignedIndexOutOfBounds.into())
+            );
+        }
+        // 3. Program b stages an address assigned to its second output.
+        {
+            instruction_data_b.new_address_params[0].assigned_account_index = 1;
+            instruction_data_b.output_compressed_accounts[1]
+                .compressed_account
+                .lamports = 100;
+            let input_bytes = instruction_data_b.try_to_vec().unwrap();
+            let (z_inputs, _) =
+                ZInstructionDataInvokeCpiWithReadOnly::zero_copy_at(&input_bytes).unwrap();
+            let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
+            set_cpi_context(
+                fee_payer,
+                program_b,
+                &cpi_context_account,
+                w_instruction_data,
+            )
+            .unwrap();
+            let data = cpi_context_account.try_borrow_data().unwrap();
+            let cpi_context = CpiContextAccount::deserialize(&mut &data[8..]).unwrap();
+            // Index is relative to the outputs in the cpi context,
+            // program a staged one output before.
+            let expected_new_addresses = vec![
+                CpiContextNewAddressParamsAssignedPacked {
+                    owner: program_a,
+                    seed: [1u8; 32],
+                    address_queue_account_index: 1,
+                    address_merkle_tree_account_index: 1,
+                    address_merkle_tree_root_index: 1,
+                    assigned_to_account: false,
+                    assigned_account_index: 0,
+                },
+                CpiContextNewAddressParamsAssignedPacked {
+                    owner: program_b,
+                    seed: [2u8; 32],
+                    address_queue_account_index: 1,
+                    address_merkle_tree_account_index: 1,
+                    address_merkle_tree_root_index: 1,
+                    assigned_to_account: true,
+                    assigned_account_index: 2,
+                },
+            ];
+            assert_eq!(cpi_context.new_addresses, expected_new_addresses);
+        }
+        // 4. Execute the cpi context.
+        let instruction_data = create_test_instruction_data(false, false, 3);
+        let input_bytes = instruction_data.try_to_vec().unwrap();
+        let (z_inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(&input_bytes).unwrap();
+        let w_instruction_data = WrappedInstructionData::new(z_inputs).unwrap();
+        let (_, result) = process_cpi_context(
+            w_instruction_data,
+            Some(&cpi_context_account),
+            fee_payer,
+            TEST_INVOKING_PROGRAM,
+            remaining_accounts,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(result.address_len(), 2);
+        let new_addresses = result.new_addresses().collect::<Vec<_>>();
+        assert_eq!(new_addresses[0].owner(), Some(program_a));
+        assert_eq!(new_addresses[0].assigned_compressed_account_index(), None);
+        assert_eq!(new_addresses[1].owner(), Some(program_b));
+        let assigned_account_index = result.instruction_outputs_len()
+            + new_addresses[1]
+                .assigned_compressed_account_index()
+                .unwrap();
+        assert_eq!(
+            result
+                .get_output_account(assigned_account_index)
+                .unwrap()
+                .lamports(),
+            100
+        );
+        // Outputs of the executing instruction precede the outputs of the cpi context,
+        // assigned indices of staged addresses are offset by instruction outputs.
+        let output_lamports = result
+            .output_accounts()
+            .map(|x| x.lamports())
+            .collect::<Vec<_>>();
+        assert_eq!(output_lamports, vec![3, 1, 2, 100]);
+        for (i, output) in result.output_accounts().enumerate() {
+            assert_eq!(
+                result.get_output_account(i).unwrap().lamports(),
+                output.lamports()
+            );
+        }
+        // Staged addresses can only be used by outputs owned by the staging program.
+        let mut context = SystemContext {
+            account_indices: vec![],
+            accounts: vec![],
+            account_infos: vec![],
+            hashed_pubkeys: vec![],
+            addresses: new_addresses
+                .iter()
+                .map(|x| (Some(x.seed()), x.owner()))
+                .collect(),
+            rollover_fee_payments: vec![],
+            address_fee_is_set: false,
+            network_fee_is_set: false,
+            legacy_merkle_context: vec![],
+            invoking_program_id: Some(TEST_INVOKING_PROGRAM),
+        };
+        assert_eq!(
+            context.use_output_address([1u8; 32], &program_b),
+            Err(SystemProgramError::InvalidAddress.into())
+        );
+        assert_eq!(
+            context.use_output_address([2u8; 32], &TEST_INVOKING_PROGRAM),
+            Err(SystemProgramError::InvalidAddress.into())
+        );
+        context.use_output_address([1u8; 32], &program_a).unwrap();
+        context.use_output_address([2u8; 32], &program_b).unwrap();
+        assert!(context.addresses.is_empty());
+        // 5. New addresses are not read from a cleared cpi context account.
+        clear_cpi_context_account(Some(&cpi_context_account)).unwrap();
+        let (cpi_context, _) = deserialize_cpi_context_account(&cpi_context_account).unwrap();
+        assert!(cpi_context.new_addresses.is_empty());
+        let data = cpi_context_account.try_borrow_data().unwrap();
+        let cpi_context = CpiContextAccount::deserialize(&mut &data[8..]).unwrap();
+        assert!(cpi_context.new_addresses.is_empty());
+    }
 }
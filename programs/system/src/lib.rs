@@ -28,7 +28,8 @@ use pinocchio::{
 };
 
 use crate::{
-    invoke::verify_signer::input_compressed_accounts_signer_check, processor::process::process,
+    errors::SystemProgramError, invoke::verify_signer::input_compressed_accounts_signer_check,
+    processor::process::process,
 };
 
 pub const ID: Pubkey = pubkey!("SySTEM1eSU2p4BGQfQpimFEWWSC1XDFeun3Nqzz3rT7");
@@ -90,9 +91,16 @@ pub fn invoke<'a, 'b, 'c: 'info, 'info>(
     instruction_data: &[u8],
 ) -> Result<()> {
     // remove vec prefix
-    let instruction_data = &instruction_data[4..];
-
-    let (inputs, _) = ZInstructionDataInvoke::zero_copy_at(instruction_data).unwrap();
+    let instruction_data = instruction_data
+        .get(4..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let (inputs, _) =
+        ZInstructionDataInvoke::zero_copy_at(instruction_data).map_err(ProgramError::from)?;
+    // Relay fees are not supported.
+    if inputs.relay_fee.is_some() {
+        return Err(SystemProgramError::InvalidArgument.into());
+    }
     let (ctx, remaining_accounts) = InvokeInstruction::from_account_infos(accounts)?;
 
     input_compressed_accounts_signer_check(
@@ -116,9 +124,12 @@ pub fn invoke_cpi<'a, 'b, 'c: 'info, 'info>(
     instruction_data: &[u8],
 ) -> Result<()> {
     // remove vec prefix
-    let instruction_data = &instruction_data[4..];
+    let instruction_data = instruction_data
+        .get(4..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
 
-    let (inputs, _) = ZInstructionDataInvokeCpi::zero_copy_at(instruction_data).unwrap();
+    let (inputs, _) =
+        ZInstructionDataInvokeCpi::zero_copy_at(instruction_data).map_err(ProgramError::from)?;
 
     let (ctx, remaining_accounts) = InvokeCpiInstruction::from_account_infos(accounts)?;
 
@@ -135,7 +146,9 @@ pub fn invoke_cpi_with_read_only<'a, 'b, 'c: 'info, 'info>(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> Result<()> {
-    let instruction_data = &instruction_data[4..];
+    let instruction_data = instruction_data
+        .get(4..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
     msg!("invoke_cpi_with_read_only");
     let (inputs, _) = InstructionDataInvokeCpiWithReadOnly::zero_copy_at(instruction_data)
         .map_err(ProgramError::from)?;
@@ -153,7 +166,9 @@ pub fn invoke_cpi_with_account_info<'a, 'b, 'c: 'info, 'info>(
     instruction_data: &[u8],
 ) -> Result<()> {
     msg!("invoke_cpi_with_account_info");
-    let instruction_data = &instruction_data[4..];
+    let instruction_data = instruction_data
+        .get(4..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
 
     let (inputs, _) = InstructionDataInvokeCpiWithAccountInfo::zero_copy_at(instruction_data)
         .map_err(ProgramError::from)?;
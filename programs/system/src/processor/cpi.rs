@@ -1,11 +1,12 @@
-use std::cmp::min;
-
 use light_compressed_account::{
-    constants::ACCOUNT_COMPRESSION_PROGRAM_ID, discriminators::DISCRIMINATOR_INSERT_INTO_QUEUES,
-    instruction_data::insert_into_queues::InsertIntoQueuesInstructionDataMut,
+    constants::ACCOUNT_COMPRESSION_PROGRAM_ID,
+    discriminators::DISCRIMINATOR_INSERT_INTO_QUEUES,
+    instruction_data::insert_into_queues::{
+        InsertIntoQueuesInstructionData, InsertIntoQueuesInstructionDataConfig,
+    },
 };
+use light_zero_copy::init_mut::ZeroCopyNew;
 use pinocchio::{
-    account_info::AccountInfo,
     cpi::slice_invoke_signed,
     instruction::{AccountMeta, Instruction, Seed, Signer},
     pubkey::Pubkey,
@@ -17,16 +18,12 @@ use crate::{
     context::SystemContext,
     Result,
 };
-#[allow(clippy::too_many_arguments)]
 pub fn create_cpi_data_and_context<'info, A: InvokeAccounts<'info> + SignerAccounts<'info>>(
     ctx: &A,
-    num_leaves: u8,
-    num_nullifiers: u8,
-    num_new_addresses: u8,
+    cpi_ix_config: &InsertIntoQueuesInstructionDataConfig,
     hashed_pubkeys_capacity: usize,
     cpi_data_len: usize,
     invoking_program_id: Option<Pubkey>,
-    remaining_accounts: &'info [AccountInfo],
 ) -> Result<(SystemContext<'info>, Vec<u8>)> {
     let account_infos = vec![
         ctx.get_account_compression_authority(),
@@ -36,17 +33,11 @@ pub fn create_cpi_data_and_context<'info, A: InvokeAccounts<'info> + SignerAccou
         AccountMeta::new(account_infos[0].key(), false, true),
         AccountMeta::readonly(account_infos[1].key()),
     ];
-    let account_indices =
-        Vec::<u8>::with_capacity((num_nullifiers + num_leaves + num_new_addresses) as usize);
-    // Min (remaining accounts or num values) for there cannot be more trees than accounts or values.
-    let bytes_size = InsertIntoQueuesInstructionDataMut::required_size_for_capacity(
-        num_leaves,
-        num_nullifiers,
-        num_new_addresses,
-        min(remaining_accounts.len() as u8, num_leaves),
-        min(remaining_accounts.len() as u8, num_nullifiers),
-        min(remaining_accounts.len() as u8, num_new_addresses),
-    );
+    let num_leaves = cpi_ix_config.leaves_capacity as usize;
+    let num_nullifiers = cpi_ix_config.nullifiers_capacity as usize;
+    let num_new_addresses = cpi_ix_config.addresses_capacity as usize;
+    let account_indices = Vec::<u8>::with_capacity(num_nullifiers + num_leaves + num_new_addresses);
+    let bytes_size = InsertIntoQueuesInstructionData::byte_len(cpi_ix_config);
     // Data size + 8 bytes for discriminator + 4 bytes for vec length, + 4 cpi data vec length, + cpi data length.
     let byte_len = bytes_size + 8 + 4 + 4 + cpi_data_len;
     let mut bytes = vec![0u8; byte_len];
@@ -59,7 +50,7 @@ pub fn create_cpi_data_and_context<'info, A: InvokeAccounts<'info> + SignerAccou
             accounts,
             account_infos,
             hashed_pubkeys: Vec::with_capacity(hashed_pubkeys_capacity),
-            addresses: Vec::with_capacity((num_nullifiers + num_new_addresses) as usize),
+            addresses: Vec::with_capacity(num_nullifiers + num_new_addresses),
             rollover_fee_payments: Vec::new(),
             address_fee_is_set: false,
             network_fee_is_set: false,
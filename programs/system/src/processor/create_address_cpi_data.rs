@@ -23,6 +23,8 @@ pub fn derive_new_addresses<'info, 'a, 'b: 'a, const ADDRESS_ASSIGNMENT: bool>(
     let mut seq_index = 0;
 
     for (i, new_address_params) in new_address_params.enumerate() {
+        // Addresses staged in a cpi context are derived with the program that staged them.
+        let invoking_program_id_clone = new_address_params.owner().or(invoking_program_id_clone);
         let (address, rollover_fee) = match &accounts
             [new_address_params.address_merkle_tree_account_index() as usize]
         {
@@ -86,13 +88,17 @@ pub fn derive_new_addresses<'info, 'a, 'b: 'a, const ADDRESS_ASSIGNMENT: bool>(
         if !ADDRESS_ASSIGNMENT {
             // We are inserting addresses into two vectors to avoid unwrapping
             // the option in following functions.
-            context.addresses.push(Some(address));
+            context
+                .addresses
+                .push((Some(address), new_address_params.owner()));
         } else if new_address_params
             .assigned_compressed_account_index()
             .is_some()
         {
             // Only addresses assigned to output accounts can be used in output accounts.
-            context.addresses.push(Some(address));
+            context
+                .addresses
+                .push((Some(address), new_address_params.owner()));
         }
         cpi_ix_data.addresses[i].address = address;
 
@@ -45,7 +45,7 @@ pub fn create_inputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
     {
         context
             .addresses
-            .push(input_compressed_account_with_context.address());
+            .push((input_compressed_account_with_context.address(), None));
 
         let merkle_context = input_compressed_account_with_context.merkle_context();
         #[allow(clippy::comparison_chain)]
@@ -55,7 +55,10 @@ pub fn create_inputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
             current_hashed_mt = match &accounts[current_mt_index as usize] {
                 AcpAccount::BatchedStateTree(tree) => {
                     context.set_network_fee(
-                        tree.metadata.rollover_metadata.network_fee,
+                        tree.network_fee_curve.get_network_fee(
+                            tree.metadata.rollover_metadata.network_fee,
+                            tree.queue_batches.get_fill_level(),
+                        ),
                         current_mt_index,
                     );
                     is_batched = true;
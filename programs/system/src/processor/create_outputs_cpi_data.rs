@@ -28,6 +28,8 @@ use crate::{
 /// 3. Checks that addresses in output compressed accounts have been created or
 ///    exist in input compressed accounts. An address may not be used in an
 ///    output compressed accounts. This will close the account.
+///    Addresses staged in a cpi context can only be used by outputs owned by
+///    the staging program.
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub fn create_outputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
@@ -67,7 +69,10 @@ pub fn create_outputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
             let pubkey = match &accounts[current_index as usize] {
                 AcpAccount::OutputQueue(output_queue) => {
                     context.set_network_fee(
-                        output_queue.metadata.rollover_metadata.network_fee,
+                        output_queue.network_fee_curve.get_network_fee(
+                            output_queue.metadata.rollover_metadata.network_fee,
+                            output_queue.batch_metadata.get_fill_level(),
+                        ),
                         current_index as u8,
                     );
                     hashed_merkle_tree = output_queue.hashed_merkle_tree_pubkey;
@@ -128,16 +133,7 @@ pub fn create_outputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
 
         // Check 3.
         if let Some(address) = account.address() {
-            if let Some(position) = context
-                .addresses
-                .iter()
-                .filter(|x| x.is_some())
-                .position(|&x| x.unwrap() == address)
-            {
-                context.addresses.remove(position);
-            } else {
-                return Err(SystemProgramError::InvalidAddress.into());
-            }
+            context.use_output_address(address, &account.owner().to_bytes())?;
         }
 
         cpi_ix_data.output_leaf_indices[j] = (mt_next_index + num_leaves_in_tree).into();
@@ -188,6 +184,8 @@ pub fn create_outputs_cpi_data<'a, 'info, T: InstructionData<'a>>(
 }
 
 // Check that new addresses are assigned correctly to the compressed output accounts specified by index
+// Outputs of a cpi context follow the outputs of the executing instruction,
+// see WrappedInstructionData::output_accounts.
 pub fn check_new_address_assignment<'a, 'info, T: InstructionData<'a>>(
     inputs: &WrappedInstructionData<'a, T>,
     cpi_ix_data: &InsertIntoQueuesInstructionDataMut<'_>,
@@ -195,7 +193,12 @@ pub fn check_new_address_assignment<'a, 'info, T: InstructionData<'a>>(
     for (derived_addresses, new_addresses) in
         cpi_ix_data.addresses.iter().zip(inputs.new_addresses())
     {
-        if let Some(assigned_account_index) = new_addresses.assigned_compressed_account_index() {
+        if let Some(mut assigned_account_index) = new_addresses.assigned_compressed_account_index()
+        {
+            // Addresses staged in a cpi context are assigned to outputs of the cpi context.
+            if new_addresses.owner().is_some() {
+                assigned_account_index += inputs.instruction_outputs_len();
+            }
             let output_account = inputs
                 .get_output_account(assigned_account_index)
                 .ok_or(SystemProgramError::NewAddressAssignedIndexOutOfBounds)?;
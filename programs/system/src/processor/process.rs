@@ -3,13 +3,16 @@ use std::cmp::min;
 use light_compressed_account::{
     instruction_data::{
         compressed_proof::CompressedProof,
-        insert_into_queues::{InsertIntoQueuesInstructionDataMut, InsertNullifierInput},
+        insert_into_queues::{
+            InsertIntoQueuesInstructionData, InsertIntoQueuesInstructionDataConfig,
+            InsertNullifierInput,
+        },
         traits::InstructionData,
         zero_copy::ZPackedReadOnlyCompressedAccount,
     },
     tx_hash::create_tx_hash_from_hash_chains,
 };
-use light_zero_copy::slice_mut::ZeroCopySliceMut;
+use light_zero_copy::{init_mut::ZeroCopyNew, slice_mut::ZeroCopySliceMut};
 use pinocchio::{
     account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
     sysvars::clock::Clock,
@@ -102,29 +105,30 @@ pub fn process<
 
     let cpi_outputs_data_len =
         inputs.get_cpi_context_outputs_end_offset() - inputs.get_cpi_context_outputs_start_offset();
+    // Min (remaining accounts or num values) for there cannot be more trees than accounts or values.
+    let cpi_ix_config = InsertIntoQueuesInstructionDataConfig {
+        leaves_capacity: num_output_compressed_accounts as u8,
+        nullifiers_capacity: num_input_accounts as u8,
+        addresses_capacity: num_new_addresses as u8,
+        num_output_trees: min(remaining_accounts.len(), num_output_compressed_accounts) as u8,
+        num_input_trees: min(remaining_accounts.len(), num_input_accounts) as u8,
+        num_address_trees: min(remaining_accounts.len(), num_new_addresses) as u8,
+    };
     // 1. Allocate cpi data and initialize context
     let (mut context, mut cpi_ix_bytes) = create_cpi_data_and_context(
         ctx,
-        num_output_compressed_accounts as u8,
-        num_input_accounts as u8,
-        num_new_addresses as u8,
+        &cpi_ix_config,
         hashed_pubkeys_capacity,
         cpi_outputs_data_len,
         invoking_program,
-        remaining_accounts,
     )?;
 
     // 2. Deserialize and check all Merkle tree and queue accounts.
     let mut accounts = try_from_account_infos(remaining_accounts, &mut context)?;
-    // 3. Deserialize cpi instruction data as zero copy to fill it.
-    let (mut cpi_ix_data, bytes) = InsertIntoQueuesInstructionDataMut::new_at(
+    // 3. Initialize cpi instruction data as zero copy to fill it.
+    let (mut cpi_ix_data, bytes) = InsertIntoQueuesInstructionData::new_zero_copy(
         &mut cpi_ix_bytes[12..], // 8 bytes instruction discriminator + 4 bytes vector length
-        num_output_compressed_accounts as u8,
-        num_input_accounts as u8,
-        num_new_addresses as u8,
-        min(remaining_accounts.len(), num_output_compressed_accounts) as u8,
-        min(remaining_accounts.len(), num_input_accounts) as u8,
-        min(remaining_accounts.len(), num_new_addresses) as u8,
+        cpi_ix_config,
     )
     .map_err(ProgramError::from)?;
     cpi_ix_data.set_invoked_by_program(true);
@@ -147,7 +151,7 @@ pub fn process<
     // 6. Collect all addresses to check that every address in the output compressed accounts
     // is an existing address or a new address.
     inputs.input_accounts().for_each(|account| {
-        context.addresses.push(account.address());
+        context.addresses.push((account.address(), None));
     });
 
     // 7. Derive new addresses from seed and invoking program
@@ -216,7 +220,7 @@ pub fn process<
     let num_input_accounts_by_index = sum_check(&inputs, &None, &inputs.is_compress())?;
 
     // 12. Compress or decompress lamports ---------------------------------------------------
-    compress_or_decompress_lamports::<A, T>(&inputs, ctx)?;
+    compress_or_decompress_lamports::<A, T>(&inputs, ctx, remaining_accounts)?;
 
     // 13. Verify read-only account inclusion by index ---------------------------------------------------
     let read_only_accounts = inputs.read_only_accounts().unwrap_or_default();
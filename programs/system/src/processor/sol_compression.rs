@@ -1,5 +1,7 @@
 use aligned_sized::*;
-use light_compressed_account::instruction_data::traits::InstructionData;
+use light_compressed_account::instruction_data::{
+    traits::InstructionData, zero_copy::ZPackedDecompressionRecipient,
+};
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
@@ -27,8 +29,20 @@ pub fn compress_or_decompress_lamports<
 >(
     inputs: &WrappedInstructionData<'a, T>,
     ctx: &A,
+    remaining_accounts: &'info [AccountInfo],
 ) -> crate::Result<()> {
-    if inputs.compress_or_decompress_lamports().is_some() {
+    let decompression_recipients = inputs.decompression_recipients().unwrap_or_default();
+    if !decompression_recipients.is_empty() {
+        if inputs.compress_or_decompress_lamports().is_some() {
+            return Err(
+                SystemProgramError::DecompressionRecipientsWithCompressOrDecompressLamports.into(),
+            );
+        }
+        if ctx.get_decompression_recipient().is_some() {
+            return Err(SystemProgramError::DecompressionRecipientDefined.into());
+        }
+        decompress_lamports_to_recipients(decompression_recipients, ctx, remaining_accounts)?;
+    } else if inputs.compress_or_decompress_lamports().is_some() {
         if inputs.is_compress() && ctx.get_decompression_recipient().is_some() {
             return Err(SystemProgramError::DecompressionRecipientDefined.into());
         }
@@ -74,6 +88,36 @@ pub fn decompress_lamports<
     transfer_lamports(sol_pool_pda, recipient, lamports)
 }
 
+/// Decompresses lamports from the sol pool pda
+/// to each recipient selected by index from the remaining accounts.
+pub fn decompress_lamports_to_recipients<
+    'a,
+    'b,
+    'c: 'info,
+    'info,
+    A: InvokeAccounts<'info> + SignerAccounts<'info>,
+>(
+    decompression_recipients: &[ZPackedDecompressionRecipient],
+    ctx: &'a A,
+    remaining_accounts: &'info [AccountInfo],
+) -> crate::Result<()> {
+    let sol_pool_pda = match ctx.get_sol_pool_pda() {
+        Some(sol_pool_pda) => sol_pool_pda,
+        None => return Err(SystemProgramError::CompressedSolPdaUndefinedForDecompressSol.into()),
+    };
+    for decompression_recipient in decompression_recipients {
+        let recipient = remaining_accounts
+            .get(decompression_recipient.recipient_index as usize)
+            .ok_or(SystemProgramError::DecompressionRecipientIndexOutOfBounds)?;
+        let lamports: u64 = decompression_recipient.lamports.into();
+        if lamports == 0 {
+            return Err(SystemProgramError::DeCompressLamportsUndefinedForDecompressSol.into());
+        }
+        transfer_lamports(sol_pool_pda, recipient, lamports)?;
+    }
+    Ok(())
+}
+
 pub fn compress_lamports<
     'a,
     'b,
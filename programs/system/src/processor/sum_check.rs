@@ -43,6 +43,13 @@ pub fn sum_check<'a, T: InstructionData<'a>>(
         }
     }
 
+    for decompression_recipient in inputs.decompression_recipients().unwrap_or_default() {
+        sum = sum
+            .checked_sub(decompression_recipient.lamports.into())
+            .ok_or(ProgramError::ArithmeticOverflow)
+            .map_err(|_| SystemProgramError::ComputeOutputSumFailed)?;
+    }
+
     for compressed_account in output_compressed_accounts {
         sum = sum
             .checked_sub(compressed_account.lamports())
@@ -72,7 +79,8 @@ mod test {
             CompressedAccount, PackedCompressedAccountWithMerkleContext, PackedMerkleContext,
         },
         instruction_data::{
-            data::OutputCompressedAccountWithPackedContext,
+            data::{OutputCompressedAccountWithPackedContext, PackedDecompressionRecipient},
+            with_readonly::{InAccount, InstructionDataInvokeCpiWithReadOnly},
             zero_copy::{
                 ZInstructionDataInvokeCpi, ZOutputCompressedAccountWithPackedContext,
                 ZPackedCompressedAccountWithMerkleContext,
@@ -135,6 +143,62 @@ mod test {
             sum_check_test_with_num(&vec![150; i], &vec![150; i], None, None, false, i).unwrap();
         }
     }
+
+    #[test]
+    fn test_sum_check_decompression_recipients() {
+        // SUCCEED: decompress to multiple recipients
+        sum_check_recipients_test(&[100, 50], &[50], &[60, 40]).unwrap();
+        sum_check_recipients_test(&[100, 50], &[], &[100, 25, 25]).unwrap();
+        sum_check_recipients_test(&[100], &[100], &[]).unwrap();
+        // FAIL: recipients lamports don't match
+        sum_check_recipients_test(&[100, 50], &[50], &[60, 40 - 1]).unwrap_err();
+        sum_check_recipients_test(&[100, 50], &[50], &[60, 40 + 1]).unwrap_err();
+        sum_check_recipients_test(&[100], &[], &[50, 50, 1]).unwrap_err();
+        sum_check_recipients_test(&[], &[], &[1]).unwrap_err();
+    }
+
+    fn sum_check_recipients_test(
+        input_amounts: &[u64],
+        output_amounts: &[u64],
+        recipient_amounts: &[u64],
+    ) -> Result<()> {
+        let ix_data = InstructionDataInvokeCpiWithReadOnly {
+            input_compressed_accounts: input_amounts
+                .iter()
+                .map(|lamports| InAccount {
+                    lamports: *lamports,
+                    ..Default::default()
+                })
+                .collect(),
+            output_compressed_accounts: output_amounts
+                .iter()
+                .map(|lamports| OutputCompressedAccountWithPackedContext {
+                    compressed_account: CompressedAccount {
+                        owner: Pubkey::new_unique().into(),
+                        lamports: *lamports,
+                        address: None,
+                        data: None,
+                    },
+                    merkle_tree_index: 0,
+                })
+                .collect(),
+            decompression_recipients: recipient_amounts
+                .iter()
+                .enumerate()
+                .map(|(index, lamports)| PackedDecompressionRecipient {
+                    recipient_index: index as u8,
+                    lamports: *lamports,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let bytes = ix_data.try_to_vec().unwrap();
+        let (z_ix_data, _) = InstructionDataInvokeCpiWithReadOnly::zero_copy_at(&bytes).unwrap();
+        let wrapped_inputs = WrappedInstructionData::new(z_ix_data)?;
+        sum_check(&wrapped_inputs, &None, &wrapped_inputs.is_compress())?;
+        Ok(())
+    }
+
     fn sum_check_test(
         input_amounts: &[u64],
         output_amounts: &[u64],
@@ -66,6 +66,7 @@ pub trait Indexer<R: RpcConnection>: Sync + Send + Debug + 'static {
         start_offset: Option<u64>,
     ) -> Result<Vec<MerkleProofWithContext>, IndexerError>;
 
+    /// Returns the subtrees of the Merkle tree with the given pubkey.
     async fn get_subtrees(
         &self,
         merkle_tree_pubkey: [u8; 32],
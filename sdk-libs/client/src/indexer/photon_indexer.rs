@@ -1,10 +1,14 @@
-use std::{fmt::Debug, str::FromStr, time::Duration};
+use std::{fmt::Debug, mem::offset_of, str::FromStr, time::Duration};
 
 use async_trait::async_trait;
-use light_compressed_account::compressed_account::{
-    CompressedAccount, CompressedAccountData, CompressedAccountWithMerkleContext, MerkleContext,
+use light_batched_merkle_tree::merkle_tree::BatchedMerkleTreeAccount;
+use light_compressed_account::{
+    compressed_account::{
+        CompressedAccount, CompressedAccountData, CompressedAccountWithMerkleContext, MerkleContext,
+    },
+    constants::{ACCOUNT_COMPRESSION_PROGRAM_ID, ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR},
 };
-use light_merkle_tree_metadata::QueueType;
+use light_merkle_tree_metadata::{merkle_tree::MerkleTreeMetadata, QueueType};
 use light_sdk::token::{AccountState, TokenData, TokenDataWithMerkleContext};
 use photon_api::{
     apis::configuration::{ApiKey, Configuration},
@@ -16,25 +20,28 @@ use photon_api::{
     },
 };
 use solana_program::pubkey::Pubkey;
-use solana_sdk::bs58;
+use solana_sdk::{account::Account as SolanaAccount, bs58};
 use tracing::{debug, error, warn};
 
 use super::{AddressQueueIndex, BatchAddressUpdateIndexerResponse, MerkleProofWithContext};
 use crate::{
     indexer::{
-        Address, AddressMerkleTreeBundle, AddressWithTree, Base58Conversions,
-        FromPhotonTokenAccountList, Hash, Indexer, IndexerError, MerkleProof,
+        Address, AddressMerkleTreeAccounts, AddressMerkleTreeBundle, AddressWithTree,
+        Base58Conversions, FromPhotonTokenAccountList, Hash, Indexer, IndexerError, MerkleProof,
         NewAddressProofWithContext,
     },
     rate_limiter::{RateLimiter, UseRateLimiter},
     rpc::{types::ProofRpcResult, RpcConnection},
 };
 
+/// Discriminator of batched state and address Merkle tree accounts.
+const BATCHED_MERKLE_TREE_DISCRIMINATOR: &[u8] = b"BatchMta";
+
 pub struct PhotonIndexer<R: RpcConnection> {
     configuration: Configuration,
-    #[allow(dead_code)]
     rpc: R,
     rate_limiter: Option<RateLimiter>,
+    address_merkle_trees: Vec<AddressMerkleTreeBundle>,
 }
 
 impl<R: RpcConnection> PhotonIndexer<R> {
@@ -67,6 +74,7 @@ impl<R: RpcConnection> PhotonIndexer<R> {
             configuration,
             rpc,
             rate_limiter: None,
+            address_merkle_trees: Vec::new(),
         }
     }
 
@@ -78,6 +86,129 @@ impl<R: RpcConnection> PhotonIndexer<R> {
         &mut self.rpc
     }
 
+    /// Loads the address Merkle trees of the account compression program.
+    /// Photon does not list trees, hence the tree accounts are fetched
+    /// from the rpc. The bundles only contain the tree accounts,
+    /// their reference Merkle trees are not synced.
+    /// Proofs and subtrees are fetched from Photon.
+    pub async fn load_address_merkle_trees(&mut self) -> Result<(), IndexerError> {
+        let mut accounts = Vec::new();
+        for discriminator in [
+            &ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR[..],
+            BATCHED_MERKLE_TREE_DISCRIMINATOR,
+        ] {
+            accounts.extend(
+                self.rpc
+                    .get_program_accounts_with_discriminator(
+                        &ACCOUNT_COMPRESSION_PROGRAM_ID,
+                        discriminator,
+                    )
+                    .await
+                    .map_err(|e| IndexerError::RpcError(e.to_string()))?,
+            );
+        }
+        self.address_merkle_trees = accounts
+            .into_iter()
+            .filter_map(|(pubkey, mut account)| address_merkle_tree_accounts(pubkey, &mut account))
+            .map(|accounts| {
+                if accounts.merkle_tree == accounts.queue {
+                    AddressMerkleTreeBundle::new_v2(accounts)
+                } else {
+                    AddressMerkleTreeBundle::new_v1(accounts)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(())
+    }
+
+    /// Fetches the batch address update info of a batched address Merkle tree.
+    async fn get_batch_address_update_info(
+        &self,
+        merkle_tree_pubkey: &Pubkey,
+        batch_size: u16,
+    ) -> Result<BatchAddressUpdateIndexerResponse, IndexerError> {
+        self.rate_limited_request_with_retry(|| async {
+            let merkle_tree = Hash::from_bytes(merkle_tree_pubkey.to_bytes().as_ref())?;
+            let request = photon_api::models::GetBatchAddressUpdateInfoPostRequest {
+                params: Box::new(
+                    photon_api::models::GetBatchAddressUpdateInfoPostRequestParams {
+                        batch_size,
+                        tree: merkle_tree.to_base58(),
+                    },
+                ),
+                ..Default::default()
+            };
+
+            let result = photon_api::apis::default_api::get_batch_address_update_info_post(
+                &self.configuration,
+                request,
+            )
+            .await?;
+
+            let response = Self::extract_result("get_batch_address_update_info", result.result)?;
+
+            let addresses = response
+                .addresses
+                .iter()
+                .map(|x| {
+                    Ok(AddressQueueIndex {
+                        address: Hash::from_base58(&x.address)?,
+                        queue_index: x.queue_index,
+                    })
+                })
+                .collect::<Result<Vec<_>, IndexerError>>()?;
+
+            let mut proofs: Vec<NewAddressProofWithContext<40>> = vec![];
+            for proof in response.non_inclusion_proofs {
+                let proof = NewAddressProofWithContext::<40> {
+                    merkle_tree: merkle_tree_pubkey.to_bytes(),
+                    low_address_index: proof.low_element_leaf_index,
+                    low_address_value: Hash::from_base58(&proof.lower_range_address)?,
+                    low_address_next_index: proof.next_index,
+                    low_address_next_value: Hash::from_base58(&proof.higher_range_address)?,
+                    low_address_proof: proof
+                        .proof
+                        .iter()
+                        .map(|x| Hash::from_base58(x))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .try_into()
+                        .map_err(|x: Vec<_>| {
+                            IndexerError::decode_error(
+                                "proof",
+                                format!("expected 40 proof elements, got {}", x.len()),
+                            )
+                        })?,
+                    root: Hash::from_base58(&proof.root)?,
+                    root_seq: proof.root_seq,
+
+                    new_low_element: None,
+                    new_element: None,
+                    new_element_next_value: None,
+                };
+                proofs.push(proof);
+            }
+
+            let subtrees = response
+                .subtrees
+                .iter()
+                .map(|x| {
+                    x.as_slice()
+                        .try_into()
+                        .map_err(|e| IndexerError::decode_error("subtrees", e))
+                })
+                .collect::<Result<Vec<[u8; 32]>, _>>()?;
+
+            let result = BatchAddressUpdateIndexerResponse {
+                batch_start_index: response.start_index,
+                addresses,
+                non_inclusion_proofs: proofs,
+                subtrees,
+            };
+            Ok(result)
+        })
+        .await
+    }
+
     async fn rate_limited_request_with_retry<F, Fut, T>(
         &self,
         mut operation: F,
@@ -164,6 +295,114 @@ impl<R: RpcConnection> PhotonIndexer<R> {
         result.ok_or_else(|| IndexerError::missing_result(context, "value not present"))
     }
 
+    /// Fetches non-inclusion proofs for new addresses.
+    /// Proofs of v1 address trees (height 26) are returned
+    /// without the canopy, proofs of batched address trees have height 40.
+    async fn get_new_address_proofs<const HEIGHT: usize>(
+        &self,
+        merkle_tree_pubkey: [u8; 32],
+        addresses: Vec<[u8; 32]>,
+    ) -> Result<Vec<NewAddressProofWithContext<HEIGHT>>, IndexerError> {
+        self.rate_limited_request_with_retry(|| async {
+            let params: Vec<photon_api::models::address_with_tree::AddressWithTree> = addresses
+                .iter()
+                .map(|x| photon_api::models::address_with_tree::AddressWithTree {
+                    address: bs58::encode(x).into_string(),
+                    tree: bs58::encode(&merkle_tree_pubkey).into_string(),
+                })
+                .collect();
+
+            let request = photon_api::models::GetMultipleNewAddressProofsV2PostRequest {
+                params,
+                ..Default::default()
+            };
+
+            let result = photon_api::apis::default_api::get_multiple_new_address_proofs_v2_post(
+                &self.configuration,
+                request,
+            )
+            .await;
+
+            match &result {
+                Ok(response) => debug!("Raw API response: {:?}", response),
+                Err(e) => error!("API request failed: {:?}", e),
+            }
+
+            let result = result?;
+
+            let photon_proofs =
+                match Self::extract_result("get_multiple_new_address_proofs", result.result) {
+                    Ok(proofs) => proofs,
+                    Err(e) => {
+                        error!("Failed to extract proofs: {:?}", e);
+                        return Err(e);
+                    }
+                }
+                .value;
+            let mut proofs = Vec::new();
+            for photon_proof in photon_proofs {
+                let tree_pubkey = Hash::from_base58(&photon_proof.merkle_tree).map_err(|e| {
+                    IndexerError::Base58DecodeError {
+                        field: "merkle_tree".to_string(),
+                        message: e.to_string(),
+                    }
+                })?;
+
+                let low_address_value = Hash::from_base58(&photon_proof.lower_range_address)
+                    .map_err(|e| IndexerError::Base58DecodeError {
+                        field: "lower_range_address".to_string(),
+                        message: e.to_string(),
+                    })?;
+
+                let next_address_value = Hash::from_base58(&photon_proof.higher_range_address)
+                    .map_err(|e| IndexerError::Base58DecodeError {
+                        field: "higher_range_address".to_string(),
+                        message: e.to_string(),
+                    })?;
+
+                let mut proof_vec: Vec<[u8; 32]> = photon_proof
+                    .proof
+                    .iter()
+                    .map(|x: &String| Hash::from_base58(x))
+                    .collect::<Result<Vec<[u8; 32]>, IndexerError>>()?;
+
+                // Remove canopy, v1 address trees have a canopy of depth 10.
+                proof_vec.truncate(HEIGHT);
+                let proof_arr: [[u8; 32]; HEIGHT] = proof_vec.try_into().map_err(|e: Vec<_>| {
+                    IndexerError::decode_error(
+                        "proof",
+                        format!("expected {} proof nodes, got {}", HEIGHT, e.len()),
+                    )
+                })?;
+
+                let root = Hash::from_base58(&photon_proof.root).map_err(|e| {
+                    IndexerError::Base58DecodeError {
+                        field: "root".to_string(),
+                        message: e.to_string(),
+                    }
+                })?;
+
+                let proof = NewAddressProofWithContext {
+                    merkle_tree: tree_pubkey,
+                    low_address_index: photon_proof.low_element_leaf_index,
+                    low_address_value,
+                    low_address_next_index: photon_proof.next_index,
+                    low_address_next_value: next_address_value,
+                    low_address_proof: proof_arr,
+                    root,
+                    root_seq: photon_proof.root_seq,
+                    new_low_element: None,
+                    new_element: None,
+                    new_element_next_value: None,
+                };
+                proofs.push(proof);
+            }
+
+            Ok(proofs)
+        })
+        .await
+    }
+
     fn build_account_params(
         &self,
         address: Option<Address>,
@@ -272,11 +511,31 @@ impl<R: RpcConnection> Indexer<R> for PhotonIndexer<R> {
         .await
     }
 
+    /// Fetches the subtrees of a Merkle tree from Photon.
     async fn get_subtrees(
         &self,
-        _merkle_tree_pubkey: [u8; 32],
+        merkle_tree_pubkey: [u8; 32],
     ) -> Result<Vec<[u8; 32]>, IndexerError> {
-        unimplemented!()
+        self.rate_limited_request_with_retry(|| async {
+            let request = photon_api::models::GetSubtreesPostRequest {
+                params: Box::new(photon_api::models::GetSubtreesPostRequestParams {
+                    tree: merkle_tree_pubkey.to_base58(),
+                }),
+                ..Default::default()
+            };
+
+            let result =
+                photon_api::apis::default_api::get_subtrees_post(&self.configuration, request)
+                    .await?;
+
+            let response = Self::extract_result("get_subtrees", result.result)?;
+            response
+                .subtrees
+                .iter()
+                .map(|x| Hash::from_base58(x))
+                .collect()
+        })
+        .await
     }
 
     async fn create_proof_for_compressed_accounts(
@@ -722,107 +981,17 @@ impl<R: RpcConnection> Indexer<R> for PhotonIndexer<R> {
         merkle_tree_pubkey: [u8; 32],
         addresses: Vec<[u8; 32]>,
     ) -> Result<Vec<NewAddressProofWithContext<16>>, IndexerError> {
-        self.rate_limited_request_with_retry(|| async {
-            let params: Vec<photon_api::models::address_with_tree::AddressWithTree> = addresses
-                .iter()
-                .map(|x| photon_api::models::address_with_tree::AddressWithTree {
-                    address: bs58::encode(x).into_string(),
-                    tree: bs58::encode(&merkle_tree_pubkey).into_string(),
-                })
-                .collect();
-
-            let request = photon_api::models::GetMultipleNewAddressProofsV2PostRequest {
-                params,
-                ..Default::default()
-            };
-
-            let result = photon_api::apis::default_api::get_multiple_new_address_proofs_v2_post(
-                &self.configuration,
-                request,
-            )
-            .await;
-
-            match &result {
-                Ok(response) => debug!("Raw API response: {:?}", response),
-                Err(e) => error!("API request failed: {:?}", e),
-            }
-
-            let result = result?;
-
-            let photon_proofs =
-                match Self::extract_result("get_multiple_new_address_proofs", result.result) {
-                    Ok(proofs) => proofs,
-                    Err(e) => {
-                        error!("Failed to extract proofs: {:?}", e);
-                        return Err(e);
-                    }
-                }
-                .value;
-            let mut proofs = Vec::new();
-            for photon_proof in photon_proofs {
-                let tree_pubkey = Hash::from_base58(&photon_proof.merkle_tree).map_err(|e| {
-                    IndexerError::Base58DecodeError {
-                        field: "merkle_tree".to_string(),
-                        message: e.to_string(),
-                    }
-                })?;
-
-                let low_address_value = Hash::from_base58(&photon_proof.lower_range_address)
-                    .map_err(|e| IndexerError::Base58DecodeError {
-                        field: "lower_range_address".to_string(),
-                        message: e.to_string(),
-                    })?;
-
-                let next_address_value = Hash::from_base58(&photon_proof.higher_range_address)
-                    .map_err(|e| IndexerError::Base58DecodeError {
-                        field: "higher_range_address".to_string(),
-                        message: e.to_string(),
-                    })?;
-
-                let mut proof_vec: Vec<[u8; 32]> = photon_proof
-                    .proof
-                    .iter()
-                    .map(|x: &String| Hash::from_base58(x))
-                    .collect::<Result<Vec<[u8; 32]>, IndexerError>>()?;
-
-                proof_vec.truncate(proof_vec.len() - 10); // Remove canopy
-                let mut proof_arr = [[0u8; 32]; 16];
-                proof_arr.copy_from_slice(&proof_vec);
-
-                let root = Hash::from_base58(&photon_proof.root).map_err(|e| {
-                    IndexerError::Base58DecodeError {
-                        field: "root".to_string(),
-                        message: e.to_string(),
-                    }
-                })?;
-
-                let proof = NewAddressProofWithContext {
-                    merkle_tree: tree_pubkey,
-                    low_address_index: photon_proof.low_element_leaf_index,
-                    low_address_value,
-                    low_address_next_index: photon_proof.next_index,
-                    low_address_next_value: next_address_value,
-                    low_address_proof: proof_arr,
-                    root,
-                    root_seq: photon_proof.root_seq,
-                    new_low_element: None,
-                    new_element: None,
-                    new_element_next_value: None,
-                };
-                proofs.push(proof);
-            }
-
-            Ok(proofs)
-        })
-        .await
+        self.get_new_address_proofs::<16>(merkle_tree_pubkey, addresses)
+            .await
     }
 
     async fn get_multiple_new_address_proofs_h40(
         &self,
-        _merkle_tree_pubkey: [u8; 32],
-        _addresses: Vec<[u8; 32]>,
+        merkle_tree_pubkey: [u8; 32],
+        addresses: Vec<[u8; 32]>,
     ) -> Result<Vec<NewAddressProofWithContext<40>>, IndexerError> {
-        unimplemented!()
+        self.get_new_address_proofs::<40>(merkle_tree_pubkey, addresses)
+            .await
     }
 
     async fn get_validity_proof(
@@ -906,8 +1075,10 @@ impl<R: RpcConnection> Indexer<R> for PhotonIndexer<R> {
         Ok(result)
     }
 
+    /// Returns the address Merkle trees loaded with
+    /// [`PhotonIndexer::load_address_merkle_trees`].
     fn get_address_merkle_trees(&self) -> &Vec<AddressMerkleTreeBundle> {
-        todo!()
+        &self.address_merkle_trees
     }
 
     async fn get_address_queue_with_proofs(
@@ -915,85 +1086,67 @@ impl<R: RpcConnection> Indexer<R> for PhotonIndexer<R> {
         merkle_tree_pubkey: &Pubkey,
         zkp_batch_size: u16,
     ) -> Result<BatchAddressUpdateIndexerResponse, IndexerError> {
-        self.rate_limited_request_with_retry(|| async {
-            let merkle_tree = Hash::from_bytes(merkle_tree_pubkey.to_bytes().as_ref())?;
-            let request = photon_api::models::GetBatchAddressUpdateInfoPostRequest {
-                params: Box::new(
-                    photon_api::models::GetBatchAddressUpdateInfoPostRequestParams {
-                        batch_size: zkp_batch_size,
-                        tree: merkle_tree.to_base58(),
-                    },
-                ),
-                ..Default::default()
-            };
-
-            let result = photon_api::apis::default_api::get_batch_address_update_info_post(
-                &self.configuration,
-                request,
-            )
-            .await?;
-
-            let response =
-                Self::extract_result("get_compressed_token_account_balance", result.result)?;
-
-            let addresses = response
-                .addresses
-                .iter()
-                .map(|x| AddressQueueIndex {
-                    address: Hash::from_base58(x.address.clone().as_ref()).unwrap(),
-                    queue_index: x.queue_index,
-                })
-                .collect();
-
-            let mut proofs: Vec<NewAddressProofWithContext<40>> = vec![];
-            for proof in response.non_inclusion_proofs {
-                let proof = NewAddressProofWithContext::<40> {
-                    merkle_tree: merkle_tree_pubkey.to_bytes(),
-                    low_address_index: proof.low_element_leaf_index,
-                    low_address_value: Hash::from_base58(
-                        proof.lower_range_address.clone().as_ref(),
-                    )
-                    .unwrap(),
-                    low_address_next_index: proof.next_index,
-                    low_address_next_value: Hash::from_base58(
-                        proof.higher_range_address.clone().as_ref(),
-                    )
-                    .unwrap(),
-                    low_address_proof: proof
-                        .proof
-                        .iter()
-                        .map(|x| Hash::from_base58(x.clone().as_ref()).unwrap())
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .unwrap(),
-                    root: Hash::from_base58(proof.root.clone().as_ref()).unwrap(),
-                    root_seq: proof.root_seq,
-
-                    new_low_element: None,
-                    new_element: None,
-                    new_element_next_value: None,
-                };
-                proofs.push(proof);
-            }
-
-            let subtrees = response
-                .subtrees
-                .iter()
-                .map(|x| {
-                    let mut arr = [0u8; 32];
-                    arr.copy_from_slice(x.as_slice());
-                    arr
-                })
-                .collect::<Vec<_>>();
+        self.get_batch_address_update_info(merkle_tree_pubkey, zkp_batch_size)
+            .await
+    }
+}
 
-            let result = BatchAddressUpdateIndexerResponse {
-                batch_start_index: response.start_index,
-                addresses,
-                non_inclusion_proofs: proofs,
-                subtrees,
-            };
-            Ok(result)
+/// Returns the accounts of an indexed (v1) or batched address Merkle tree
+/// account, None for other accounts of the account compression program.
+/// The queue of a batched address Merkle tree is the tree itself.
+fn address_merkle_tree_accounts(
+    pubkey: Pubkey,
+    account: &mut SolanaAccount,
+) -> Option<AddressMerkleTreeAccounts> {
+    if account
+        .data
+        .starts_with(&ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR)
+    {
+        // The account data is the discriminator followed by the metadata.
+        let offset = ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR.len()
+            + offset_of!(MerkleTreeMetadata, associated_queue);
+        let queue: [u8; 32] = account.data.get(offset..offset + 32)?.try_into().ok()?;
+        return Some(AddressMerkleTreeAccounts {
+            merkle_tree: pubkey,
+            queue: Pubkey::new_from_array(queue),
+        });
+    }
+    BatchedMerkleTreeAccount::address_from_bytes(&mut account.data, &pubkey.into())
+        .ok()
+        .map(|_| AddressMerkleTreeAccounts {
+            merkle_tree: pubkey,
+            queue: pubkey,
         })
-        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_merkle_tree_accounts() {
+        let merkle_tree = Pubkey::new_unique();
+        let queue = Pubkey::new_unique();
+        let offset = ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR.len()
+            + offset_of!(MerkleTreeMetadata, associated_queue);
+        let mut data = vec![0u8; 8 + std::mem::size_of::<MerkleTreeMetadata>()];
+        data[..8].copy_from_slice(&ADDRESS_MERKLE_TREE_ACCOUNT_DISCRIMINATOR);
+        data[offset..offset + 32].copy_from_slice(&queue.to_bytes());
+        let mut account = SolanaAccount {
+            data,
+            ..Default::default()
+        };
+        let accounts = address_merkle_tree_accounts(merkle_tree, &mut account).unwrap();
+        assert_eq!(accounts.merkle_tree, merkle_tree);
+        assert_eq!(accounts.queue, queue);
+        // Truncated account data is not a tree.
+        account.data.truncate(offset);
+        assert!(address_merkle_tree_accounts(merkle_tree, &mut account).is_none());
+        // Other accounts of the program are not trees.
+        let mut account = SolanaAccount {
+            data: vec![0u8; 1000],
+            ..Default::default()
+        };
+        assert!(address_merkle_tree_accounts(merkle_tree, &mut account).is_none());
     }
 }
@@ -67,6 +67,22 @@ pub trait RpcConnection: Send + Sync + Debug + 'static {
         &self,
         program_id: &Pubkey,
     ) -> Result<Vec<(Pubkey, Account)>, RpcError>;
+
+    /// Returns the accounts of program_id whose data starts with
+    /// discriminator. Rpc nodes filter the accounts, the default
+    /// implementation filters the result of get_program_accounts.
+    async fn get_program_accounts_with_discriminator(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>, RpcError> {
+        Ok(self
+            .get_program_accounts(program_id)
+            .await?
+            .into_iter()
+            .filter(|(_, account)| account.data.starts_with(discriminator))
+            .collect())
+    }
     async fn process_transaction(
         &mut self,
         transaction: Transaction,
@@ -9,9 +9,14 @@ use light_compressed_account::indexer_event::{
     event::{BatchPublicTransactionEvent, PublicTransactionEvent},
     parse::event_from_light_transaction,
 };
+use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
     rpc_client::RpcClient,
-    rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig},
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcTransactionConfig,
+    },
+    rpc_filter::{Memcmp, RpcFilterType},
 };
 use solana_program::{clock::Slot, hash::Hash, pubkey::Pubkey};
 use solana_sdk::{
@@ -328,6 +333,30 @@ impl RpcConnection for SolanaRpcConnection {
         .await
     }
 
+    async fn get_program_accounts_with_discriminator(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>, RpcError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        self.retry(|| async {
+            self.client
+                .get_program_accounts_with_config(program_id, config.clone())
+                .map_err(RpcError::from)
+        })
+        .await
+    }
+
     async fn process_transaction(
         &mut self,
         transaction: Transaction,
@@ -1,3 +1,13 @@
+use std::mem;
+
+use light_concurrent_merkle_tree::ConcurrentMerkleTree;
+use light_hash_set::HashSet;
+use light_hasher::Poseidon;
+use light_merkle_tree_metadata::{
+    fee::compute_rollover_fee, merkle_tree::MerkleTreeMetadata, queue::QueueMetadata,
+};
+use solana_sdk::rent::Rent;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TransactionParams {
     pub num_input_compressed_accounts: u8,
@@ -22,8 +32,7 @@ pub struct FeeConfig {
 impl Default for FeeConfig {
     fn default() -> Self {
         Self {
-            // rollover fee plus additional lamports for the cpi account
-            state_merkle_tree_rollover: 300,
+            state_merkle_tree_rollover: default_state_merkle_tree_rollover_fee(),
             address_queue_rollover: 392,
             // TODO: refactor to allow multiple state and address tree configs
             // state_tree_configs: vec![StateMerkleTreeConfig::default()],
@@ -35,6 +44,45 @@ impl Default for FeeConfig {
     }
 }
 
+// Default configuration of v1 state Merkle trees, nullifier queues
+// and cpi context accounts.
+const STATE_MERKLE_TREE_HEIGHT: usize = 26;
+const STATE_MERKLE_TREE_CHANGELOG: usize = 1400;
+const STATE_MERKLE_TREE_ROOTS: usize = 2400;
+const STATE_MERKLE_TREE_CANOPY_DEPTH: usize = 10;
+const STATE_MERKLE_TREE_ROLLOVER_THRESHOLD: u64 = 95;
+const STATE_NULLIFIER_QUEUE_VALUES: usize = 28_807;
+const CPI_CONTEXT_ACCOUNT_SIZE: usize = 20 * 1024 + 8;
+
+/// Rollover fee of a v1 state Merkle tree with the default configuration.
+/// Rent of the Merkle tree, the nullifier queue and the cpi context account
+/// (additional bytes) is collected until the rollover threshold is reached.
+pub fn default_state_merkle_tree_rollover_fee() -> u64 {
+    let merkle_tree_size = 8
+        + mem::size_of::<MerkleTreeMetadata>()
+        + ConcurrentMerkleTree::<Poseidon, STATE_MERKLE_TREE_HEIGHT>::size_in_account(
+            STATE_MERKLE_TREE_HEIGHT,
+            STATE_MERKLE_TREE_CHANGELOG,
+            STATE_MERKLE_TREE_ROOTS,
+            STATE_MERKLE_TREE_CANOPY_DEPTH,
+        );
+    let queue_size = 8
+        + mem::size_of::<QueueMetadata>()
+        + HashSet::size_in_account(STATE_NULLIFIER_QUEUE_VALUES);
+    let rent = Rent::default();
+    [merkle_tree_size, queue_size, CPI_CONTEXT_ACCOUNT_SIZE]
+        .iter()
+        .map(|size| {
+            compute_rollover_fee(
+                STATE_MERKLE_TREE_ROLLOVER_THRESHOLD,
+                STATE_MERKLE_TREE_HEIGHT as u32,
+                rent.minimum_balance(*size),
+            )
+            .unwrap()
+        })
+        .sum()
+}
+
 impl FeeConfig {
     pub fn test_batched() -> Self {
         Self {
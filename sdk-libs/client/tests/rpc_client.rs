@@ -281,4 +281,23 @@ async fn test_all_endpoints() {
         new_address_proofs[0].merkle_tree.to_bytes(),
         env_accounts.address_merkle_tree_pubkey.to_bytes()
     );
+    indexer.load_address_merkle_trees().await.unwrap();
+    let address_merkle_tree = indexer
+        .get_address_merkle_trees()
+        .iter()
+        .find(|bundle| bundle.accounts.merkle_tree == env_accounts.address_merkle_tree_pubkey)
+        .unwrap();
+    assert_eq!(
+        address_merkle_tree.accounts.queue,
+        env_accounts.address_merkle_tree_queue_pubkey
+    );
+    let batched_address_merkle_tree = indexer
+        .get_address_merkle_trees()
+        .iter()
+        .find(|bundle| bundle.accounts.merkle_tree == env_accounts.batch_address_merkle_tree)
+        .unwrap();
+    assert_eq!(
+        batched_address_merkle_tree.accounts.queue,
+        env_accounts.batch_address_merkle_tree
+    );
 }
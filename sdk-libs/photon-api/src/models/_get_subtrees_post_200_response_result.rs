@@ -0,0 +1,31 @@
+/*
+ * photon-indexer
+ *
+ * Solana indexer for general compression
+ *
+ * The version of the OpenAPI document: 0.50.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetSubtreesPost200ResponseResult {
+    #[serde(rename = "context")]
+    pub context: Box<models::Context>,
+    #[serde(rename = "subtrees")]
+    pub subtrees: Vec<String>,
+}
+
+impl GetSubtreesPost200ResponseResult {
+    pub fn new(
+        context: models::Context,
+        subtrees: Vec<String>,
+    ) -> GetSubtreesPost200ResponseResult {
+        GetSubtreesPost200ResponseResult {
+            context: Box::new(context),
+            subtrees,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/*
+ * photon-indexer
+ *
+ * Solana indexer for general compression
+ *
+ * The version of the OpenAPI document: 0.50.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetSubtreesPostRequestParams {
+    /// A 32-byte hash represented as a base58 string.
+    #[serde(rename = "tree")]
+    pub tree: String,
+}
+
+impl GetSubtreesPostRequestParams {
+    pub fn new(tree: String) -> GetSubtreesPostRequestParams {
+        GetSubtreesPostRequestParams { tree }
+    }
+}
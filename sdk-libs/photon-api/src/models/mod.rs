@@ -238,6 +238,14 @@ pub mod _get_queue_elements_post_request_params;
 pub use self::_get_queue_elements_post_request_params::GetQueueElementsPostRequestParams;
 pub mod get_queue_elements_response_value;
 pub use self::get_queue_elements_response_value::GetQueueElementsResponseValue;
+pub mod _get_subtrees_post_200_response;
+pub use self::_get_subtrees_post_200_response::GetSubtreesPost200Response;
+pub mod _get_subtrees_post_200_response_result;
+pub use self::_get_subtrees_post_200_response_result::GetSubtreesPost200ResponseResult;
+pub mod _get_subtrees_post_request;
+pub use self::_get_subtrees_post_request::GetSubtreesPostRequest;
+pub mod _get_subtrees_post_request_params;
+pub use self::_get_subtrees_post_request_params::GetSubtreesPostRequestParams;
 pub mod _get_transaction_with_compression_info_post_200_response;
 pub use self::_get_transaction_with_compression_info_post_200_response::GetTransactionWithCompressionInfoPost200Response;
 pub mod _get_transaction_with_compression_info_post_200_response_result;
@@ -22,7 +22,7 @@ pub struct TokenData {
     pub delegate: Option<Pubkey>,
     /// The account's state
     pub state: AccountState,
-    /// Placeholder for TokenExtension tlv data (unimplemented)
+    /// Token extension tlv data, see light_compressed_token::tlv for the encoding.
     pub tlv: Option<Vec<u8>>,
 }
 
@@ -8,18 +8,68 @@ use account_compression::{
     },
     AddressMerkleTreeAccount, StateMerkleTreeAccount, StateMerkleTreeConfig,
 };
-use light_merkle_tree_metadata::fee::compute_rollover_fee;
+use clap::{Parser, ValueEnum};
+use light_batched_merkle_tree::{
+    initialize_address_tree::{get_address_tree_fee_model, InitAddressTreeAccountsInstructionData},
+    initialize_state_tree::{get_state_tree_fee_model, InitStateTreeAccountsInstructionData},
+};
+use light_merkle_tree_metadata::fee::{compute_rollover_fee, FeeModel};
 use solana_program::rent::Rent;
 use tabled::{Table, Tabled};
 
+#[derive(Debug, Clone, ValueEnum)]
+enum Tree {
+    State,
+    Address,
+}
+
+#[derive(Debug, Parser)]
+pub struct Options {
+    /// Simulates the lifetime of a batched tree with default parameters.
+    /// Example:
+    /// cargo xtask fee --simulate state --fill-level 50
+    #[clap(long)]
+    simulate: Option<Tree>,
+    /// Queue fill level in percent used to compute the network fee.
+    #[clap(long, default_value = "0")]
+    fill_level: u64,
+    /// Number of simulation steps.
+    #[clap(long, default_value = "10")]
+    steps: u64,
+}
+
 #[derive(Tabled)]
 struct AccountFee {
     account: String,
     fee: u64,
 }
 
-pub fn fees() -> anyhow::Result<()> {
+#[derive(Tabled)]
+struct SimulationStep {
+    transactions: u64,
+    rollover_fees: u64,
+    network_fees: u64,
+    /// Collected rollover fees minus rent of the next tree.
+    rollover_balance: i128,
+    break_even: bool,
+}
+
+pub fn fees(options: Options) -> anyhow::Result<()> {
     let rent = Rent::default();
+    if let Some(tree) = options.simulate {
+        let fee_model = match tree {
+            Tree::State => {
+                get_state_tree_fee_model(InitStateTreeAccountsInstructionData::default(), |size| {
+                    rent.minimum_balance(size)
+                })
+            }
+            Tree::Address => get_address_tree_fee_model(
+                InitAddressTreeAccountsInstructionData::default(),
+                |size| rent.minimum_balance(size),
+            ),
+        };
+        return simulate(fee_model, options.fill_level, options.steps);
+    }
 
     let state_merkle_tree_config = StateMerkleTreeConfig::default();
 
@@ -48,6 +98,22 @@ pub fn fees() -> anyhow::Result<()> {
                 rent.minimum_balance(QueueAccount::size(ADDRESS_QUEUE_VALUES.into())?),
             )?,
         },
+        AccountFee {
+            account: "Batched state tree (rollover)".to_owned(),
+            fee: get_state_tree_fee_model(
+                InitStateTreeAccountsInstructionData::default(),
+                |size| rent.minimum_balance(size),
+            )
+            .rollover_fee()?,
+        },
+        AccountFee {
+            account: "Batched address tree (rollover)".to_owned(),
+            fee: get_address_tree_fee_model(
+                InitAddressTreeAccountsInstructionData::default(),
+                |size| rent.minimum_balance(size),
+            )
+            .rollover_fee()?,
+        },
     ];
 
     let table = Table::new(fees);
@@ -55,3 +121,44 @@ pub fn fees() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Simulates the lifetime of a tree from creation until it is ready for rollover.
+fn simulate(fee_model: FeeModel, fill_level: u64, steps: u64) -> anyhow::Result<()> {
+    let report = fee_model.report(fill_level)?;
+    println!("rent: {}", report.rent);
+    println!("rollover fee per tx: {}", report.rollover_fee);
+    println!("network fee per tx: {}", report.network_fee);
+    println!("lifetime transactions: {}", report.lifetime_transactions);
+    println!(
+        "lifetime rollover revenue: {}",
+        report.lifetime_rollover_revenue
+    );
+    println!(
+        "lifetime network fee revenue: {}",
+        report.lifetime_network_fee_revenue
+    );
+    match report.break_even_transactions {
+        Some(break_even_transactions) => {
+            println!("break even after transactions: {}", break_even_transactions)
+        }
+        None => println!("break even: never (no rollover fee)"),
+    }
+
+    let steps = steps.max(1);
+    let simulation = (1..=steps)
+        .map(|step| {
+            let transactions = report.lifetime_transactions * step / steps;
+            let rollover_fees = report.rollover_fee.saturating_mul(transactions);
+            SimulationStep {
+                transactions,
+                rollover_fees,
+                network_fees: report.network_fee.saturating_mul(transactions),
+                rollover_balance: rollover_fees as i128 - report.rent as i128,
+                break_even: rollover_fees >= report.rent,
+            }
+        })
+        .collect::<Vec<_>>();
+    let table = Table::new(simulation);
+    println!("{table}");
+    Ok(())
+}
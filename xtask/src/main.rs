@@ -41,7 +41,9 @@ enum Command {
     /// Generates cu and heap memory usage report from a log.txt file
     Bench(bench::Options),
     /// Prints fees for different accounts.
-    Fee,
+    /// Example:
+    /// cargo xtask fee --simulate state
+    Fee(fee::Options),
     /// Hash set utilities.
     HashSet(hash_set::HashSetOptions),
     /// Create state tree
@@ -71,7 +73,7 @@ async fn main() -> Result<(), anyhow::Error> {
             create_vkeyrs_from_gnark_key::create_vkeyrs_from_gnark_key(opts)
         }
         Command::Bench(opts) => bench::bench(opts),
-        Command::Fee => fee::fees(),
+        Command::Fee(opts) => fee::fees(opts),
         Command::HashSet(opts) => hash_set::hash_set(opts),
         Command::CreateStateTree(opts) => create_state_tree::create_state_tree(opts).await,
         Command::ExportPhotonTestData(opts) => {